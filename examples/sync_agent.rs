@@ -0,0 +1,35 @@
+use snmp::agent::{Agent, ScalarHandler};
+use snmp::sync_session::SyncAgent;
+use snmp::OwnedValue;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// an object below the reserved example enterprise
+const CLOCK: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 1];
+
+fn main() {
+    let mut agent = Agent::new(b"public");
+    {
+        let mut system = agent.system();
+        system.descr = "rust-snmp example agent".to_string();
+        system.location = "lab".to_string();
+    }
+    agent.register(
+        CLOCK,
+        ScalarHandler::new(CLOCK, || {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+            OwnedValue::Counter64(now.as_secs())
+        }),
+    );
+
+    let mut agent = match SyncAgent::new("127.0.0.1:1161", agent) {
+        Ok(agent) => agent,
+        Err(err) => {
+            eprintln!("{err:?}");
+            return;
+        }
+    };
+
+    if let Err(err) = agent.serve() {
+        eprintln!("{:?}", err);
+    }
+}
@@ -0,0 +1,572 @@
+//! MIB handlers and their registry.
+
+use std::collections::BTreeMap;
+
+use crate::snmp;
+use crate::OwnedValue;
+
+/// Provides the instances of one OID subtree.
+pub trait MibHandler: Send {
+    /// Returns the value of the instance `oid`, or `NoSuchObject` /
+    /// `NoSuchInstance` if it does not exist.
+    fn get(&mut self, oid: &[u32]) -> OwnedValue;
+
+    /// Returns the first instance served by the handler that is
+    /// lexicographically greater than `oid`. `oid` may lie before the
+    /// handler's subtree.
+    fn get_next(&mut self, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)>;
+
+    /// First SET phase: checks that `value` can be written to `oid` and
+    /// reserves whatever committing it requires. Errors are one of the
+    /// `snmp::ERRSTATUS_*` codes.
+    fn test_set(&mut self, _oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        Err(snmp::ERRSTATUS_NOTWRITABLE)
+    }
+
+    /// Second SET phase, run once every varbind of the request passed
+    /// `test_set`: checks `value` against the other values of the request,
+    /// e.g. columns of a row created by the same request.
+    fn check_consistency(&mut self, _oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        Ok(())
+    }
+
+    /// Applies a tested assignment.
+    fn commit_set(&mut self, _oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        Err(snmp::ERRSTATUS_COMMITFAILED)
+    }
+
+    /// Reverts a committed assignment after another one of the same request
+    /// failed to commit.
+    fn undo_set(&mut self, _oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        Err(snmp::ERRSTATUS_UNDOFAILED)
+    }
+
+    /// Releases what `test_set` reserved. Called for every varbind once the
+    /// request is finished, whether it succeeded or not.
+    fn cleanup_set(&mut self, _oid: &[u32]) {}
+}
+
+struct Registration {
+    subtree: Vec<u32>,
+    handler: Box<dyn MibHandler>,
+}
+
+/// Handlers ordered by the OID subtree they are registered for.
+///
+/// Registrations may overlap. An instance always belongs to the most
+/// specific registration enclosing it, so a handler registered for
+/// `ifTable` hides whatever a handler registered for `mib-2` returns below
+/// `ifTable`.
+#[derive(Default)]
+pub struct MibRegistry {
+    // sorted by subtree, which is the preorder of the OID tree
+    registrations: Vec<Registration>,
+}
+
+impl MibRegistry {
+    pub fn new() -> Self {
+        MibRegistry::default()
+    }
+
+    /// Registers `handler` for all instances below `subtree`, replacing any
+    /// handler registered for exactly the same subtree.
+    pub fn register(&mut self, subtree: &[u32], handler: Box<dyn MibHandler>) {
+        match self.position(subtree) {
+            Ok(pos) => self.registrations[pos].handler = handler,
+            Err(pos) => self.registrations.insert(
+                pos,
+                Registration {
+                    subtree: subtree.to_vec(),
+                    handler,
+                },
+            ),
+        }
+    }
+
+    pub fn unregister(&mut self, subtree: &[u32]) -> Option<Box<dyn MibHandler>> {
+        let pos = self.position(subtree).ok()?;
+        Some(self.registrations.remove(pos).handler)
+    }
+
+    /// Registered subtrees in OID order.
+    pub fn subtrees(&self) -> impl Iterator<Item = &[u32]> {
+        self.registrations.iter().map(|r| &r.subtree[..])
+    }
+
+    fn position(&self, subtree: &[u32]) -> Result<usize, usize> {
+        self.registrations
+            .binary_search_by(|r| r.subtree[..].cmp(subtree))
+    }
+
+    /// Index of the most specific registration enclosing `oid`.
+    fn authority(&self, oid: &[u32]) -> Option<usize> {
+        (0..=oid.len())
+            .rev()
+            .find_map(|len| self.position(&oid[..len]).ok())
+    }
+
+    /// The subtree of the registration `oid` belongs to.
+    pub fn subtree_of(&self, oid: &[u32]) -> Option<&[u32]> {
+        self.authority(oid)
+            .map(|pos| &self.registrations[pos].subtree[..])
+    }
+
+    /// GET semantics of RFC 3416 section 4.2.1.
+    pub fn get(&mut self, oid: &[u32]) -> OwnedValue {
+        match self.authority(oid) {
+            Some(pos) => self.registrations[pos].handler.get(oid),
+            None => OwnedValue::NoSuchObject,
+        }
+    }
+
+    /// GETNEXT semantics of RFC 3416 section 4.2.2: the next instance of
+    /// any handler, or `None` at the end of the MIB view.
+    pub fn get_next(&mut self, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)> {
+        let mut best: Option<(Vec<u32>, OwnedValue)> = None;
+
+        for pos in 0..self.registrations.len() {
+            let subtree = &self.registrations[pos].subtree;
+
+            if let Some((ref found, _)) = best {
+                // everything below this and later subtrees sorts after `found`
+                if subtree[..] > found[..] {
+                    break;
+                }
+            }
+
+            if &subtree[..] < oid && !oid.starts_with(subtree) {
+                continue;
+            }
+
+            if let Some((next, value)) = self.next_in_region(pos, oid) {
+                if best.as_ref().is_none_or(|(found, _)| next < *found) {
+                    best = Some((next, value));
+                }
+            }
+        }
+
+        best
+    }
+
+    /// The next instance after `oid` served by registration `pos`, skipping
+    /// instances that belong to more specific registrations.
+    fn next_in_region(&mut self, pos: usize, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)> {
+        let mut cursor = oid.to_vec();
+
+        loop {
+            let registration = &mut self.registrations[pos];
+            let (next, value) = registration.handler.get_next(&cursor)?;
+
+            if next[..] <= cursor[..] || !next.starts_with(&registration.subtree) {
+                return None;
+            }
+
+            match self.authority(&next) {
+                Some(owner) if owner == pos => return Some((next, value)),
+                Some(owner) => {
+                    // continue after the whole overlapping subtree, at the
+                    // first OID that is not in it
+                    let after = successor(&self.registrations[owner].subtree)?;
+                    let registration = &mut self.registrations[pos];
+                    if !after.starts_with(&registration.subtree) {
+                        return None;
+                    }
+                    let value = registration.handler.get(&after);
+                    let exists = !matches!(
+                        value,
+                        OwnedValue::NoSuchObject
+                            | OwnedValue::NoSuchInstance
+                            | OwnedValue::EndOfMibView
+                    );
+                    if exists && self.authority(&after) == Some(pos) {
+                        return Some((after, value));
+                    }
+                    cursor = after;
+                }
+                None => return None,
+            }
+        }
+    }
+
+    /// Performs a SET request "as if simultaneous" (RFC 3416 section
+    /// 4.2.5): every varbind is tested before any is committed, and committed
+    /// varbinds are undone if a later one fails to commit.
+    ///
+    /// Errors carry the error-status and the 1-based error-index.
+    pub fn set(&mut self, varbinds: &[(Vec<u32>, OwnedValue)]) -> Result<(), (u32, u32)> {
+        let res = self.test_set(varbinds).and_then(|()| {
+            self.commit_set(varbinds).map_err(|(status, index)| {
+                let committed = index.saturating_sub(1) as usize;
+                match self.undo_set(&varbinds[..committed]) {
+                    Ok(()) => (status, index),
+                    Err(_) => (snmp::ERRSTATUS_UNDOFAILED, 0),
+                }
+            })
+        });
+        self.cleanup_set(varbinds);
+        res
+    }
+
+    /// The test and consistency phases of `set`.
+    pub fn test_set(&mut self, varbinds: &[(Vec<u32>, OwnedValue)]) -> Result<(), (u32, u32)> {
+        let owners = self.owners(varbinds)?;
+
+        for (i, (&pos, (name, value))) in owners.iter().zip(varbinds).enumerate() {
+            let handler = &mut self.registrations[pos].handler;
+            handler
+                .test_set(name, value)
+                .map_err(|e| (e, i as u32 + 1))?;
+        }
+
+        for (i, (&pos, (name, value))) in owners.iter().zip(varbinds).enumerate() {
+            let handler = &mut self.registrations[pos].handler;
+            handler
+                .check_consistency(name, value)
+                .map_err(|e| (e, i as u32 + 1))?;
+        }
+        Ok(())
+    }
+
+    /// The commit phase of `set`. On failure, the varbinds before the
+    /// error-index have been committed and need to be undone.
+    pub fn commit_set(&mut self, varbinds: &[(Vec<u32>, OwnedValue)]) -> Result<(), (u32, u32)> {
+        let owners = self.owners(varbinds)?;
+        for (i, (&pos, (name, value))) in owners.iter().zip(varbinds).enumerate() {
+            if self.registrations[pos]
+                .handler
+                .commit_set(name, value)
+                .is_err()
+            {
+                return Err((snmp::ERRSTATUS_COMMITFAILED, i as u32 + 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Undoes committed varbinds, last first. Every varbind is undone even
+    /// if undoing another one fails.
+    pub fn undo_set(&mut self, varbinds: &[(Vec<u32>, OwnedValue)]) -> Result<(), (u32, u32)> {
+        let owners = self.owners(varbinds)?;
+        let mut res = Ok(());
+        for (i, (&pos, (name, value))) in owners.iter().zip(varbinds).enumerate().rev() {
+            if self.registrations[pos]
+                .handler
+                .undo_set(name, value)
+                .is_err()
+            {
+                res = Err((snmp::ERRSTATUS_UNDOFAILED, i as u32 + 1));
+            }
+        }
+        res
+    }
+
+    /// Lets the handlers of all varbinds release what the other phases left
+    /// behind.
+    pub fn cleanup_set(&mut self, varbinds: &[(Vec<u32>, OwnedValue)]) {
+        for (name, _) in varbinds {
+            if let Some(pos) = self.authority(name) {
+                self.registrations[pos].handler.cleanup_set(name);
+            }
+        }
+    }
+
+    fn owners(&self, varbinds: &[(Vec<u32>, OwnedValue)]) -> Result<Vec<usize>, (u32, u32)> {
+        varbinds
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| {
+                self.authority(name)
+                    .ok_or((snmp::ERRSTATUS_NOACCESS, i as u32 + 1))
+            })
+            .collect()
+    }
+}
+
+type Getter = Box<dyn FnMut() -> OwnedValue + Send>;
+type Setter = Box<dyn FnMut(&OwnedValue) -> Result<(), u32> + Send>;
+
+/// A scalar object, registered for the object OID and served as instance
+/// `.0`.
+pub struct ScalarHandler {
+    instance: Vec<u32>,
+    get: Getter,
+    set: Option<Setter>,
+    undo: Option<OwnedValue>,
+}
+
+impl ScalarHandler {
+    /// A read-only scalar with a fixed value.
+    pub fn constant(oid: &[u32], value: OwnedValue) -> Self {
+        ScalarHandler::new(oid, move || value.clone())
+    }
+
+    /// A read-only scalar whose value is computed on every request.
+    pub fn new<G>(oid: &[u32], get: G) -> Self
+    where
+        G: FnMut() -> OwnedValue + Send + 'static,
+    {
+        let mut instance = oid.to_vec();
+        instance.push(0);
+
+        ScalarHandler {
+            instance,
+            get: Box::new(get),
+            set: None,
+            undo: None,
+        }
+    }
+
+    /// A writable scalar. `set` is called to commit a new value and, with
+    /// the previous value, to undo it.
+    pub fn read_write<G, S>(oid: &[u32], get: G, set: S) -> Self
+    where
+        G: FnMut() -> OwnedValue + Send + 'static,
+        S: FnMut(&OwnedValue) -> Result<(), u32> + Send + 'static,
+    {
+        let mut scalar = ScalarHandler::new(oid, get);
+        scalar.set = Some(Box::new(set));
+        scalar
+    }
+}
+
+impl MibHandler for ScalarHandler {
+    fn get(&mut self, oid: &[u32]) -> OwnedValue {
+        if oid == &self.instance[..] {
+            (self.get)()
+        } else if oid.starts_with(&self.instance[..self.instance.len() - 1]) {
+            OwnedValue::NoSuchInstance
+        } else {
+            OwnedValue::NoSuchObject
+        }
+    }
+
+    fn get_next(&mut self, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)> {
+        if oid < &self.instance[..] {
+            Some((self.instance.clone(), (self.get)()))
+        } else {
+            None
+        }
+    }
+
+    fn test_set(&mut self, oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        if self.set.is_none() {
+            return Err(snmp::ERRSTATUS_NOTWRITABLE);
+        }
+        if oid != &self.instance[..] {
+            return Err(snmp::ERRSTATUS_NOCREATION);
+        }
+        if !same_type(&(self.get)(), value) {
+            return Err(snmp::ERRSTATUS_WRONGTYPE);
+        }
+        Ok(())
+    }
+
+    fn commit_set(&mut self, _oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        let previous = (self.get)();
+        let set = self.set.as_mut().ok_or(snmp::ERRSTATUS_COMMITFAILED)?;
+        set(value).map_err(|_| snmp::ERRSTATUS_COMMITFAILED)?;
+        // a request may name the scalar more than once; undoing any of its
+        // assignments restores the value from before the request
+        if self.undo.is_none() {
+            self.undo = Some(previous);
+        }
+        Ok(())
+    }
+
+    fn undo_set(&mut self, _oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        match (self.set.as_mut(), self.undo.as_ref()) {
+            (Some(set), Some(previous)) => set(previous).map_err(|_| snmp::ERRSTATUS_UNDOFAILED),
+            _ => Err(snmp::ERRSTATUS_UNDOFAILED),
+        }
+    }
+
+    fn cleanup_set(&mut self, _oid: &[u32]) {
+        self.undo = None;
+    }
+}
+
+/// Whether `value` has the same SNMP type as `current`. Exceptions such as
+/// `NoSuchInstance` match any type.
+pub fn same_type(current: &OwnedValue, value: &OwnedValue) -> bool {
+    use OwnedValue::*;
+    match current {
+        NoSuchObject | NoSuchInstance | EndOfMibView => true,
+        current => std::mem::discriminant(current) == std::mem::discriminant(value),
+    }
+}
+
+/// An in-memory conceptual table, registered for its entry OID
+/// (e.g. `ifEntry`). Instances are `entry.column.index`.
+pub struct TableHandler {
+    entry: Vec<u32>,
+    columns: Vec<u32>,
+    writable: Vec<u32>,
+    rows: BTreeMap<Vec<u32>, BTreeMap<u32, OwnedValue>>,
+    undo: Vec<(Vec<u32>, Option<OwnedValue>)>,
+}
+
+impl TableHandler {
+    /// Creates a read-only table with the given accessible columns.
+    pub fn new(entry: &[u32], columns: &[u32]) -> Self {
+        let mut columns = columns.to_vec();
+        columns.sort_unstable();
+        columns.dedup();
+
+        TableHandler {
+            entry: entry.to_vec(),
+            columns,
+            writable: Vec::new(),
+            rows: BTreeMap::new(),
+            undo: Vec::new(),
+        }
+    }
+
+    /// Allows SETs of existing rows' values in `columns`.
+    pub fn set_writable(&mut self, columns: &[u32]) {
+        self.writable = columns.to_vec();
+    }
+
+    pub fn entry(&self) -> &[u32] {
+        &self.entry
+    }
+
+    /// Adds or replaces the row with the given index.
+    pub fn insert_row(&mut self, index: &[u32], values: Vec<(u32, OwnedValue)>) {
+        self.rows
+            .insert(index.to_vec(), values.into_iter().collect());
+    }
+
+    pub fn remove_row(&mut self, index: &[u32]) -> Option<BTreeMap<u32, OwnedValue>> {
+        self.rows.remove(index)
+    }
+
+    pub fn row(&self, index: &[u32]) -> Option<&BTreeMap<u32, OwnedValue>> {
+        self.rows.get(index)
+    }
+
+    pub fn row_mut(&mut self, index: &[u32]) -> Option<&mut BTreeMap<u32, OwnedValue>> {
+        self.rows.get_mut(index)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = (&Vec<u32>, &BTreeMap<u32, OwnedValue>)> {
+        self.rows.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    /// Splits an instance OID into column and index.
+    pub fn split_instance<'o>(&self, oid: &'o [u32]) -> Option<(u32, &'o [u32])> {
+        let rest = oid.strip_prefix(&self.entry[..])?;
+        let (&column, index) = rest.split_first()?;
+        Some((column, index))
+    }
+
+    fn instance(&self, column: u32, index: &[u32]) -> Vec<u32> {
+        let mut oid = Vec::with_capacity(self.entry.len() + 1 + index.len());
+        oid.extend_from_slice(&self.entry);
+        oid.push(column);
+        oid.extend_from_slice(index);
+        oid
+    }
+}
+
+impl MibHandler for TableHandler {
+    fn get(&mut self, oid: &[u32]) -> OwnedValue {
+        match self.split_instance(oid) {
+            Some((column, index)) if self.columns.contains(&column) => self
+                .rows
+                .get(index)
+                .and_then(|row| row.get(&column))
+                .cloned()
+                .unwrap_or(OwnedValue::NoSuchInstance),
+            _ => OwnedValue::NoSuchObject,
+        }
+    }
+
+    fn get_next(&mut self, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)> {
+        let (start_column, start_index) = match oid.strip_prefix(&self.entry[..]) {
+            Some([]) => (0, None),
+            Some([column, index @ ..]) => (*column, Some(index)),
+            None if oid < &self.entry[..] => (0, None),
+            None => return None,
+        };
+
+        for &column in self.columns.iter().filter(|&&c| c >= start_column) {
+            let after = if column == start_column {
+                start_index
+            } else {
+                None
+            };
+            let next = self.rows.iter().find(|(index, row)| {
+                after.is_none_or(|after| &index[..] > after) && row.contains_key(&column)
+            });
+            if let Some((index, row)) = next {
+                return Some((self.instance(column, index), row[&column].clone()));
+            }
+        }
+        None
+    }
+
+    fn test_set(&mut self, oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        let Some((column, index)) = self.split_instance(oid) else {
+            return Err(snmp::ERRSTATUS_NOTWRITABLE);
+        };
+        if !self.writable.contains(&column) {
+            return Err(snmp::ERRSTATUS_NOTWRITABLE);
+        }
+        let Some(row) = self.rows.get(index) else {
+            return Err(snmp::ERRSTATUS_NOCREATION);
+        };
+        match row.get(&column) {
+            Some(current) if !same_type(current, value) => Err(snmp::ERRSTATUS_WRONGTYPE),
+            _ => Ok(()),
+        }
+    }
+
+    fn commit_set(&mut self, oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        let (column, index) = self
+            .split_instance(oid)
+            .ok_or(snmp::ERRSTATUS_COMMITFAILED)?;
+        let row = self
+            .rows
+            .get_mut(index)
+            .ok_or(snmp::ERRSTATUS_COMMITFAILED)?;
+        let previous = row.insert(column, value.clone());
+        self.undo.push((oid.to_vec(), previous));
+        Ok(())
+    }
+
+    fn undo_set(&mut self, oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        let pos = self
+            .undo
+            .iter()
+            .rposition(|(name, _)| &name[..] == oid)
+            .ok_or(snmp::ERRSTATUS_UNDOFAILED)?;
+        let (_, previous) = self.undo.remove(pos);
+        let (column, index) = self.split_instance(oid).ok_or(snmp::ERRSTATUS_UNDOFAILED)?;
+        let row = self.rows.get_mut(index).ok_or(snmp::ERRSTATUS_UNDOFAILED)?;
+        match previous {
+            Some(previous) => row.insert(column, previous),
+            None => row.remove(&column),
+        };
+        Ok(())
+    }
+
+    fn cleanup_set(&mut self, _oid: &[u32]) {
+        self.undo.clear();
+    }
+}
+
+/// The first OID after every OID that starts with `prefix`, if any.
+fn successor(prefix: &[u32]) -> Option<Vec<u32>> {
+    let mut oid = prefix.to_vec();
+    while let Some(last) = oid.pop() {
+        if last < u32::MAX {
+            oid.push(last + 1);
+            return Some(oid);
+        }
+    }
+    None
+}
@@ -0,0 +1,591 @@
+//! SNMP agent (command responder).
+//!
+//! An `Agent` decodes requests, dispatches every varbind to the `MibHandler`
+//! registered for the enclosing OID subtree and encodes the response. It is
+//! transport independent; `SyncAgent` and `AsyncAgent` serve it over UDP.
+//!
+//! SNMPv3 requests are accepted once a `Usm` is set, and only forwarded by
+//! the proxy by their contextName; the agent's own MIB is served to SNMPv2c
+//! communities.
+
+mod handler;
+mod row_status;
+mod snmpv2_mib;
+mod usm;
+mod vacm;
+
+pub use self::handler::{MibHandler, MibRegistry, ScalarHandler, TableHandler};
+pub use self::row_status::{Column, RowStatus, RowStatusTable};
+pub use self::snmpv2_mib::{
+    SnmpGroup, SnmpStats, SysOrEntry, SystemGroup, SystemInfo, SNMP, SYSTEM,
+};
+pub use self::usm::{
+    Usm, UsmStats, USM_STATS_UNKNOWN_ENGINE_IDS, USM_STATS_UNKNOWN_USER_NAMES,
+    USM_STATS_UNSUPPORTED_SEC_LEVELS,
+};
+pub use self::vacm::{
+    Access, ContextMatch, SecurityLevel, SecurityModel, Vacm, VacmError, View, ViewFamily, ViewType,
+};
+
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::notification::owned_varbinds;
+use crate::pdu;
+use crate::proxy::Proxy;
+use crate::snmp;
+use crate::SnmpError;
+use crate::SnmpMessageType;
+use crate::SnmpPdu;
+use crate::SnmpV3Message;
+use crate::{is_encodable_varbind, OwnedValue};
+
+/// Largest UDP payload that fits into an Ethernet frame without
+/// fragmentation.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 1472;
+
+pub struct Agent {
+    community: Vec<u8>,
+    registry: MibRegistry,
+    max_message_size: usize,
+    system: Arc<Mutex<SystemInfo>>,
+    stats: Arc<SnmpStats>,
+    vacm: Option<Vacm>,
+    proxy: Option<Proxy>,
+    usm: Option<Usm>,
+}
+
+impl Agent {
+    /// Creates an agent serving the `system` and `snmp` groups of
+    /// SNMPv2-MIB. Handlers registered for these subtrees replace them.
+    pub fn new(community: &[u8]) -> Self {
+        let system = Arc::new(Mutex::new(SystemInfo::default()));
+        let stats = Arc::new(SnmpStats::default());
+
+        let mut registry = MibRegistry::new();
+        registry.register(SYSTEM, Box::new(SystemGroup::new(system.clone())));
+        registry.register(SNMP, Box::new(SnmpGroup::new(stats.clone())));
+
+        Agent {
+            community: community.to_vec(),
+            registry,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            system,
+            stats,
+            vacm: None,
+            proxy: None,
+            usm: None,
+        }
+    }
+
+    /// Forwards requests with the proxied communities of `proxy` instead of
+    /// answering them. Forwarding blocks until the target answers.
+    pub fn set_proxy(&mut self, proxy: Proxy) {
+        self.proxy = Some(proxy);
+    }
+
+    pub fn proxy(&self) -> Option<&Proxy> {
+        self.proxy.as_ref()
+    }
+
+    /// Accepts SNMPv3 requests of the users of `usm`, to be forwarded by the
+    /// proxy by their contextName.
+    pub fn set_usm(&mut self, usm: Usm) {
+        self.usm = Some(usm);
+    }
+
+    pub fn usm(&self) -> Option<&Usm> {
+        self.usm.as_ref()
+    }
+
+    /// Enforces VACM. The communities of `vacm` then replace the agent's
+    /// community.
+    pub fn set_vacm(&mut self, vacm: Vacm) {
+        self.vacm = Some(vacm);
+    }
+
+    pub fn vacm(&self) -> Option<&Vacm> {
+        self.vacm.as_ref()
+    }
+
+    /// The contents of the `system` group.
+    pub fn system(&self) -> MutexGuard<'_, SystemInfo> {
+        self.system.lock().unwrap()
+    }
+
+    /// The `snmp` group counters.
+    pub fn stats(&self) -> &SnmpStats {
+        &self.stats
+    }
+
+    /// Sets the size responses must fit in. Larger responses are replaced by
+    /// a tooBig error, and GETBULK responses are cut short instead.
+    pub fn set_max_message_size(&mut self, size: usize) {
+        self.max_message_size = size;
+    }
+
+    /// Registers `handler` for all instances below `subtree`. See
+    /// `MibRegistry` for how overlapping registrations are resolved.
+    pub fn register<H>(&mut self, subtree: &[u32], handler: H)
+    where
+        H: MibHandler + 'static,
+    {
+        self.registry.register(subtree, Box::new(handler));
+    }
+
+    pub fn registry(&mut self) -> &mut MibRegistry {
+        &mut self.registry
+    }
+
+    /// Handles an encoded request. Returns `true` if a response was written
+    /// to `response`; requests that must not be answered, e.g. with a wrong
+    /// community, are dropped.
+    pub fn process(&mut self, request: &[u8], response: &mut pdu::Buf) -> bool {
+        let stats = self.stats.clone();
+        SnmpStats::bump(&stats.in_pkts);
+
+        match crate::peek_version(request) {
+            Ok(snmp::VERSION_2) => {}
+            Ok(snmp::VERSION_3) if self.usm.is_some() => return self.process_v3(request, response),
+            Ok(_) => {
+                SnmpStats::bump(&stats.in_bad_versions);
+                return false;
+            }
+            Err(_) => {
+                SnmpStats::bump(&stats.in_asn_parse_errs);
+                return false;
+            }
+        }
+
+        let Ok(req) = SnmpPdu::from_bytes(request) else {
+            SnmpStats::bump(&stats.in_asn_parse_errs);
+            return false;
+        };
+
+        let target = self.proxy.as_ref().and_then(|proxy| {
+            let context = proxy.context(req.community)?;
+            proxy
+                .target_for_context(context, &req.message_type)
+                .map(str::to_string)
+        });
+        if let Some(target) = target {
+            let Ok(varbinds) = owned_varbinds(req.varbinds.clone()) else {
+                SnmpStats::bump(&stats.in_asn_parse_errs);
+                return false;
+            };
+            let forwarded = self.proxy.as_mut().and_then(|proxy| {
+                proxy.forward(
+                    &target,
+                    &req.message_type,
+                    req.error_status,
+                    req.error_index,
+                    &varbinds,
+                )
+            });
+            let Some((error_status, error_index, values)) = forwarded else {
+                SnmpStats::bump(&stats.proxy_drops);
+                return false;
+            };
+            let (fits, _) = self.build_response(&req, error_status, error_index, &values, response);
+            if !fits {
+                SnmpStats::bump(&stats.out_too_bigs);
+            }
+            SnmpStats::bump(&stats.out_get_responses);
+            SnmpStats::bump(&stats.out_pkts);
+            return true;
+        }
+
+        let view_type = match req.message_type {
+            SnmpMessageType::SetRequest => ViewType::Write,
+            _ => ViewType::Read,
+        };
+        let view = match self.vacm {
+            Some(ref vacm) => {
+                let Some(security_name) = vacm.security_name(req.community) else {
+                    SnmpStats::bump(&stats.in_bad_community_names);
+                    return false;
+                };
+                let view = vacm.view(
+                    SecurityModel::V2c,
+                    security_name,
+                    SecurityLevel::NoAuthNoPriv,
+                    view_type,
+                    "",
+                );
+                Some(view)
+            }
+            None if req.community != &self.community[..] => {
+                SnmpStats::bump(&stats.in_bad_community_names);
+                return false;
+            }
+            None => None,
+        };
+
+        let Ok(varbinds) = owned_varbinds(req.varbinds.clone()) else {
+            SnmpStats::bump(&stats.in_asn_parse_errs);
+            return false;
+        };
+        let names: Vec<Vec<u32>> = varbinds.iter().map(|(name, _)| name.clone()).collect();
+
+        let max_len = pdu::max_varbinds_len(req.community, req.req_id, self.max_message_size);
+        let (mut error_status, mut error_index) = (0, 0);
+
+        // RFC 3413 section 3.2 step (5): a request whose principal has no
+        // view at all fails as a whole
+        let view = match view {
+            Some(Err(_)) if is_request(&req.message_type) => {
+                SnmpStats::bump(&stats.in_bad_community_uses);
+                error_status = snmp::ERRSTATUS_AUTHORIZATIONERROR;
+                error_index = varbinds.len().min(1) as u32;
+                None
+            }
+            Some(view) => view.ok(),
+            None => None,
+        };
+        let view = view.as_ref();
+
+        let values = match req.message_type {
+            _ if error_status != 0 => varbinds,
+            SnmpMessageType::GetRequest => {
+                SnmpStats::bump(&stats.in_get_requests);
+                names
+                    .into_iter()
+                    .map(|name| {
+                        let value = match view {
+                            Some(view) if !view.contains(&name) => OwnedValue::NoSuchObject,
+                            _ => self.get(&name),
+                        };
+                        (name, value)
+                    })
+                    .collect()
+            }
+            SnmpMessageType::GetNextRequest => {
+                SnmpStats::bump(&stats.in_get_nexts);
+                names
+                    .iter()
+                    .map(|name| self.get_next_in_view(name, view))
+                    .collect()
+            }
+            SnmpMessageType::GetBulkRequest => {
+                self.get_bulk_in_view(&names, req.error_status, req.error_index, max_len, view)
+            }
+            SnmpMessageType::SetRequest => {
+                SnmpStats::bump(&stats.in_set_requests);
+                let denied =
+                    view.and_then(|view| names.iter().position(|name| !view.contains(name)));
+                let res = match denied {
+                    Some(i) => Err((snmp::ERRSTATUS_NOACCESS, i as u32 + 1)),
+                    None => self.registry.set(&varbinds),
+                };
+                match res {
+                    Ok(()) => {
+                        let n = varbinds.len() as u32;
+                        stats.in_total_set_vars.fetch_add(n, Ordering::Relaxed);
+                    }
+                    Err((status, index)) => {
+                        error_status = status;
+                        error_index = index;
+                    }
+                }
+                varbinds
+            }
+            SnmpMessageType::Response => {
+                SnmpStats::bump(&stats.in_get_responses);
+                return false;
+            }
+            SnmpMessageType::Trap => {
+                SnmpStats::bump(&stats.in_traps);
+                return false;
+            }
+            _ => return false,
+        };
+
+        let (fits, error_status) =
+            self.build_response(&req, error_status, error_index, &values, response);
+        if !fits {
+            SnmpStats::bump(&stats.out_too_bigs);
+            // not even the tooBig error fits
+            if response.len() > self.max_message_size {
+                SnmpStats::bump(&stats.silent_drops);
+                return false;
+            }
+        } else if error_status == snmp::ERRSTATUS_GENERR {
+            SnmpStats::bump(&stats.out_gen_errs);
+        } else if req.message_type != SnmpMessageType::SetRequest {
+            let retrieved = values
+                .iter()
+                .filter(|(_, value)| {
+                    !matches!(
+                        value,
+                        OwnedValue::NoSuchObject
+                            | OwnedValue::NoSuchInstance
+                            | OwnedValue::EndOfMibView
+                    )
+                })
+                .count() as u32;
+            stats
+                .in_total_req_vars
+                .fetch_add(retrieved, Ordering::Relaxed);
+        }
+
+        SnmpStats::bump(&stats.out_get_responses);
+        SnmpStats::bump(&stats.out_pkts);
+        true
+    }
+
+    /// Handles an SNMPv3 message: answers discovery and security failures
+    /// with a report, and forwards requests to the proxy target of their
+    /// contextName. Requests for contexts that are not proxied are dropped.
+    fn process_v3(&mut self, request: &[u8], response: &mut pdu::Buf) -> bool {
+        let stats = self.stats.clone();
+        let Some(usm) = self.usm.as_mut() else {
+            return false;
+        };
+        let message = match SnmpV3Message::from_bytes(request) {
+            Ok(message) => message,
+            Err(SnmpError::UnknownSecurityModel) => {
+                usm.stats_mut().unknown_security_models += 1;
+                return false;
+            }
+            Err(_) => {
+                SnmpStats::bump(&stats.in_asn_parse_errs);
+                return false;
+            }
+        };
+        let header = &message.header;
+        let scoped = message.scoped_pdu();
+        let max_size = (header.max_size as usize).min(self.max_message_size);
+        let own_max_size = self.max_message_size.min(i32::MAX as usize) as i32;
+
+        if let Err((oid, counter)) = usm.check(header) {
+            if header.flags & snmp::MSG_FLAG_REPORTABLE == 0 {
+                return false;
+            }
+            // the request-id is unknown if the ScopedPDU is encrypted
+            let (req_id, context_name) = match scoped {
+                Ok(ref scoped) => (scoped.pdu.req_id, scoped.context_name),
+                Err(_) => (0, &[][..]),
+            };
+            let reply = usm.header(header.msg_id, own_max_size, header.user_name);
+            let report = [(oid.to_vec(), OwnedValue::Counter32(counter))];
+            let built = pdu::build_v3(
+                &reply,
+                usm.engine_id(),
+                context_name,
+                snmp::MSG_REPORT,
+                req_id,
+                0,
+                0,
+                &report,
+                response,
+            );
+            if built.is_err() || response.len() > max_size {
+                SnmpStats::bump(&stats.silent_drops);
+                return false;
+            }
+            SnmpStats::bump(&stats.out_pkts);
+            return true;
+        }
+
+        let Ok(scoped) = scoped else {
+            SnmpStats::bump(&stats.in_asn_parse_errs);
+            return false;
+        };
+        let req = &scoped.pdu;
+        if !is_request(&req.message_type) {
+            return false;
+        }
+        let target = self.proxy.as_ref().and_then(|proxy| {
+            let context = std::str::from_utf8(scoped.context_name).ok()?;
+            proxy
+                .target_for_context(context, &req.message_type)
+                .map(str::to_string)
+        });
+        let Ok(varbinds) = owned_varbinds(req.varbinds.clone()) else {
+            SnmpStats::bump(&stats.in_asn_parse_errs);
+            return false;
+        };
+        let forwarded = target.and_then(|target| {
+            self.proxy.as_mut()?.forward(
+                &target,
+                &req.message_type,
+                req.error_status,
+                req.error_index,
+                &varbinds,
+            )
+        });
+        let Some((error_status, error_index, values)) = forwarded else {
+            SnmpStats::bump(&stats.proxy_drops);
+            return false;
+        };
+
+        let reply = usm.header(header.msg_id, own_max_size, header.user_name);
+        let build = |error_status, error_index, values: &[_], response: &mut pdu::Buf| {
+            pdu::build_v3(
+                &reply,
+                scoped.context_engine_id,
+                scoped.context_name,
+                snmp::MSG_RESPONSE,
+                req.req_id,
+                error_status,
+                error_index,
+                values,
+                response,
+            )
+        };
+        let failed = match build(error_status, error_index, &values, response) {
+            Ok(()) if response.len() <= max_size => None,
+            Ok(()) => {
+                SnmpStats::bump(&stats.out_too_bigs);
+                Some(snmp::ERRSTATUS_TOOBIG)
+            }
+            Err(_) => {
+                SnmpStats::bump(&stats.out_gen_errs);
+                Some(snmp::ERRSTATUS_GENERR)
+            }
+        };
+        if let Some(error_status) = failed {
+            // not even the error fits
+            if build(error_status, 0, &[], response).is_err() || response.len() > max_size {
+                SnmpStats::bump(&stats.silent_drops);
+                return false;
+            }
+        }
+        SnmpStats::bump(&stats.out_get_responses);
+        SnmpStats::bump(&stats.out_pkts);
+        true
+    }
+
+    /// Builds the response to `req`, or a genErr echoing its varbinds when
+    /// `values` cannot be encoded, e.g. an out of range OID returned by a
+    /// handler. Returns whether the varbinds fit and the error-status sent.
+    fn build_response(
+        &self,
+        req: &SnmpPdu,
+        error_status: u32,
+        error_index: u32,
+        values: &[(Vec<u32>, OwnedValue)],
+        response: &mut pdu::Buf,
+    ) -> (bool, u32) {
+        let built = pdu::build_owned_response(
+            req.community,
+            req.req_id,
+            error_status,
+            error_index,
+            values,
+            self.max_message_size,
+            response,
+        );
+        if let Ok(fits) = built {
+            return (fits, error_status);
+        }
+
+        let varbinds = owned_varbinds(req.varbinds.clone()).unwrap_or_default();
+        let failed = values
+            .iter()
+            .position(|(name, value)| !is_encodable_varbind(name, value));
+        let error_index = match failed {
+            Some(i) if i < varbinds.len() => i as u32 + 1,
+            _ => 0,
+        };
+        let fits = pdu::build_owned_response(
+            req.community,
+            req.req_id,
+            snmp::ERRSTATUS_GENERR,
+            error_index,
+            &varbinds,
+            self.max_message_size,
+            response,
+        );
+        (fits.unwrap_or(false), snmp::ERRSTATUS_GENERR)
+    }
+
+    /// GET semantics of RFC 3416 section 4.2.1.
+    pub fn get(&mut self, oid: &[u32]) -> OwnedValue {
+        self.registry.get(oid)
+    }
+
+    /// GETNEXT semantics of RFC 3416 section 4.2.2: the next instance of
+    /// any handler, or `endOfMibView` bound to `oid`.
+    pub fn get_next(&mut self, oid: &[u32]) -> (Vec<u32>, OwnedValue) {
+        self.get_next_in_view(oid, None)
+    }
+
+    /// Like `get_next`, skipping instances that are not in `view`.
+    fn get_next_in_view(&mut self, oid: &[u32], view: Option<&View>) -> (Vec<u32>, OwnedValue) {
+        let mut cursor = oid.to_vec();
+        while let Some((next, value)) = self.registry.get_next(&cursor) {
+            if view.is_none_or(|view| view.contains(&next)) {
+                return (next, value);
+            }
+            cursor = next;
+        }
+        (oid.to_vec(), OwnedValue::EndOfMibView)
+    }
+
+    /// GETBULK semantics of RFC 3416 section 4.2.3. Repetitions are cut
+    /// short when the response would exceed `max_len`.
+    pub fn get_bulk(
+        &mut self,
+        names: &[Vec<u32>],
+        non_repeaters: u32,
+        max_repetitions: u32,
+        max_len: usize,
+    ) -> Vec<(Vec<u32>, OwnedValue)> {
+        self.get_bulk_in_view(names, non_repeaters, max_repetitions, max_len, None)
+    }
+
+    fn get_bulk_in_view(
+        &mut self,
+        names: &[Vec<u32>],
+        non_repeaters: u32,
+        max_repetitions: u32,
+        max_len: usize,
+        view: Option<&View>,
+    ) -> Vec<(Vec<u32>, OwnedValue)> {
+        let non_repeaters = (non_repeaters as usize).min(names.len());
+        let (non_repeaters, repeaters) = names.split_at(non_repeaters);
+
+        let mut out: Vec<_> = non_repeaters
+            .iter()
+            .map(|n| self.get_next_in_view(n, view))
+            .collect();
+        let mut len: usize = out.iter().map(|(n, v)| pdu::varbind_len(n, v)).sum();
+
+        let mut cursor = repeaters.to_vec();
+        for _ in 0..max_repetitions {
+            if cursor.is_empty() {
+                break;
+            }
+
+            let mut all_done = true;
+            for name in cursor.iter_mut() {
+                let (next, value) = self.get_next_in_view(name, view);
+                len += pdu::varbind_len(&next, &value);
+                if len > max_len {
+                    return out;
+                }
+                if value != OwnedValue::EndOfMibView {
+                    all_done = false;
+                }
+                *name = next.clone();
+                out.push((next, value));
+            }
+
+            if all_done {
+                break;
+            }
+        }
+        out
+    }
+}
+
+fn is_request(message_type: &SnmpMessageType) -> bool {
+    matches!(
+        message_type,
+        SnmpMessageType::GetRequest
+            | SnmpMessageType::GetNextRequest
+            | SnmpMessageType::GetBulkRequest
+            | SnmpMessageType::SetRequest
+    )
+}
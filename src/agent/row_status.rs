@@ -0,0 +1,303 @@
+//! Tables whose rows are created and deleted by managers through a RowStatus
+//! column (SNMPv2-TC, RFC 2579).
+
+use std::collections::BTreeMap;
+
+use super::handler::{same_type, MibHandler, TableHandler};
+use crate::snmp;
+use crate::OwnedValue;
+
+/// Values of the SNMPv2-TC RowStatus textual convention.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RowStatus {
+    Active = 1,
+    NotInService = 2,
+    NotReady = 3,
+    CreateAndGo = 4,
+    CreateAndWait = 5,
+    Destroy = 6,
+}
+
+impl RowStatus {
+    pub fn from_value(value: &OwnedValue) -> Option<RowStatus> {
+        use RowStatus::*;
+        match *value {
+            OwnedValue::Integer(1) => Some(Active),
+            OwnedValue::Integer(2) => Some(NotInService),
+            OwnedValue::Integer(3) => Some(NotReady),
+            OwnedValue::Integer(4) => Some(CreateAndGo),
+            OwnedValue::Integer(5) => Some(CreateAndWait),
+            OwnedValue::Integer(6) => Some(Destroy),
+            _ => None,
+        }
+    }
+
+    pub fn to_value(self) -> OwnedValue {
+        OwnedValue::Integer(self as i64)
+    }
+}
+
+/// A writable column of a `RowStatusTable`.
+#[derive(Clone, Debug)]
+pub struct Column {
+    pub id: u32,
+    /// Value of the column in newly created rows. Columns without a default
+    /// must be set before the row can become active.
+    pub default: Option<OwnedValue>,
+    /// Whether the column may be changed while the row is active.
+    pub writable_when_active: bool,
+}
+
+impl Column {
+    pub fn new(id: u32, default: Option<OwnedValue>) -> Self {
+        Column {
+            id,
+            default,
+            writable_when_active: true,
+        }
+    }
+}
+
+/// The changes a SET request makes to one row.
+#[derive(Default)]
+struct PendingRow {
+    status: Option<RowStatus>,
+    values: BTreeMap<u32, OwnedValue>,
+}
+
+/// A conceptual table following the RowStatus state machine.
+///
+/// Rows are created with createAndGo or createAndWait, taken out of service
+/// with notInService and deleted with destroy. A row is ready once every
+/// column without a default has a value; only ready rows can become active.
+/// The values of a row are checked together with the other varbinds of the
+/// same request, so a row can be created and filled by a single SET.
+pub struct RowStatusTable {
+    table: TableHandler,
+    status_column: u32,
+    columns: Vec<Column>,
+    pending: BTreeMap<Vec<u32>, PendingRow>,
+    undo: BTreeMap<Vec<u32>, Option<BTreeMap<u32, OwnedValue>>>,
+}
+
+impl RowStatusTable {
+    /// Creates an empty table. `columns` are the writable columns besides
+    /// the RowStatus column `status_column`.
+    pub fn new(entry: &[u32], status_column: u32, columns: Vec<Column>) -> Self {
+        let mut ids: Vec<u32> = columns.iter().map(|c| c.id).collect();
+        ids.push(status_column);
+
+        RowStatusTable {
+            table: TableHandler::new(entry, &ids),
+            status_column,
+            columns,
+            pending: BTreeMap::new(),
+            undo: BTreeMap::new(),
+        }
+    }
+
+    /// Adds or replaces a row, e.g. one restored from configuration. The
+    /// status is stored as given.
+    pub fn insert_row(&mut self, index: &[u32], status: RowStatus, values: Vec<(u32, OwnedValue)>) {
+        let mut row: BTreeMap<u32, OwnedValue> = values.into_iter().collect();
+        row.insert(self.status_column, status.to_value());
+        self.table.insert_row(index, row.into_iter().collect());
+    }
+
+    pub fn remove_row(&mut self, index: &[u32]) -> Option<BTreeMap<u32, OwnedValue>> {
+        self.table.remove_row(index)
+    }
+
+    pub fn row(&self, index: &[u32]) -> Option<&BTreeMap<u32, OwnedValue>> {
+        self.table.row(index)
+    }
+
+    pub fn status(&self, index: &[u32]) -> Option<RowStatus> {
+        RowStatus::from_value(self.table.row(index)?.get(&self.status_column)?)
+    }
+
+    /// Rows with status active.
+    pub fn active_rows(&self) -> impl Iterator<Item = (&Vec<u32>, &BTreeMap<u32, OwnedValue>)> {
+        let status = RowStatus::Active.to_value();
+        self.table
+            .rows()
+            .filter(move |(_, row)| row.get(&self.status_column) == Some(&status))
+    }
+
+    fn column(&self, id: u32) -> Option<&Column> {
+        self.columns.iter().find(|c| c.id == id)
+    }
+
+    /// Whether every column without a default has a value.
+    fn is_ready(&self, row: &BTreeMap<u32, OwnedValue>) -> bool {
+        self.columns.iter().all(|c| row.contains_key(&c.id))
+    }
+
+    /// The row as it would look after applying `pending`, or `None` if it
+    /// would not exist.
+    fn apply(
+        &self,
+        index: &[u32],
+        pending: &PendingRow,
+    ) -> Result<Option<BTreeMap<u32, OwnedValue>>, u32> {
+        use RowStatus::*;
+
+        let existing = self.table.row(index);
+        let mut row = match (existing, pending.status) {
+            (_, Some(Destroy)) => return Ok(None),
+            (Some(_), Some(CreateAndGo | CreateAndWait)) => {
+                return Err(snmp::ERRSTATUS_INCONSISTENTVALUE)
+            }
+            (Some(row), _) => row.clone(),
+            (None, Some(CreateAndGo | CreateAndWait)) => self
+                .columns
+                .iter()
+                .filter_map(|c| Some((c.id, c.default.clone()?)))
+                .collect(),
+            (None, Some(_)) => return Err(snmp::ERRSTATUS_INCONSISTENTVALUE),
+            (None, None) => return Err(snmp::ERRSTATUS_NOCREATION),
+        };
+
+        let current = existing.and_then(|row| RowStatus::from_value(row.get(&self.status_column)?));
+        if current == Some(Active) && pending.status != Some(NotInService) {
+            let locked = pending
+                .values
+                .keys()
+                .any(|&id| self.column(id).is_some_and(|c| !c.writable_when_active));
+            if locked {
+                return Err(snmp::ERRSTATUS_INCONSISTENTVALUE);
+            }
+        }
+
+        row.extend(pending.values.iter().map(|(&id, v)| (id, v.clone())));
+        let ready = self.is_ready(&row);
+
+        let status = match pending.status {
+            Some(CreateAndGo | Active) if !ready => return Err(snmp::ERRSTATUS_INCONSISTENTVALUE),
+            Some(CreateAndGo | Active) => Active,
+            Some(CreateAndWait) if ready => NotInService,
+            Some(CreateAndWait) => NotReady,
+            Some(NotInService) if !ready => return Err(snmp::ERRSTATUS_INCONSISTENTVALUE),
+            Some(status) => status,
+            // a row that is not active becomes ready as its columns are set
+            None => match current {
+                Some(NotReady) if ready => NotInService,
+                Some(status) => status,
+                None => NotReady,
+            },
+        };
+        row.insert(self.status_column, status.to_value());
+        Ok(Some(row))
+    }
+}
+
+impl MibHandler for RowStatusTable {
+    fn get(&mut self, oid: &[u32]) -> OwnedValue {
+        self.table.get(oid)
+    }
+
+    fn get_next(&mut self, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)> {
+        self.table.get_next(oid)
+    }
+
+    fn test_set(&mut self, oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        let Some((column, index)) = self.table.split_instance(oid) else {
+            return Err(snmp::ERRSTATUS_NOTWRITABLE);
+        };
+        if index.is_empty() {
+            return Err(snmp::ERRSTATUS_NOCREATION);
+        }
+
+        if column == self.status_column {
+            if !matches!(value, OwnedValue::Integer(_)) {
+                return Err(snmp::ERRSTATUS_WRONGTYPE);
+            }
+            let status = match RowStatus::from_value(value) {
+                Some(RowStatus::NotReady) | None => return Err(snmp::ERRSTATUS_WRONGVALUE),
+                Some(status) => status,
+            };
+            let pending = self.pending.entry(index.to_vec()).or_default();
+            if pending.status.is_some_and(|s| s != status) {
+                return Err(snmp::ERRSTATUS_INCONSISTENTVALUE);
+            }
+            pending.status = Some(status);
+            return Ok(());
+        }
+
+        let Some(spec) = self.column(column) else {
+            return Err(snmp::ERRSTATUS_NOTWRITABLE);
+        };
+        let current = self.table.row(index).and_then(|row| row.get(&column));
+        if let Some(template) = current.or(spec.default.as_ref()) {
+            if !same_type(template, value) {
+                return Err(snmp::ERRSTATUS_WRONGTYPE);
+            }
+        }
+
+        self.pending
+            .entry(index.to_vec())
+            .or_default()
+            .values
+            .insert(column, value.clone());
+        Ok(())
+    }
+
+    fn check_consistency(&mut self, oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        let (_, index) = self
+            .table
+            .split_instance(oid)
+            .ok_or(snmp::ERRSTATUS_GENERR)?;
+        let pending = self.pending.get(index).ok_or(snmp::ERRSTATUS_GENERR)?;
+        self.apply(index, pending).map(|_| ())
+    }
+
+    fn commit_set(&mut self, oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        let (_, index) = self
+            .table
+            .split_instance(oid)
+            .ok_or(snmp::ERRSTATUS_COMMITFAILED)?;
+        // the whole row is committed with its first varbind
+        if self.undo.contains_key(index) {
+            return Ok(());
+        }
+
+        let pending = self
+            .pending
+            .get(index)
+            .ok_or(snmp::ERRSTATUS_COMMITFAILED)?;
+        let row = self
+            .apply(index, pending)
+            .map_err(|_| snmp::ERRSTATUS_COMMITFAILED)?;
+        let previous = self.table.row(index).cloned();
+        match row {
+            Some(row) => self.table.insert_row(index, row.into_iter().collect()),
+            None => {
+                self.table.remove_row(index);
+            }
+        }
+        self.undo.insert(index.to_vec(), previous);
+        Ok(())
+    }
+
+    fn undo_set(&mut self, oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        let (_, index) = self
+            .table
+            .split_instance(oid)
+            .ok_or(snmp::ERRSTATUS_UNDOFAILED)?;
+        // the first varbind of the row undoes it, the others find it gone
+        if let Some(previous) = self.undo.remove(index) {
+            match previous {
+                Some(row) => self.table.insert_row(index, row.into_iter().collect()),
+                None => {
+                    self.table.remove_row(index);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn cleanup_set(&mut self, _oid: &[u32]) {
+        self.pending.clear();
+        self.undo.clear();
+    }
+}
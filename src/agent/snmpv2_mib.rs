@@ -0,0 +1,369 @@
+//! The `system` and `snmp` groups of SNMPv2-MIB (RFC 3418), served by every
+//! `Agent`.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use super::handler::MibHandler;
+use crate::snmp;
+use crate::OwnedValue;
+
+/// SNMPv2-MIB::system
+pub const SYSTEM: &[u32] = &[1, 3, 6, 1, 2, 1, 1];
+/// SNMPv2-MIB::snmp
+pub const SNMP: &[u32] = &[1, 3, 6, 1, 2, 1, 11];
+
+const SYS_OR_ENTRY: u32 = 9;
+
+/// A capability listed in sysORTable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SysOrEntry {
+    /// sysORID, usually the OID of a MIB module compliance statement.
+    pub id: Vec<u32>,
+    pub descr: String,
+    /// sysUpTime when the entry was added.
+    pub uptime: u32,
+}
+
+/// Contents of the `system` group.
+#[derive(Clone, Debug)]
+pub struct SystemInfo {
+    pub descr: String,
+    pub object_id: Vec<u32>,
+    pub contact: String,
+    pub name: String,
+    pub location: String,
+    /// sysServices, a bit per OSI layer the device offers services at.
+    pub services: u32,
+    started: Instant,
+    or_last_change: u32,
+    or_table: Vec<SysOrEntry>,
+}
+
+impl Default for SystemInfo {
+    fn default() -> Self {
+        SystemInfo {
+            descr: format!("rust-snmp {}", env!("CARGO_PKG_VERSION")),
+            object_id: vec![0, 0],
+            contact: String::new(),
+            name: String::new(),
+            location: String::new(),
+            // applications and end-to-end
+            services: 72,
+            started: Instant::now(),
+            or_last_change: 0,
+            or_table: Vec::new(),
+        }
+    }
+}
+
+impl SystemInfo {
+    /// sysUpTime, in hundredths of a second since the agent was created.
+    pub fn uptime(&self) -> u32 {
+        (self.started.elapsed().as_millis() / 10) as u32
+    }
+
+    /// Adds a sysORTable entry and returns its sysORIndex.
+    pub fn add_or_entry(&mut self, id: &[u32], descr: &str) -> u32 {
+        let uptime = self.uptime();
+        self.or_table.push(SysOrEntry {
+            id: id.to_vec(),
+            descr: descr.to_string(),
+            uptime,
+        });
+        self.or_last_change = uptime;
+        self.or_table.len() as u32
+    }
+
+    pub fn or_table(&self) -> &[SysOrEntry] {
+        &self.or_table
+    }
+
+    fn instances(&self) -> Vec<(Vec<u32>, OwnedValue)> {
+        let scalar = |id: u32, value| (instance(SYSTEM, &[id, 0]), value);
+        let text = |s: &str| OwnedValue::OctetString(s.as_bytes().to_vec());
+
+        let mut out = vec![
+            scalar(1, text(&self.descr)),
+            scalar(2, OwnedValue::ObjectIdentifier(self.object_id.clone())),
+            scalar(3, OwnedValue::Timeticks(self.uptime())),
+            scalar(4, text(&self.contact)),
+            scalar(5, text(&self.name)),
+            scalar(6, text(&self.location)),
+            scalar(7, OwnedValue::Integer(self.services as i64)),
+            scalar(8, OwnedValue::Timeticks(self.or_last_change)),
+        ];
+
+        // sysORID, sysORDescr and sysORUpTime, column by column
+        for column in 2..=4 {
+            for (i, entry) in self.or_table.iter().enumerate() {
+                let value = match column {
+                    2 => OwnedValue::ObjectIdentifier(entry.id.clone()),
+                    3 => text(&entry.descr),
+                    _ => OwnedValue::Timeticks(entry.uptime),
+                };
+                let index = i as u32 + 1;
+                out.push((instance(SYSTEM, &[SYS_OR_ENTRY, 1, column, index]), value));
+            }
+        }
+        out
+    }
+}
+
+fn instance(group: &[u32], suffix: &[u32]) -> Vec<u32> {
+    let mut oid = group.to_vec();
+    oid.extend_from_slice(suffix);
+    oid
+}
+
+fn get(instances: &[(Vec<u32>, OwnedValue)], oid: &[u32]) -> OwnedValue {
+    if let Some((_, value)) = instances.iter().find(|(name, _)| &name[..] == oid) {
+        return value.clone();
+    }
+    // the object exists if the OID extends one of the object identifiers
+    if instances
+        .iter()
+        .any(|(name, _)| oid.starts_with(&name[..name.len() - 1]))
+    {
+        OwnedValue::NoSuchInstance
+    } else {
+        OwnedValue::NoSuchObject
+    }
+}
+
+fn get_next(instances: Vec<(Vec<u32>, OwnedValue)>, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)> {
+    instances
+        .into_iter()
+        .filter(|(name, _)| &name[..] > oid)
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+}
+
+/// Serves the `system` group from a shared `SystemInfo`. sysContact, sysName
+/// and sysLocation are writable.
+pub struct SystemGroup {
+    info: Arc<Mutex<SystemInfo>>,
+    undo: Vec<(u32, String)>,
+}
+
+impl SystemGroup {
+    pub fn new(info: Arc<Mutex<SystemInfo>>) -> Self {
+        SystemGroup {
+            info,
+            undo: Vec::new(),
+        }
+    }
+
+    fn field<'i>(info: &'i mut SystemInfo, oid: &[u32]) -> Option<(u32, &'i mut String)> {
+        match oid.strip_prefix(SYSTEM)? {
+            [4, 0] => Some((4, &mut info.contact)),
+            [5, 0] => Some((5, &mut info.name)),
+            [6, 0] => Some((6, &mut info.location)),
+            _ => None,
+        }
+    }
+}
+
+impl MibHandler for SystemGroup {
+    fn get(&mut self, oid: &[u32]) -> OwnedValue {
+        let instances = self.info.lock().unwrap().instances();
+        match get(&instances, oid) {
+            // the sysORTable columns exist even while the table is empty
+            OwnedValue::NoSuchObject
+                if matches!(
+                    oid.strip_prefix(SYSTEM),
+                    Some([SYS_OR_ENTRY, 1, 2..=4, _, ..])
+                ) =>
+            {
+                OwnedValue::NoSuchInstance
+            }
+            value => value,
+        }
+    }
+
+    fn get_next(&mut self, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)> {
+        get_next(self.info.lock().unwrap().instances(), oid)
+    }
+
+    fn test_set(&mut self, oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        let mut info = self.info.lock().unwrap();
+        if SystemGroup::field(&mut info, oid).is_none() {
+            return match get(&info.instances(), oid) {
+                OwnedValue::NoSuchObject | OwnedValue::NoSuchInstance => {
+                    Err(snmp::ERRSTATUS_NOCREATION)
+                }
+                _ => Err(snmp::ERRSTATUS_NOTWRITABLE),
+            };
+        }
+        match *value {
+            // DisplayString (SIZE (0..255))
+            OwnedValue::OctetString(ref s) if s.len() > 255 => Err(snmp::ERRSTATUS_WRONGLENGTH),
+            OwnedValue::OctetString(ref s) if std::str::from_utf8(s).is_err() => {
+                Err(snmp::ERRSTATUS_WRONGVALUE)
+            }
+            OwnedValue::OctetString(_) => Ok(()),
+            _ => Err(snmp::ERRSTATUS_WRONGTYPE),
+        }
+    }
+
+    fn commit_set(&mut self, oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        let mut info = self.info.lock().unwrap();
+        let (id, field) = SystemGroup::field(&mut info, oid).ok_or(snmp::ERRSTATUS_COMMITFAILED)?;
+        let OwnedValue::OctetString(ref s) = *value else {
+            return Err(snmp::ERRSTATUS_COMMITFAILED);
+        };
+        let previous = std::mem::replace(field, String::from_utf8_lossy(s).into_owned());
+        self.undo.push((id, previous));
+        Ok(())
+    }
+
+    fn undo_set(&mut self, oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        let mut info = self.info.lock().unwrap();
+        let (id, field) = SystemGroup::field(&mut info, oid).ok_or(snmp::ERRSTATUS_UNDOFAILED)?;
+        let pos = self
+            .undo
+            .iter()
+            .rposition(|&(undo_id, _)| undo_id == id)
+            .ok_or(snmp::ERRSTATUS_UNDOFAILED)?;
+        *field = self.undo.remove(pos).1;
+        Ok(())
+    }
+
+    fn cleanup_set(&mut self, _oid: &[u32]) {
+        self.undo.clear();
+    }
+}
+
+/// Counters of the `snmp` group, updated by `Agent::process`.
+#[derive(Debug, Default)]
+pub struct SnmpStats {
+    pub in_pkts: AtomicU32,
+    pub out_pkts: AtomicU32,
+    pub in_bad_versions: AtomicU32,
+    pub in_bad_community_names: AtomicU32,
+    pub in_bad_community_uses: AtomicU32,
+    pub in_asn_parse_errs: AtomicU32,
+    pub in_total_req_vars: AtomicU32,
+    pub in_total_set_vars: AtomicU32,
+    pub in_get_requests: AtomicU32,
+    pub in_get_nexts: AtomicU32,
+    pub in_set_requests: AtomicU32,
+    pub in_get_responses: AtomicU32,
+    pub in_traps: AtomicU32,
+    pub out_too_bigs: AtomicU32,
+    pub out_gen_errs: AtomicU32,
+    pub out_get_responses: AtomicU32,
+    pub silent_drops: AtomicU32,
+    pub proxy_drops: AtomicU32,
+}
+
+impl SnmpStats {
+    pub(crate) fn bump(counter: &AtomicU32) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn instances(&self) -> Vec<(Vec<u32>, OwnedValue)> {
+        let counters = [
+            (1, &self.in_pkts),
+            (2, &self.out_pkts),
+            (3, &self.in_bad_versions),
+            (4, &self.in_bad_community_names),
+            (5, &self.in_bad_community_uses),
+            (6, &self.in_asn_parse_errs),
+            (13, &self.in_total_req_vars),
+            (14, &self.in_total_set_vars),
+            (15, &self.in_get_requests),
+            (16, &self.in_get_nexts),
+            (17, &self.in_set_requests),
+            (18, &self.in_get_responses),
+            (19, &self.in_traps),
+            (20, &self.out_too_bigs),
+            (24, &self.out_gen_errs),
+            (28, &self.out_get_responses),
+            (31, &self.silent_drops),
+            (32, &self.proxy_drops),
+        ];
+        counters
+            .iter()
+            .map(|&(id, counter)| {
+                let value = OwnedValue::Counter32(counter.load(Ordering::Relaxed));
+                (instance(SNMP, &[id, 0]), value)
+            })
+            .collect()
+    }
+}
+
+/// Serves the `snmp` group from a shared `SnmpStats`.
+pub struct SnmpGroup {
+    stats: Arc<SnmpStats>,
+    /// snmpEnableAuthenTraps; authenticationFailure traps are not sent, so
+    /// the value is only stored.
+    enable_authen_traps: i64,
+    undo: Option<i64>,
+}
+
+const ENABLE_AUTHEN_TRAPS: u32 = 30;
+
+impl SnmpGroup {
+    pub fn new(stats: Arc<SnmpStats>) -> Self {
+        SnmpGroup {
+            stats,
+            // disabled(2)
+            enable_authen_traps: 2,
+            undo: None,
+        }
+    }
+
+    fn instances(&self) -> Vec<(Vec<u32>, OwnedValue)> {
+        let mut out = self.stats.instances();
+        out.push((
+            instance(SNMP, &[ENABLE_AUTHEN_TRAPS, 0]),
+            OwnedValue::Integer(self.enable_authen_traps),
+        ));
+        out.sort_by(|(a, _), (b, _)| a.cmp(b));
+        out
+    }
+}
+
+impl MibHandler for SnmpGroup {
+    fn get(&mut self, oid: &[u32]) -> OwnedValue {
+        get(&self.instances(), oid)
+    }
+
+    fn get_next(&mut self, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)> {
+        get_next(self.instances(), oid)
+    }
+
+    fn test_set(&mut self, oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        if oid != &instance(SNMP, &[ENABLE_AUTHEN_TRAPS, 0])[..] {
+            return match self.get(oid) {
+                OwnedValue::NoSuchObject | OwnedValue::NoSuchInstance => {
+                    Err(snmp::ERRSTATUS_NOCREATION)
+                }
+                _ => Err(snmp::ERRSTATUS_NOTWRITABLE),
+            };
+        }
+        match *value {
+            OwnedValue::Integer(1 | 2) => Ok(()),
+            OwnedValue::Integer(_) => Err(snmp::ERRSTATUS_WRONGVALUE),
+            _ => Err(snmp::ERRSTATUS_WRONGTYPE),
+        }
+    }
+
+    fn commit_set(&mut self, _oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        let OwnedValue::Integer(enable) = *value else {
+            return Err(snmp::ERRSTATUS_COMMITFAILED);
+        };
+        self.undo = Some(std::mem::replace(&mut self.enable_authen_traps, enable));
+        Ok(())
+    }
+
+    fn undo_set(&mut self, _oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        self.enable_authen_traps = self.undo.take().ok_or(snmp::ERRSTATUS_UNDOFAILED)?;
+        Ok(())
+    }
+
+    fn cleanup_set(&mut self, _oid: &[u32]) {
+        self.undo = None;
+    }
+}
@@ -0,0 +1,114 @@
+//! User-based Security Model (RFC 3414) at the noAuthNoPriv level.
+//!
+//! The crate has no cryptography, so users can neither authenticate nor
+//! encrypt; their requests are refused with an unsupportedSecurityLevel
+//! report. Managers discover the engine ID, boots and time with an empty
+//! engine ID as in RFC 3414 section 4.
+
+use std::collections::HashSet;
+use std::time::Instant;
+
+use crate::snmp;
+use crate::V3Header;
+
+/// usmStats (SNMP-USER-BASED-SM-MIB), the counters reports carry.
+pub const USM_STATS_UNSUPPORTED_SEC_LEVELS: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 1, 1, 0];
+pub const USM_STATS_UNKNOWN_USER_NAMES: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 1, 3, 0];
+pub const USM_STATS_UNKNOWN_ENGINE_IDS: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 1, 4, 0];
+
+/// Counters of SNMP-MPD-MIB and SNMP-USER-BASED-SM-MIB.
+#[derive(Debug, Default)]
+pub struct UsmStats {
+    pub unknown_security_models: u32,
+    pub unsupported_sec_levels: u32,
+    pub unknown_user_names: u32,
+    pub unknown_engine_ids: u32,
+}
+
+/// The local SNMP engine and its users.
+pub struct Usm {
+    engine_id: Vec<u8>,
+    engine_boots: i32,
+    started: Instant,
+    users: HashSet<Vec<u8>>,
+    stats: UsmStats,
+}
+
+impl Usm {
+    /// An engine that has been restarted `engine_boots` times, which the
+    /// caller keeps in non-volatile storage.
+    pub fn new(engine_id: &[u8], engine_boots: i32) -> Self {
+        Usm {
+            engine_id: engine_id.to_vec(),
+            engine_boots,
+            started: Instant::now(),
+            users: HashSet::new(),
+            stats: UsmStats::default(),
+        }
+    }
+
+    /// Adds a user without authentication or privacy keys.
+    pub fn add_user(&mut self, name: &[u8]) {
+        self.users.insert(name.to_vec());
+    }
+
+    pub fn engine_id(&self) -> &[u8] {
+        &self.engine_id
+    }
+
+    /// Seconds since the engine started, snmpEngineTime.
+    pub fn engine_time(&self) -> i32 {
+        i32::try_from(self.started.elapsed().as_secs()).unwrap_or(i32::MAX)
+    }
+
+    pub fn stats(&self) -> &UsmStats {
+        &self.stats
+    }
+
+    pub(crate) fn stats_mut(&mut self) -> &mut UsmStats {
+        &mut self.stats
+    }
+
+    /// Checks an incoming message as in RFC 3414 section 3.2. On failure,
+    /// returns the usmStats counter to report and its new value.
+    pub(crate) fn check(&mut self, header: &V3Header) -> Result<(), (&'static [u32], u32)> {
+        let (oid, counter) = if header.engine_id != &self.engine_id[..] {
+            (
+                USM_STATS_UNKNOWN_ENGINE_IDS,
+                &mut self.stats.unknown_engine_ids,
+            )
+        } else if !self.users.contains(header.user_name) {
+            (
+                USM_STATS_UNKNOWN_USER_NAMES,
+                &mut self.stats.unknown_user_names,
+            )
+        } else if header.flags & snmp::MSG_FLAG_AUTH != 0 {
+            (
+                USM_STATS_UNSUPPORTED_SEC_LEVELS,
+                &mut self.stats.unsupported_sec_levels,
+            )
+        } else {
+            return Ok(());
+        };
+        *counter += 1;
+        Err((oid, *counter))
+    }
+
+    /// The header of a response or report to a message of `user_name`.
+    pub(crate) fn header<'a>(
+        &'a self,
+        msg_id: i32,
+        max_size: i32,
+        user_name: &'a [u8],
+    ) -> V3Header<'a> {
+        V3Header {
+            msg_id,
+            max_size,
+            flags: 0,
+            engine_id: &self.engine_id,
+            engine_boots: self.engine_boots,
+            engine_time: self.engine_time(),
+            user_name,
+        }
+    }
+}
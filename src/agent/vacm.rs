@@ -0,0 +1,418 @@
+//! View-based Access Control Model (RFC 3415).
+//!
+//! Communities are mapped to security names as in the SNMP-COMMUNITY-MIB
+//! (RFC 3584), security names to groups, and groups to the read, write and
+//! notify views of their access entries.
+//!
+//! Rules can be added in code or loaded from a configuration file in the
+//! format of net-snmp's `snmpd.conf`:
+//!
+//! ```text
+//! # com2sec SECNAME SOURCE COMMUNITY
+//! com2sec   tenantA default  secretA
+//! # group   GROUP   MODEL    SECNAME
+//! group     readers v2c      tenantA
+//! # view    NAME    TYPE     SUBTREE           [MASK]
+//! view      system  included .1.3.6.1.2.1.1
+//! view      ifRow1  included .1.3.6.1.2.1.2.2.1.0.1 ff:a0
+//! # access  GROUP   CONTEXT  MODEL LEVEL  PREFX READ   WRITE NOTIFY
+//! access    readers ""       any   noauth exact system none  none
+//! ```
+//!
+//! Only the `default` source is supported by `com2sec`.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SecurityModel {
+    /// Matches every security model in access entries.
+    Any = 0,
+    V1 = 1,
+    V2c = 2,
+    Usm = 3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SecurityLevel {
+    NoAuthNoPriv = 1,
+    AuthNoPriv = 2,
+    AuthPriv = 3,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViewType {
+    Read,
+    Write,
+    Notify,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextMatch {
+    Exact,
+    Prefix,
+}
+
+/// Reasons `isAccessAllowed` denies access.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VacmError {
+    NoGroupName,
+    NoAccessEntry,
+    NoSuchView,
+    NotInView,
+}
+
+/// An entry of vacmAccessTable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Access {
+    pub group: String,
+    pub context_prefix: String,
+    pub context_match: ContextMatch,
+    pub security_model: SecurityModel,
+    /// Minimum security level of requests the entry applies to.
+    pub security_level: SecurityLevel,
+    /// Empty view names deny the respective kind of access.
+    pub read_view: String,
+    pub write_view: String,
+    pub notify_view: String,
+}
+
+impl Access {
+    /// An entry for the default context and any security model that grants
+    /// no access yet.
+    pub fn new(group: &str) -> Self {
+        Access {
+            group: group.to_string(),
+            context_prefix: String::new(),
+            context_match: ContextMatch::Exact,
+            security_model: SecurityModel::Any,
+            security_level: SecurityLevel::NoAuthNoPriv,
+            read_view: String::new(),
+            write_view: String::new(),
+            notify_view: String::new(),
+        }
+    }
+
+    fn view(&self, view_type: ViewType) -> &str {
+        match view_type {
+            ViewType::Read => &self.read_view,
+            ViewType::Write => &self.write_view,
+            ViewType::Notify => &self.notify_view,
+        }
+    }
+
+    fn matches_context(&self, context: &str) -> bool {
+        match self.context_match {
+            ContextMatch::Exact => context == self.context_prefix,
+            ContextMatch::Prefix => context.starts_with(&self.context_prefix),
+        }
+    }
+}
+
+/// An entry of vacmViewTreeFamilyTable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewFamily {
+    pub subtree: Vec<u32>,
+    /// One bit per sub-identifier of `subtree`, most significant bit first.
+    /// A cleared bit makes the sub-identifier a wildcard; missing bits are
+    /// set.
+    pub mask: Vec<u8>,
+    pub included: bool,
+}
+
+impl ViewFamily {
+    fn contains(&self, oid: &[u32]) -> bool {
+        if oid.len() < self.subtree.len() {
+            return false;
+        }
+        self.subtree.iter().zip(oid).enumerate().all(|(i, (a, b))| {
+            let wildcard = self
+                .mask
+                .get(i / 8)
+                .is_some_and(|byte| byte & (0x80 >> (i % 8)) == 0);
+            wildcard || a == b
+        })
+    }
+}
+
+/// The families of a single MIB view.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct View {
+    families: Vec<ViewFamily>,
+}
+
+impl View {
+    /// Whether `oid` is in the view. Of all families containing `oid`, the
+    /// one with the longest subtree, then the lexicographically greatest,
+    /// decides.
+    pub fn contains(&self, oid: &[u32]) -> bool {
+        self.families
+            .iter()
+            .filter(|f| f.contains(oid))
+            .max_by(|a, b| {
+                a.subtree
+                    .len()
+                    .cmp(&b.subtree.len())
+                    .then_with(|| a.subtree.cmp(&b.subtree))
+            })
+            .is_some_and(|f| f.included)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Vacm {
+    communities: Vec<(Vec<u8>, String)>,
+    groups: Vec<(SecurityModel, String, String)>,
+    access: Vec<Access>,
+    views: Vec<(String, ViewFamily)>,
+}
+
+impl Vacm {
+    pub fn new() -> Self {
+        Vacm::default()
+    }
+
+    /// Accepts requests with `community` as coming from `security_name`.
+    pub fn add_community(&mut self, community: &[u8], security_name: &str) {
+        self.communities
+            .push((community.to_vec(), security_name.to_string()));
+    }
+
+    /// Puts `security_name` of `security_model` into `group`, replacing its
+    /// previous group.
+    pub fn add_group(&mut self, security_model: SecurityModel, security_name: &str, group: &str) {
+        self.groups
+            .retain(|(model, name, _)| !(*model == security_model && name == security_name));
+        self.groups
+            .push((security_model, security_name.to_string(), group.to_string()));
+    }
+
+    pub fn add_access(&mut self, access: Access) {
+        self.access.push(access);
+    }
+
+    /// Adds the subtree `subtree`, filtered by `mask`, to `view`.
+    pub fn add_view(&mut self, view: &str, subtree: &[u32], mask: &[u8], included: bool) {
+        self.views.push((
+            view.to_string(),
+            ViewFamily {
+                subtree: subtree.to_vec(),
+                mask: mask.to_vec(),
+                included,
+            },
+        ));
+    }
+
+    /// The security name requests with `community` are processed as.
+    pub fn security_name(&self, community: &[u8]) -> Option<&str> {
+        self.communities
+            .iter()
+            .find(|(c, _)| c == community)
+            .map(|(_, name)| &name[..])
+    }
+
+    /// Resolves the view of `view_type` a principal may access in
+    /// `context`, steps 1 to 4 of `isAccessAllowed`.
+    pub fn view(
+        &self,
+        security_model: SecurityModel,
+        security_name: &str,
+        security_level: SecurityLevel,
+        view_type: ViewType,
+        context: &str,
+    ) -> Result<View, VacmError> {
+        let group = self
+            .groups
+            .iter()
+            .find(|(model, name, _)| *model == security_model && name == security_name)
+            .map(|(_, _, group)| group)
+            .ok_or(VacmError::NoGroupName)?;
+
+        // RFC 3415 section 4, vacmAccessTable: prefer an exact security
+        // model, then an exact context, then a longer context prefix, then a
+        // higher security level
+        let access = self
+            .access
+            .iter()
+            .filter(|a| {
+                a.group == *group
+                    && a.matches_context(context)
+                    && (a.security_model == security_model
+                        || a.security_model == SecurityModel::Any)
+                    && a.security_level <= security_level
+            })
+            .max_by_key(|a| {
+                (
+                    a.security_model != SecurityModel::Any,
+                    a.context_match == ContextMatch::Exact,
+                    a.context_prefix.len(),
+                    a.security_level,
+                )
+            })
+            .ok_or(VacmError::NoAccessEntry)?;
+
+        let name = access.view(view_type);
+        if name.is_empty() {
+            return Err(VacmError::NoSuchView);
+        }
+        let families: Vec<ViewFamily> = self
+            .views
+            .iter()
+            .filter(|(view, _)| view == name)
+            .map(|(_, family)| family.clone())
+            .collect();
+        if families.is_empty() {
+            return Err(VacmError::NoSuchView);
+        }
+        Ok(View { families })
+    }
+
+    /// `isAccessAllowed` of RFC 3415 section 3.2.
+    pub fn is_access_allowed(
+        &self,
+        security_model: SecurityModel,
+        security_name: &str,
+        security_level: SecurityLevel,
+        view_type: ViewType,
+        context: &str,
+        oid: &[u32],
+    ) -> Result<(), VacmError> {
+        let view = self.view(
+            security_model,
+            security_name,
+            security_level,
+            view_type,
+            context,
+        )?;
+        if view.contains(oid) {
+            Ok(())
+        } else {
+            Err(VacmError::NotInView)
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Vacm::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses `com2sec`, `group`, `view` and `access` lines. Blank lines and
+    /// lines starting with `#` are skipped.
+    pub fn parse(config: &str) -> io::Result<Self> {
+        let mut vacm = Vacm::new();
+
+        for (n, line) in config.lines().enumerate() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let res = match words[..] {
+                [] => Ok(()),
+                [word, ..] if word.starts_with('#') => Ok(()),
+                ["com2sec", name, "default", community] => {
+                    vacm.add_community(community.as_bytes(), name);
+                    Ok(())
+                }
+                ["com2sec", _, _, _] => Err("only the default source is supported"),
+                ["group", group, model, name] => parse_model(model).map(|model| {
+                    vacm.add_group(model, name, group);
+                }),
+                ["view", name, kind, subtree, ref mask @ ..] if mask.len() <= 1 => {
+                    parse_view(kind, subtree, mask.first().copied()).map(
+                        |(included, subtree, mask)| {
+                            vacm.add_view(name, &subtree, &mask, included);
+                        },
+                    )
+                }
+                ["access", group, context, model, level, prefix, read, write, notify] => {
+                    parse_access(group, context, model, level, prefix, [read, write, notify])
+                        .map(|access| vacm.add_access(access))
+                }
+                _ => Err("unknown or malformed directive"),
+            };
+            res.map_err(|msg| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", n + 1, msg),
+                )
+            })?;
+        }
+
+        Ok(vacm)
+    }
+}
+
+fn parse_model(model: &str) -> Result<SecurityModel, &'static str> {
+    match model {
+        "any" => Ok(SecurityModel::Any),
+        "v1" => Ok(SecurityModel::V1),
+        "v2c" => Ok(SecurityModel::V2c),
+        "usm" => Ok(SecurityModel::Usm),
+        _ => Err("invalid security model"),
+    }
+}
+
+fn parse_oid(oid: &str) -> Result<Vec<u32>, &'static str> {
+    oid.trim_start_matches('.')
+        .split('.')
+        .map(|subid| subid.parse().map_err(|_| "invalid OID"))
+        .collect()
+}
+
+fn parse_view(
+    kind: &str,
+    subtree: &str,
+    mask: Option<&str>,
+) -> Result<(bool, Vec<u32>, Vec<u8>), &'static str> {
+    let included = match kind {
+        "included" => true,
+        "excluded" => false,
+        _ => return Err("view type must be included or excluded"),
+    };
+
+    let mask = match mask {
+        Some(mask) => {
+            let hex: String = mask.chars().filter(|&c| c != ':' && c != '.').collect();
+            if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+                return Err("invalid view mask");
+            }
+            (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| "invalid view mask"))
+                .collect::<Result<_, _>>()?
+        }
+        None => Vec::new(),
+    };
+
+    Ok((included, parse_oid(subtree)?, mask))
+}
+
+fn parse_access(
+    group: &str,
+    context: &str,
+    model: &str,
+    level: &str,
+    prefix: &str,
+    views: [&str; 3],
+) -> Result<Access, &'static str> {
+    let mut access = Access::new(group);
+    access.context_prefix = context.trim_matches('"').to_string();
+    access.security_model = parse_model(model)?;
+    access.security_level = match level {
+        "noauth" => SecurityLevel::NoAuthNoPriv,
+        "auth" => SecurityLevel::AuthNoPriv,
+        "priv" => SecurityLevel::AuthPriv,
+        _ => return Err("invalid security level"),
+    };
+    access.context_match = match prefix {
+        "exact" => ContextMatch::Exact,
+        "prefix" => ContextMatch::Prefix,
+        _ => return Err("context match must be exact or prefix"),
+    };
+
+    let [read, write, notify] = views.map(|view| match view {
+        "none" => String::new(),
+        view => view.to_string(),
+    });
+    access.read_view = read;
+    access.write_view = write;
+    access.notify_view = notify;
+    Ok(access)
+}
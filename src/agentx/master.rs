@@ -0,0 +1,609 @@
+//! AgentX master agent (RFC 2741 section 7.2).
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::pdu::{self, Payload, Pdu, SearchRange};
+use crate::agent::MibHandler;
+use crate::{snmp, OwnedValue};
+
+/// Consecutive timeouts after which a session is closed.
+const MAX_TIMEOUTS: u32 = 3;
+/// The most subtrees a single range registration may cover.
+const MAX_RANGE: u32 = 1024;
+
+type Connection = Arc<Mutex<UnixStream>>;
+type Varbinds = Vec<(Vec<u32>, OwnedValue)>;
+
+struct Session {
+    connection: Connection,
+    // from the Open-PDU, in seconds; 0 for the master's default
+    timeout: u8,
+    timeouts: u32,
+}
+
+/// An entry of the registration table. A range registration has one entry
+/// per subtree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Registered {
+    pub subtree: Vec<u32>,
+    pub priority: u8,
+    pub session_id: u32,
+    /// Response timeout in seconds, 0 for the session's.
+    pub timeout: u8,
+}
+
+struct State {
+    sessions: HashMap<u32, Session>,
+    // sorted by subtree, then priority
+    registrations: Vec<Registered>,
+    // response channels by packet id
+    pending: HashMap<u32, Sender<Pdu>>,
+    timeout: Duration,
+    next_session_id: u32,
+    next_packet_id: u32,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    started: Instant,
+}
+
+/// An AgentX master agent accepting subagents on a Unix domain socket.
+///
+/// The subtrees the subagents register are served through the
+/// `MasterHandler` returned by `handler`, which is registered with an
+/// `Agent` like any other handler.
+pub struct Master {
+    shared: Arc<Shared>,
+}
+
+impl Master {
+    /// Listens for subagents on `path`, accepting and serving them on
+    /// background threads.
+    pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<Master> {
+        let listener = UnixListener::bind(path)?;
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                sessions: HashMap::new(),
+                registrations: Vec::new(),
+                pending: HashMap::new(),
+                timeout: Duration::from_secs(5),
+                next_session_id: 0,
+                next_packet_id: 0,
+            }),
+            started: Instant::now(),
+        });
+
+        let accepting = shared.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { continue };
+                let shared = accepting.clone();
+                thread::spawn(move || shared.serve_connection(stream));
+            }
+        });
+
+        Ok(Master { shared })
+    }
+
+    /// Sets how long to wait for a subagent's response when neither its
+    /// registration nor its session specify a timeout. The default is five
+    /// seconds.
+    pub fn set_timeout(&self, timeout: Duration) {
+        self.shared.lock().timeout = timeout;
+    }
+
+    /// The registration table in OID order.
+    pub fn registrations(&self) -> Vec<Registered> {
+        self.shared.lock().registrations.clone()
+    }
+
+    /// A handler forwarding requests to the subagents. Register it for the
+    /// root, `&[]`; handlers registered locally for more specific subtrees
+    /// take precedence over the subagents.
+    pub fn handler(&self) -> MasterHandler {
+        MasterHandler {
+            shared: self.shared.clone(),
+            set: BTreeMap::new(),
+            transaction_id: 0,
+        }
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    fn uptime(&self) -> u32 {
+        (self.started.elapsed().as_millis() / 10) as u32
+    }
+
+    fn serve_connection(&self, stream: UnixStream) {
+        let Ok(writer) = stream.try_clone() else {
+            return;
+        };
+        let connection = Arc::new(Mutex::new(writer));
+        let mut reader = stream;
+        let mut opened = Vec::new();
+
+        while let Ok(request) = super::read_pdu(&mut reader) {
+            if let Some(response) = self.answer(&connection, &mut opened, request) {
+                let mut writer = connection.lock().unwrap();
+                if writer.write_all(&response.encode()).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let mut state = self.lock();
+        for session_id in opened {
+            state.remove_session(session_id);
+        }
+    }
+
+    /// Handles a PDU sent by a subagent on `connection`.
+    fn answer(&self, connection: &Connection, opened: &mut Vec<u32>, request: Pdu) -> Option<Pdu> {
+        let mut state = self.lock();
+        let uptime = self.uptime();
+
+        if let Payload::Response { .. } = request.payload {
+            if let Some(tx) = state.pending.remove(&request.packet_id) {
+                let _ = tx.send(request);
+            }
+            return None;
+        }
+
+        if let Payload::Open { timeout, .. } = request.payload {
+            state.next_session_id += 1;
+            let session_id = state.next_session_id;
+            state.sessions.insert(
+                session_id,
+                Session {
+                    connection: connection.clone(),
+                    timeout,
+                    timeouts: 0,
+                },
+            );
+            opened.push(session_id);
+            let mut response = request.response(uptime, 0, 0, Vec::new());
+            response.session_id = session_id;
+            return Some(response);
+        }
+
+        let session_id = request.session_id;
+        let is_open = state
+            .sessions
+            .get(&session_id)
+            .is_some_and(|session| Arc::ptr_eq(&session.connection, connection));
+        if !is_open {
+            return Some(request.response(uptime, pdu::ERR_NOT_OPEN, 0, Vec::new()));
+        }
+        if request.context.is_some() {
+            return Some(request.response(uptime, pdu::ERR_UNSUPPORTED_CONTEXT, 0, Vec::new()));
+        }
+
+        let error = match request.payload {
+            Payload::Close { .. } => {
+                state.remove_session(session_id);
+                0
+            }
+            Payload::Register {
+                timeout,
+                priority,
+                range_subid,
+                ref subtree,
+                upper_bound,
+            } => match expand(range_subid, subtree, upper_bound) {
+                Some(subtrees) => state.register(session_id, timeout, priority, subtrees),
+                None => pdu::ERR_PARSE_ERROR,
+            },
+            Payload::Unregister {
+                priority,
+                range_subid,
+                ref subtree,
+                upper_bound,
+            } => match expand(range_subid, subtree, upper_bound) {
+                Some(subtrees) => state.unregister(session_id, priority, &subtrees),
+                None => pdu::ERR_PARSE_ERROR,
+            },
+            Payload::Ping => 0,
+            // notifications are not forwarded to managers
+            _ => pdu::ERR_PROCESSING_ERROR,
+        };
+        Some(request.response(uptime, error, 0, Vec::new()))
+    }
+
+    /// Sends a request on behalf of the registration `target` and waits for
+    /// the response. `None` if the session is gone or did not answer in
+    /// time.
+    fn request(
+        &self,
+        target: &Registered,
+        transaction_id: u32,
+        payload: Payload,
+    ) -> Option<(u16, u16, Varbinds)> {
+        let (tx, rx) = mpsc::channel();
+        let (packet_id, timeout, connection, request) = {
+            let mut state = self.lock();
+            let session = state.sessions.get(&target.session_id)?;
+            let timeout = [target.timeout, session.timeout]
+                .into_iter()
+                .find(|&t| t != 0)
+                .map_or(state.timeout, |t| Duration::from_secs(t.into()));
+            let connection = session.connection.clone();
+
+            state.next_packet_id = state.next_packet_id.wrapping_add(1);
+            let packet_id = state.next_packet_id;
+            let mut request = Pdu::new(target.session_id, packet_id, payload);
+            request.transaction_id = transaction_id;
+
+            state.pending.insert(packet_id, tx);
+            (packet_id, timeout, connection, request)
+        };
+
+        // a slow subagent must not block the other sessions while writing
+        let written = connection.lock().unwrap().write_all(&request.encode());
+        if written.is_err() {
+            self.lock().pending.remove(&packet_id);
+            return None;
+        }
+
+        let response = rx.recv_timeout(timeout);
+        let close = {
+            let mut state = self.lock();
+            match response {
+                Ok(Pdu {
+                    payload:
+                        Payload::Response {
+                            error,
+                            index,
+                            varbinds,
+                            ..
+                        },
+                    ..
+                }) => {
+                    if let Some(session) = state.sessions.get_mut(&target.session_id) {
+                        session.timeouts = 0;
+                    }
+                    return Some((error, index, varbinds));
+                }
+                _ => {
+                    state.pending.remove(&packet_id);
+                    state.timed_out(target.session_id)
+                }
+            }
+        };
+
+        if let Some((connection, close)) = close {
+            let _ = connection.lock().unwrap().write_all(&close.encode());
+        }
+        None
+    }
+
+    /// Sends a PDU no response is expected to.
+    fn send(&self, session_id: u32, transaction_id: u32, payload: Payload) {
+        let (connection, pdu) = {
+            let mut state = self.lock();
+            let Some(connection) = state
+                .sessions
+                .get(&session_id)
+                .map(|s| s.connection.clone())
+            else {
+                return;
+            };
+            state.next_packet_id = state.next_packet_id.wrapping_add(1);
+            let mut pdu = Pdu::new(session_id, state.next_packet_id, payload);
+            pdu.transaction_id = transaction_id;
+            (connection, pdu)
+        };
+        let _ = connection.lock().unwrap().write_all(&pdu.encode());
+    }
+}
+
+impl State {
+    fn remove_session(&mut self, session_id: u32) {
+        self.sessions.remove(&session_id);
+        self.registrations.retain(|r| r.session_id != session_id);
+    }
+
+    /// Closes sessions that keep failing to answer in time (RFC 2741
+    /// section 7.2.4.1). Returns the Close-PDU and the connection to write
+    /// it to once the state is unlocked.
+    fn timed_out(&mut self, session_id: u32) -> Option<(Connection, Pdu)> {
+        let session = self.sessions.get_mut(&session_id)?;
+        session.timeouts += 1;
+        if session.timeouts < MAX_TIMEOUTS {
+            return None;
+        }
+
+        let reason = pdu::CLOSE_TIMEOUTS;
+        let close = Pdu::new(session_id, 0, Payload::Close { reason });
+        let connection = session.connection.clone();
+        self.remove_session(session_id);
+        Some((connection, close))
+    }
+
+    fn register(
+        &mut self,
+        session_id: u32,
+        timeout: u8,
+        priority: u8,
+        subtrees: Vec<Vec<u32>>,
+    ) -> u16 {
+        // RFC 2741 section 7.1.5.1: the same subtree with the same priority
+        let duplicate = subtrees.iter().any(|subtree| {
+            self.registrations
+                .iter()
+                .any(|r| r.subtree == *subtree && r.priority == priority)
+        });
+        if duplicate {
+            return pdu::ERR_DUPLICATE_REGISTRATION;
+        }
+
+        for subtree in subtrees {
+            let pos = self
+                .registrations
+                .partition_point(|r| (&r.subtree, r.priority) < (&subtree, priority));
+            self.registrations.insert(
+                pos,
+                Registered {
+                    subtree,
+                    priority,
+                    session_id,
+                    timeout,
+                },
+            );
+        }
+        0
+    }
+
+    fn unregister(&mut self, session_id: u32, priority: u8, subtrees: &[Vec<u32>]) -> u16 {
+        let before = self.registrations.len();
+        self.registrations.retain(|r| {
+            r.session_id != session_id || r.priority != priority || !subtrees.contains(&r.subtree)
+        });
+        if self.registrations.len() == before {
+            pdu::ERR_UNKNOWN_REGISTRATION
+        } else {
+            0
+        }
+    }
+
+    /// The registration responsible for `oid`: the most specific one, and of
+    /// those the one with the best priority.
+    fn owner(&self, oid: &[u32]) -> Option<&Registered> {
+        self.registrations
+            .iter()
+            .filter(|r| oid.starts_with(&r.subtree))
+            .min_by_key(|r| (Reverse(r.subtree.len()), r.priority))
+    }
+
+    /// The owner of the instances following `oid` up to the next point
+    /// where ownership may change, and that point.
+    fn segment(&self, oid: &[u32]) -> (Option<Registered>, Option<Vec<u32>>) {
+        let mut boundaries: Vec<Vec<u32>> = self
+            .registrations
+            .iter()
+            .flat_map(|r| std::iter::once(r.subtree.clone()).chain(region_end(&r.subtree)))
+            .collect();
+        boundaries.sort();
+        boundaries.dedup();
+
+        let i = boundaries.partition_point(|b| b[..] <= *oid);
+        let owner = match i {
+            0 => None,
+            _ => self.owner(&boundaries[i - 1]).cloned(),
+        };
+        (owner, boundaries.get(i).cloned())
+    }
+}
+
+/// The subtrees covered by a (range) registration.
+fn expand(range_subid: u8, subtree: &[u32], upper_bound: u32) -> Option<Vec<Vec<u32>>> {
+    if range_subid == 0 {
+        return Some(vec![subtree.to_vec()]);
+    }
+
+    let pos = range_subid as usize - 1;
+    let first = *subtree.get(pos)?;
+    if upper_bound < first || upper_bound - first >= MAX_RANGE {
+        return None;
+    }
+    let subtrees = (first..=upper_bound)
+        .map(|subid| {
+            let mut subtree = subtree.to_vec();
+            subtree[pos] = subid;
+            subtree
+        })
+        .collect();
+    Some(subtrees)
+}
+
+/// The first OID after everything below `subtree`, `None` if there is none.
+fn region_end(subtree: &[u32]) -> Option<Vec<u32>> {
+    let mut end = subtree.to_vec();
+    while let Some(last) = end.pop() {
+        if last < u32::MAX {
+            end.push(last + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+#[derive(Default)]
+struct Transaction {
+    target: Option<Registered>,
+    varbinds: Varbinds,
+    // results of the phases sent so far, with 0-based error indices
+    tested: Option<Result<(), (u32, usize)>>,
+    committed: Option<Result<(), (u32, usize)>>,
+    undone: bool,
+}
+
+/// Serves the subtrees registered by the subagents of a `Master`.
+///
+/// Requests are forwarded to the session of the registration responsible
+/// for each varbind. Instances of subagents that do not answer in time are
+/// treated as missing, SETs involving them fail; sessions that time out
+/// repeatedly are closed.
+pub struct MasterHandler {
+    shared: Arc<Shared>,
+    // the SET in progress, by session
+    set: BTreeMap<u32, Transaction>,
+    transaction_id: u32,
+}
+
+impl MasterHandler {
+    /// The session and the 0-based position within its part of the SET of
+    /// `oid`.
+    fn locate(&self, oid: &[u32]) -> Option<(u32, usize)> {
+        self.set.iter().find_map(|(&session_id, transaction)| {
+            let pos = transaction
+                .varbinds
+                .iter()
+                .position(|(name, _)| name == oid)?;
+            Some((session_id, pos))
+        })
+    }
+
+    /// Sends a SET phase to the session, translating the response.
+    fn phase(&self, session_id: u32, payload: Payload, failed: u32) -> Result<(), (u32, usize)> {
+        let transaction = &self.set[&session_id];
+        let target = transaction.target.as_ref().unwrap();
+        match self.shared.request(target, self.transaction_id, payload) {
+            Some((0, _, _)) => Ok(()),
+            Some((error, index, _)) if error <= snmp::ERRSTATUS_INCONSISTENTNAME as u16 => {
+                Err((error.into(), (index as usize).saturating_sub(1)))
+            }
+            _ => Err((failed, 0)),
+        }
+    }
+}
+
+impl MibHandler for MasterHandler {
+    fn get(&mut self, oid: &[u32]) -> OwnedValue {
+        let Some(target) = self.shared.lock().owner(oid).cloned() else {
+            return OwnedValue::NoSuchObject;
+        };
+        let range = SearchRange {
+            start: oid.to_vec(),
+            include: false,
+            end: Vec::new(),
+        };
+        match self.shared.request(&target, 0, Payload::Get(vec![range])) {
+            Some((0, _, varbinds)) if varbinds.len() == 1 => varbinds.into_iter().next().unwrap().1,
+            _ => OwnedValue::NoSuchObject,
+        }
+    }
+
+    fn get_next(&mut self, oid: &[u32]) -> Option<(Vec<u32>, OwnedValue)> {
+        let mut range = SearchRange {
+            start: oid.to_vec(),
+            include: false,
+            end: Vec::new(),
+        };
+
+        loop {
+            let (target, end) = self.shared.lock().segment(&range.start);
+            if let Some(target) = target {
+                range.end = end.clone().unwrap_or_default();
+                let response = self
+                    .shared
+                    .request(&target, 0, Payload::GetNext(vec![range]));
+                if let Some((0, _, varbinds)) = response {
+                    if let Some((name, value)) = varbinds.into_iter().next() {
+                        let in_range = *name > *oid && end.as_ref().is_none_or(|end| name < *end);
+                        if value != OwnedValue::EndOfMibView && in_range {
+                            return Some((name, value));
+                        }
+                    }
+                }
+            }
+
+            // continue with the next segment
+            range = SearchRange {
+                start: end?,
+                include: true,
+                end: Vec::new(),
+            };
+        }
+    }
+
+    fn test_set(&mut self, oid: &[u32], value: &OwnedValue) -> Result<(), u32> {
+        let target = self
+            .shared
+            .lock()
+            .owner(oid)
+            .cloned()
+            .ok_or(snmp::ERRSTATUS_NOTWRITABLE)?;
+        if self.set.is_empty() {
+            self.transaction_id = self.transaction_id.wrapping_add(1);
+        }
+
+        // the session's varbinds are sent together once all are known
+        let transaction = self.set.entry(target.session_id).or_default();
+        transaction.target.get_or_insert(target);
+        transaction.varbinds.push((oid.to_vec(), value.clone()));
+        Ok(())
+    }
+
+    fn check_consistency(&mut self, oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        let (session_id, pos) = self.locate(oid).ok_or(snmp::ERRSTATUS_GENERR)?;
+        if self.set[&session_id].tested.is_none() {
+            let varbinds = self.set[&session_id].varbinds.clone();
+            let res = self.phase(
+                session_id,
+                Payload::TestSet(varbinds),
+                snmp::ERRSTATUS_GENERR,
+            );
+            self.set.get_mut(&session_id).unwrap().tested = Some(res);
+        }
+        match self.set[&session_id].tested {
+            Some(Err((error, index))) if pos >= index => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    fn commit_set(&mut self, oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        let (session_id, pos) = self.locate(oid).ok_or(snmp::ERRSTATUS_COMMITFAILED)?;
+        if self.set[&session_id].committed.is_none() {
+            let res = self.phase(session_id, Payload::CommitSet, snmp::ERRSTATUS_COMMITFAILED);
+            self.set.get_mut(&session_id).unwrap().committed = Some(res);
+        }
+        match self.set[&session_id].committed {
+            Some(Err((error, index))) if pos >= index => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    fn undo_set(&mut self, oid: &[u32], _value: &OwnedValue) -> Result<(), u32> {
+        let (session_id, _) = self.locate(oid).ok_or(snmp::ERRSTATUS_UNDOFAILED)?;
+        if self.set[&session_id].undone {
+            return Ok(());
+        }
+        self.set.get_mut(&session_id).unwrap().undone = true;
+        self.phase(session_id, Payload::UndoSet, snmp::ERRSTATUS_UNDOFAILED)
+            .map_err(|(error, _)| error)
+    }
+
+    fn cleanup_set(&mut self, oid: &[u32]) {
+        let Some((session_id, _)) = self.locate(oid) else {
+            return;
+        };
+        let transaction = self.set.remove(&session_id).unwrap();
+        if transaction.tested.is_some() {
+            self.shared
+                .send(session_id, self.transaction_id, Payload::CleanupSet);
+        }
+    }
+}
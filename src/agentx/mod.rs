@@ -0,0 +1,41 @@
+//! AgentX (RFC 2741).
+//!
+//! A `Subagent` exposes `MibHandler`s through a master agent such as
+//! net-snmp's snmpd, over a Unix domain socket or TCP. On Unix, a `Master`
+//! lets the crate's own `Agent` serve the subtrees of subagents connected
+//! to a Unix domain socket.
+
+use std::io::{self, Read};
+
+#[cfg(unix)]
+mod master;
+pub mod pdu;
+mod subagent;
+
+#[cfg(unix)]
+pub use self::master::{Master, MasterHandler, Registered};
+#[cfg(unix)]
+pub use self::subagent::DEFAULT_SOCKET;
+pub use self::subagent::{MasterAddress, Subagent, DEFAULT_PRIORITY};
+
+use self::pdu::Pdu;
+
+/// Largest PDU accepted from a peer, header included.
+const MAX_PDU_LEN: usize = 64 * 1024;
+
+/// Reads one PDU from a stream.
+fn read_pdu<R: Read>(stream: &mut R) -> io::Result<Pdu> {
+    let invalid = |err| io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", err));
+    let mut bytes = vec![0; pdu::HEADER_LEN];
+    stream.read_exact(&mut bytes)?;
+    let len = Pdu::len_from_header(&bytes).map_err(invalid)?;
+    if len > MAX_PDU_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("PDU of {} octets exceeds {}", len, MAX_PDU_LEN),
+        ));
+    }
+    bytes.resize(len, 0);
+    stream.read_exact(&mut bytes[pdu::HEADER_LEN..])?;
+    Pdu::decode(&bytes).map_err(invalid)
+}
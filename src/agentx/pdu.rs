@@ -0,0 +1,580 @@
+//! AgentX PDU encoding (RFC 2741 section 5 and 6).
+//!
+//! PDUs are always encoded in network byte order; both byte orders are
+//! decoded.
+
+use crate::OwnedValue;
+use crate::SnmpError;
+use crate::SnmpResult;
+
+pub const VERSION: u8 = 1;
+pub const HEADER_LEN: usize = 20;
+
+pub const FLAG_INSTANCE_REGISTRATION: u8 = 0x01;
+pub const FLAG_NEW_INDEX: u8 = 0x02;
+pub const FLAG_ANY_INDEX: u8 = 0x04;
+pub const FLAG_NON_DEFAULT_CONTEXT: u8 = 0x08;
+pub const FLAG_NETWORK_BYTE_ORDER: u8 = 0x10;
+
+pub const TYPE_OPEN: u8 = 1;
+pub const TYPE_CLOSE: u8 = 2;
+pub const TYPE_REGISTER: u8 = 3;
+pub const TYPE_UNREGISTER: u8 = 4;
+pub const TYPE_GET: u8 = 5;
+pub const TYPE_GET_NEXT: u8 = 6;
+pub const TYPE_GET_BULK: u8 = 7;
+pub const TYPE_TEST_SET: u8 = 8;
+pub const TYPE_COMMIT_SET: u8 = 9;
+pub const TYPE_UNDO_SET: u8 = 10;
+pub const TYPE_CLEANUP_SET: u8 = 11;
+pub const TYPE_NOTIFY: u8 = 12;
+pub const TYPE_PING: u8 = 13;
+pub const TYPE_RESPONSE: u8 = 18;
+
+pub const CLOSE_OTHER: u8 = 1;
+pub const CLOSE_PARSE_ERROR: u8 = 2;
+pub const CLOSE_PROTOCOL_ERROR: u8 = 3;
+pub const CLOSE_TIMEOUTS: u8 = 4;
+pub const CLOSE_SHUTDOWN: u8 = 5;
+pub const CLOSE_BY_MANAGER: u8 = 6;
+
+// res.error values besides the SNMP error-status codes
+pub const ERR_OPEN_FAILED: u16 = 256;
+pub const ERR_NOT_OPEN: u16 = 257;
+pub const ERR_UNSUPPORTED_CONTEXT: u16 = 262;
+pub const ERR_DUPLICATE_REGISTRATION: u16 = 263;
+pub const ERR_UNKNOWN_REGISTRATION: u16 = 264;
+pub const ERR_PARSE_ERROR: u16 = 266;
+pub const ERR_REQUEST_DENIED: u16 = 267;
+pub const ERR_PROCESSING_ERROR: u16 = 268;
+
+const VALUE_INTEGER: u16 = 2;
+const VALUE_OCTET_STRING: u16 = 4;
+const VALUE_NULL: u16 = 5;
+const VALUE_OBJECT_IDENTIFIER: u16 = 6;
+const VALUE_IP_ADDRESS: u16 = 64;
+const VALUE_COUNTER32: u16 = 65;
+const VALUE_GAUGE32: u16 = 66;
+const VALUE_TIMETICKS: u16 = 67;
+const VALUE_OPAQUE: u16 = 68;
+const VALUE_COUNTER64: u16 = 70;
+const VALUE_NO_SUCH_OBJECT: u16 = 128;
+const VALUE_NO_SUCH_INSTANCE: u16 = 129;
+const VALUE_END_OF_MIB_VIEW: u16 = 130;
+
+const INTERNET: &[u32] = &[1, 3, 6, 1];
+
+/// A SearchRange: the instances after `start`, or at `start` if `include`
+/// is set, and before `end`. An empty `end` is unbounded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchRange {
+    pub start: Vec<u32>,
+    pub include: bool,
+    pub end: Vec<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Payload {
+    Open {
+        timeout: u8,
+        id: Vec<u32>,
+        descr: Vec<u8>,
+    },
+    Close {
+        reason: u8,
+    },
+    /// With a non-zero `range_subid`, registers the subtrees that have the
+    /// sub-identifier at that 1-based position in the range from its value
+    /// in `subtree` to `upper_bound`.
+    Register {
+        timeout: u8,
+        priority: u8,
+        range_subid: u8,
+        subtree: Vec<u32>,
+        upper_bound: u32,
+    },
+    Unregister {
+        priority: u8,
+        range_subid: u8,
+        subtree: Vec<u32>,
+        upper_bound: u32,
+    },
+    Get(Vec<SearchRange>),
+    GetNext(Vec<SearchRange>),
+    GetBulk {
+        non_repeaters: u16,
+        max_repetitions: u16,
+        ranges: Vec<SearchRange>,
+    },
+    TestSet(Vec<(Vec<u32>, OwnedValue)>),
+    CommitSet,
+    UndoSet,
+    CleanupSet,
+    Notify(Vec<(Vec<u32>, OwnedValue)>),
+    Ping,
+    Response {
+        uptime: u32,
+        error: u16,
+        index: u16,
+        varbinds: Vec<(Vec<u32>, OwnedValue)>,
+    },
+}
+
+impl Payload {
+    pub fn pdu_type(&self) -> u8 {
+        use Payload::*;
+        match *self {
+            Open { .. } => TYPE_OPEN,
+            Close { .. } => TYPE_CLOSE,
+            Register { .. } => TYPE_REGISTER,
+            Unregister { .. } => TYPE_UNREGISTER,
+            Get(_) => TYPE_GET,
+            GetNext(_) => TYPE_GET_NEXT,
+            GetBulk { .. } => TYPE_GET_BULK,
+            TestSet(_) => TYPE_TEST_SET,
+            CommitSet => TYPE_COMMIT_SET,
+            UndoSet => TYPE_UNDO_SET,
+            CleanupSet => TYPE_CLEANUP_SET,
+            Notify(_) => TYPE_NOTIFY,
+            Ping => TYPE_PING,
+            Response { .. } => TYPE_RESPONSE,
+        }
+    }
+
+    fn has_context(&self) -> bool {
+        !matches!(
+            *self,
+            Payload::Open { .. }
+                | Payload::Close { .. }
+                | Payload::CommitSet
+                | Payload::UndoSet
+                | Payload::CleanupSet
+                | Payload::Response { .. }
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pdu {
+    /// Header flags other than the byte order and context flags, which are
+    /// derived when encoding.
+    pub flags: u8,
+    pub session_id: u32,
+    pub transaction_id: u32,
+    pub packet_id: u32,
+    /// A non-default context; only carried by PDUs that have one.
+    pub context: Option<Vec<u8>>,
+    pub payload: Payload,
+}
+
+impl Pdu {
+    pub fn new(session_id: u32, packet_id: u32, payload: Payload) -> Self {
+        Pdu {
+            flags: 0,
+            session_id,
+            transaction_id: 0,
+            packet_id,
+            context: None,
+            payload,
+        }
+    }
+
+    /// A Response-PDU answering `self`.
+    pub fn response(
+        &self,
+        uptime: u32,
+        error: u16,
+        index: u16,
+        varbinds: Vec<(Vec<u32>, OwnedValue)>,
+    ) -> Pdu {
+        Pdu {
+            flags: 0,
+            session_id: self.session_id,
+            transaction_id: self.transaction_id,
+            packet_id: self.packet_id,
+            context: None,
+            payload: Payload::Response {
+                uptime,
+                error,
+                index,
+                varbinds,
+            },
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64);
+        let mut flags = (self.flags & !FLAG_NON_DEFAULT_CONTEXT) | FLAG_NETWORK_BYTE_ORDER;
+        let context = self.context.as_ref().filter(|_| self.payload.has_context());
+        if context.is_some() {
+            flags |= FLAG_NON_DEFAULT_CONTEXT;
+        }
+
+        out.extend_from_slice(&[VERSION, self.payload.pdu_type(), flags, 0]);
+        out.extend_from_slice(&self.session_id.to_be_bytes());
+        out.extend_from_slice(&self.transaction_id.to_be_bytes());
+        out.extend_from_slice(&self.packet_id.to_be_bytes());
+        out.extend_from_slice(&[0; 4]); // payload length
+
+        if let Some(context) = context {
+            push_octet_string(&mut out, context);
+        }
+
+        use Payload::*;
+        match self.payload {
+            Open {
+                timeout,
+                ref id,
+                ref descr,
+            } => {
+                out.extend_from_slice(&[timeout, 0, 0, 0]);
+                push_oid(&mut out, id, false);
+                push_octet_string(&mut out, descr);
+            }
+            Close { reason } => out.extend_from_slice(&[reason, 0, 0, 0]),
+            Register {
+                timeout,
+                priority,
+                range_subid,
+                ref subtree,
+                upper_bound,
+            } => {
+                out.extend_from_slice(&[timeout, priority, range_subid, 0]);
+                push_oid(&mut out, subtree, false);
+                if range_subid != 0 {
+                    out.extend_from_slice(&upper_bound.to_be_bytes());
+                }
+            }
+            Unregister {
+                priority,
+                range_subid,
+                ref subtree,
+                upper_bound,
+            } => {
+                out.extend_from_slice(&[0, priority, range_subid, 0]);
+                push_oid(&mut out, subtree, false);
+                if range_subid != 0 {
+                    out.extend_from_slice(&upper_bound.to_be_bytes());
+                }
+            }
+            Get(ref ranges) | GetNext(ref ranges) => push_ranges(&mut out, ranges),
+            GetBulk {
+                non_repeaters,
+                max_repetitions,
+                ref ranges,
+            } => {
+                out.extend_from_slice(&non_repeaters.to_be_bytes());
+                out.extend_from_slice(&max_repetitions.to_be_bytes());
+                push_ranges(&mut out, ranges);
+            }
+            TestSet(ref varbinds) | Notify(ref varbinds) => push_varbinds(&mut out, varbinds),
+            CommitSet | UndoSet | CleanupSet | Ping => {}
+            Response {
+                uptime,
+                error,
+                index,
+                ref varbinds,
+            } => {
+                out.extend_from_slice(&uptime.to_be_bytes());
+                out.extend_from_slice(&error.to_be_bytes());
+                out.extend_from_slice(&index.to_be_bytes());
+                push_varbinds(&mut out, varbinds);
+            }
+        }
+
+        let len = (out.len() - HEADER_LEN) as u32;
+        out[16..HEADER_LEN].copy_from_slice(&len.to_be_bytes());
+        out
+    }
+
+    /// The total length of the PDU starting with `header`, which must hold
+    /// at least `HEADER_LEN` bytes.
+    pub fn len_from_header(header: &[u8]) -> SnmpResult<usize> {
+        if header.len() < HEADER_LEN {
+            return Err(SnmpError::AsnEof);
+        }
+        if header[0] != VERSION {
+            return Err(SnmpError::UnsupportedVersion);
+        }
+        let mut reader = Reader {
+            bytes: header,
+            pos: 16,
+            big_endian: header[2] & FLAG_NETWORK_BYTE_ORDER != 0,
+        };
+        Ok(HEADER_LEN + reader.u32()? as usize)
+    }
+
+    pub fn decode(bytes: &[u8]) -> SnmpResult<Pdu> {
+        let len = Pdu::len_from_header(bytes)?;
+        if bytes.len() < len {
+            return Err(SnmpError::AsnEof);
+        }
+        let flags = bytes[2];
+        let mut r = Reader {
+            bytes: &bytes[..len],
+            pos: 4,
+            big_endian: flags & FLAG_NETWORK_BYTE_ORDER != 0,
+        };
+        let session_id = r.u32()?;
+        let transaction_id = r.u32()?;
+        let packet_id = r.u32()?;
+        r.u32()?;
+
+        let pdu_type = bytes[1];
+        let context = if flags & FLAG_NON_DEFAULT_CONTEXT != 0
+            && !matches!(
+                pdu_type,
+                TYPE_OPEN | TYPE_CLOSE | TYPE_COMMIT_SET | TYPE_UNDO_SET | TYPE_CLEANUP_SET
+            ) {
+            Some(r.octet_string()?)
+        } else {
+            None
+        };
+
+        let payload = match pdu_type {
+            TYPE_OPEN => {
+                let [timeout, _, _, _] = r.array()?;
+                let (id, _) = r.oid()?;
+                Payload::Open {
+                    timeout,
+                    id,
+                    descr: r.octet_string()?,
+                }
+            }
+            TYPE_CLOSE => {
+                let [reason, _, _, _] = r.array()?;
+                Payload::Close { reason }
+            }
+            TYPE_REGISTER | TYPE_UNREGISTER => {
+                let [timeout, priority, range_subid, _] = r.array()?;
+                let (subtree, _) = r.oid()?;
+                let upper_bound = if range_subid != 0 { r.u32()? } else { 0 };
+                if pdu_type == TYPE_REGISTER {
+                    Payload::Register {
+                        timeout,
+                        priority,
+                        range_subid,
+                        subtree,
+                        upper_bound,
+                    }
+                } else {
+                    Payload::Unregister {
+                        priority,
+                        range_subid,
+                        subtree,
+                        upper_bound,
+                    }
+                }
+            }
+            TYPE_GET => Payload::Get(r.ranges()?),
+            TYPE_GET_NEXT => Payload::GetNext(r.ranges()?),
+            TYPE_GET_BULK => Payload::GetBulk {
+                non_repeaters: r.u16()?,
+                max_repetitions: r.u16()?,
+                ranges: r.ranges()?,
+            },
+            TYPE_TEST_SET => Payload::TestSet(r.varbinds()?),
+            TYPE_COMMIT_SET => Payload::CommitSet,
+            TYPE_UNDO_SET => Payload::UndoSet,
+            TYPE_CLEANUP_SET => Payload::CleanupSet,
+            TYPE_NOTIFY => Payload::Notify(r.varbinds()?),
+            TYPE_PING => Payload::Ping,
+            TYPE_RESPONSE => Payload::Response {
+                uptime: r.u32()?,
+                error: r.u16()?,
+                index: r.u16()?,
+                varbinds: r.varbinds()?,
+            },
+            _ => return Err(SnmpError::AsnWrongType),
+        };
+
+        Ok(Pdu {
+            flags: flags & !(FLAG_NON_DEFAULT_CONTEXT | FLAG_NETWORK_BYTE_ORDER),
+            session_id,
+            transaction_id,
+            packet_id,
+            context,
+            payload,
+        })
+    }
+}
+
+fn push_oid(out: &mut Vec<u8>, oid: &[u32], include: bool) {
+    let (prefix, subids) = match oid {
+        // a prefix of 0 means none, so 1.3.6.1.0.x stays uncompressed
+        [1, 3, 6, 1, prefix, rest @ ..] if *prefix != 0 && *prefix < 256 => (*prefix as u8, rest),
+        _ => (0, oid),
+    };
+    out.extend_from_slice(&[subids.len() as u8, prefix, include as u8, 0]);
+    for subid in subids {
+        out.extend_from_slice(&subid.to_be_bytes());
+    }
+}
+
+fn push_octet_string(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+    let padding = (4 - bytes.len() % 4) % 4;
+    out.extend_from_slice(&[0; 3][..padding]);
+}
+
+fn push_ranges(out: &mut Vec<u8>, ranges: &[SearchRange]) {
+    for range in ranges {
+        push_oid(out, &range.start, range.include);
+        push_oid(out, &range.end, false);
+    }
+}
+
+fn push_varbinds(out: &mut Vec<u8>, varbinds: &[(Vec<u32>, OwnedValue)]) {
+    use OwnedValue::*;
+    for (name, value) in varbinds {
+        let value_type = match *value {
+            Boolean(_) | Integer(_) => VALUE_INTEGER,
+            Null => VALUE_NULL,
+            OctetString(_) => VALUE_OCTET_STRING,
+            ObjectIdentifier(_) => VALUE_OBJECT_IDENTIFIER,
+            IpAddress(_) => VALUE_IP_ADDRESS,
+            Counter32(_) => VALUE_COUNTER32,
+            Unsigned32(_) => VALUE_GAUGE32,
+            Timeticks(_) => VALUE_TIMETICKS,
+            Opaque(_) => VALUE_OPAQUE,
+            Counter64(_) => VALUE_COUNTER64,
+            NoSuchObject => VALUE_NO_SUCH_OBJECT,
+            NoSuchInstance => VALUE_NO_SUCH_INSTANCE,
+            EndOfMibView => VALUE_END_OF_MIB_VIEW,
+        };
+        out.extend_from_slice(&value_type.to_be_bytes());
+        out.extend_from_slice(&[0, 0]);
+        push_oid(out, name, false);
+
+        match *value {
+            Boolean(b) => out.extend_from_slice(&(b as u32).to_be_bytes()),
+            Integer(i) => out.extend_from_slice(&(i as i32).to_be_bytes()),
+            OctetString(ref bytes) | Opaque(ref bytes) => push_octet_string(out, bytes),
+            ObjectIdentifier(ref oid) => push_oid(out, oid, false),
+            IpAddress(ref ip) => push_octet_string(out, ip),
+            Counter32(i) | Unsigned32(i) | Timeticks(i) => out.extend_from_slice(&i.to_be_bytes()),
+            Counter64(i) => out.extend_from_slice(&i.to_be_bytes()),
+            Null | NoSuchObject | NoSuchInstance | EndOfMibView => {}
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    fn array<const N: usize>(&mut self) -> SnmpResult<[u8; N]> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or(SnmpError::AsnEof)?;
+        self.pos += N;
+        let mut out = [0; N];
+        out.copy_from_slice(bytes);
+        Ok(out)
+    }
+
+    fn u16(&mut self) -> SnmpResult<u16> {
+        let bytes = self.array()?;
+        Ok(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32(&mut self) -> SnmpResult<u32> {
+        let bytes = self.array()?;
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn u64(&mut self) -> SnmpResult<u64> {
+        let bytes = self.array()?;
+        Ok(if self.big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn octet_string(&mut self) -> SnmpResult<Vec<u8>> {
+        let len = self.u32()? as usize;
+        let padded = len.checked_add(3).ok_or(SnmpError::AsnInvalidLen)? & !3;
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + padded)
+            .ok_or(SnmpError::AsnEof)?;
+        self.pos += padded;
+        Ok(bytes[..len].to_vec())
+    }
+
+    /// Returns the OID and its include flag.
+    fn oid(&mut self) -> SnmpResult<(Vec<u32>, bool)> {
+        let [n_subid, prefix, include, _] = self.array()?;
+        let mut oid = Vec::with_capacity(n_subid as usize + 5);
+        if prefix != 0 {
+            oid.extend_from_slice(INTERNET);
+            oid.push(prefix as u32);
+        }
+        for _ in 0..n_subid {
+            oid.push(self.u32()?);
+        }
+        Ok((oid, include != 0))
+    }
+
+    fn ranges(&mut self) -> SnmpResult<Vec<SearchRange>> {
+        let mut out = Vec::new();
+        while !self.is_empty() {
+            let (start, include) = self.oid()?;
+            let (end, _) = self.oid()?;
+            out.push(SearchRange {
+                start,
+                include,
+                end,
+            });
+        }
+        Ok(out)
+    }
+
+    fn varbinds(&mut self) -> SnmpResult<Vec<(Vec<u32>, OwnedValue)>> {
+        let mut out = Vec::new();
+        while !self.is_empty() {
+            let value_type = self.u16()?;
+            self.u16()?;
+            let (name, _) = self.oid()?;
+            let value = match value_type {
+                VALUE_INTEGER => OwnedValue::Integer(self.u32()? as i32 as i64),
+                VALUE_OCTET_STRING => OwnedValue::OctetString(self.octet_string()?),
+                VALUE_NULL => OwnedValue::Null,
+                VALUE_OBJECT_IDENTIFIER => OwnedValue::ObjectIdentifier(self.oid()?.0),
+                VALUE_IP_ADDRESS => {
+                    let ip = self.octet_string()?;
+                    OwnedValue::IpAddress(ip[..].try_into().map_err(|_| SnmpError::AsnInvalidLen)?)
+                }
+                VALUE_COUNTER32 => OwnedValue::Counter32(self.u32()?),
+                VALUE_GAUGE32 => OwnedValue::Unsigned32(self.u32()?),
+                VALUE_TIMETICKS => OwnedValue::Timeticks(self.u32()?),
+                VALUE_OPAQUE => OwnedValue::Opaque(self.octet_string()?),
+                VALUE_COUNTER64 => OwnedValue::Counter64(self.u64()?),
+                VALUE_NO_SUCH_OBJECT => OwnedValue::NoSuchObject,
+                VALUE_NO_SUCH_INSTANCE => OwnedValue::NoSuchInstance,
+                VALUE_END_OF_MIB_VIEW => OwnedValue::EndOfMibView,
+                _ => return Err(SnmpError::AsnUnsupportedType),
+            };
+            out.push((name, value));
+        }
+        Ok(out)
+    }
+}
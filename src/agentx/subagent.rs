@@ -0,0 +1,406 @@
+//! AgentX subagent (RFC 2741 section 7.1).
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use super::pdu::{self, Payload, Pdu, SearchRange};
+use crate::agent::{MibHandler, MibRegistry};
+use crate::OwnedValue;
+
+/// The default master agent socket of net-snmp.
+#[cfg(unix)]
+pub const DEFAULT_SOCKET: &str = "/var/agentx/master";
+/// The priority subtrees are registered with.
+pub const DEFAULT_PRIORITY: u8 = 127;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MasterAddress {
+    #[cfg(unix)]
+    Unix(PathBuf),
+    Tcp(SocketAddr),
+}
+
+impl Default for MasterAddress {
+    #[cfg(unix)]
+    fn default() -> Self {
+        MasterAddress::Unix(PathBuf::from(DEFAULT_SOCKET))
+    }
+
+    /// The AgentX port on the local host (RFC 2741 section 8.1.1).
+    #[cfg(not(unix))]
+    fn default() -> Self {
+        MasterAddress::Tcp(SocketAddr::from(([127, 0, 0, 1], 705)))
+    }
+}
+
+enum Transport {
+    #[cfg(unix)]
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl Transport {
+    fn connect(master: &MasterAddress) -> io::Result<Transport> {
+        match *master {
+            #[cfg(unix)]
+            MasterAddress::Unix(ref path) => UnixStream::connect(path).map(Transport::Unix),
+            MasterAddress::Tcp(addr) => TcpStream::connect(addr).map(Transport::Tcp),
+        }
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match *self {
+            #[cfg(unix)]
+            Transport::Unix(ref s) => s.set_read_timeout(timeout),
+            Transport::Tcp(ref s) => s.set_read_timeout(timeout),
+        }
+    }
+
+    fn send(&mut self, pdu: &Pdu) -> io::Result<()> {
+        self.write_all(&pdu.encode())
+    }
+
+    fn recv(&mut self) -> io::Result<Pdu> {
+        super::read_pdu(self)
+    }
+}
+
+impl Read for Transport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match *self {
+            #[cfg(unix)]
+            Transport::Unix(ref mut s) => s.read(buf),
+            Transport::Tcp(ref mut s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Transport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            #[cfg(unix)]
+            Transport::Unix(ref mut s) => s.write(buf),
+            Transport::Tcp(ref mut s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            #[cfg(unix)]
+            Transport::Unix(ref mut s) => s.flush(),
+            Transport::Tcp(ref mut s) => s.flush(),
+        }
+    }
+}
+
+struct Session {
+    transport: Transport,
+    session_id: u32,
+    next_packet_id: u32,
+}
+
+/// A subagent publishing the subtrees of its `MibHandler`s through a master
+/// agent.
+pub struct Subagent {
+    master: MasterAddress,
+    id: Vec<u32>,
+    descr: String,
+    /// Default timeout the master waits for responses, announced in the
+    /// Open-PDU. Also bounds the wait for the master's own responses.
+    pub timeout: Duration,
+    /// Delay between connection attempts in `serve`.
+    pub reconnect_interval: Duration,
+    registry: MibRegistry,
+    subtrees: Vec<(Vec<u32>, u8)>,
+    session: Option<Session>,
+    set: Vec<(Vec<u32>, OwnedValue)>,
+    committed: usize,
+}
+
+impl Subagent {
+    /// `id` and `descr` identify the subagent to the master, e.g. in
+    /// net-snmp's agentxSessionTable.
+    pub fn new(master: MasterAddress, id: &[u32], descr: &str) -> Self {
+        Subagent {
+            master,
+            id: id.to_vec(),
+            descr: descr.to_string(),
+            timeout: Duration::from_secs(5),
+            reconnect_interval: Duration::from_secs(5),
+            registry: MibRegistry::new(),
+            subtrees: Vec::new(),
+            session: None,
+            set: Vec::new(),
+            committed: 0,
+        }
+    }
+
+    /// Registers `handler` for `subtree`, locally and, once connected, with
+    /// the master.
+    pub fn register<H>(&mut self, subtree: &[u32], handler: H)
+    where
+        H: MibHandler + 'static,
+    {
+        self.register_with_priority(subtree, DEFAULT_PRIORITY, handler);
+    }
+
+    /// Like `register`. Of overlapping registrations of the same subtree
+    /// the master prefers the one with the lowest priority value.
+    pub fn register_with_priority<H>(&mut self, subtree: &[u32], priority: u8, handler: H)
+    where
+        H: MibHandler + 'static,
+    {
+        self.registry.register(subtree, Box::new(handler));
+        self.subtrees.retain(|(s, _)| s != subtree);
+        self.subtrees.push((subtree.to_vec(), priority));
+    }
+
+    pub fn registry(&mut self) -> &mut MibRegistry {
+        &mut self.registry
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// The session id assigned by the master.
+    pub fn session_id(&self) -> Option<u32> {
+        self.session.as_ref().map(|s| s.session_id)
+    }
+
+    /// Opens a session with the master and registers all subtrees.
+    pub fn connect(&mut self) -> io::Result<()> {
+        self.session = None;
+
+        let transport = Transport::connect(&self.master)?;
+        transport.set_read_timeout(Some(self.timeout))?;
+        let mut session = Session {
+            transport,
+            session_id: 0,
+            next_packet_id: 1,
+        };
+
+        let timeout = self.timeout.as_secs().min(255) as u8;
+        let open = Payload::Open {
+            timeout,
+            id: self.id.clone(),
+            descr: self.descr.as_bytes().to_vec(),
+        };
+        session.session_id = session.request(open)?.session_id;
+
+        for (subtree, priority) in &self.subtrees {
+            session.request(Payload::Register {
+                timeout: 0,
+                priority: *priority,
+                range_subid: 0,
+                subtree: subtree.clone(),
+                upper_bound: 0,
+            })?;
+        }
+
+        session.transport.set_read_timeout(None)?;
+        self.session = Some(session);
+        Ok(())
+    }
+
+    /// Closes the session.
+    pub fn close(&mut self) -> io::Result<()> {
+        match self.session.take() {
+            Some(mut session) => {
+                let reason = pdu::CLOSE_SHUTDOWN;
+                let close = Pdu::new(session.session_id, 0, Payload::Close { reason });
+                session.transport.send(&close)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Receives and answers a single request from the master. The session
+    /// is dropped on errors and when the master closes it.
+    pub fn serve_one(&mut self) -> io::Result<()> {
+        let Some(session) = self.session.as_mut() else {
+            return Err(io::ErrorKind::NotConnected.into());
+        };
+        let res = session.transport.recv().and_then(|request| {
+            if let Payload::Close { .. } = request.payload {
+                return Err(io::ErrorKind::ConnectionAborted.into());
+            }
+            match self.answer(&request) {
+                Some(response) => self.session.as_mut().unwrap().transport.send(&response),
+                None => Ok(()),
+            }
+        });
+        if res.is_err() {
+            self.session = None;
+        }
+        res
+    }
+
+    /// Answers requests forever, reconnecting whenever the connection to the
+    /// master is lost.
+    pub fn serve(&mut self) -> ! {
+        loop {
+            if self.session.is_none() && self.connect().is_err() {
+                thread::sleep(self.reconnect_interval);
+                continue;
+            }
+            if self.serve_one().is_err() {
+                thread::sleep(self.reconnect_interval);
+            }
+        }
+    }
+
+    fn answer(&mut self, request: &Pdu) -> Option<Pdu> {
+        if request.context.is_some() {
+            return Some(request.response(0, pdu::ERR_UNSUPPORTED_CONTEXT, 0, Vec::new()));
+        }
+
+        let (error, index, varbinds) = match request.payload {
+            Payload::Get(ref ranges) => {
+                let values = ranges
+                    .iter()
+                    .map(|r| (r.start.clone(), self.registry.get(&r.start)))
+                    .collect();
+                (0, 0, values)
+            }
+            Payload::GetNext(ref ranges) => {
+                let values = ranges
+                    .iter()
+                    .map(|r| next_in_range(&mut self.registry, r))
+                    .collect();
+                (0, 0, values)
+            }
+            Payload::GetBulk {
+                non_repeaters,
+                max_repetitions,
+                ref ranges,
+            } => {
+                let values = get_bulk(&mut self.registry, non_repeaters, max_repetitions, ranges);
+                (0, 0, values)
+            }
+            Payload::TestSet(ref varbinds) => {
+                self.set = varbinds.clone();
+                self.committed = 0;
+                match self.registry.test_set(&self.set) {
+                    Ok(()) => (0, 0, Vec::new()),
+                    Err((status, index)) => (status, index, Vec::new()),
+                }
+            }
+            Payload::CommitSet => match self.registry.commit_set(&self.set) {
+                Ok(()) => {
+                    self.committed = self.set.len();
+                    (0, 0, Vec::new())
+                }
+                Err((status, index)) => {
+                    self.committed = index.saturating_sub(1) as usize;
+                    (status, index, Vec::new())
+                }
+            },
+            Payload::UndoSet => {
+                let committed = self.committed.min(self.set.len());
+                match self.registry.undo_set(&self.set[..committed]) {
+                    Ok(()) => (0, 0, Vec::new()),
+                    Err((status, index)) => (status, index, Vec::new()),
+                }
+            }
+            Payload::CleanupSet => {
+                // no response to CleanupSet
+                let set = std::mem::take(&mut self.set);
+                self.registry.cleanup_set(&set);
+                self.committed = 0;
+                return None;
+            }
+            _ => (pdu::ERR_PARSE_ERROR as u32, 0, Vec::new()),
+        };
+
+        Some(request.response(0, error as u16, index as u16, varbinds))
+    }
+}
+
+impl Session {
+    /// Sends a PDU and waits for the master's response, failing unless it
+    /// reports success.
+    fn request(&mut self, payload: Payload) -> io::Result<Pdu> {
+        let packet_id = self.next_packet_id;
+        self.next_packet_id = self.next_packet_id.wrapping_add(1);
+        self.transport
+            .send(&Pdu::new(self.session_id, packet_id, payload))?;
+
+        loop {
+            let response = self.transport.recv()?;
+            match response.payload {
+                Payload::Response { error: 0, .. } if response.packet_id == packet_id => {
+                    return Ok(response)
+                }
+                Payload::Response { error, .. } if response.packet_id == packet_id => {
+                    return Err(io::Error::other(format!("AgentX error {}", error)))
+                }
+                // a stale response
+                _ => continue,
+            }
+        }
+    }
+}
+
+/// The first instance in `range`, or `endOfMibView` bound to its start.
+pub(crate) fn next_in_range(
+    registry: &mut MibRegistry,
+    range: &SearchRange,
+) -> (Vec<u32>, OwnedValue) {
+    if range.include {
+        let value = registry.get(&range.start);
+        if !is_exception(&value) {
+            return (range.start.clone(), value);
+        }
+    }
+    match registry.get_next(&range.start) {
+        Some((oid, value)) if range.end.is_empty() || oid < range.end => (oid, value),
+        _ => (range.start.clone(), OwnedValue::EndOfMibView),
+    }
+}
+
+fn get_bulk(
+    registry: &mut MibRegistry,
+    non_repeaters: u16,
+    max_repetitions: u16,
+    ranges: &[SearchRange],
+) -> Vec<(Vec<u32>, OwnedValue)> {
+    let non_repeaters = (non_repeaters as usize).min(ranges.len());
+    let (non_repeaters, repeaters) = ranges.split_at(non_repeaters);
+
+    let mut out: Vec<_> = non_repeaters
+        .iter()
+        .map(|r| next_in_range(registry, r))
+        .collect();
+
+    let mut cursor = repeaters.to_vec();
+    for _ in 0..max_repetitions {
+        let mut all_done = true;
+        for range in cursor.iter_mut() {
+            let (next, value) = next_in_range(registry, range);
+            if value != OwnedValue::EndOfMibView {
+                all_done = false;
+            }
+            range.start = next.clone();
+            range.include = false;
+            out.push((next, value));
+        }
+        if all_done || cursor.is_empty() {
+            break;
+        }
+    }
+    out
+}
+
+fn is_exception(value: &OwnedValue) -> bool {
+    matches!(
+        value,
+        OwnedValue::NoSuchObject | OwnedValue::NoSuchInstance | OwnedValue::EndOfMibView
+    )
+}
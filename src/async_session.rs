@@ -1,12 +1,28 @@
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
 use std::{io, time::Duration};
 
-use crate::{pdu, SnmpError, SnmpMessageType, SnmpPdu, SnmpResult, Value};
-use tokio::net::{ToSocketAddrs, UdpSocket};
+use crate::agent::Agent;
+use crate::mib::MibTree;
+use crate::notification::{NamedNotification, Notification, TrapV1};
+use crate::notification_log::NotificationLog;
+use crate::trap_filter::{Suppressor, Verdict};
+use crate::{pdu, IntoOid, SnmpError, SnmpMessageType, SnmpPdu, SnmpResult, Value};
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::{lookup_host, ToSocketAddrs, UdpSocket};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tokio::time::timeout;
 
 const BUFFER_SIZE: usize = 4096;
-
+/// Delays between receive errors on a listener socket.
+const LISTEN_BACKOFF_MIN: Duration = Duration::from_millis(10);
+const LISTEN_BACKOFF_MAX: Duration = Duration::from_secs(1);
+
+/// Asynchronous SNMPv2 client.
+///
+/// Requests accept names as anything `IntoOid`, e.g.
+/// `"IF-MIB::ifDescr.1"` once a MIB tree is set with `set_mibs`.
 pub struct AsyncSession {
     socket: UdpSocket,
     community: Vec<u8>,
@@ -14,6 +30,7 @@ pub struct AsyncSession {
     req_id: i32,
     send_pdu: pdu::Buf,
     recv_buf: [u8; BUFFER_SIZE],
+    mibs: Option<Arc<MibTree>>,
 }
 
 impl AsyncSession {
@@ -36,9 +53,19 @@ impl AsyncSession {
             req_id,
             send_pdu: pdu::Buf::default(),
             recv_buf: [0; 4096],
+            mibs: None,
         })
     }
 
+    /// Sets the MIB tree symbolic names are resolved in.
+    pub fn set_mibs(&mut self, mibs: Option<Arc<MibTree>>) {
+        self.mibs = mibs;
+    }
+
+    pub fn mibs(&self) -> Option<&MibTree> {
+        self.mibs.as_deref()
+    }
+
     async fn send_and_recv(&mut self) -> SnmpResult<usize> {
         if self.socket.send(&self.send_pdu[..]).await.is_err() {
             return Err(SnmpError::SendError);
@@ -63,11 +90,12 @@ impl AsyncSession {
         Ok(usize)
     }
 
-    pub async fn get(&mut self, name: &[u32]) -> SnmpResult<SnmpPdu> {
+    pub async fn get<N: IntoOid>(&mut self, name: N) -> SnmpResult<SnmpPdu<'_>> {
+        let name = name.into_oid(self.mibs())?;
         pdu::build_get(
             self.community.as_slice(),
             self.req_id,
-            name,
+            &name,
             &mut self.send_pdu,
         );
 
@@ -93,11 +121,12 @@ impl AsyncSession {
         Ok(resp)
     }
 
-    pub async fn getnext(&mut self, name: &[u32]) -> SnmpResult<SnmpPdu> {
+    pub async fn getnext<N: IntoOid>(&mut self, name: N) -> SnmpResult<SnmpPdu<'_>> {
+        let name = name.into_oid(self.mibs())?;
         pdu::build_getnext(
             self.community.as_slice(),
             self.req_id,
-            name,
+            &name,
             &mut self.send_pdu,
         );
 
@@ -123,16 +152,21 @@ impl AsyncSession {
         Ok(resp)
     }
 
-    pub async fn getbulk(
+    pub async fn getbulk<N: IntoOid + Clone>(
         &mut self,
-        names: &[&[u32]],
+        names: &[N],
         non_repeaters: u32,
         max_repetitions: u32,
-    ) -> SnmpResult<SnmpPdu> {
+    ) -> SnmpResult<SnmpPdu<'_>> {
+        let names = names
+            .iter()
+            .map(|name| name.clone().into_oid(self.mibs()))
+            .collect::<SnmpResult<Vec<_>>>()?;
+        let names: Vec<&[u32]> = names.iter().map(|name| &name[..]).collect();
         pdu::build_getbulk(
             self.community.as_slice(),
             self.req_id,
-            names,
+            &names,
             non_repeaters,
             max_repetitions,
             &mut self.send_pdu,
@@ -172,11 +206,23 @@ impl AsyncSession {
     ///   - `Timeticks`
     ///   - `Opaque`
     ///   - `Counter64`
-    pub async fn set(&mut self, values: &[(&[u32], Value<'_>)]) -> SnmpResult<SnmpPdu> {
+    pub async fn set<N: IntoOid + Clone>(
+        &mut self,
+        values: &[(N, Value<'_>)],
+    ) -> SnmpResult<SnmpPdu<'_>> {
+        let names = values
+            .iter()
+            .map(|(name, _)| name.clone().into_oid(self.mibs()))
+            .collect::<SnmpResult<Vec<_>>>()?;
+        let values: Vec<(&[u32], Value)> = names
+            .iter()
+            .zip(values)
+            .map(|(name, (_, value))| (&name[..], value.clone()))
+            .collect();
         pdu::build_set(
             self.community.as_slice(),
             self.req_id,
-            values,
+            &values,
             &mut self.send_pdu,
         );
 
@@ -206,6 +252,9 @@ impl AsyncSession {
 pub struct AsyncTrapSession {
     socket: UdpSocket,
     recv_buf: [u8; BUFFER_SIZE],
+    suppressor: Option<Suppressor>,
+    log: Option<Arc<Mutex<NotificationLog>>>,
+    mibs: Option<Arc<MibTree>>,
 }
 
 impl AsyncTrapSession {
@@ -218,9 +267,31 @@ impl AsyncTrapSession {
         Ok(AsyncTrapSession {
             socket,
             recv_buf: [0; 4096],
+            suppressor: None,
+            log: None,
+            mibs: None,
         })
     }
 
+    /// Installs a suppression stage applied by `recv_notification`.
+    pub fn set_suppressor(&mut self, suppressor: Option<Suppressor>) {
+        self.suppressor = suppressor;
+    }
+
+    pub fn suppressor(&self) -> Option<&Suppressor> {
+        self.suppressor.as_ref()
+    }
+
+    /// Records every notification returned by `recv_notification` in `log`.
+    pub fn set_log(&mut self, log: Option<Arc<Mutex<NotificationLog>>>) {
+        self.log = log;
+    }
+
+    /// Loaded MIBs for `recv_named_notification` to name notifications by.
+    pub fn set_mibs(&mut self, mibs: Option<Arc<MibTree>>) {
+        self.mibs = mibs;
+    }
+
     async fn recv(&mut self) -> SnmpResult<usize> {
         let Ok(len) = self.socket.recv(&mut self.recv_buf[..]).await else {
             return Err(SnmpError::ReceiveError);
@@ -229,6 +300,14 @@ impl AsyncTrapSession {
         Ok(len)
     }
 
+    async fn recv_from(&mut self) -> SnmpResult<(usize, SocketAddr)> {
+        let Ok(res) = self.socket.recv_from(&mut self.recv_buf[..]).await else {
+            return Err(SnmpError::ReceiveError);
+        };
+
+        Ok(res)
+    }
+
     pub async fn recv_trap(&mut self) -> SnmpResult<SnmpPdu> {
         let len = self.recv().await?;
         let pdu_bytes = &self.recv_buf[..len];
@@ -240,4 +319,333 @@ impl AsyncTrapSession {
 
         Ok(message)
     }
+
+    /// Receives an SNMPv1 or SNMPv2c trap and returns it as an SNMPv2-style
+    /// notification. SNMPv1 traps are translated per RFC 3584.
+    ///
+    /// Notifications rejected by the suppressor are skipped.
+    pub async fn recv_notification(&mut self) -> SnmpResult<Notification> {
+        loop {
+            let (len, source) = self.recv_from().await?;
+            let mut notification = Notification::from_bytes(&self.recv_buf[..len])?;
+            notification.source = Some(source);
+            notification.local = self.socket.local_addr().ok();
+
+            if let Some(ref mut suppressor) = self.suppressor {
+                if suppressor.check(&notification) != Verdict::Pass {
+                    continue;
+                }
+            }
+
+            if let Some(ref log) = self.log {
+                if let Ok(mut log) = log.lock() {
+                    log.log(notification.clone());
+                }
+            }
+
+            return Ok(notification);
+        }
+    }
+
+    /// Receives a notification as `recv_notification` and names it with the
+    /// MIBs set with `set_mibs`, e.g. `IF-MIB::linkDown` with its OBJECTS by
+    /// name. The name is `None` for notifications the MIBs do not define.
+    pub async fn recv_named_notification(
+        &mut self,
+    ) -> SnmpResult<(Notification, Option<NamedNotification>)> {
+        let notification = self.recv_notification().await?;
+        let named = self
+            .mibs
+            .as_deref()
+            .and_then(|mibs| notification.named(mibs));
+        Ok((notification, named))
+    }
+}
+
+/// Receives notifications on several local addresses, e.g. 162/udp on both
+/// IPv4 and IPv6 plus an unprivileged port, merged into a single stream.
+///
+/// IPv6 sockets are bound IPv6-only so they can share a port with an IPv4
+/// socket.
+pub struct AsyncTrapListener {
+    local_addrs: Vec<SocketAddr>,
+    rx: mpsc::Receiver<SnmpResult<Notification>>,
+    tasks: Vec<JoinHandle<()>>,
+    suppressor: Option<Suppressor>,
+    log: Option<Arc<Mutex<NotificationLog>>>,
+    mibs: Option<Arc<MibTree>>,
+}
+
+impl AsyncTrapListener {
+    pub async fn new<I, SA>(addrs: I) -> io::Result<Self>
+    where
+        I: IntoIterator<Item = SA>,
+        SA: ToSocketAddrs,
+    {
+        let mut sockets = Vec::new();
+        for addr in addrs {
+            for addr in lookup_host(addr).await? {
+                sockets.push(bind_udp(addr)?);
+            }
+        }
+
+        let (tx, rx) = mpsc::channel(1024);
+        let mut local_addrs = Vec::with_capacity(sockets.len());
+        let mut tasks = Vec::with_capacity(sockets.len());
+
+        for socket in sockets {
+            let local = socket.local_addr()?;
+            local_addrs.push(local);
+            tasks.push(tokio::spawn(listen(socket, local, tx.clone())));
+        }
+
+        Ok(AsyncTrapListener {
+            local_addrs,
+            rx,
+            tasks,
+            suppressor: None,
+            log: None,
+            mibs: None,
+        })
+    }
+
+    /// Addresses the listener is bound to, with ephemeral ports resolved.
+    pub fn local_addrs(&self) -> &[SocketAddr] {
+        &self.local_addrs
+    }
+
+    /// Installs a suppression stage applied by `recv_notification`.
+    pub fn set_suppressor(&mut self, suppressor: Option<Suppressor>) {
+        self.suppressor = suppressor;
+    }
+
+    pub fn suppressor(&self) -> Option<&Suppressor> {
+        self.suppressor.as_ref()
+    }
+
+    /// Records every notification returned by `recv_notification` in `log`.
+    pub fn set_log(&mut self, log: Option<Arc<Mutex<NotificationLog>>>) {
+        self.log = log;
+    }
+
+    /// Loaded MIBs for `recv_named_notification` to name notifications by.
+    pub fn set_mibs(&mut self, mibs: Option<Arc<MibTree>>) {
+        self.mibs = mibs;
+    }
+
+    /// Receives the next notification from any of the sockets. The
+    /// notification's `local` field tells which one.
+    pub async fn recv_notification(&mut self) -> SnmpResult<Notification> {
+        loop {
+            let notification = self.rx.recv().await.ok_or(SnmpError::ReceiveError)??;
+
+            if let Some(ref mut suppressor) = self.suppressor {
+                if suppressor.check(&notification) != Verdict::Pass {
+                    continue;
+                }
+            }
+
+            if let Some(ref log) = self.log {
+                if let Ok(mut log) = log.lock() {
+                    log.log(notification.clone());
+                }
+            }
+
+            return Ok(notification);
+        }
+    }
+
+    /// Receives a notification as `recv_notification` and names it with the
+    /// MIBs set with `set_mibs`, e.g. `IF-MIB::linkDown` with its OBJECTS by
+    /// name. The name is `None` for notifications the MIBs do not define.
+    pub async fn recv_named_notification(
+        &mut self,
+    ) -> SnmpResult<(Notification, Option<NamedNotification>)> {
+        let notification = self.recv_notification().await?;
+        let named = self
+            .mibs
+            .as_deref()
+            .and_then(|mibs| notification.named(mibs));
+        Ok((notification, named))
+    }
+}
+
+impl Drop for AsyncTrapListener {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+fn bind_udp(addr: SocketAddr) -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+
+    UdpSocket::from_std(socket.into())
+}
+
+async fn listen(socket: UdpSocket, local: SocketAddr, tx: mpsc::Sender<SnmpResult<Notification>>) {
+    let mut recv_buf = [0u8; BUFFER_SIZE];
+    let mut backoff = LISTEN_BACKOFF_MIN;
+
+    loop {
+        let res = match socket.recv_from(&mut recv_buf[..]).await {
+            Ok((len, source)) => {
+                backoff = LISTEN_BACKOFF_MIN;
+                Notification::from_bytes(&recv_buf[..len]).map(|mut n| {
+                    n.source = Some(source);
+                    n.local = Some(local);
+                    n
+                })
+            }
+            Err(_) => Err(SnmpError::ReceiveError),
+        };
+        let failed = matches!(res, Err(SnmpError::ReceiveError));
+
+        if tx.send(res).await.is_err() {
+            return;
+        }
+        // a socket that keeps failing must not flood the channel
+        if failed {
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(LISTEN_BACKOFF_MAX);
+        }
+    }
+}
+
+/// Sends notifications to a single manager.
+pub struct AsyncTrapSender {
+    socket: UdpSocket,
+    community: Vec<u8>,
+    req_id: i32,
+    send_pdu: pdu::Buf,
+}
+
+impl AsyncTrapSender {
+    pub async fn new<SA>(destination: SA, community: &[u8], req_id: i32) -> io::Result<Self>
+    where
+        SA: ToSocketAddrs,
+    {
+        let destination = match lookup_host(destination).await?.next() {
+            Some(addr) => addr,
+            None => panic!("empty list of socket addrs"),
+        };
+        let socket = match destination {
+            SocketAddr::V4(_) => UdpSocket::bind((Ipv4Addr::new(0, 0, 0, 0), 0)).await?,
+            SocketAddr::V6(_) => {
+                UdpSocket::bind((Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0), 0)).await?
+            }
+        };
+        socket.connect(destination).await?;
+
+        Ok(AsyncTrapSender {
+            socket,
+            community: community.to_vec(),
+            req_id,
+            send_pdu: pdu::Buf::default(),
+        })
+    }
+
+    async fn send(&mut self) -> SnmpResult<()> {
+        if self.socket.send(&self.send_pdu[..]).await.is_err() {
+            return Err(SnmpError::SendError);
+        }
+
+        Ok(())
+    }
+
+    /// Sends an SNMPv2-Trap-PDU. `community` overrides the sender's
+    /// community string.
+    pub async fn send_trap(
+        &mut self,
+        notification: &Notification,
+        community: Option<&[u8]>,
+    ) -> SnmpResult<()> {
+        let community = community.unwrap_or(&self.community[..]);
+        notification.build_trap(community, self.req_id, &mut self.send_pdu)?;
+        self.req_id = self.req_id.wrapping_add(1);
+
+        self.send().await
+    }
+
+    /// Sends an SNMPv1 Trap-PDU. `community` overrides the sender's
+    /// community string.
+    pub async fn send_trap_v1(
+        &mut self,
+        trap: &TrapV1,
+        community: Option<&[u8]>,
+    ) -> SnmpResult<()> {
+        let community = community.unwrap_or(&self.community[..]);
+        trap.build_trap(community, &mut self.send_pdu)?;
+
+        self.send().await
+    }
+}
+
+/// Serves an `Agent` over UDP.
+pub struct AsyncAgent {
+    socket: UdpSocket,
+    agent: Agent,
+    send_pdu: pdu::Buf,
+    recv_buf: [u8; BUFFER_SIZE],
+}
+
+impl AsyncAgent {
+    pub async fn new<SA>(addr: SA, agent: Agent) -> io::Result<Self>
+    where
+        SA: ToSocketAddrs,
+    {
+        let socket = UdpSocket::bind(addr).await?;
+
+        Ok(AsyncAgent {
+            socket,
+            agent,
+            send_pdu: pdu::Buf::default(),
+            recv_buf: [0; 4096],
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub fn agent(&mut self) -> &mut Agent {
+        &mut self.agent
+    }
+
+    /// Receives and answers a single request.
+    pub async fn serve_one(&mut self) -> SnmpResult<()> {
+        let Ok((len, source)) = self.socket.recv_from(&mut self.recv_buf[..]).await else {
+            return Err(SnmpError::ReceiveError);
+        };
+
+        if self
+            .agent
+            .process(&self.recv_buf[..len], &mut self.send_pdu)
+            && self
+                .socket
+                .send_to(&self.send_pdu[..], source)
+                .await
+                .is_err()
+        {
+            return Err(SnmpError::SendError);
+        }
+
+        Ok(())
+    }
+
+    /// Answers requests until receiving fails.
+    pub async fn serve(&mut self) -> SnmpResult<()> {
+        loop {
+            match self.serve_one().await {
+                Err(SnmpError::ReceiveError) => return Err(SnmpError::ReceiveError),
+                _ => continue,
+            }
+        }
+    }
 }
@@ -10,8 +10,16 @@ const USIZE_LEN: usize = 8;
 #[cfg(test)]
 mod tests;
 
+pub mod agent;
+pub mod agentx;
 pub mod async_session;
+pub mod mib;
+pub mod notification;
+pub mod notification_log;
+pub mod proxy;
 pub mod sync_session;
+pub mod trap_filter;
+pub mod trap_relay;
 
 #[derive(Debug, PartialEq)]
 pub enum SnmpError {
@@ -29,6 +37,19 @@ pub enum SnmpError {
 
     SendError,
     ReceiveError,
+
+    /// A name that is not a numeric OID and could not be resolved in the
+    /// loaded MIBs.
+    UnknownName(String),
+    /// A value refused before sending because it does not fit the
+    /// object's syntax, with the reason.
+    InvalidValue(String),
+    /// An object that is not writable according to the loaded MIBs.
+    NotWritable(String),
+    /// A response with a non-zero error-status, and its error-index.
+    ErrorStatus(u32, u32),
+    /// An SNMPv3 message of a security model other than USM.
+    UnknownSecurityModel,
 }
 
 type SnmpResult<T> = Result<T, SnmpError>;
@@ -59,12 +80,22 @@ pub mod snmp {
 
     use super::asn1;
 
+    pub const VERSION_1: i64 = 0;
     pub const VERSION_2: i64 = 1;
+    pub const VERSION_3: i64 = 3;
+
+    /// The User-based Security Model (RFC 3414).
+    pub const SECURITY_MODEL_USM: i64 = 3;
+
+    pub const MSG_FLAG_AUTH: u8 = 0x01;
+    pub const MSG_FLAG_PRIV: u8 = 0x02;
+    pub const MSG_FLAG_REPORTABLE: u8 = 0x04;
 
     pub const MSG_GET: u8 = asn1::CLASS_CONTEXTSPECIFIC | asn1::CONSTRUCTED | 0;
     pub const MSG_GET_NEXT: u8 = asn1::CLASS_CONTEXTSPECIFIC | asn1::CONSTRUCTED | 1;
     pub const MSG_RESPONSE: u8 = asn1::CLASS_CONTEXTSPECIFIC | asn1::CONSTRUCTED | 2;
     pub const MSG_SET: u8 = asn1::CLASS_CONTEXTSPECIFIC | asn1::CONSTRUCTED | 3;
+    pub const MSG_TRAP_V1: u8 = asn1::CLASS_CONTEXTSPECIFIC | asn1::CONSTRUCTED | 4;
     pub const MSG_GET_BULK: u8 = asn1::CLASS_CONTEXTSPECIFIC | asn1::CONSTRUCTED | 5;
     pub const MSG_INFORM: u8 = asn1::CLASS_CONTEXTSPECIFIC | asn1::CONSTRUCTED | 6;
     pub const MSG_TRAP: u8 = asn1::CLASS_CONTEXTSPECIFIC | asn1::CONSTRUCTED | 7;
@@ -104,8 +135,11 @@ pub mod snmp {
 }
 
 pub mod pdu {
-    use super::{asn1, snmp, Value, BUFFER_SIZE, USIZE_LEN};
-    use std::{fmt, mem, ops, ptr};
+    use super::{
+        asn1, is_encodable_oid, snmp, OwnedValue, SnmpError, SnmpResult, V3Header, Value,
+        BUFFER_SIZE, USIZE_LEN,
+    };
+    use std::{fmt, iter, mem, ops, ptr};
 
     pub struct Buf {
         len: usize,
@@ -313,17 +347,19 @@ pub mod pdu {
             self.push_byte(asn1::TYPE_OBJECTIDENTIFIER);
         }
 
-        fn push_object_identifier(&mut self, input: &[u32]) {
-            assert!(input.len() >= 2);
+        fn push_object_identifier(&mut self, input: &[u32]) -> SnmpResult<()> {
+            if !is_encodable_oid(input) {
+                return Err(SnmpError::ValueOutOfRange);
+            }
             let length_before = self.len;
 
             self.scribble_bytes(|output| {
                 let mut pos = output.len() - 1;
                 let (head, tail) = input.split_at(2);
-                assert!(head[0] < 3 && head[1] < 40);
+                let head = head[0] * 40 + head[1];
 
-                // encode the subids in reverse order
-                for subid in tail.iter().rev() {
+                // encode the subids in reverse order, the head last
+                for subid in tail.iter().rev().chain(iter::once(&head)) {
                     let mut subid = *subid;
                     let mut last_byte = true;
                     loop {
@@ -344,14 +380,12 @@ pub mod pdu {
                         }
                     }
                 }
-
-                // encode the head last
-                output[pos] = (head[0] * 40 + head[1]) as u8;
-                output.len() - pos
+                output.len() - pos - 1
             });
             let length_after = self.len;
             self.push_length(length_after - length_before);
             self.push_byte(asn1::TYPE_OBJECTIDENTIFIER);
+            Ok(())
         }
 
         fn push_octet_string(&mut self, bytes: &[u8]) {
@@ -359,8 +393,75 @@ pub mod pdu {
             self.push_length(bytes.len());
             self.push_byte(asn1::TYPE_OCTETSTRING);
         }
+
+        fn push_owned_value(&mut self, val: &OwnedValue) -> SnmpResult<()> {
+            use OwnedValue::*;
+            match *val {
+                Boolean(b) => self.push_boolean(b),
+                Null => self.push_null(),
+                Integer(i) => self.push_integer(i),
+                OctetString(ref ostr) => self.push_octet_string(ostr),
+                ObjectIdentifier(ref objid) => return self.push_object_identifier(objid),
+                IpAddress(ref ip) => self.push_ipaddress(ip),
+                Counter32(i) => self.push_counter32(i),
+                Unsigned32(i) => self.push_unsigned32(i),
+                Timeticks(tt) => self.push_timeticks(tt),
+                Opaque(ref bytes) => self.push_opaque(bytes),
+                Counter64(i) => self.push_counter64(i),
+                EndOfMibView => self.push_endofmibview(),
+                NoSuchObject => self.push_nosuchobject(),
+                NoSuchInstance => self.push_nosuchinstance(),
+            }
+            Ok(())
+        }
+
+        fn push_value(&mut self, val: &Value) -> SnmpResult<()> {
+            use Value::*;
+            match *val {
+                Boolean(b) => self.push_boolean(b),
+                Null => self.push_null(),
+                Integer(i) => self.push_integer(i),
+                OctetString(ostr) => self.push_octet_string(ostr),
+                ObjectIdentifier(ref objid) => self.push_object_identifier_raw(objid.raw()),
+                IpAddress(ref ip) => self.push_ipaddress(ip),
+                Counter32(i) => self.push_counter32(i),
+                Unsigned32(i) => self.push_unsigned32(i),
+                Timeticks(tt) => self.push_timeticks(tt),
+                Opaque(bytes) => self.push_opaque(bytes),
+                Counter64(i) => self.push_counter64(i),
+                EndOfMibView => self.push_endofmibview(),
+                NoSuchObject => self.push_nosuchobject(),
+                NoSuchInstance => self.push_nosuchinstance(),
+                _ => return Err(SnmpError::AsnUnsupportedType),
+            }
+            Ok(())
+        }
+
+        fn push_owned_varbinds(&mut self, values: &[(Vec<u32>, OwnedValue)]) -> SnmpResult<()> {
+            let mut result = Ok(());
+            self.push_sequence(|buf| {
+                for (name, val) in values.iter().rev() {
+                    buf.push_sequence(|buf| {
+                        let pushed = buf
+                            .push_owned_value(val)
+                            .and_then(|_| buf.push_object_identifier(name)); // name
+                        if let Err(err) = pushed {
+                            result = Err(err);
+                        }
+                    });
+                }
+            });
+            result
+        }
+
+        /// Pushes the name of a request built by the infallible builders.
+        fn push_name(&mut self, name: &[u32]) {
+            self.push_object_identifier(name)
+                .expect("name is not an encodable object identifier");
+        }
     }
 
+    /// # Panics if `name` cannot be encoded, see `is_encodable_oid`.
     pub fn build_get(community: &[u8], req_id: i32, name: &[u32], buf: &mut Buf) {
         buf.reset();
         buf.push_sequence(|buf| {
@@ -368,7 +469,7 @@ pub mod pdu {
                 buf.push_sequence(|buf| {
                     buf.push_sequence(|buf| {
                         buf.push_null(); // value
-                        buf.push_object_identifier(name); // name
+                        buf.push_name(name);
                     })
                 });
                 buf.push_integer(0); // error index
@@ -380,6 +481,7 @@ pub mod pdu {
         });
     }
 
+    /// # Panics if `name` cannot be encoded, see `is_encodable_oid`.
     pub fn build_getnext(community: &[u8], req_id: i32, name: &[u32], buf: &mut Buf) {
         buf.reset();
         buf.push_sequence(|buf| {
@@ -387,7 +489,7 @@ pub mod pdu {
                 buf.push_sequence(|buf| {
                     buf.push_sequence(|buf| {
                         buf.push_null(); // value
-                        buf.push_object_identifier(name); // name
+                        buf.push_name(name);
                     })
                 });
                 buf.push_integer(0); // error index
@@ -399,6 +501,7 @@ pub mod pdu {
         });
     }
 
+    /// # Panics if a name cannot be encoded, see `is_encodable_oid`.
     pub fn build_getbulk(
         community: &[u8],
         req_id: i32,
@@ -414,7 +517,7 @@ pub mod pdu {
                     for name in names.iter().rev() {
                         buf.push_sequence(|buf| {
                             buf.push_null(); // value
-                            buf.push_object_identifier(name); // name
+                            buf.push_name(name);
                         });
                     }
                 });
@@ -427,6 +530,8 @@ pub mod pdu {
         });
     }
 
+    /// # Panics if a name cannot be encoded, see `is_encodable_oid`, or on
+    /// values that cannot appear in a varbind.
     pub fn build_set(community: &[u8], req_id: i32, values: &[(&[u32], Value)], buf: &mut Buf) {
         buf.reset();
         buf.push_sequence(|buf| {
@@ -451,7 +556,7 @@ pub mod pdu {
                                 Counter64(i) => buf.push_counter64(i),
                                 _ => unimplemented!(),
                             }
-                            buf.push_object_identifier(name); // name
+                            buf.push_name(name);
                         });
                     }
                 });
@@ -464,49 +569,351 @@ pub mod pdu {
         });
     }
 
+    /// # Panics on values that cannot appear in a varbind and on names that
+    /// cannot be encoded; `build_response_with_error` fails on them instead.
     pub fn build_response(
         community: &[u8],
         req_id: i32,
         values: &[(&[u32], Value)],
         buf: &mut Buf,
     ) {
-        buf.reset();
-        buf.push_sequence(|buf| {
-            buf.push_constructed(snmp::MSG_RESPONSE, |buf| {
+        build_response_with_error(community, req_id, 0, 0, values, BUFFER_SIZE, buf)
+            .expect("response varbinds cannot be encoded");
+    }
+
+    /// Builds a Response-PDU carrying `error_status` and `error_index`.
+    ///
+    /// If the message would exceed `max_size`, it is re-encoded with an empty
+    /// varbind list and a tooBig error instead, and `false` is returned. Fails
+    /// on names that cannot be encoded and on values that cannot appear in a
+    /// varbind.
+    pub fn build_response_with_error(
+        community: &[u8],
+        req_id: i32,
+        error_status: u32,
+        error_index: u32,
+        values: &[(&[u32], Value)],
+        max_size: usize,
+        buf: &mut Buf,
+    ) -> SnmpResult<bool> {
+        let mut len = 0;
+        for &(name, ref val) in values {
+            len += tlv_len(tlv_len(oid_len(name)) + tlv_len(value_len(val)?));
+        }
+
+        let max_size = max_size.min(BUFFER_SIZE);
+        if response_len(community, req_id, error_status, error_index, len) <= max_size {
+            let mut result = Ok(());
+            push_response(community, req_id, error_status, error_index, buf, |buf| {
                 buf.push_sequence(|buf| {
                     for &(ref name, ref val) in values.iter().rev() {
                         buf.push_sequence(|buf| {
-                            use Value::*;
-                            match *val {
-                                Boolean(b) => buf.push_boolean(b),
-                                Null => buf.push_null(),
-                                Integer(i) => buf.push_integer(i),
-                                OctetString(ostr) => buf.push_octet_string(ostr),
-                                ObjectIdentifier(ref objid) => {
-                                    buf.push_object_identifier_raw(objid.raw())
-                                }
-                                IpAddress(ref ip) => buf.push_ipaddress(ip),
-                                Counter32(i) => buf.push_counter32(i),
-                                Unsigned32(i) => buf.push_unsigned32(i),
-                                Timeticks(tt) => buf.push_timeticks(tt),
-                                Opaque(bytes) => buf.push_opaque(bytes),
-                                Counter64(i) => buf.push_counter64(i),
-                                EndOfMibView => buf.push_endofmibview(),
-                                NoSuchObject => buf.push_nosuchobject(),
-                                NoSuchInstance => buf.push_nosuchinstance(),
-                                _ => unimplemented!(),
+                            let pushed = buf
+                                .push_value(val)
+                                .and_then(|_| buf.push_object_identifier(name)); // name
+                            if let Err(err) = pushed {
+                                result = Err(err);
                             }
-                            buf.push_object_identifier(name); // name
                         });
                     }
                 });
+            });
+            result?;
+            debug_assert!(buf.len() <= max_size);
+            return Ok(true);
+        }
+
+        build_too_big(community, req_id, buf);
+        Ok(false)
+    }
+
+    /// Builds a Response-PDU from owned varbinds, trimmed to a tooBig error
+    /// like `build_response_with_error`. Fails on object identifiers that
+    /// cannot be encoded.
+    pub fn build_owned_response(
+        community: &[u8],
+        req_id: i32,
+        error_status: u32,
+        error_index: u32,
+        values: &[(Vec<u32>, OwnedValue)],
+        max_size: usize,
+        buf: &mut Buf,
+    ) -> SnmpResult<bool> {
+        let len: usize = values.iter().map(|(n, v)| varbind_len(n, v)).sum();
+
+        let max_size = max_size.min(BUFFER_SIZE);
+        if response_len(community, req_id, error_status, error_index, len) <= max_size {
+            let mut result = Ok(());
+            push_response(community, req_id, error_status, error_index, buf, |buf| {
+                result = buf.push_owned_varbinds(values);
+            });
+            result?;
+            debug_assert_eq!(
+                buf.len(),
+                response_len(community, req_id, error_status, error_index, len)
+            );
+            return Ok(true);
+        }
+
+        build_too_big(community, req_id, buf);
+        Ok(false)
+    }
+
+    /// Builds a request of any type and version from owned varbinds, e.g.
+    /// one being forwarded. For GETBULK, `error_status` and `error_index`
+    /// carry non-repeaters and max-repetitions. Fails on object identifiers
+    /// that cannot be encoded.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build_owned_request(
+        version: i64,
+        community: &[u8],
+        req_id: i32,
+        message_type: u8,
+        error_status: u32,
+        error_index: u32,
+        values: &[(Vec<u32>, OwnedValue)],
+        buf: &mut Buf,
+    ) -> SnmpResult<()> {
+        let mut result = Ok(());
+        buf.reset();
+        buf.push_sequence(|buf| {
+            buf.push_constructed(message_type, |buf| {
+                result = buf.push_owned_varbinds(values);
+                buf.push_integer(error_index as i64);
+                buf.push_integer(error_status as i64);
+                buf.push_integer(req_id as i64);
+            });
+            buf.push_octet_string(community);
+            buf.push_integer(version);
+        });
+        result
+    }
+
+    fn build_too_big(community: &[u8], req_id: i32, buf: &mut Buf) {
+        push_response(community, req_id, snmp::ERRSTATUS_TOOBIG, 0, buf, |buf| {
+            buf.push_sequence(|_| {});
+        });
+    }
+
+    fn push_response<F: FnMut(&mut Buf)>(
+        community: &[u8],
+        req_id: i32,
+        error_status: u32,
+        error_index: u32,
+        buf: &mut Buf,
+        mut push_varbinds: F,
+    ) {
+        buf.reset();
+        buf.push_sequence(|buf| {
+            buf.push_constructed(snmp::MSG_RESPONSE, |buf| {
+                push_varbinds(buf);
+                buf.push_integer(error_index as i64);
+                buf.push_integer(error_status as i64);
+                buf.push_integer(req_id as i64);
+            });
+            buf.push_octet_string(community);
+            buf.push_integer(snmp::VERSION_2);
+        });
+    }
+
+    /// Encoded size of a varbind.
+    pub fn varbind_len(name: &[u32], value: &OwnedValue) -> usize {
+        use OwnedValue::*;
+        let value_len = match *value {
+            Boolean(_) => 1,
+            Null | EndOfMibView | NoSuchObject | NoSuchInstance => 0,
+            Integer(i) => integer_len(i),
+            OctetString(ref bytes) | Opaque(ref bytes) => bytes.len(),
+            ObjectIdentifier(ref objid) => oid_len(objid),
+            IpAddress(_) => 4,
+            Counter32(i) | Unsigned32(i) | Timeticks(i) => integer_len(i as i64),
+            Counter64(i) => integer_len(i as i64),
+        };
+        tlv_len(tlv_len(oid_len(name)) + tlv_len(value_len))
+    }
+
+    /// Content length of an encoded varbind value.
+    fn value_len(value: &Value) -> SnmpResult<usize> {
+        use Value::*;
+        Ok(match *value {
+            Boolean(_) => 1,
+            Null | EndOfMibView | NoSuchObject | NoSuchInstance => 0,
+            Integer(i) => integer_len(i),
+            OctetString(bytes) | Opaque(bytes) => bytes.len(),
+            ObjectIdentifier(ref objid) => objid.raw().len(),
+            IpAddress(_) => 4,
+            Counter32(i) | Unsigned32(i) | Timeticks(i) => integer_len(i as i64),
+            Counter64(i) => integer_len(i as i64),
+            _ => return Err(SnmpError::AsnUnsupportedType),
+        })
+    }
+
+    /// Content length of an encoded integer, as written by `Buf::push_i64`.
+    fn integer_len(mut n: i64) -> usize {
+        let mut len = 1;
+        while !(-128..128).contains(&n) {
+            n >>= 8;
+            len += 1;
+        }
+        len
+    }
+
+    /// Content length of an encoded object identifier.
+    fn oid_len(name: &[u32]) -> usize {
+        let subid_len = |subid: u32| ((32 - subid.leading_zeros() as usize).div_ceil(7)).max(1);
+        match *name {
+            [first, second, ref tail @ ..] => {
+                let head = first.saturating_mul(40).saturating_add(second);
+                subid_len(head) + tail.iter().map(|&subid| subid_len(subid)).sum::<usize>()
+            }
+            _ => 0,
+        }
+    }
+
+    /// Size of a TLV with `len` octets of content.
+    fn tlv_len(len: usize) -> usize {
+        let length_len = if len < 128 {
+            1
+        } else {
+            1 + mem::size_of::<usize>() - (len.leading_zeros() / 8) as usize
+        };
+        1 + length_len + len
+    }
+
+    /// Largest varbind list, as measured by `varbind_len`, that fits into an
+    /// error-free response to `req_id` of at most `max_size` octets, and
+    /// into a `Buf`, together with `community`.
+    pub fn max_varbinds_len(community: &[u8], req_id: i32, max_size: usize) -> usize {
+        let max_size = max_size.min(BUFFER_SIZE);
+        let mut len = max_size;
+        loop {
+            let total = response_len(community, req_id, 0, 0, len);
+            if total <= max_size || len == 0 {
+                return len;
+            }
+            len = len.saturating_sub(total - max_size);
+        }
+    }
+
+    /// Encoded size of a response whose varbinds take `varbinds_len` octets.
+    fn response_len(
+        community: &[u8],
+        req_id: i32,
+        error_status: u32,
+        error_index: u32,
+        varbinds_len: usize,
+    ) -> usize {
+        let pdu_len = tlv_len(integer_len(req_id as i64))
+            + tlv_len(integer_len(error_status as i64))
+            + tlv_len(integer_len(error_index as i64))
+            + tlv_len(varbinds_len);
+        tlv_len(tlv_len(integer_len(snmp::VERSION_2)) + tlv_len(community.len()) + tlv_len(pdu_len))
+    }
+
+    /// Builds an SNMPv2-Trap-PDU. The first two varbinds are expected to be
+    /// `sysUpTime.0` and `snmpTrapOID.0`. Fails on object identifiers that
+    /// cannot be encoded.
+    pub fn build_trap(
+        community: &[u8],
+        req_id: i32,
+        values: &[(Vec<u32>, OwnedValue)],
+        buf: &mut Buf,
+    ) -> SnmpResult<()> {
+        let mut result = Ok(());
+        buf.reset();
+        buf.push_sequence(|buf| {
+            buf.push_constructed(snmp::MSG_TRAP, |buf| {
+                result = buf.push_owned_varbinds(values);
                 buf.push_integer(0);
                 buf.push_integer(0);
                 buf.push_integer(req_id as i64);
             });
             buf.push_octet_string(community);
-            buf.push_integer(snmp::VERSION_2 as i64);
+            buf.push_integer(snmp::VERSION_2);
+        });
+        result
+    }
+
+    /// Builds an SNMPv3 message carrying a plaintext ScopedPDU of any type,
+    /// failing like `build_owned_request`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build_v3(
+        header: &V3Header,
+        context_engine_id: &[u8],
+        context_name: &[u8],
+        message_type: u8,
+        req_id: i32,
+        error_status: u32,
+        error_index: u32,
+        values: &[(Vec<u32>, OwnedValue)],
+        buf: &mut Buf,
+    ) -> SnmpResult<()> {
+        let mut result = Ok(());
+        buf.reset();
+        buf.push_sequence(|buf| {
+            // msgData
+            buf.push_sequence(|buf| {
+                buf.push_constructed(message_type, |buf| {
+                    result = buf.push_owned_varbinds(values);
+                    buf.push_integer(error_index as i64);
+                    buf.push_integer(error_status as i64);
+                    buf.push_integer(req_id as i64);
+                });
+                buf.push_octet_string(context_name);
+                buf.push_octet_string(context_engine_id);
+            });
+            // msgSecurityParameters
+            buf.push_constructed(asn1::TYPE_OCTETSTRING, |buf| {
+                buf.push_sequence(|buf| {
+                    buf.push_octet_string(&[]);
+                    buf.push_octet_string(&[]);
+                    buf.push_octet_string(header.user_name);
+                    buf.push_integer(header.engine_time as i64);
+                    buf.push_integer(header.engine_boots as i64);
+                    buf.push_octet_string(header.engine_id);
+                });
+            });
+            // msgGlobalData
+            buf.push_sequence(|buf| {
+                buf.push_integer(snmp::SECURITY_MODEL_USM);
+                buf.push_octet_string(&[header.flags]);
+                buf.push_integer(header.max_size as i64);
+                buf.push_integer(header.msg_id as i64);
+            });
+            buf.push_integer(snmp::VERSION_3);
         });
+        result
+    }
+
+    /// Builds an SNMPv1 Trap-PDU, failing like `build_trap`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build_trap_v1(
+        community: &[u8],
+        enterprise: &[u32],
+        agent_addr: &[u8; 4],
+        generic_trap: i64,
+        specific_trap: i64,
+        timestamp: u32,
+        values: &[(Vec<u32>, OwnedValue)],
+        buf: &mut Buf,
+    ) -> SnmpResult<()> {
+        let mut result = Ok(());
+        buf.reset();
+        buf.push_sequence(|buf| {
+            buf.push_constructed(snmp::MSG_TRAP_V1, |buf| {
+                result = buf.push_owned_varbinds(values);
+                buf.push_timeticks(timestamp);
+                buf.push_integer(specific_trap);
+                buf.push_integer(generic_trap);
+                buf.push_ipaddress(agent_addr);
+                if let Err(err) = buf.push_object_identifier(enterprise) {
+                    result = Err(err);
+                }
+            });
+            buf.push_octet_string(community);
+            buf.push_integer(snmp::VERSION_1);
+        });
+        result
     }
 }
 
@@ -526,8 +933,82 @@ fn decode_i64(i: &[u8]) -> SnmpResult<i64> {
     Ok(ret)
 }
 
+/// Names accepted by session methods: numeric OIDs, and symbolic names
+/// such as `IF-MIB::ifDescr.1` or `ifHCInOctets.5` when a MIB tree is
+/// loaded. OIDs that cannot be encoded fail with `ValueOutOfRange`.
+pub trait IntoOid {
+    fn into_oid(self, mibs: Option<&mib::MibTree>) -> SnmpResult<Vec<u32>>;
+}
+
+fn encodable(oid: Vec<u32>) -> SnmpResult<Vec<u32>> {
+    if is_encodable_oid(&oid) {
+        Ok(oid)
+    } else {
+        Err(SnmpError::ValueOutOfRange)
+    }
+}
+
+impl IntoOid for &[u32] {
+    fn into_oid(self, _: Option<&mib::MibTree>) -> SnmpResult<Vec<u32>> {
+        encodable(self.to_vec())
+    }
+}
+
+impl<const N: usize> IntoOid for &[u32; N] {
+    fn into_oid(self, _: Option<&mib::MibTree>) -> SnmpResult<Vec<u32>> {
+        encodable(self.to_vec())
+    }
+}
+
+impl IntoOid for Vec<u32> {
+    fn into_oid(self, _: Option<&mib::MibTree>) -> SnmpResult<Vec<u32>> {
+        encodable(self)
+    }
+}
+
+impl IntoOid for &Vec<u32> {
+    fn into_oid(self, _: Option<&mib::MibTree>) -> SnmpResult<Vec<u32>> {
+        encodable(self.clone())
+    }
+}
+
+/// Without a MIB tree, only numeric OIDs are accepted, and with the `mibs`
+/// feature the names of the bundled modules.
+impl IntoOid for &str {
+    fn into_oid(self, mibs: Option<&mib::MibTree>) -> SnmpResult<Vec<u32>> {
+        let unknown = || SnmpError::UnknownName(self.to_string());
+        if let Some(mibs) = mibs {
+            return mibs.resolve(self).map_err(|_| unknown());
+        }
+        let numeric: Result<Vec<u32>, _> = self
+            .trim_start_matches('.')
+            .split('.')
+            .map(str::parse)
+            .collect();
+        match numeric {
+            Ok(oid) => encodable(oid),
+            #[cfg(feature = "mibs")]
+            Err(_) => mib::bundled::resolve(self).ok_or_else(unknown),
+            #[cfg(not(feature = "mibs"))]
+            Err(_) => Err(unknown()),
+        }
+    }
+}
+
+impl IntoOid for &String {
+    fn into_oid(self, mibs: Option<&mib::MibTree>) -> SnmpResult<Vec<u32>> {
+        self.as_str().into_oid(mibs)
+    }
+}
+
+impl IntoOid for String {
+    fn into_oid(self, mibs: Option<&mib::MibTree>) -> SnmpResult<Vec<u32>> {
+        self.as_str().into_oid(mibs)
+    }
+}
+
 /// Wrapper around raw bytes representing an ASN.1 OBJECT IDENTIFIER.
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct ObjectIdentifier<'a> {
     inner: &'a [u8],
 }
@@ -544,10 +1025,13 @@ pub type ObjIdBuf = [u32; 128];
 impl<'a> fmt::Display for ObjectIdentifier<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut buf: [u32; 128] = [0; 128];
-        let mut first = true;
 
         match self.read_name(&mut buf) {
+            #[cfg(feature = "mibs")]
+            Ok(name) => f.write_str(&mib::bundled::format_oid(name)),
+            #[cfg(not(feature = "mibs"))]
             Ok(name) => {
+                let mut first = true;
                 for subid in name {
                     if first {
                         first = false;
@@ -626,6 +1110,26 @@ impl<'a> ObjectIdentifier<'a> {
     }
 }
 
+/// Whether `name` can be encoded as an object identifier: it has at least
+/// two arcs, the first at most 2 and the second below 40 under 0 and 1.
+pub fn is_encodable_oid(name: &[u32]) -> bool {
+    match *name {
+        [0 | 1, second, ..] => second < 40,
+        [2, second, ..] => second <= u32::MAX - 80,
+        _ => false,
+    }
+}
+
+/// Whether the name and, for object identifiers, the value of a varbind
+/// can be encoded.
+pub(crate) fn is_encodable_varbind(name: &[u32], value: &OwnedValue) -> bool {
+    let value_ok = match value {
+        OwnedValue::ObjectIdentifier(oid) => is_encodable_oid(oid),
+        _ => true,
+    };
+    is_encodable_oid(name) && value_ok
+}
+
 /// ASN.1/DER decoder iterator.
 ///
 /// Supports:
@@ -909,6 +1413,7 @@ impl<'a> AsnReader<'a> {
     // }
 }
 
+#[derive(Clone)]
 pub enum Value<'a> {
     Boolean(bool),
     Null,
@@ -946,7 +1451,10 @@ impl<'a> fmt::Debug for Value<'a> {
         match *self {
             Boolean(v) => write!(f, "BOOLEAN: {}", v),
             Integer(n) => write!(f, "INTEGER: {}", n),
-            OctetString(slice) => write!(f, "OCTET STRING: {}", String::from_utf8_lossy(slice)),
+            OctetString(slice) if mib::is_printable(slice) => {
+                write!(f, "OCTET STRING: {}", String::from_utf8_lossy(slice))
+            }
+            OctetString(slice) => write!(f, "OCTET STRING: {}", mib::hex(slice)),
             ObjectIdentifier(ref obj_id) => write!(f, "OBJECT IDENTIFIER: {}", obj_id),
             Null => write!(f, "NULL"),
             Sequence(ref val) => write!(f, "SEQUENCE: {:#?}", val),
@@ -976,6 +1484,65 @@ impl<'a> fmt::Debug for Value<'a> {
     }
 }
 
+/// Owned counterpart of the scalar `Value` variants.
+///
+/// Used where varbinds have to outlive the receive buffer they were decoded
+/// from, e.g. when notifications are queued, translated or forwarded.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OwnedValue {
+    Boolean(bool),
+    Null,
+    Integer(i64),
+    OctetString(Vec<u8>),
+    ObjectIdentifier(Vec<u32>),
+
+    IpAddress([u8; 4]),
+    Counter32(u32),
+    Unsigned32(u32),
+    Timeticks(u32),
+    Opaque(Vec<u8>),
+    Counter64(u64),
+
+    EndOfMibView,
+    NoSuchObject,
+    NoSuchInstance,
+}
+
+impl OwnedValue {
+    /// Copies a decoded `Value` out of its buffer.
+    ///
+    /// Returns `None` for constructed values and PDUs, which never appear as
+    /// varbind values, and for object identifiers that cannot be encoded
+    /// again.
+    pub fn from_value(value: &Value) -> Option<OwnedValue> {
+        use Value::*;
+        Some(match *value {
+            Boolean(b) => OwnedValue::Boolean(b),
+            Null => OwnedValue::Null,
+            Integer(i) => OwnedValue::Integer(i),
+            OctetString(ostr) => OwnedValue::OctetString(ostr.to_vec()),
+            ObjectIdentifier(ref objid) => {
+                let mut buf: ObjIdBuf = [0; 128];
+                let name = objid.read_name(&mut buf).ok()?;
+                if !is_encodable_oid(name) {
+                    return None;
+                }
+                OwnedValue::ObjectIdentifier(name.to_vec())
+            }
+            IpAddress(ip) => OwnedValue::IpAddress(ip),
+            Counter32(i) => OwnedValue::Counter32(i),
+            Unsigned32(i) => OwnedValue::Unsigned32(i),
+            Timeticks(tt) => OwnedValue::Timeticks(tt),
+            Opaque(bytes) => OwnedValue::Opaque(bytes.to_vec()),
+            Counter64(i) => OwnedValue::Counter64(i),
+            EndOfMibView => OwnedValue::EndOfMibView,
+            NoSuchObject => OwnedValue::NoSuchObject,
+            NoSuchInstance => OwnedValue::NoSuchInstance,
+            _ => return None,
+        })
+    }
+}
+
 impl<'a> Iterator for AsnReader<'a> {
     type Item = Value<'a>;
 
@@ -1000,6 +1567,9 @@ impl<'a> Iterator for AsnReader<'a> {
                 snmp::TYPE_TIMETICKS => self.read_snmp_timeticks().map(Timeticks),
                 snmp::TYPE_OPAQUE => self.read_snmp_opaque().map(Opaque),
                 snmp::TYPE_COUNTER64 => self.read_snmp_counter64().map(Counter64),
+                snmp::SNMP_NOSUCHOBJECT => self.read_raw(ident).map(|_| NoSuchObject),
+                snmp::SNMP_NOSUCHINSTANCE => self.read_raw(ident).map(|_| NoSuchInstance),
+                snmp::SNMP_ENDOFMIBVIEW => self.read_raw(ident).map(|_| EndOfMibView),
                 snmp::MSG_GET => self
                     .read_raw(ident)
                     .map(|v| SnmpGetRequest(AsnReader::from_bytes(v))),
@@ -1050,17 +1620,35 @@ pub struct SnmpPdu<'a> {
 
 impl<'a> SnmpPdu<'a> {
     pub fn from_bytes(bytes: &'a [u8]) -> SnmpResult<SnmpPdu<'a>> {
+        SnmpPdu::from_bytes_with_version(bytes, snmp::VERSION_2)
+    }
+
+    /// Like `from_bytes`, for messages of `expected` version. SNMPv1 PDUs
+    /// other than the Trap-PDU share the SNMPv2 layout.
+    pub(crate) fn from_bytes_with_version(
+        bytes: &'a [u8],
+        expected: i64,
+    ) -> SnmpResult<SnmpPdu<'a>> {
         let seq = AsnReader::from_bytes(bytes).read_raw(asn1::TYPE_SEQUENCE)?;
         let mut rdr = AsnReader::from_bytes(seq);
 
         let version = rdr.read_asn_integer()?;
 
-        if version != snmp::VERSION_2 {
+        if version != expected {
             return Err(SnmpError::UnsupportedVersion);
         }
 
         let community = rdr.read_asn_octetstring()?;
 
+        SnmpPdu::read_pdu(&mut rdr, version, community)
+    }
+
+    /// Reads the PDU that follows the header of a message.
+    fn read_pdu(
+        rdr: &mut AsnReader<'a>,
+        version: i64,
+        community: &'a [u8],
+    ) -> SnmpResult<SnmpPdu<'a>> {
         let ident = rdr.peek_byte()?;
         let message_type = SnmpMessageType::from_ident(ident)?;
 
@@ -1094,6 +1682,173 @@ impl<'a> SnmpPdu<'a> {
             varbinds,
         })
     }
+
+    /// Copies the varbinds out of the message buffer.
+    pub fn owned_varbinds(&self) -> SnmpResult<Vec<(Vec<u32>, OwnedValue)>> {
+        notification::owned_varbinds(self.varbinds.clone())
+    }
+}
+
+/// The header of an SNMPv3 message (RFC 3412) with the security parameters
+/// of the User-based Security Model (RFC 3414).
+#[derive(Clone, Debug, PartialEq)]
+pub struct V3Header<'a> {
+    pub msg_id: i32,
+    pub max_size: i32,
+    pub flags: u8,
+    pub engine_id: &'a [u8],
+    pub engine_boots: i32,
+    pub engine_time: i32,
+    pub user_name: &'a [u8],
+}
+
+/// An SNMPv3 message. Authentication and privacy parameters are not
+/// checked; the crate only processes noAuthNoPriv messages.
+#[derive(Debug)]
+pub struct SnmpV3Message<'a> {
+    pub header: V3Header<'a>,
+    data: &'a [u8],
+}
+
+/// The plaintext ScopedPDU of an SNMPv3 message.
+#[derive(Debug)]
+pub struct ScopedPdu<'a> {
+    pub context_engine_id: &'a [u8],
+    pub context_name: &'a [u8],
+    pub pdu: SnmpPdu<'a>,
+}
+
+impl<'a> SnmpV3Message<'a> {
+    /// Decodes the header of an SNMPv3 message. Fails with
+    /// `UnknownSecurityModel` for security models other than USM.
+    pub fn from_bytes(bytes: &'a [u8]) -> SnmpResult<SnmpV3Message<'a>> {
+        let seq = AsnReader::from_bytes(bytes).read_raw(asn1::TYPE_SEQUENCE)?;
+        let mut rdr = AsnReader::from_bytes(seq);
+
+        if rdr.read_asn_integer()? != snmp::VERSION_3 {
+            return Err(SnmpError::UnsupportedVersion);
+        }
+
+        let mut global = AsnReader::from_bytes(rdr.read_raw(asn1::TYPE_SEQUENCE)?);
+        let msg_id = read_i32(&mut global, 0)?;
+        // RFC 3412: msgMaxSize is at least 484
+        let max_size = read_i32(&mut global, 484)?;
+        let flags = match global.read_asn_octetstring()? {
+            &[flags] => flags,
+            _ => return Err(SnmpError::AsnInvalidLen),
+        };
+        if global.read_asn_integer()? != snmp::SECURITY_MODEL_USM {
+            return Err(SnmpError::UnknownSecurityModel);
+        }
+        // privacy without authentication
+        if flags & (snmp::MSG_FLAG_AUTH | snmp::MSG_FLAG_PRIV) == snmp::MSG_FLAG_PRIV {
+            return Err(SnmpError::AsnParseError);
+        }
+
+        let params = rdr.read_asn_octetstring()?;
+        let mut usm =
+            AsnReader::from_bytes(AsnReader::from_bytes(params).read_raw(asn1::TYPE_SEQUENCE)?);
+        let engine_id = usm.read_asn_octetstring()?;
+        let engine_boots = read_i32(&mut usm, 0)?;
+        let engine_time = read_i32(&mut usm, 0)?;
+        let user_name = usm.read_asn_octetstring()?;
+        usm.read_asn_octetstring()?; // msgAuthenticationParameters
+        usm.read_asn_octetstring()?; // msgPrivacyParameters
+
+        Ok(SnmpV3Message {
+            header: V3Header {
+                msg_id,
+                max_size,
+                flags,
+                engine_id,
+                engine_boots,
+                engine_time,
+                user_name,
+            },
+            data: rdr.inner,
+        })
+    }
+
+    /// Decodes the ScopedPDU, which fails for encrypted messages.
+    pub fn scoped_pdu(&self) -> SnmpResult<ScopedPdu<'a>> {
+        if self.header.flags & snmp::MSG_FLAG_PRIV != 0 {
+            return Err(SnmpError::AsnWrongType);
+        }
+        let seq = AsnReader::from_bytes(self.data).read_raw(asn1::TYPE_SEQUENCE)?;
+        let mut rdr = AsnReader::from_bytes(seq);
+        let context_engine_id = rdr.read_asn_octetstring()?;
+        let context_name = rdr.read_asn_octetstring()?;
+        let pdu = SnmpPdu::read_pdu(&mut rdr, snmp::VERSION_3, b"")?;
+        Ok(ScopedPdu {
+            context_engine_id,
+            context_name,
+            pdu,
+        })
+    }
+}
+
+/// Reads an INTEGER of the range `min..=i32::MAX`.
+fn read_i32(rdr: &mut AsnReader, min: i32) -> SnmpResult<i32> {
+    let n = rdr.read_asn_integer()?;
+    if n < min as i64 || n > i32::MAX as i64 {
+        return Err(SnmpError::ValueOutOfRange);
+    }
+    Ok(n as i32)
+}
+
+/// SNMPv1 Trap-PDU (RFC 1157), which has its own layout instead of the
+/// request-id/error-status/error-index header shared by all other PDUs.
+#[derive(Debug)]
+pub struct SnmpTrapV1Pdu<'a> {
+    pub community: &'a [u8],
+    pub enterprise: ObjectIdentifier<'a>,
+    pub agent_addr: [u8; 4],
+    pub generic_trap: i64,
+    pub specific_trap: i64,
+    pub timestamp: u32,
+    pub varbinds: Varbinds<'a>,
+}
+
+impl<'a> SnmpTrapV1Pdu<'a> {
+    pub fn from_bytes(bytes: &'a [u8]) -> SnmpResult<SnmpTrapV1Pdu<'a>> {
+        let seq = AsnReader::from_bytes(bytes).read_raw(asn1::TYPE_SEQUENCE)?;
+        let mut rdr = AsnReader::from_bytes(seq);
+
+        let version = rdr.read_asn_integer()?;
+
+        if version != snmp::VERSION_1 {
+            return Err(SnmpError::UnsupportedVersion);
+        }
+
+        let community = rdr.read_asn_octetstring()?;
+
+        let mut trap_pdu = AsnReader::from_bytes(rdr.read_raw(snmp::MSG_TRAP_V1)?);
+
+        let enterprise = trap_pdu.read_asn_objectidentifier()?;
+        let agent_addr = trap_pdu.read_snmp_ipaddress()?;
+        let generic_trap = trap_pdu.read_asn_integer()?;
+        let specific_trap = trap_pdu.read_asn_integer()?;
+        let timestamp = trap_pdu.read_snmp_timeticks()?;
+
+        let varbind_bytes = trap_pdu.read_raw(asn1::TYPE_SEQUENCE)?;
+        let varbinds = Varbinds::from_bytes(varbind_bytes);
+
+        Ok(SnmpTrapV1Pdu {
+            community,
+            enterprise,
+            agent_addr,
+            generic_trap,
+            specific_trap,
+            timestamp,
+            varbinds,
+        })
+    }
+}
+
+/// Reads the version field of an SNMP message without decoding the PDU.
+pub fn peek_version(bytes: &[u8]) -> SnmpResult<i64> {
+    let seq = AsnReader::from_bytes(bytes).read_raw(asn1::TYPE_SEQUENCE)?;
+    AsnReader::from_bytes(seq).read_asn_integer()
 }
 
 #[derive(Debug, PartialEq)]
@@ -0,0 +1,254 @@
+//! Browsing a loaded tree: children of a node, the contents of a module and
+//! name search, with JSON output for user interfaces.
+
+use std::fmt::Write;
+
+use super::tree::{MibTree, Node, NodeKind, Syntax};
+use super::MibError;
+
+impl MibTree {
+    /// The nodes whose closest named ancestor is `oid`, in OID order.
+    pub fn children<'a>(&'a self, oid: &'a [u32]) -> impl Iterator<Item = &'a Node> + 'a {
+        let mut last: Option<&[u32]> = None;
+        self.subtree(oid).filter(move |node| {
+            if last.is_some_and(|last| node.oid.starts_with(last)) {
+                return false;
+            }
+            last = Some(&node.oid);
+            true
+        })
+    }
+
+    /// The closest named ancestor of `oid`.
+    pub fn parent(&self, oid: &[u32]) -> Option<&Node> {
+        let (_, ancestor) = oid.split_last()?;
+        self.lookup(ancestor).map(|(node, _)| node)
+    }
+
+    /// The nodes defined by a module, in OID order.
+    pub fn module_nodes<'a>(&'a self, module: &'a str) -> impl Iterator<Item = &'a Node> + 'a {
+        self.nodes().filter(move |node| node.module == module)
+    }
+
+    /// The notifications and SMIv1 traps defined by a module.
+    pub fn notifications<'a>(&'a self, module: &'a str) -> impl Iterator<Item = &'a Node> + 'a {
+        self.module_nodes(module)
+            .filter(|node| matches!(node.kind, NodeKind::Notification | NodeKind::Trap))
+    }
+
+    /// The columns of a table, given the table, its row or a column.
+    pub fn columns(&self, name: &str) -> Result<Vec<&Node>, MibError> {
+        let unknown = || MibError::UnknownName(name.to_string());
+        let node = self.node(name).ok_or_else(unknown)?;
+        let row = match node.kind {
+            NodeKind::Table => [&node.oid[..], &[1]].concat(),
+            NodeKind::Row => node.oid.clone(),
+            NodeKind::Column => node.oid[..node.oid.len() - 1].to_vec(),
+            _ => return Err(unknown()),
+        };
+        Ok(self
+            .subtree(&row)
+            .filter(|node| node.kind == NodeKind::Column)
+            .collect())
+    }
+
+    /// Nodes whose names match `query`, best matches first: exact names,
+    /// then prefixes, substrings and names containing the characters of
+    /// `query` in order, ignoring case. `MODULE::query` searches only that
+    /// module.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&Node> {
+        let (module, query) = match query.split_once("::") {
+            Some((module, query)) => (Some(module), query),
+            None => (None, query),
+        };
+        let query = query.to_lowercase();
+        let mut hits: Vec<((u8, usize), &Node)> = self
+            .nodes()
+            .filter(|node| module.is_none_or(|module| node.module == module))
+            .filter_map(|node| match_score(&query, &node.name).map(|score| (score, node)))
+            .collect();
+        hits.sort_by(|(a, x), (b, y)| {
+            a.cmp(b)
+                .then_with(|| x.name.len().cmp(&y.name.len()))
+                .then_with(|| x.oid.cmp(&y.oid))
+        });
+        hits.into_iter().take(limit).map(|(_, node)| node).collect()
+    }
+}
+
+/// How well `name` matches the lowercase `query`, lower being better, as
+/// the kind of match and the characters skipped.
+fn match_score(query: &str, name: &str) -> Option<(u8, usize)> {
+    let name = name.to_lowercase();
+    if name == query {
+        return Some((0, 0));
+    }
+    if name.starts_with(query) {
+        return Some((1, 0));
+    }
+    if let Some(at) = name.find(query) {
+        return Some((2, at));
+    }
+    // a subsequence, scored by the characters between the first and last
+    // matched ones
+    let mut chars = name.char_indices();
+    let mut first = None;
+    let mut last = 0;
+    for wanted in query.chars() {
+        let (at, _) = chars.find(|&(_, c)| c == wanted)?;
+        first.get_or_insert(at);
+        last = at;
+    }
+    Some((3, last - first.unwrap_or(0) + 1 - query.chars().count()))
+}
+
+impl Node {
+    /// The node as a JSON object. The OID is dotted text, and clauses the
+    /// definition does not have are `null` or empty. Numbers that a
+    /// JavaScript number cannot hold exactly, such as the bounds `MIN` and
+    /// `MAX` stand for, are strings.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let oid: Vec<String> = self.oid.iter().map(|subid| subid.to_string()).collect();
+        out.push('{');
+        field(out, "name", &self.name);
+        field(out, "module", &self.module);
+        field(out, "oid", &oid.join("."));
+        field(out, "kind", self.kind.name());
+        out.push_str("\"syntax\":");
+        match self.syntax {
+            Some(ref syntax) => syntax.write_json(out),
+            None => out.push_str("null"),
+        }
+        out.push(',');
+        optional_field(out, "access", self.access.map(|access| access.name()));
+        optional_field(out, "status", self.status.map(|status| status.name()));
+        optional_field(out, "description", self.description.as_deref());
+        optional_field(out, "reference", self.reference.as_deref());
+        optional_field(out, "units", self.units.as_deref());
+        out.push_str("\"index\":[");
+        for (i, part) in self.index.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push('{');
+            field(out, "name", &part.name);
+            let _ = write!(out, "\"implied\":{}}}", part.implied);
+        }
+        out.push_str("],");
+        optional_field(out, "augments", self.augments.as_deref());
+        optional_field(out, "defval", self.defval.as_deref());
+        out.push_str("\"objects\":[");
+        for (i, object) in self.objects.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            string(out, object);
+        }
+        let _ = write!(out, "],\"line\":{}}}", self.line);
+    }
+}
+
+impl Syntax {
+    fn write_json(&self, out: &mut String) {
+        out.push('{');
+        field(out, "base", self.base.name());
+        optional_field(out, "tc", self.tc.as_deref());
+        optional_field(out, "displayHint", self.display_hint.as_deref());
+        out.push_str("\"enums\":{");
+        for (i, (label, value)) in self.enums.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            string(out, label);
+            out.push(':');
+            number(out, *value as i128);
+        }
+        out.push_str("},\"ranges\":");
+        ranges(out, &self.ranges);
+        out.push_str(",\"sizes\":");
+        ranges(out, &self.sizes);
+        out.push('}');
+    }
+}
+
+/// A JSON array of nodes, e.g. of the results of `MibTree::search`.
+pub fn nodes_to_json<'a, I>(nodes: I) -> String
+where
+    I: IntoIterator<Item = &'a Node>,
+{
+    let mut out = "[".to_string();
+    for (i, node) in nodes.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        node.write_json(&mut out);
+    }
+    out.push(']');
+    out
+}
+
+fn field(out: &mut String, name: &str, value: &str) {
+    optional_field(out, name, Some(value));
+}
+
+fn optional_field(out: &mut String, name: &str, value: Option<&str>) {
+    string(out, name);
+    out.push(':');
+    match value {
+        Some(value) => string(out, value),
+        None => out.push_str("null"),
+    }
+    out.push(',');
+}
+
+fn ranges(out: &mut String, ranges: &[(i128, i128)]) {
+    out.push('[');
+    for (i, (min, max)) in ranges.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('[');
+        number(out, *min);
+        out.push(',');
+        number(out, *max);
+        out.push(']');
+    }
+    out.push(']');
+}
+
+/// The largest integer a JavaScript number holds exactly.
+const MAX_SAFE_INTEGER: i128 = (1 << 53) - 1;
+
+/// A JSON number, or a string beyond `MAX_SAFE_INTEGER`.
+fn number(out: &mut String, value: i128) {
+    if value.abs() <= MAX_SAFE_INTEGER {
+        let _ = write!(out, "{}", value);
+    } else {
+        let _ = write!(out, "\"{}\"", value);
+    }
+}
+
+/// A JSON string literal.
+fn string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,1775 @@
+//! Names from the common IETF modules, compiled into the crate with the
+//! `mibs` feature so that OIDs can be shown symbolically without loading
+//! any MIB files.
+//!
+//! Only the OID assignments are kept: a module index and a name per node,
+//! sorted by OID. Use a `MibTree` for syntax, descriptions and other
+//! modules.
+
+pub const MODULES: &[&str] = &[
+    "SNMPv2-SMI",
+    "SNMPv2-MIB",
+    "IF-MIB",
+    "IP-MIB",
+    "HOST-RESOURCES-MIB",
+    "ENTITY-MIB",
+    "BRIDGE-MIB",
+    "LLDP-MIB",
+    "SNMP-TARGET-MIB",
+    "SNMP-NOTIFICATION-MIB",
+    "SNMP-COMMUNITY-MIB",
+    "NOTIFICATION-LOG-MIB",
+];
+
+/// (module, name, OID), sorted by OID.
+static NODES: &[(u8, &str, &[u32])] = &[
+    (0, "zeroDotZero", &[0, 0]),
+    (0, "iso", &[1]),
+    (7, "lldpMIB", &[1, 0, 8802, 1, 1, 2]),
+    (7, "lldpNotifications", &[1, 0, 8802, 1, 1, 2, 0]),
+    (7, "lldpNotificationPrefix", &[1, 0, 8802, 1, 1, 2, 0, 0]),
+    (7, "lldpRemTablesChange", &[1, 0, 8802, 1, 1, 2, 0, 0, 1]),
+    (7, "lldpObjects", &[1, 0, 8802, 1, 1, 2, 1]),
+    (7, "lldpConfiguration", &[1, 0, 8802, 1, 1, 2, 1, 1]),
+    (7, "lldpMessageTxInterval", &[1, 0, 8802, 1, 1, 2, 1, 1, 1]),
+    (
+        7,
+        "lldpMessageTxHoldMultiplier",
+        &[1, 0, 8802, 1, 1, 2, 1, 1, 2],
+    ),
+    (7, "lldpReinitDelay", &[1, 0, 8802, 1, 1, 2, 1, 1, 3]),
+    (7, "lldpTxDelay", &[1, 0, 8802, 1, 1, 2, 1, 1, 4]),
+    (
+        7,
+        "lldpNotificationInterval",
+        &[1, 0, 8802, 1, 1, 2, 1, 1, 5],
+    ),
+    (7, "lldpPortConfigTable", &[1, 0, 8802, 1, 1, 2, 1, 1, 6]),
+    (7, "lldpPortConfigEntry", &[1, 0, 8802, 1, 1, 2, 1, 1, 6, 1]),
+    (
+        7,
+        "lldpPortConfigPortNum",
+        &[1, 0, 8802, 1, 1, 2, 1, 1, 6, 1, 1],
+    ),
+    (
+        7,
+        "lldpPortConfigAdminStatus",
+        &[1, 0, 8802, 1, 1, 2, 1, 1, 6, 1, 2],
+    ),
+    (
+        7,
+        "lldpPortConfigNotificationEnable",
+        &[1, 0, 8802, 1, 1, 2, 1, 1, 6, 1, 3],
+    ),
+    (
+        7,
+        "lldpPortConfigTLVsTxEnable",
+        &[1, 0, 8802, 1, 1, 2, 1, 1, 6, 1, 4],
+    ),
+    (7, "lldpConfigManAddrTable", &[1, 0, 8802, 1, 1, 2, 1, 1, 7]),
+    (
+        7,
+        "lldpConfigManAddrEntry",
+        &[1, 0, 8802, 1, 1, 2, 1, 1, 7, 1],
+    ),
+    (
+        7,
+        "lldpConfigManAddrPortsTxEnable",
+        &[1, 0, 8802, 1, 1, 2, 1, 1, 7, 1, 1],
+    ),
+    (7, "lldpStatistics", &[1, 0, 8802, 1, 1, 2, 1, 2]),
+    (
+        7,
+        "lldpStatsRemTablesLastChangeTime",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 1],
+    ),
+    (
+        7,
+        "lldpStatsRemTablesInserts",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 2],
+    ),
+    (
+        7,
+        "lldpStatsRemTablesDeletes",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 3],
+    ),
+    (
+        7,
+        "lldpStatsRemTablesDrops",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 4],
+    ),
+    (
+        7,
+        "lldpStatsRemTablesAgeouts",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 5],
+    ),
+    (7, "lldpStatsTxPortTable", &[1, 0, 8802, 1, 1, 2, 1, 2, 6]),
+    (
+        7,
+        "lldpStatsTxPortEntry",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 6, 1],
+    ),
+    (
+        7,
+        "lldpStatsTxPortNum",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 6, 1, 1],
+    ),
+    (
+        7,
+        "lldpStatsTxPortFramesTotal",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 6, 1, 2],
+    ),
+    (7, "lldpStatsRxPortTable", &[1, 0, 8802, 1, 1, 2, 1, 2, 7]),
+    (
+        7,
+        "lldpStatsRxPortEntry",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 7, 1],
+    ),
+    (
+        7,
+        "lldpStatsRxPortNum",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 7, 1, 1],
+    ),
+    (
+        7,
+        "lldpStatsRxPortFramesDiscardedTotal",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 7, 1, 2],
+    ),
+    (
+        7,
+        "lldpStatsRxPortFramesErrors",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 7, 1, 3],
+    ),
+    (
+        7,
+        "lldpStatsRxPortFramesTotal",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 7, 1, 4],
+    ),
+    (
+        7,
+        "lldpStatsRxPortTLVsDiscardedTotal",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 7, 1, 5],
+    ),
+    (
+        7,
+        "lldpStatsRxPortTLVsUnrecognizedTotal",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 7, 1, 6],
+    ),
+    (
+        7,
+        "lldpStatsRxPortAgeoutsTotal",
+        &[1, 0, 8802, 1, 1, 2, 1, 2, 7, 1, 7],
+    ),
+    (7, "lldpLocalSystemData", &[1, 0, 8802, 1, 1, 2, 1, 3]),
+    (
+        7,
+        "lldpLocChassisIdSubtype",
+        &[1, 0, 8802, 1, 1, 2, 1, 3, 1],
+    ),
+    (7, "lldpLocChassisId", &[1, 0, 8802, 1, 1, 2, 1, 3, 2]),
+    (7, "lldpLocSysName", &[1, 0, 8802, 1, 1, 2, 1, 3, 3]),
+    (7, "lldpLocSysDesc", &[1, 0, 8802, 1, 1, 2, 1, 3, 4]),
+    (7, "lldpLocSysCapSupported", &[1, 0, 8802, 1, 1, 2, 1, 3, 5]),
+    (7, "lldpLocSysCapEnabled", &[1, 0, 8802, 1, 1, 2, 1, 3, 6]),
+    (7, "lldpLocPortTable", &[1, 0, 8802, 1, 1, 2, 1, 3, 7]),
+    (7, "lldpLocPortEntry", &[1, 0, 8802, 1, 1, 2, 1, 3, 7, 1]),
+    (7, "lldpLocPortNum", &[1, 0, 8802, 1, 1, 2, 1, 3, 7, 1, 1]),
+    (
+        7,
+        "lldpLocPortIdSubtype",
+        &[1, 0, 8802, 1, 1, 2, 1, 3, 7, 1, 2],
+    ),
+    (7, "lldpLocPortId", &[1, 0, 8802, 1, 1, 2, 1, 3, 7, 1, 3]),
+    (7, "lldpLocPortDesc", &[1, 0, 8802, 1, 1, 2, 1, 3, 7, 1, 4]),
+    (7, "lldpLocManAddrTable", &[1, 0, 8802, 1, 1, 2, 1, 3, 8]),
+    (7, "lldpLocManAddrEntry", &[1, 0, 8802, 1, 1, 2, 1, 3, 8, 1]),
+    (
+        7,
+        "lldpLocManAddrSubtype",
+        &[1, 0, 8802, 1, 1, 2, 1, 3, 8, 1, 1],
+    ),
+    (7, "lldpLocManAddr", &[1, 0, 8802, 1, 1, 2, 1, 3, 8, 1, 2]),
+    (
+        7,
+        "lldpLocManAddrLen",
+        &[1, 0, 8802, 1, 1, 2, 1, 3, 8, 1, 3],
+    ),
+    (
+        7,
+        "lldpLocManAddrIfSubtype",
+        &[1, 0, 8802, 1, 1, 2, 1, 3, 8, 1, 4],
+    ),
+    (
+        7,
+        "lldpLocManAddrIfId",
+        &[1, 0, 8802, 1, 1, 2, 1, 3, 8, 1, 5],
+    ),
+    (
+        7,
+        "lldpLocManAddrOID",
+        &[1, 0, 8802, 1, 1, 2, 1, 3, 8, 1, 6],
+    ),
+    (7, "lldpRemoteSystemsData", &[1, 0, 8802, 1, 1, 2, 1, 4]),
+    (7, "lldpRemTable", &[1, 0, 8802, 1, 1, 2, 1, 4, 1]),
+    (7, "lldpRemEntry", &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1]),
+    (7, "lldpRemTimeMark", &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 1]),
+    (
+        7,
+        "lldpRemLocalPortNum",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 2],
+    ),
+    (7, "lldpRemIndex", &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 3]),
+    (
+        7,
+        "lldpRemChassisIdSubtype",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 4],
+    ),
+    (7, "lldpRemChassisId", &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 5]),
+    (
+        7,
+        "lldpRemPortIdSubtype",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 6],
+    ),
+    (7, "lldpRemPortId", &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 7]),
+    (7, "lldpRemPortDesc", &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 8]),
+    (7, "lldpRemSysName", &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 9]),
+    (7, "lldpRemSysDesc", &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 10]),
+    (
+        7,
+        "lldpRemSysCapSupported",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 11],
+    ),
+    (
+        7,
+        "lldpRemSysCapEnabled",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 12],
+    ),
+    (7, "lldpRemManAddrTable", &[1, 0, 8802, 1, 1, 2, 1, 4, 2]),
+    (7, "lldpRemManAddrEntry", &[1, 0, 8802, 1, 1, 2, 1, 4, 2, 1]),
+    (
+        7,
+        "lldpRemManAddrSubtype",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 2, 1, 1],
+    ),
+    (7, "lldpRemManAddr", &[1, 0, 8802, 1, 1, 2, 1, 4, 2, 1, 2]),
+    (
+        7,
+        "lldpRemManAddrIfSubtype",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 2, 1, 3],
+    ),
+    (
+        7,
+        "lldpRemManAddrIfId",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 2, 1, 4],
+    ),
+    (
+        7,
+        "lldpRemManAddrOID",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 2, 1, 5],
+    ),
+    (7, "lldpRemUnknownTLVTable", &[1, 0, 8802, 1, 1, 2, 1, 4, 3]),
+    (
+        7,
+        "lldpRemUnknownTLVEntry",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 3, 1],
+    ),
+    (
+        7,
+        "lldpRemUnknownTLVType",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 3, 1, 1],
+    ),
+    (
+        7,
+        "lldpRemUnknownTLVInfo",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 3, 1, 2],
+    ),
+    (7, "lldpRemOrgDefInfoTable", &[1, 0, 8802, 1, 1, 2, 1, 4, 4]),
+    (
+        7,
+        "lldpRemOrgDefInfoEntry",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 4, 1],
+    ),
+    (
+        7,
+        "lldpRemOrgDefInfoOUI",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 4, 1, 1],
+    ),
+    (
+        7,
+        "lldpRemOrgDefInfoSubtype",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 4, 1, 2],
+    ),
+    (
+        7,
+        "lldpRemOrgDefInfoIndex",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 4, 1, 3],
+    ),
+    (
+        7,
+        "lldpRemOrgDefInfo",
+        &[1, 0, 8802, 1, 1, 2, 1, 4, 4, 1, 4],
+    ),
+    (7, "lldpExtensions", &[1, 0, 8802, 1, 1, 2, 1, 5]),
+    (7, "lldpConformance", &[1, 0, 8802, 1, 1, 2, 2]),
+    (0, "org", &[1, 3]),
+    (0, "dod", &[1, 3, 6]),
+    (0, "internet", &[1, 3, 6, 1]),
+    (0, "directory", &[1, 3, 6, 1, 1]),
+    (0, "mgmt", &[1, 3, 6, 1, 2]),
+    (0, "mib-2", &[1, 3, 6, 1, 2, 1]),
+    (1, "system", &[1, 3, 6, 1, 2, 1, 1]),
+    (1, "sysDescr", &[1, 3, 6, 1, 2, 1, 1, 1]),
+    (1, "sysObjectID", &[1, 3, 6, 1, 2, 1, 1, 2]),
+    (1, "sysUpTime", &[1, 3, 6, 1, 2, 1, 1, 3]),
+    (1, "sysContact", &[1, 3, 6, 1, 2, 1, 1, 4]),
+    (1, "sysName", &[1, 3, 6, 1, 2, 1, 1, 5]),
+    (1, "sysLocation", &[1, 3, 6, 1, 2, 1, 1, 6]),
+    (1, "sysServices", &[1, 3, 6, 1, 2, 1, 1, 7]),
+    (1, "sysORLastChange", &[1, 3, 6, 1, 2, 1, 1, 8]),
+    (1, "sysORTable", &[1, 3, 6, 1, 2, 1, 1, 9]),
+    (1, "sysOREntry", &[1, 3, 6, 1, 2, 1, 1, 9, 1]),
+    (1, "sysORIndex", &[1, 3, 6, 1, 2, 1, 1, 9, 1, 1]),
+    (1, "sysORID", &[1, 3, 6, 1, 2, 1, 1, 9, 1, 2]),
+    (1, "sysORDescr", &[1, 3, 6, 1, 2, 1, 1, 9, 1, 3]),
+    (1, "sysORUpTime", &[1, 3, 6, 1, 2, 1, 1, 9, 1, 4]),
+    (2, "interfaces", &[1, 3, 6, 1, 2, 1, 2]),
+    (2, "ifNumber", &[1, 3, 6, 1, 2, 1, 2, 1]),
+    (2, "ifTable", &[1, 3, 6, 1, 2, 1, 2, 2]),
+    (2, "ifEntry", &[1, 3, 6, 1, 2, 1, 2, 2, 1]),
+    (2, "ifIndex", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 1]),
+    (2, "ifDescr", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 2]),
+    (2, "ifType", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 3]),
+    (2, "ifMtu", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 4]),
+    (2, "ifSpeed", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 5]),
+    (2, "ifPhysAddress", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 6]),
+    (2, "ifAdminStatus", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 7]),
+    (2, "ifOperStatus", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 8]),
+    (2, "ifLastChange", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 9]),
+    (2, "ifInOctets", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 10]),
+    (2, "ifInUcastPkts", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 11]),
+    (2, "ifInNUcastPkts", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 12]),
+    (2, "ifInDiscards", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 13]),
+    (2, "ifInErrors", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 14]),
+    (2, "ifInUnknownProtos", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 15]),
+    (2, "ifOutOctets", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 16]),
+    (2, "ifOutUcastPkts", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 17]),
+    (2, "ifOutNUcastPkts", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 18]),
+    (2, "ifOutDiscards", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 19]),
+    (2, "ifOutErrors", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 20]),
+    (2, "ifOutQLen", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 21]),
+    (2, "ifSpecific", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 22]),
+    (3, "ip", &[1, 3, 6, 1, 2, 1, 4]),
+    (3, "ipForwarding", &[1, 3, 6, 1, 2, 1, 4, 1]),
+    (3, "ipDefaultTTL", &[1, 3, 6, 1, 2, 1, 4, 2]),
+    (3, "ipInReceives", &[1, 3, 6, 1, 2, 1, 4, 3]),
+    (3, "ipInHdrErrors", &[1, 3, 6, 1, 2, 1, 4, 4]),
+    (3, "ipInAddrErrors", &[1, 3, 6, 1, 2, 1, 4, 5]),
+    (3, "ipForwDatagrams", &[1, 3, 6, 1, 2, 1, 4, 6]),
+    (3, "ipInUnknownProtos", &[1, 3, 6, 1, 2, 1, 4, 7]),
+    (3, "ipInDiscards", &[1, 3, 6, 1, 2, 1, 4, 8]),
+    (3, "ipInDelivers", &[1, 3, 6, 1, 2, 1, 4, 9]),
+    (3, "ipOutRequests", &[1, 3, 6, 1, 2, 1, 4, 10]),
+    (3, "ipOutDiscards", &[1, 3, 6, 1, 2, 1, 4, 11]),
+    (3, "ipOutNoRoutes", &[1, 3, 6, 1, 2, 1, 4, 12]),
+    (3, "ipReasmTimeout", &[1, 3, 6, 1, 2, 1, 4, 13]),
+    (3, "ipReasmReqds", &[1, 3, 6, 1, 2, 1, 4, 14]),
+    (3, "ipReasmOKs", &[1, 3, 6, 1, 2, 1, 4, 15]),
+    (3, "ipReasmFails", &[1, 3, 6, 1, 2, 1, 4, 16]),
+    (3, "ipFragOKs", &[1, 3, 6, 1, 2, 1, 4, 17]),
+    (3, "ipFragFails", &[1, 3, 6, 1, 2, 1, 4, 18]),
+    (3, "ipFragCreates", &[1, 3, 6, 1, 2, 1, 4, 19]),
+    (3, "ipAddrTable", &[1, 3, 6, 1, 2, 1, 4, 20]),
+    (3, "ipAddrEntry", &[1, 3, 6, 1, 2, 1, 4, 20, 1]),
+    (3, "ipAdEntAddr", &[1, 3, 6, 1, 2, 1, 4, 20, 1, 1]),
+    (3, "ipAdEntIfIndex", &[1, 3, 6, 1, 2, 1, 4, 20, 1, 2]),
+    (3, "ipAdEntNetMask", &[1, 3, 6, 1, 2, 1, 4, 20, 1, 3]),
+    (3, "ipAdEntBcastAddr", &[1, 3, 6, 1, 2, 1, 4, 20, 1, 4]),
+    (3, "ipAdEntReasmMaxSize", &[1, 3, 6, 1, 2, 1, 4, 20, 1, 5]),
+    (3, "ipNetToMediaTable", &[1, 3, 6, 1, 2, 1, 4, 22]),
+    (3, "ipNetToMediaEntry", &[1, 3, 6, 1, 2, 1, 4, 22, 1]),
+    (3, "ipNetToMediaIfIndex", &[1, 3, 6, 1, 2, 1, 4, 22, 1, 1]),
+    (
+        3,
+        "ipNetToMediaPhysAddress",
+        &[1, 3, 6, 1, 2, 1, 4, 22, 1, 2],
+    ),
+    (
+        3,
+        "ipNetToMediaNetAddress",
+        &[1, 3, 6, 1, 2, 1, 4, 22, 1, 3],
+    ),
+    (3, "ipNetToMediaType", &[1, 3, 6, 1, 2, 1, 4, 22, 1, 4]),
+    (3, "ipRoutingDiscards", &[1, 3, 6, 1, 2, 1, 4, 23]),
+    (3, "ipv6IpForwarding", &[1, 3, 6, 1, 2, 1, 4, 25]),
+    (3, "ipv6IpDefaultHopLimit", &[1, 3, 6, 1, 2, 1, 4, 26]),
+    (
+        3,
+        "ipv4InterfaceTableLastChange",
+        &[1, 3, 6, 1, 2, 1, 4, 27],
+    ),
+    (3, "ipv4InterfaceTable", &[1, 3, 6, 1, 2, 1, 4, 28]),
+    (3, "ipv4InterfaceEntry", &[1, 3, 6, 1, 2, 1, 4, 28, 1]),
+    (3, "ipv4InterfaceIfIndex", &[1, 3, 6, 1, 2, 1, 4, 28, 1, 1]),
+    (
+        3,
+        "ipv4InterfaceReasmMaxSize",
+        &[1, 3, 6, 1, 2, 1, 4, 28, 1, 2],
+    ),
+    (
+        3,
+        "ipv4InterfaceEnableStatus",
+        &[1, 3, 6, 1, 2, 1, 4, 28, 1, 3],
+    ),
+    (
+        3,
+        "ipv4InterfaceRetransmitTime",
+        &[1, 3, 6, 1, 2, 1, 4, 28, 1, 4],
+    ),
+    (
+        3,
+        "ipv6InterfaceTableLastChange",
+        &[1, 3, 6, 1, 2, 1, 4, 29],
+    ),
+    (3, "ipv6InterfaceTable", &[1, 3, 6, 1, 2, 1, 4, 30]),
+    (3, "ipv6InterfaceEntry", &[1, 3, 6, 1, 2, 1, 4, 30, 1]),
+    (3, "ipv6InterfaceIfIndex", &[1, 3, 6, 1, 2, 1, 4, 30, 1, 1]),
+    (
+        3,
+        "ipv6InterfaceReasmMaxSize",
+        &[1, 3, 6, 1, 2, 1, 4, 30, 1, 2],
+    ),
+    (
+        3,
+        "ipv6InterfaceIdentifier",
+        &[1, 3, 6, 1, 2, 1, 4, 30, 1, 3],
+    ),
+    (
+        3,
+        "ipv6InterfaceEnableStatus",
+        &[1, 3, 6, 1, 2, 1, 4, 30, 1, 5],
+    ),
+    (
+        3,
+        "ipv6InterfaceReachableTime",
+        &[1, 3, 6, 1, 2, 1, 4, 30, 1, 6],
+    ),
+    (
+        3,
+        "ipv6InterfaceRetransmitTime",
+        &[1, 3, 6, 1, 2, 1, 4, 30, 1, 7],
+    ),
+    (
+        3,
+        "ipv6InterfaceForwarding",
+        &[1, 3, 6, 1, 2, 1, 4, 30, 1, 8],
+    ),
+    (3, "ipTrafficStats", &[1, 3, 6, 1, 2, 1, 4, 31]),
+    (3, "ipSystemStatsTable", &[1, 3, 6, 1, 2, 1, 4, 31, 1]),
+    (3, "ipSystemStatsEntry", &[1, 3, 6, 1, 2, 1, 4, 31, 1, 1]),
+    (3, "ipIfStatsTableLastChange", &[1, 3, 6, 1, 2, 1, 4, 31, 2]),
+    (3, "ipIfStatsTable", &[1, 3, 6, 1, 2, 1, 4, 31, 3]),
+    (3, "ipIfStatsEntry", &[1, 3, 6, 1, 2, 1, 4, 31, 3, 1]),
+    (3, "ipAddressPrefixTable", &[1, 3, 6, 1, 2, 1, 4, 32]),
+    (3, "ipAddressPrefixEntry", &[1, 3, 6, 1, 2, 1, 4, 32, 1]),
+    (
+        3,
+        "ipAddressPrefixIfIndex",
+        &[1, 3, 6, 1, 2, 1, 4, 32, 1, 1],
+    ),
+    (3, "ipAddressPrefixType", &[1, 3, 6, 1, 2, 1, 4, 32, 1, 2]),
+    (3, "ipAddressPrefixPrefix", &[1, 3, 6, 1, 2, 1, 4, 32, 1, 3]),
+    (3, "ipAddressPrefixLength", &[1, 3, 6, 1, 2, 1, 4, 32, 1, 4]),
+    (3, "ipAddressPrefixOrigin", &[1, 3, 6, 1, 2, 1, 4, 32, 1, 5]),
+    (
+        3,
+        "ipAddressPrefixOnLinkFlag",
+        &[1, 3, 6, 1, 2, 1, 4, 32, 1, 6],
+    ),
+    (
+        3,
+        "ipAddressPrefixAutonomousFlag",
+        &[1, 3, 6, 1, 2, 1, 4, 32, 1, 7],
+    ),
+    (
+        3,
+        "ipAddressPrefixAdvPreferredLifetime",
+        &[1, 3, 6, 1, 2, 1, 4, 32, 1, 8],
+    ),
+    (
+        3,
+        "ipAddressPrefixAdvValidLifetime",
+        &[1, 3, 6, 1, 2, 1, 4, 32, 1, 9],
+    ),
+    (3, "ipAddressSpinLock", &[1, 3, 6, 1, 2, 1, 4, 33]),
+    (3, "ipAddressTable", &[1, 3, 6, 1, 2, 1, 4, 34]),
+    (3, "ipAddressEntry", &[1, 3, 6, 1, 2, 1, 4, 34, 1]),
+    (3, "ipAddressAddrType", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 1]),
+    (3, "ipAddressAddr", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 2]),
+    (3, "ipAddressIfIndex", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 3]),
+    (3, "ipAddressType", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 4]),
+    (3, "ipAddressPrefix", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 5]),
+    (3, "ipAddressOrigin", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 6]),
+    (3, "ipAddressStatus", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 7]),
+    (3, "ipAddressCreated", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 8]),
+    (3, "ipAddressLastChanged", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 9]),
+    (3, "ipAddressRowStatus", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 10]),
+    (3, "ipAddressStorageType", &[1, 3, 6, 1, 2, 1, 4, 34, 1, 11]),
+    (3, "ipNetToPhysicalTable", &[1, 3, 6, 1, 2, 1, 4, 35]),
+    (3, "ipNetToPhysicalEntry", &[1, 3, 6, 1, 2, 1, 4, 35, 1]),
+    (
+        3,
+        "ipNetToPhysicalIfIndex",
+        &[1, 3, 6, 1, 2, 1, 4, 35, 1, 1],
+    ),
+    (
+        3,
+        "ipNetToPhysicalNetAddressType",
+        &[1, 3, 6, 1, 2, 1, 4, 35, 1, 2],
+    ),
+    (
+        3,
+        "ipNetToPhysicalNetAddress",
+        &[1, 3, 6, 1, 2, 1, 4, 35, 1, 3],
+    ),
+    (
+        3,
+        "ipNetToPhysicalPhysAddress",
+        &[1, 3, 6, 1, 2, 1, 4, 35, 1, 4],
+    ),
+    (
+        3,
+        "ipNetToPhysicalLastUpdated",
+        &[1, 3, 6, 1, 2, 1, 4, 35, 1, 5],
+    ),
+    (3, "ipNetToPhysicalType", &[1, 3, 6, 1, 2, 1, 4, 35, 1, 6]),
+    (3, "ipNetToPhysicalState", &[1, 3, 6, 1, 2, 1, 4, 35, 1, 7]),
+    (
+        3,
+        "ipNetToPhysicalRowStatus",
+        &[1, 3, 6, 1, 2, 1, 4, 35, 1, 8],
+    ),
+    (3, "ipv6ScopeZoneIndexTable", &[1, 3, 6, 1, 2, 1, 4, 36]),
+    (3, "ipv6ScopeZoneIndexEntry", &[1, 3, 6, 1, 2, 1, 4, 36, 1]),
+    (
+        3,
+        "ipv6ScopeZoneIndexIfIndex",
+        &[1, 3, 6, 1, 2, 1, 4, 36, 1, 1],
+    ),
+    (
+        3,
+        "ipv6ScopeZoneIndexLinkLocal",
+        &[1, 3, 6, 1, 2, 1, 4, 36, 1, 2],
+    ),
+    (3, "ipv6ScopeZoneIndex3", &[1, 3, 6, 1, 2, 1, 4, 36, 1, 3]),
+    (
+        3,
+        "ipv6ScopeZoneIndexAdminLocal",
+        &[1, 3, 6, 1, 2, 1, 4, 36, 1, 4],
+    ),
+    (
+        3,
+        "ipv6ScopeZoneIndexSiteLocal",
+        &[1, 3, 6, 1, 2, 1, 4, 36, 1, 5],
+    ),
+    (3, "ipv6ScopeZoneIndex6", &[1, 3, 6, 1, 2, 1, 4, 36, 1, 6]),
+    (3, "ipv6ScopeZoneIndex7", &[1, 3, 6, 1, 2, 1, 4, 36, 1, 7]),
+    (
+        3,
+        "ipv6ScopeZoneIndexOrganizationLocal",
+        &[1, 3, 6, 1, 2, 1, 4, 36, 1, 8],
+    ),
+    (3, "ipv6ScopeZoneIndex9", &[1, 3, 6, 1, 2, 1, 4, 36, 1, 9]),
+    (3, "ipv6ScopeZoneIndexA", &[1, 3, 6, 1, 2, 1, 4, 36, 1, 10]),
+    (3, "ipv6ScopeZoneIndexB", &[1, 3, 6, 1, 2, 1, 4, 36, 1, 11]),
+    (3, "ipv6ScopeZoneIndexC", &[1, 3, 6, 1, 2, 1, 4, 36, 1, 12]),
+    (3, "ipv6ScopeZoneIndexD", &[1, 3, 6, 1, 2, 1, 4, 36, 1, 13]),
+    (3, "ipDefaultRouterTable", &[1, 3, 6, 1, 2, 1, 4, 37]),
+    (3, "ipDefaultRouterEntry", &[1, 3, 6, 1, 2, 1, 4, 37, 1]),
+    (
+        3,
+        "ipDefaultRouterAddressType",
+        &[1, 3, 6, 1, 2, 1, 4, 37, 1, 1],
+    ),
+    (
+        3,
+        "ipDefaultRouterAddress",
+        &[1, 3, 6, 1, 2, 1, 4, 37, 1, 2],
+    ),
+    (
+        3,
+        "ipDefaultRouterIfIndex",
+        &[1, 3, 6, 1, 2, 1, 4, 37, 1, 3],
+    ),
+    (
+        3,
+        "ipDefaultRouterLifetime",
+        &[1, 3, 6, 1, 2, 1, 4, 37, 1, 4],
+    ),
+    (
+        3,
+        "ipDefaultRouterPreference",
+        &[1, 3, 6, 1, 2, 1, 4, 37, 1, 5],
+    ),
+    (3, "ipv6RouterAdvertSpinLock", &[1, 3, 6, 1, 2, 1, 4, 38]),
+    (3, "ipv6RouterAdvertTable", &[1, 3, 6, 1, 2, 1, 4, 39]),
+    (3, "ipv6RouterAdvertEntry", &[1, 3, 6, 1, 2, 1, 4, 39, 1]),
+    (
+        3,
+        "ipv6RouterAdvertIfIndex",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 1],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertSendAdverts",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 2],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertMaxInterval",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 3],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertMinInterval",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 4],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertManagedFlag",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 5],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertOtherConfigFlag",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 6],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertLinkMTU",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 7],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertReachableTime",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 8],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertRetransmitTime",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 9],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertCurHopLimit",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 10],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertDefaultLifetime",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 11],
+    ),
+    (
+        3,
+        "ipv6RouterAdvertRowStatus",
+        &[1, 3, 6, 1, 2, 1, 4, 39, 1, 12],
+    ),
+    (3, "icmp", &[1, 3, 6, 1, 2, 1, 5]),
+    (3, "icmpInMsgs", &[1, 3, 6, 1, 2, 1, 5, 1]),
+    (3, "icmpInErrors", &[1, 3, 6, 1, 2, 1, 5, 2]),
+    (3, "icmpInDestUnreachs", &[1, 3, 6, 1, 2, 1, 5, 3]),
+    (3, "icmpInTimeExcds", &[1, 3, 6, 1, 2, 1, 5, 4]),
+    (3, "icmpInParmProbs", &[1, 3, 6, 1, 2, 1, 5, 5]),
+    (3, "icmpInSrcQuenchs", &[1, 3, 6, 1, 2, 1, 5, 6]),
+    (3, "icmpInRedirects", &[1, 3, 6, 1, 2, 1, 5, 7]),
+    (3, "icmpInEchos", &[1, 3, 6, 1, 2, 1, 5, 8]),
+    (3, "icmpInEchoReps", &[1, 3, 6, 1, 2, 1, 5, 9]),
+    (3, "icmpInTimestamps", &[1, 3, 6, 1, 2, 1, 5, 10]),
+    (3, "icmpInTimestampReps", &[1, 3, 6, 1, 2, 1, 5, 11]),
+    (3, "icmpInAddrMasks", &[1, 3, 6, 1, 2, 1, 5, 12]),
+    (3, "icmpInAddrMaskReps", &[1, 3, 6, 1, 2, 1, 5, 13]),
+    (3, "icmpOutMsgs", &[1, 3, 6, 1, 2, 1, 5, 14]),
+    (3, "icmpOutErrors", &[1, 3, 6, 1, 2, 1, 5, 15]),
+    (3, "icmpOutDestUnreachs", &[1, 3, 6, 1, 2, 1, 5, 16]),
+    (3, "icmpOutTimeExcds", &[1, 3, 6, 1, 2, 1, 5, 17]),
+    (3, "icmpOutParmProbs", &[1, 3, 6, 1, 2, 1, 5, 18]),
+    (3, "icmpOutSrcQuenchs", &[1, 3, 6, 1, 2, 1, 5, 19]),
+    (3, "icmpOutRedirects", &[1, 3, 6, 1, 2, 1, 5, 20]),
+    (3, "icmpOutEchos", &[1, 3, 6, 1, 2, 1, 5, 21]),
+    (3, "icmpOutEchoReps", &[1, 3, 6, 1, 2, 1, 5, 22]),
+    (3, "icmpOutTimestamps", &[1, 3, 6, 1, 2, 1, 5, 23]),
+    (3, "icmpOutTimestampReps", &[1, 3, 6, 1, 2, 1, 5, 24]),
+    (3, "icmpOutAddrMasks", &[1, 3, 6, 1, 2, 1, 5, 25]),
+    (3, "icmpOutAddrMaskReps", &[1, 3, 6, 1, 2, 1, 5, 26]),
+    (3, "icmpStatsTable", &[1, 3, 6, 1, 2, 1, 5, 29]),
+    (3, "icmpStatsEntry", &[1, 3, 6, 1, 2, 1, 5, 29, 1]),
+    (3, "icmpStatsIPVersion", &[1, 3, 6, 1, 2, 1, 5, 29, 1, 1]),
+    (3, "icmpStatsInMsgs", &[1, 3, 6, 1, 2, 1, 5, 29, 1, 2]),
+    (3, "icmpStatsInErrors", &[1, 3, 6, 1, 2, 1, 5, 29, 1, 3]),
+    (3, "icmpStatsOutMsgs", &[1, 3, 6, 1, 2, 1, 5, 29, 1, 4]),
+    (3, "icmpStatsOutErrors", &[1, 3, 6, 1, 2, 1, 5, 29, 1, 5]),
+    (3, "icmpMsgStatsTable", &[1, 3, 6, 1, 2, 1, 5, 30]),
+    (3, "icmpMsgStatsEntry", &[1, 3, 6, 1, 2, 1, 5, 30, 1]),
+    (3, "icmpMsgStatsIPVersion", &[1, 3, 6, 1, 2, 1, 5, 30, 1, 1]),
+    (3, "icmpMsgStatsType", &[1, 3, 6, 1, 2, 1, 5, 30, 1, 2]),
+    (3, "icmpMsgStatsInPkts", &[1, 3, 6, 1, 2, 1, 5, 30, 1, 3]),
+    (3, "icmpMsgStatsOutPkts", &[1, 3, 6, 1, 2, 1, 5, 30, 1, 4]),
+    (0, "transmission", &[1, 3, 6, 1, 2, 1, 10]),
+    (1, "snmp", &[1, 3, 6, 1, 2, 1, 11]),
+    (1, "snmpInPkts", &[1, 3, 6, 1, 2, 1, 11, 1]),
+    (1, "snmpOutPkts", &[1, 3, 6, 1, 2, 1, 11, 2]),
+    (1, "snmpInBadVersions", &[1, 3, 6, 1, 2, 1, 11, 3]),
+    (1, "snmpInBadCommunityNames", &[1, 3, 6, 1, 2, 1, 11, 4]),
+    (1, "snmpInBadCommunityUses", &[1, 3, 6, 1, 2, 1, 11, 5]),
+    (1, "snmpInASNParseErrs", &[1, 3, 6, 1, 2, 1, 11, 6]),
+    (1, "snmpInTooBigs", &[1, 3, 6, 1, 2, 1, 11, 8]),
+    (1, "snmpInNoSuchNames", &[1, 3, 6, 1, 2, 1, 11, 9]),
+    (1, "snmpInBadValues", &[1, 3, 6, 1, 2, 1, 11, 10]),
+    (1, "snmpInReadOnlys", &[1, 3, 6, 1, 2, 1, 11, 11]),
+    (1, "snmpInGenErrs", &[1, 3, 6, 1, 2, 1, 11, 12]),
+    (1, "snmpInTotalReqVars", &[1, 3, 6, 1, 2, 1, 11, 13]),
+    (1, "snmpInTotalSetVars", &[1, 3, 6, 1, 2, 1, 11, 14]),
+    (1, "snmpInGetRequests", &[1, 3, 6, 1, 2, 1, 11, 15]),
+    (1, "snmpInGetNexts", &[1, 3, 6, 1, 2, 1, 11, 16]),
+    (1, "snmpInSetRequests", &[1, 3, 6, 1, 2, 1, 11, 17]),
+    (1, "snmpInGetResponses", &[1, 3, 6, 1, 2, 1, 11, 18]),
+    (1, "snmpInTraps", &[1, 3, 6, 1, 2, 1, 11, 19]),
+    (1, "snmpOutTooBigs", &[1, 3, 6, 1, 2, 1, 11, 20]),
+    (1, "snmpOutNoSuchNames", &[1, 3, 6, 1, 2, 1, 11, 21]),
+    (1, "snmpOutBadValues", &[1, 3, 6, 1, 2, 1, 11, 22]),
+    (1, "snmpOutGenErrs", &[1, 3, 6, 1, 2, 1, 11, 24]),
+    (1, "snmpOutGetRequests", &[1, 3, 6, 1, 2, 1, 11, 25]),
+    (1, "snmpOutGetNexts", &[1, 3, 6, 1, 2, 1, 11, 26]),
+    (1, "snmpOutSetRequests", &[1, 3, 6, 1, 2, 1, 11, 27]),
+    (1, "snmpOutGetResponses", &[1, 3, 6, 1, 2, 1, 11, 28]),
+    (1, "snmpOutTraps", &[1, 3, 6, 1, 2, 1, 11, 29]),
+    (1, "snmpEnableAuthenTraps", &[1, 3, 6, 1, 2, 1, 11, 30]),
+    (1, "snmpSilentDrops", &[1, 3, 6, 1, 2, 1, 11, 31]),
+    (1, "snmpProxyDrops", &[1, 3, 6, 1, 2, 1, 11, 32]),
+    (6, "dot1dBridge", &[1, 3, 6, 1, 2, 1, 17]),
+    (6, "dot1dNotifications", &[1, 3, 6, 1, 2, 1, 17, 0]),
+    (6, "newRoot", &[1, 3, 6, 1, 2, 1, 17, 0, 1]),
+    (6, "topologyChange", &[1, 3, 6, 1, 2, 1, 17, 0, 2]),
+    (6, "dot1dBase", &[1, 3, 6, 1, 2, 1, 17, 1]),
+    (6, "dot1dBaseBridgeAddress", &[1, 3, 6, 1, 2, 1, 17, 1, 1]),
+    (6, "dot1dBaseNumPorts", &[1, 3, 6, 1, 2, 1, 17, 1, 2]),
+    (6, "dot1dBaseType", &[1, 3, 6, 1, 2, 1, 17, 1, 3]),
+    (6, "dot1dBasePortTable", &[1, 3, 6, 1, 2, 1, 17, 1, 4]),
+    (6, "dot1dBasePortEntry", &[1, 3, 6, 1, 2, 1, 17, 1, 4, 1]),
+    (6, "dot1dBasePort", &[1, 3, 6, 1, 2, 1, 17, 1, 4, 1, 1]),
+    (
+        6,
+        "dot1dBasePortIfIndex",
+        &[1, 3, 6, 1, 2, 1, 17, 1, 4, 1, 2],
+    ),
+    (
+        6,
+        "dot1dBasePortCircuit",
+        &[1, 3, 6, 1, 2, 1, 17, 1, 4, 1, 3],
+    ),
+    (
+        6,
+        "dot1dBasePortDelayExceededDiscards",
+        &[1, 3, 6, 1, 2, 1, 17, 1, 4, 1, 4],
+    ),
+    (
+        6,
+        "dot1dBasePortMtuExceededDiscards",
+        &[1, 3, 6, 1, 2, 1, 17, 1, 4, 1, 5],
+    ),
+    (6, "dot1dStp", &[1, 3, 6, 1, 2, 1, 17, 2]),
+    (
+        6,
+        "dot1dStpProtocolSpecification",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 1],
+    ),
+    (6, "dot1dStpPriority", &[1, 3, 6, 1, 2, 1, 17, 2, 2]),
+    (
+        6,
+        "dot1dStpTimeSinceTopologyChange",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 3],
+    ),
+    (6, "dot1dStpTopChanges", &[1, 3, 6, 1, 2, 1, 17, 2, 4]),
+    (6, "dot1dStpDesignatedRoot", &[1, 3, 6, 1, 2, 1, 17, 2, 5]),
+    (6, "dot1dStpRootCost", &[1, 3, 6, 1, 2, 1, 17, 2, 6]),
+    (6, "dot1dStpRootPort", &[1, 3, 6, 1, 2, 1, 17, 2, 7]),
+    (6, "dot1dStpMaxAge", &[1, 3, 6, 1, 2, 1, 17, 2, 8]),
+    (6, "dot1dStpHelloTime", &[1, 3, 6, 1, 2, 1, 17, 2, 9]),
+    (6, "dot1dStpHoldTime", &[1, 3, 6, 1, 2, 1, 17, 2, 10]),
+    (6, "dot1dStpForwardDelay", &[1, 3, 6, 1, 2, 1, 17, 2, 11]),
+    (6, "dot1dStpBridgeMaxAge", &[1, 3, 6, 1, 2, 1, 17, 2, 12]),
+    (6, "dot1dStpBridgeHelloTime", &[1, 3, 6, 1, 2, 1, 17, 2, 13]),
+    (
+        6,
+        "dot1dStpBridgeForwardDelay",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 14],
+    ),
+    (6, "dot1dStpPortTable", &[1, 3, 6, 1, 2, 1, 17, 2, 15]),
+    (6, "dot1dStpPortEntry", &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1]),
+    (6, "dot1dStpPort", &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 1]),
+    (
+        6,
+        "dot1dStpPortPriority",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 2],
+    ),
+    (6, "dot1dStpPortState", &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 3]),
+    (
+        6,
+        "dot1dStpPortEnable",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 4],
+    ),
+    (
+        6,
+        "dot1dStpPortPathCost",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 5],
+    ),
+    (
+        6,
+        "dot1dStpPortDesignatedRoot",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 6],
+    ),
+    (
+        6,
+        "dot1dStpPortDesignatedCost",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 7],
+    ),
+    (
+        6,
+        "dot1dStpPortDesignatedBridge",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 8],
+    ),
+    (
+        6,
+        "dot1dStpPortDesignatedPort",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 9],
+    ),
+    (
+        6,
+        "dot1dStpPortForwardTransitions",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 10],
+    ),
+    (
+        6,
+        "dot1dStpPortPathCost32",
+        &[1, 3, 6, 1, 2, 1, 17, 2, 15, 1, 11],
+    ),
+    (6, "dot1dSr", &[1, 3, 6, 1, 2, 1, 17, 3]),
+    (6, "dot1dTp", &[1, 3, 6, 1, 2, 1, 17, 4]),
+    (
+        6,
+        "dot1dTpLearnedEntryDiscards",
+        &[1, 3, 6, 1, 2, 1, 17, 4, 1],
+    ),
+    (6, "dot1dTpAgingTime", &[1, 3, 6, 1, 2, 1, 17, 4, 2]),
+    (6, "dot1dTpFdbTable", &[1, 3, 6, 1, 2, 1, 17, 4, 3]),
+    (6, "dot1dTpFdbEntry", &[1, 3, 6, 1, 2, 1, 17, 4, 3, 1]),
+    (6, "dot1dTpFdbAddress", &[1, 3, 6, 1, 2, 1, 17, 4, 3, 1, 1]),
+    (6, "dot1dTpFdbPort", &[1, 3, 6, 1, 2, 1, 17, 4, 3, 1, 2]),
+    (6, "dot1dTpFdbStatus", &[1, 3, 6, 1, 2, 1, 17, 4, 3, 1, 3]),
+    (6, "dot1dTpPortTable", &[1, 3, 6, 1, 2, 1, 17, 4, 4]),
+    (6, "dot1dTpPortEntry", &[1, 3, 6, 1, 2, 1, 17, 4, 4, 1]),
+    (6, "dot1dTpPort", &[1, 3, 6, 1, 2, 1, 17, 4, 4, 1, 1]),
+    (6, "dot1dTpPortMaxInfo", &[1, 3, 6, 1, 2, 1, 17, 4, 4, 1, 2]),
+    (
+        6,
+        "dot1dTpPortInFrames",
+        &[1, 3, 6, 1, 2, 1, 17, 4, 4, 1, 3],
+    ),
+    (
+        6,
+        "dot1dTpPortOutFrames",
+        &[1, 3, 6, 1, 2, 1, 17, 4, 4, 1, 4],
+    ),
+    (
+        6,
+        "dot1dTpPortInDiscards",
+        &[1, 3, 6, 1, 2, 1, 17, 4, 4, 1, 5],
+    ),
+    (6, "dot1dStatic", &[1, 3, 6, 1, 2, 1, 17, 5]),
+    (6, "dot1dStaticTable", &[1, 3, 6, 1, 2, 1, 17, 5, 1]),
+    (6, "dot1dStaticEntry", &[1, 3, 6, 1, 2, 1, 17, 5, 1, 1]),
+    (6, "dot1dStaticAddress", &[1, 3, 6, 1, 2, 1, 17, 5, 1, 1, 1]),
+    (
+        6,
+        "dot1dStaticReceivePort",
+        &[1, 3, 6, 1, 2, 1, 17, 5, 1, 1, 2],
+    ),
+    (
+        6,
+        "dot1dStaticAllowedToGoTo",
+        &[1, 3, 6, 1, 2, 1, 17, 5, 1, 1, 3],
+    ),
+    (6, "dot1dStaticStatus", &[1, 3, 6, 1, 2, 1, 17, 5, 1, 1, 4]),
+    (4, "host", &[1, 3, 6, 1, 2, 1, 25]),
+    (4, "hrSystem", &[1, 3, 6, 1, 2, 1, 25, 1]),
+    (4, "hrSystemUptime", &[1, 3, 6, 1, 2, 1, 25, 1, 1]),
+    (4, "hrSystemDate", &[1, 3, 6, 1, 2, 1, 25, 1, 2]),
+    (
+        4,
+        "hrSystemInitialLoadDevice",
+        &[1, 3, 6, 1, 2, 1, 25, 1, 3],
+    ),
+    (
+        4,
+        "hrSystemInitialLoadParameters",
+        &[1, 3, 6, 1, 2, 1, 25, 1, 4],
+    ),
+    (4, "hrSystemNumUsers", &[1, 3, 6, 1, 2, 1, 25, 1, 5]),
+    (4, "hrSystemProcesses", &[1, 3, 6, 1, 2, 1, 25, 1, 6]),
+    (4, "hrSystemMaxProcesses", &[1, 3, 6, 1, 2, 1, 25, 1, 7]),
+    (4, "hrStorage", &[1, 3, 6, 1, 2, 1, 25, 2]),
+    (4, "hrStorageTypes", &[1, 3, 6, 1, 2, 1, 25, 2, 1]),
+    (4, "hrMemorySize", &[1, 3, 6, 1, 2, 1, 25, 2, 2]),
+    (4, "hrStorageTable", &[1, 3, 6, 1, 2, 1, 25, 2, 3]),
+    (4, "hrStorageEntry", &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1]),
+    (4, "hrStorageIndex", &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 1]),
+    (4, "hrStorageType", &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 2]),
+    (4, "hrStorageDescr", &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 3]),
+    (
+        4,
+        "hrStorageAllocationUnits",
+        &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 4],
+    ),
+    (4, "hrStorageSize", &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 5]),
+    (4, "hrStorageUsed", &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 6]),
+    (
+        4,
+        "hrStorageAllocationFailures",
+        &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 7],
+    ),
+    (4, "hrDevice", &[1, 3, 6, 1, 2, 1, 25, 3]),
+    (4, "hrDeviceTypes", &[1, 3, 6, 1, 2, 1, 25, 3, 1]),
+    (4, "hrDeviceTable", &[1, 3, 6, 1, 2, 1, 25, 3, 2]),
+    (4, "hrDeviceEntry", &[1, 3, 6, 1, 2, 1, 25, 3, 2, 1]),
+    (4, "hrDeviceIndex", &[1, 3, 6, 1, 2, 1, 25, 3, 2, 1, 1]),
+    (4, "hrDeviceType", &[1, 3, 6, 1, 2, 1, 25, 3, 2, 1, 2]),
+    (4, "hrDeviceDescr", &[1, 3, 6, 1, 2, 1, 25, 3, 2, 1, 3]),
+    (4, "hrDeviceID", &[1, 3, 6, 1, 2, 1, 25, 3, 2, 1, 4]),
+    (4, "hrDeviceStatus", &[1, 3, 6, 1, 2, 1, 25, 3, 2, 1, 5]),
+    (4, "hrDeviceErrors", &[1, 3, 6, 1, 2, 1, 25, 3, 2, 1, 6]),
+    (4, "hrProcessorTable", &[1, 3, 6, 1, 2, 1, 25, 3, 3]),
+    (4, "hrProcessorEntry", &[1, 3, 6, 1, 2, 1, 25, 3, 3, 1]),
+    (4, "hrProcessorFrwID", &[1, 3, 6, 1, 2, 1, 25, 3, 3, 1, 1]),
+    (4, "hrProcessorLoad", &[1, 3, 6, 1, 2, 1, 25, 3, 3, 1, 2]),
+    (4, "hrNetworkTable", &[1, 3, 6, 1, 2, 1, 25, 3, 4]),
+    (4, "hrNetworkEntry", &[1, 3, 6, 1, 2, 1, 25, 3, 4, 1]),
+    (4, "hrNetworkIfIndex", &[1, 3, 6, 1, 2, 1, 25, 3, 4, 1, 1]),
+    (4, "hrPrinterTable", &[1, 3, 6, 1, 2, 1, 25, 3, 5]),
+    (4, "hrPrinterEntry", &[1, 3, 6, 1, 2, 1, 25, 3, 5, 1]),
+    (4, "hrPrinterStatus", &[1, 3, 6, 1, 2, 1, 25, 3, 5, 1, 1]),
+    (
+        4,
+        "hrPrinterDetectedErrorState",
+        &[1, 3, 6, 1, 2, 1, 25, 3, 5, 1, 2],
+    ),
+    (4, "hrDiskStorageTable", &[1, 3, 6, 1, 2, 1, 25, 3, 6]),
+    (4, "hrDiskStorageEntry", &[1, 3, 6, 1, 2, 1, 25, 3, 6, 1]),
+    (
+        4,
+        "hrDiskStorageAccess",
+        &[1, 3, 6, 1, 2, 1, 25, 3, 6, 1, 1],
+    ),
+    (4, "hrDiskStorageMedia", &[1, 3, 6, 1, 2, 1, 25, 3, 6, 1, 2]),
+    (
+        4,
+        "hrDiskStorageRemoveble",
+        &[1, 3, 6, 1, 2, 1, 25, 3, 6, 1, 3],
+    ),
+    (
+        4,
+        "hrDiskStorageCapacity",
+        &[1, 3, 6, 1, 2, 1, 25, 3, 6, 1, 4],
+    ),
+    (4, "hrPartitionTable", &[1, 3, 6, 1, 2, 1, 25, 3, 7]),
+    (4, "hrPartitionEntry", &[1, 3, 6, 1, 2, 1, 25, 3, 7, 1]),
+    (4, "hrPartitionIndex", &[1, 3, 6, 1, 2, 1, 25, 3, 7, 1, 1]),
+    (4, "hrPartitionLabel", &[1, 3, 6, 1, 2, 1, 25, 3, 7, 1, 2]),
+    (4, "hrPartitionID", &[1, 3, 6, 1, 2, 1, 25, 3, 7, 1, 3]),
+    (4, "hrPartitionSize", &[1, 3, 6, 1, 2, 1, 25, 3, 7, 1, 4]),
+    (4, "hrPartitionFSIndex", &[1, 3, 6, 1, 2, 1, 25, 3, 7, 1, 5]),
+    (4, "hrFSTable", &[1, 3, 6, 1, 2, 1, 25, 3, 8]),
+    (4, "hrFSEntry", &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1]),
+    (4, "hrFSIndex", &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1, 1]),
+    (4, "hrFSMountPoint", &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1, 2]),
+    (
+        4,
+        "hrFSRemoteMountPoint",
+        &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1, 3],
+    ),
+    (4, "hrFSType", &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1, 4]),
+    (4, "hrFSAccess", &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1, 5]),
+    (4, "hrFSBootable", &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1, 6]),
+    (4, "hrFSStorageIndex", &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1, 7]),
+    (
+        4,
+        "hrFSLastFullBackupDate",
+        &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1, 8],
+    ),
+    (
+        4,
+        "hrFSLastPartialBackupDate",
+        &[1, 3, 6, 1, 2, 1, 25, 3, 8, 1, 9],
+    ),
+    (4, "hrFSTypes", &[1, 3, 6, 1, 2, 1, 25, 3, 9]),
+    (4, "hrSWRun", &[1, 3, 6, 1, 2, 1, 25, 4]),
+    (4, "hrSWOSIndex", &[1, 3, 6, 1, 2, 1, 25, 4, 1]),
+    (4, "hrSWRunTable", &[1, 3, 6, 1, 2, 1, 25, 4, 2]),
+    (4, "hrSWRunEntry", &[1, 3, 6, 1, 2, 1, 25, 4, 2, 1]),
+    (4, "hrSWRunIndex", &[1, 3, 6, 1, 2, 1, 25, 4, 2, 1, 1]),
+    (4, "hrSWRunName", &[1, 3, 6, 1, 2, 1, 25, 4, 2, 1, 2]),
+    (4, "hrSWRunID", &[1, 3, 6, 1, 2, 1, 25, 4, 2, 1, 3]),
+    (4, "hrSWRunPath", &[1, 3, 6, 1, 2, 1, 25, 4, 2, 1, 4]),
+    (4, "hrSWRunParameters", &[1, 3, 6, 1, 2, 1, 25, 4, 2, 1, 5]),
+    (4, "hrSWRunType", &[1, 3, 6, 1, 2, 1, 25, 4, 2, 1, 6]),
+    (4, "hrSWRunStatus", &[1, 3, 6, 1, 2, 1, 25, 4, 2, 1, 7]),
+    (4, "hrSWRunPerf", &[1, 3, 6, 1, 2, 1, 25, 5]),
+    (4, "hrSWRunPerfTable", &[1, 3, 6, 1, 2, 1, 25, 5, 1]),
+    (4, "hrSWRunPerfEntry", &[1, 3, 6, 1, 2, 1, 25, 5, 1, 1]),
+    (4, "hrSWRunPerfCPU", &[1, 3, 6, 1, 2, 1, 25, 5, 1, 1, 1]),
+    (4, "hrSWRunPerfMem", &[1, 3, 6, 1, 2, 1, 25, 5, 1, 1, 2]),
+    (4, "hrSWInstalled", &[1, 3, 6, 1, 2, 1, 25, 6]),
+    (4, "hrSWInstalledLastChange", &[1, 3, 6, 1, 2, 1, 25, 6, 1]),
+    (
+        4,
+        "hrSWInstalledLastUpdateTime",
+        &[1, 3, 6, 1, 2, 1, 25, 6, 2],
+    ),
+    (4, "hrSWInstalledTable", &[1, 3, 6, 1, 2, 1, 25, 6, 3]),
+    (4, "hrSWInstalledEntry", &[1, 3, 6, 1, 2, 1, 25, 6, 3, 1]),
+    (4, "hrSWInstalledIndex", &[1, 3, 6, 1, 2, 1, 25, 6, 3, 1, 1]),
+    (4, "hrSWInstalledName", &[1, 3, 6, 1, 2, 1, 25, 6, 3, 1, 2]),
+    (4, "hrSWInstalledID", &[1, 3, 6, 1, 2, 1, 25, 6, 3, 1, 3]),
+    (4, "hrSWInstalledType", &[1, 3, 6, 1, 2, 1, 25, 6, 3, 1, 4]),
+    (4, "hrSWInstalledDate", &[1, 3, 6, 1, 2, 1, 25, 6, 3, 1, 5]),
+    (4, "hrMIBAdminInfo", &[1, 3, 6, 1, 2, 1, 25, 7]),
+    (4, "hostResourcesMibModule", &[1, 3, 6, 1, 2, 1, 25, 7, 1]),
+    (4, "hrMIBCompliances", &[1, 3, 6, 1, 2, 1, 25, 7, 2]),
+    (4, "hrMIBGroups", &[1, 3, 6, 1, 2, 1, 25, 7, 3]),
+    (2, "ifMIB", &[1, 3, 6, 1, 2, 1, 31]),
+    (2, "ifMIBObjects", &[1, 3, 6, 1, 2, 1, 31, 1]),
+    (2, "ifXTable", &[1, 3, 6, 1, 2, 1, 31, 1, 1]),
+    (2, "ifXEntry", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1]),
+    (2, "ifName", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 1]),
+    (2, "ifInMulticastPkts", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 2]),
+    (2, "ifInBroadcastPkts", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 3]),
+    (2, "ifOutMulticastPkts", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 4]),
+    (2, "ifOutBroadcastPkts", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 5]),
+    (2, "ifHCInOctets", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6]),
+    (2, "ifHCInUcastPkts", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 7]),
+    (
+        2,
+        "ifHCInMulticastPkts",
+        &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 8],
+    ),
+    (
+        2,
+        "ifHCInBroadcastPkts",
+        &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 9],
+    ),
+    (2, "ifHCOutOctets", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 10]),
+    (2, "ifHCOutUcastPkts", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 11]),
+    (
+        2,
+        "ifHCOutMulticastPkts",
+        &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 12],
+    ),
+    (
+        2,
+        "ifHCOutBroadcastPkts",
+        &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 13],
+    ),
+    (
+        2,
+        "ifLinkUpDownTrapEnable",
+        &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 14],
+    ),
+    (2, "ifHighSpeed", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 15]),
+    (2, "ifPromiscuousMode", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 16]),
+    (
+        2,
+        "ifConnectorPresent",
+        &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 17],
+    ),
+    (2, "ifAlias", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 18]),
+    (
+        2,
+        "ifCounterDiscontinuityTime",
+        &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 19],
+    ),
+    (2, "ifStackTable", &[1, 3, 6, 1, 2, 1, 31, 1, 2]),
+    (2, "ifStackEntry", &[1, 3, 6, 1, 2, 1, 31, 1, 2, 1]),
+    (2, "ifStackHigherLayer", &[1, 3, 6, 1, 2, 1, 31, 1, 2, 1, 1]),
+    (2, "ifStackLowerLayer", &[1, 3, 6, 1, 2, 1, 31, 1, 2, 1, 2]),
+    (2, "ifStackStatus", &[1, 3, 6, 1, 2, 1, 31, 1, 2, 1, 3]),
+    (2, "ifTestTable", &[1, 3, 6, 1, 2, 1, 31, 1, 3]),
+    (2, "ifTestEntry", &[1, 3, 6, 1, 2, 1, 31, 1, 3, 1]),
+    (2, "ifTestId", &[1, 3, 6, 1, 2, 1, 31, 1, 3, 1, 1]),
+    (2, "ifTestStatus", &[1, 3, 6, 1, 2, 1, 31, 1, 3, 1, 2]),
+    (2, "ifTestType", &[1, 3, 6, 1, 2, 1, 31, 1, 3, 1, 3]),
+    (2, "ifTestResult", &[1, 3, 6, 1, 2, 1, 31, 1, 3, 1, 4]),
+    (2, "ifTestCode", &[1, 3, 6, 1, 2, 1, 31, 1, 3, 1, 5]),
+    (2, "ifTestOwner", &[1, 3, 6, 1, 2, 1, 31, 1, 3, 1, 6]),
+    (2, "ifRcvAddressTable", &[1, 3, 6, 1, 2, 1, 31, 1, 4]),
+    (2, "ifRcvAddressEntry", &[1, 3, 6, 1, 2, 1, 31, 1, 4, 1]),
+    (
+        2,
+        "ifRcvAddressAddress",
+        &[1, 3, 6, 1, 2, 1, 31, 1, 4, 1, 1],
+    ),
+    (2, "ifRcvAddressStatus", &[1, 3, 6, 1, 2, 1, 31, 1, 4, 1, 2]),
+    (2, "ifRcvAddressType", &[1, 3, 6, 1, 2, 1, 31, 1, 4, 1, 3]),
+    (2, "ifTableLastChange", &[1, 3, 6, 1, 2, 1, 31, 1, 5]),
+    (2, "ifStackLastChange", &[1, 3, 6, 1, 2, 1, 31, 1, 6]),
+    (2, "ifConformance", &[1, 3, 6, 1, 2, 1, 31, 2]),
+    (2, "ifGroups", &[1, 3, 6, 1, 2, 1, 31, 2, 1]),
+    (2, "ifCompliances", &[1, 3, 6, 1, 2, 1, 31, 2, 2]),
+    (5, "entityMIB", &[1, 3, 6, 1, 2, 1, 47]),
+    (5, "entityMIBObjects", &[1, 3, 6, 1, 2, 1, 47, 1]),
+    (5, "entityPhysical", &[1, 3, 6, 1, 2, 1, 47, 1, 1]),
+    (5, "entPhysicalTable", &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1]),
+    (5, "entPhysicalEntry", &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1]),
+    (
+        5,
+        "entPhysicalIndex",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 1],
+    ),
+    (
+        5,
+        "entPhysicalDescr",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 2],
+    ),
+    (
+        5,
+        "entPhysicalVendorType",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 3],
+    ),
+    (
+        5,
+        "entPhysicalContainedIn",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 4],
+    ),
+    (
+        5,
+        "entPhysicalClass",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 5],
+    ),
+    (
+        5,
+        "entPhysicalParentRelPos",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 6],
+    ),
+    (5, "entPhysicalName", &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 7]),
+    (
+        5,
+        "entPhysicalHardwareRev",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 8],
+    ),
+    (
+        5,
+        "entPhysicalFirmwareRev",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 9],
+    ),
+    (
+        5,
+        "entPhysicalSoftwareRev",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 10],
+    ),
+    (
+        5,
+        "entPhysicalSerialNum",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 11],
+    ),
+    (
+        5,
+        "entPhysicalMfgName",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 12],
+    ),
+    (
+        5,
+        "entPhysicalModelName",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 13],
+    ),
+    (
+        5,
+        "entPhysicalAlias",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 14],
+    ),
+    (
+        5,
+        "entPhysicalAssetID",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 15],
+    ),
+    (
+        5,
+        "entPhysicalIsFRU",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 16],
+    ),
+    (
+        5,
+        "entPhysicalMfgDate",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 17],
+    ),
+    (
+        5,
+        "entPhysicalUris",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 18],
+    ),
+    (
+        5,
+        "entPhysicalUUID",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 19],
+    ),
+    (5, "entityLogical", &[1, 3, 6, 1, 2, 1, 47, 1, 2]),
+    (5, "entLogicalTable", &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1]),
+    (5, "entLogicalEntry", &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1, 1]),
+    (5, "entLogicalIndex", &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1, 1, 1]),
+    (5, "entLogicalDescr", &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1, 1, 2]),
+    (5, "entLogicalType", &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1, 1, 3]),
+    (
+        5,
+        "entLogicalCommunity",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1, 1, 4],
+    ),
+    (
+        5,
+        "entLogicalTAddress",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1, 1, 5],
+    ),
+    (
+        5,
+        "entLogicalTDomain",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1, 1, 6],
+    ),
+    (
+        5,
+        "entLogicalContextEngineID",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1, 1, 7],
+    ),
+    (
+        5,
+        "entLogicalContextName",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 2, 1, 1, 8],
+    ),
+    (5, "entityMapping", &[1, 3, 6, 1, 2, 1, 47, 1, 3]),
+    (5, "entLPMappingTable", &[1, 3, 6, 1, 2, 1, 47, 1, 3, 1]),
+    (5, "entLPMappingEntry", &[1, 3, 6, 1, 2, 1, 47, 1, 3, 1, 1]),
+    (
+        5,
+        "entLPPhysicalIndex",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 3, 1, 1, 1],
+    ),
+    (5, "entAliasMappingTable", &[1, 3, 6, 1, 2, 1, 47, 1, 3, 2]),
+    (
+        5,
+        "entAliasMappingEntry",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 3, 2, 1],
+    ),
+    (
+        5,
+        "entAliasLogicalIndexOrZero",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 3, 2, 1, 1],
+    ),
+    (
+        5,
+        "entAliasMappingIdentifier",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 3, 2, 1, 2],
+    ),
+    (
+        5,
+        "entPhysicalContainsTable",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 3, 3],
+    ),
+    (
+        5,
+        "entPhysicalContainsEntry",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 3, 3, 1],
+    ),
+    (
+        5,
+        "entPhysicalChildIndex",
+        &[1, 3, 6, 1, 2, 1, 47, 1, 3, 3, 1, 1],
+    ),
+    (5, "entityGeneral", &[1, 3, 6, 1, 2, 1, 47, 1, 4]),
+    (5, "entLastChangeTime", &[1, 3, 6, 1, 2, 1, 47, 1, 4, 1]),
+    (5, "entityMIBTraps", &[1, 3, 6, 1, 2, 1, 47, 2]),
+    (5, "entityMIBTrapPrefix", &[1, 3, 6, 1, 2, 1, 47, 2, 0]),
+    (5, "entConfigChange", &[1, 3, 6, 1, 2, 1, 47, 2, 0, 1]),
+    (5, "entityConformance", &[1, 3, 6, 1, 2, 1, 47, 3]),
+    (5, "entityCompliances", &[1, 3, 6, 1, 2, 1, 47, 3, 1]),
+    (5, "entityGroups", &[1, 3, 6, 1, 2, 1, 47, 3, 2]),
+    (3, "ipMIB", &[1, 3, 6, 1, 2, 1, 48]),
+    (3, "ipMIBConformance", &[1, 3, 6, 1, 2, 1, 48, 2]),
+    (11, "notificationLogMIB", &[1, 3, 6, 1, 2, 1, 92]),
+    (11, "notificationLogMIBObjects", &[1, 3, 6, 1, 2, 1, 92, 1]),
+    (11, "nlmConfig", &[1, 3, 6, 1, 2, 1, 92, 1, 1]),
+    (
+        11,
+        "nlmConfigGlobalEntryLimit",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 1, 1],
+    ),
+    (
+        11,
+        "nlmConfigGlobalAgeOut",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 1, 2],
+    ),
+    (11, "nlmConfigLogTable", &[1, 3, 6, 1, 2, 1, 92, 1, 1, 3]),
+    (11, "nlmConfigLogEntry", &[1, 3, 6, 1, 2, 1, 92, 1, 1, 3, 1]),
+    (11, "nlmLogName", &[1, 3, 6, 1, 2, 1, 92, 1, 1, 3, 1, 1]),
+    (
+        11,
+        "nlmConfigLogFilterName",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 1, 3, 1, 2],
+    ),
+    (
+        11,
+        "nlmConfigLogEntryLimit",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 1, 3, 1, 3],
+    ),
+    (
+        11,
+        "nlmConfigLogAdminStatus",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 1, 3, 1, 4],
+    ),
+    (
+        11,
+        "nlmConfigLogOperStatus",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 1, 3, 1, 5],
+    ),
+    (
+        11,
+        "nlmConfigLogStorageType",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 1, 3, 1, 6],
+    ),
+    (
+        11,
+        "nlmConfigLogEntryStatus",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 1, 3, 1, 7],
+    ),
+    (11, "nlmStats", &[1, 3, 6, 1, 2, 1, 92, 1, 2]),
+    (
+        11,
+        "nlmStatsGlobalNotificationsLogged",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 2, 1],
+    ),
+    (
+        11,
+        "nlmStatsGlobalNotificationsBumped",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 2, 2],
+    ),
+    (11, "nlmStatsLogTable", &[1, 3, 6, 1, 2, 1, 92, 1, 2, 3]),
+    (11, "nlmStatsLogEntry", &[1, 3, 6, 1, 2, 1, 92, 1, 2, 3, 1]),
+    (
+        11,
+        "nlmStatsLogNotificationsLogged",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 2, 3, 1, 1],
+    ),
+    (
+        11,
+        "nlmStatsLogNotificationsBumped",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 2, 3, 1, 2],
+    ),
+    (11, "nlmLog", &[1, 3, 6, 1, 2, 1, 92, 1, 3]),
+    (11, "nlmLogTable", &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1]),
+    (11, "nlmLogEntry", &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1]),
+    (11, "nlmLogIndex", &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1, 1]),
+    (11, "nlmLogTime", &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1, 2]),
+    (
+        11,
+        "nlmLogDateAndTime",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1, 3],
+    ),
+    (11, "nlmLogEngineID", &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1, 4]),
+    (
+        11,
+        "nlmLogEngineTAddress",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1, 5],
+    ),
+    (
+        11,
+        "nlmLogEngineTDomain",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1, 6],
+    ),
+    (
+        11,
+        "nlmLogContextEngineID",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1, 7],
+    ),
+    (
+        11,
+        "nlmLogContextName",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1, 8],
+    ),
+    (
+        11,
+        "nlmLogNotificationID",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 1, 1, 9],
+    ),
+    (11, "nlmLogVariableTable", &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2]),
+    (
+        11,
+        "nlmLogVariableEntry",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1],
+    ),
+    (
+        11,
+        "nlmLogVariableIndex",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 1],
+    ),
+    (
+        11,
+        "nlmLogVariableID",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 2],
+    ),
+    (
+        11,
+        "nlmLogVariableValueType",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 3],
+    ),
+    (
+        11,
+        "nlmLogVariableCounter32Val",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 4],
+    ),
+    (
+        11,
+        "nlmLogVariableUnsigned32Val",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 5],
+    ),
+    (
+        11,
+        "nlmLogVariableTimeTicksVal",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 6],
+    ),
+    (
+        11,
+        "nlmLogVariableInteger32Val",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 7],
+    ),
+    (
+        11,
+        "nlmLogVariableOctetStringVal",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 8],
+    ),
+    (
+        11,
+        "nlmLogVariableIpAddressVal",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 9],
+    ),
+    (
+        11,
+        "nlmLogVariableOidVal",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 10],
+    ),
+    (
+        11,
+        "nlmLogVariableCounter64Val",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 11],
+    ),
+    (
+        11,
+        "nlmLogVariableOpaqueVal",
+        &[1, 3, 6, 1, 2, 1, 92, 1, 3, 2, 1, 12],
+    ),
+    (
+        11,
+        "notificationLogMIBConformance",
+        &[1, 3, 6, 1, 2, 1, 92, 3],
+    ),
+    (0, "experimental", &[1, 3, 6, 1, 3]),
+    (0, "private", &[1, 3, 6, 1, 4]),
+    (0, "enterprises", &[1, 3, 6, 1, 4, 1]),
+    (0, "security", &[1, 3, 6, 1, 5]),
+    (0, "snmpV2", &[1, 3, 6, 1, 6]),
+    (0, "snmpDomains", &[1, 3, 6, 1, 6, 1]),
+    (0, "snmpProxys", &[1, 3, 6, 1, 6, 2]),
+    (0, "snmpModules", &[1, 3, 6, 1, 6, 3]),
+    (1, "snmpMIB", &[1, 3, 6, 1, 6, 3, 1]),
+    (1, "snmpMIBObjects", &[1, 3, 6, 1, 6, 3, 1, 1]),
+    (1, "snmpTrap", &[1, 3, 6, 1, 6, 3, 1, 1, 4]),
+    (1, "snmpTrapOID", &[1, 3, 6, 1, 6, 3, 1, 1, 4, 1]),
+    (1, "snmpTrapEnterprise", &[1, 3, 6, 1, 6, 3, 1, 1, 4, 3]),
+    (1, "snmpTraps", &[1, 3, 6, 1, 6, 3, 1, 1, 5]),
+    (1, "coldStart", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 1]),
+    (1, "warmStart", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 2]),
+    (2, "linkDown", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 3]),
+    (2, "linkUp", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 4]),
+    (1, "authenticationFailure", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 5]),
+    (1, "snmpSet", &[1, 3, 6, 1, 6, 3, 1, 1, 6]),
+    (1, "snmpSetSerialNo", &[1, 3, 6, 1, 6, 3, 1, 1, 6, 1]),
+    (1, "snmpMIBConformance", &[1, 3, 6, 1, 6, 3, 1, 2]),
+    (1, "snmpMIBCompliances", &[1, 3, 6, 1, 6, 3, 1, 2, 1]),
+    (1, "snmpMIBGroups", &[1, 3, 6, 1, 6, 3, 1, 2, 2]),
+    (8, "snmpTargetMIB", &[1, 3, 6, 1, 6, 3, 12]),
+    (8, "snmpTargetObjects", &[1, 3, 6, 1, 6, 3, 12, 1]),
+    (8, "snmpTargetSpinLock", &[1, 3, 6, 1, 6, 3, 12, 1, 1]),
+    (8, "snmpTargetAddrTable", &[1, 3, 6, 1, 6, 3, 12, 1, 2]),
+    (8, "snmpTargetAddrEntry", &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1]),
+    (8, "snmpTargetAddrName", &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1, 1]),
+    (
+        8,
+        "snmpTargetAddrTDomain",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1, 2],
+    ),
+    (
+        8,
+        "snmpTargetAddrTAddress",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1, 3],
+    ),
+    (
+        8,
+        "snmpTargetAddrTimeout",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1, 4],
+    ),
+    (
+        8,
+        "snmpTargetAddrRetryCount",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1, 5],
+    ),
+    (
+        8,
+        "snmpTargetAddrTagList",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1, 6],
+    ),
+    (
+        8,
+        "snmpTargetAddrParams",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1, 7],
+    ),
+    (
+        8,
+        "snmpTargetAddrStorageType",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1, 8],
+    ),
+    (
+        8,
+        "snmpTargetAddrRowStatus",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 2, 1, 9],
+    ),
+    (8, "snmpTargetParamsTable", &[1, 3, 6, 1, 6, 3, 12, 1, 3]),
+    (8, "snmpTargetParamsEntry", &[1, 3, 6, 1, 6, 3, 12, 1, 3, 1]),
+    (
+        8,
+        "snmpTargetParamsName",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 3, 1, 1],
+    ),
+    (
+        8,
+        "snmpTargetParamsMPModel",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 3, 1, 2],
+    ),
+    (
+        8,
+        "snmpTargetParamsSecurityModel",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 3, 1, 3],
+    ),
+    (
+        8,
+        "snmpTargetParamsSecurityName",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 3, 1, 4],
+    ),
+    (
+        8,
+        "snmpTargetParamsSecurityLevel",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 3, 1, 5],
+    ),
+    (
+        8,
+        "snmpTargetParamsStorageType",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 3, 1, 6],
+    ),
+    (
+        8,
+        "snmpTargetParamsRowStatus",
+        &[1, 3, 6, 1, 6, 3, 12, 1, 3, 1, 7],
+    ),
+    (8, "snmpUnavailableContexts", &[1, 3, 6, 1, 6, 3, 12, 1, 4]),
+    (8, "snmpUnknownContexts", &[1, 3, 6, 1, 6, 3, 12, 1, 5]),
+    (8, "snmpTargetConformance", &[1, 3, 6, 1, 6, 3, 12, 3]),
+    (9, "snmpNotificationMIB", &[1, 3, 6, 1, 6, 3, 13]),
+    (9, "snmpNotifyObjects", &[1, 3, 6, 1, 6, 3, 13, 1]),
+    (9, "snmpNotifyTable", &[1, 3, 6, 1, 6, 3, 13, 1, 1]),
+    (9, "snmpNotifyEntry", &[1, 3, 6, 1, 6, 3, 13, 1, 1, 1]),
+    (9, "snmpNotifyName", &[1, 3, 6, 1, 6, 3, 13, 1, 1, 1, 1]),
+    (9, "snmpNotifyTag", &[1, 3, 6, 1, 6, 3, 13, 1, 1, 1, 2]),
+    (9, "snmpNotifyType", &[1, 3, 6, 1, 6, 3, 13, 1, 1, 1, 3]),
+    (
+        9,
+        "snmpNotifyStorageType",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 1, 1, 4],
+    ),
+    (
+        9,
+        "snmpNotifyRowStatus",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 1, 1, 5],
+    ),
+    (
+        9,
+        "snmpNotifyFilterProfileTable",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 2],
+    ),
+    (
+        9,
+        "snmpNotifyFilterProfileEntry",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 2, 1],
+    ),
+    (
+        9,
+        "snmpNotifyFilterProfileName",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 2, 1, 1],
+    ),
+    (
+        9,
+        "snmpNotifyFilterProfileStorType",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 2, 1, 2],
+    ),
+    (
+        9,
+        "snmpNotifyFilterProfileRowStatus",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 2, 1, 3],
+    ),
+    (9, "snmpNotifyFilterTable", &[1, 3, 6, 1, 6, 3, 13, 1, 3]),
+    (9, "snmpNotifyFilterEntry", &[1, 3, 6, 1, 6, 3, 13, 1, 3, 1]),
+    (
+        9,
+        "snmpNotifyFilterSubtree",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 3, 1, 1],
+    ),
+    (
+        9,
+        "snmpNotifyFilterMask",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 3, 1, 2],
+    ),
+    (
+        9,
+        "snmpNotifyFilterType",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 3, 1, 3],
+    ),
+    (
+        9,
+        "snmpNotifyFilterStorageType",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 3, 1, 4],
+    ),
+    (
+        9,
+        "snmpNotifyFilterRowStatus",
+        &[1, 3, 6, 1, 6, 3, 13, 1, 3, 1, 5],
+    ),
+    (9, "snmpNotifyConformance", &[1, 3, 6, 1, 6, 3, 13, 3]),
+    (10, "snmpCommunityMIB", &[1, 3, 6, 1, 6, 3, 18]),
+    (10, "snmpCommunityMIBObjects", &[1, 3, 6, 1, 6, 3, 18, 1]),
+    (10, "snmpCommunityTable", &[1, 3, 6, 1, 6, 3, 18, 1, 1]),
+    (10, "snmpCommunityEntry", &[1, 3, 6, 1, 6, 3, 18, 1, 1, 1]),
+    (
+        10,
+        "snmpCommunityIndex",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 1],
+    ),
+    (10, "snmpCommunityName", &[1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 2]),
+    (
+        10,
+        "snmpCommunitySecurityName",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 3],
+    ),
+    (
+        10,
+        "snmpCommunityContextEngineID",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 4],
+    ),
+    (
+        10,
+        "snmpCommunityContextName",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 5],
+    ),
+    (
+        10,
+        "snmpCommunityTransportTag",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 6],
+    ),
+    (
+        10,
+        "snmpCommunityStorageType",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 7],
+    ),
+    (
+        10,
+        "snmpCommunityStatus",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 1, 1, 8],
+    ),
+    (10, "snmpTargetAddrExtTable", &[1, 3, 6, 1, 6, 3, 18, 1, 2]),
+    (
+        10,
+        "snmpTargetAddrExtEntry",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 2, 1],
+    ),
+    (
+        10,
+        "snmpTargetAddrTMask",
+        &[1, 3, 6, 1, 6, 3, 18, 1, 2, 1, 1],
+    ),
+    (10, "snmpTargetAddrMMS", &[1, 3, 6, 1, 6, 3, 18, 1, 2, 1, 2]),
+    (10, "snmpTrapAddress", &[1, 3, 6, 1, 6, 3, 18, 1, 3]),
+    (10, "snmpTrapCommunity", &[1, 3, 6, 1, 6, 3, 18, 1, 4]),
+    (
+        10,
+        "snmpCommunityMIBConformance",
+        &[1, 3, 6, 1, 6, 3, 18, 2],
+    ),
+];
+
+/// The node named `name`, either `MODULE::name` or a bare `name`, as
+/// (module, name, OID).
+pub fn node(name: &str) -> Option<(&'static str, &'static str, &'static [u32])> {
+    let (module, name) = match name.find("::") {
+        Some(i) => (Some(&name[..i]), &name[i + 2..]),
+        None => (None, name),
+    };
+    NODES
+        .iter()
+        .find(|&&(m, n, _)| n == name && module.is_none_or(|module| MODULES[m as usize] == module))
+        .map(|&(m, n, oid)| (MODULES[m as usize], n, oid))
+}
+
+/// The longest named prefix of `oid`, as (module, name, rest of `oid`).
+pub fn lookup(oid: &[u32]) -> Option<(&'static str, &'static str, &[u32])> {
+    (1..=oid.len()).rev().find_map(|len| {
+        NODES
+            .binary_search_by(|&(_, _, node)| node.cmp(&oid[..len]))
+            .ok()
+            .map(|i| (MODULES[NODES[i].0 as usize], NODES[i].1, &oid[len..]))
+    })
+}
+
+/// Resolves `IF-MIB::ifHCInOctets.3`, `ifHCInOctets.3` or
+/// `1.3.6.1.2.1.31.1.1.1.6.3` to an OID.
+pub fn resolve(name: &str) -> Option<Vec<u32>> {
+    let trimmed = name.trim_start_matches('.');
+    let module_len = trimmed.find("::").map_or(0, |i| i + 2);
+    let (symbol, suffix) = match trimmed[module_len..].find('.') {
+        Some(i) => trimmed.split_at(module_len + i),
+        None => (trimmed, ""),
+    };
+
+    let mut oid = match symbol.parse::<u32>() {
+        Ok(subid) => vec![subid],
+        Err(_) => node(symbol)?.2.to_vec(),
+    };
+    for part in suffix.split('.').skip(1) {
+        oid.push(part.parse().ok()?);
+    }
+    Some(oid)
+}
+
+/// Formats `oid` as `MODULE::name.suffix`, or numerically when no prefix
+/// of it is named.
+pub fn format_oid(oid: &[u32]) -> String {
+    let (mut out, suffix) = match lookup(oid) {
+        Some((module, name, suffix)) => (format!("{}::{}", module, name), suffix),
+        None => (String::new(), oid),
+    };
+    for (i, subid) in suffix.iter().enumerate() {
+        if i > 0 || !out.is_empty() {
+            out.push('.');
+        }
+        out.push_str(&subid.to_string());
+    }
+    out
+}
@@ -0,0 +1,796 @@
+//! Rust code generation from MIB modules, for build scripts.
+//!
+//! For each module the generated code has a Rust module (`IF-MIB` becomes
+//! `if_mib`) with
+//!
+//! - a constant for the OID of every definition (`IF_HC_IN_OCTETS`), and
+//!   the instance of every scalar (`IF_NUMBER_0`),
+//! - an enum for every INTEGER enumeration (`IfAdminStatus`),
+//! - a struct for every table row (`IfEntry`), with index fields, optional
+//!   column fields, index decoding and encoding, and conversions from
+//!   walked varbinds and into `TableHandler` rows.
+//!
+//! ```no_run
+//! // build.rs
+//! use std::{env, path::Path};
+//! use snmp::mib::{Generator, MibTree};
+//!
+//! let mut mibs = MibTree::new();
+//! mibs.add_path("mibs");
+//! mibs.load_module("IF-MIB").unwrap();
+//! let out = Path::new(&env::var("OUT_DIR").unwrap()).join("mibs.rs");
+//! Generator::new(&mibs, &["IF-MIB"]).write_to(out).unwrap();
+//! println!("cargo:rerun-if-changed=mibs");
+//! ```
+//!
+//! The generated file is then included with
+//! `include!(concat!(env!("OUT_DIR"), "/mibs.rs"));`.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use super::index::{IndexObject, IndexType};
+use super::tree::{Access, BaseType, MibTree, Node, NodeKind, Syntax};
+use super::MibError;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "priv", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true",
+    "try", "type", "unsafe", "use", "where", "while", "yield",
+];
+
+/// Generates Rust code for modules of a `MibTree`.
+pub struct Generator<'a> {
+    tree: &'a MibTree,
+    modules: Vec<String>,
+    /// The path of this crate in the generated code, `::snmp` by default.
+    pub crate_path: String,
+}
+
+impl<'a> Generator<'a> {
+    /// A generator for the given modules, which must be loaded in `tree`.
+    pub fn new(tree: &'a MibTree, modules: &[&str]) -> Self {
+        Generator {
+            tree,
+            modules: modules.iter().map(|m| m.to_string()).collect(),
+            crate_path: "::snmp".to_string(),
+        }
+    }
+
+    pub fn generate(&self) -> Result<String, MibError> {
+        let mut out = String::new();
+        for module in &self.modules {
+            self.module(&mut out, module)?;
+        }
+        Ok(out)
+    }
+
+    /// Generates the code into a file, only writing it if it changed so
+    /// as not to trigger needless rebuilds.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), MibError> {
+        let code = self.generate()?;
+        if fs::read_to_string(&path).ok().as_ref() != Some(&code) {
+            fs::write(path, code)?;
+        }
+        Ok(())
+    }
+
+    /// The nodes defined by `module`, in OID order.
+    fn nodes(&self, module: &str) -> Result<Vec<&'a Node>, MibError> {
+        let parsed = self
+            .tree
+            .modules()
+            .iter()
+            .find(|m| m.name == module)
+            .ok_or_else(|| MibError::UnknownModule(module.to_string()))?;
+        let mut nodes: Vec<&Node> = parsed
+            .definitions
+            .iter()
+            .filter_map(|def| self.tree.node(&format!("{}::{}", module, def.name)))
+            .filter(|node| node.module == module)
+            .collect();
+        nodes.sort_by(|a, b| a.oid.cmp(&b.oid));
+        Ok(nodes)
+    }
+
+    fn module(&self, out: &mut String, module: &str) -> Result<(), MibError> {
+        let nodes = self.nodes(module)?;
+        let cp = &self.crate_path;
+
+        writeln!(out, "/// Generated from {}.", module).unwrap();
+        writeln!(out, "#[allow(dead_code, unused_imports, clippy::all)]").unwrap();
+        writeln!(out, "pub mod {} {{", snake_case(module)).unwrap();
+        writeln!(out, "    use {}::agent::TableHandler;", cp).unwrap();
+        writeln!(out, "    use {}::mib::index;", cp).unwrap();
+        writeln!(out, "    use {}::OwnedValue;", cp).unwrap();
+
+        for node in &nodes {
+            let name = snake_case(&node.name).to_uppercase();
+            writeln!(out).unwrap();
+            writeln!(out, "    /// {}::{}", node.module, node.name).unwrap();
+            writeln!(out, "    pub const {}: &[u32] = &{:?};", name, node.oid).unwrap();
+            if node.kind == NodeKind::Scalar {
+                let mut instance = node.oid.clone();
+                instance.push(0);
+                writeln!(out, "    pub const {}_0: &[u32] = &{:?};", name, instance).unwrap();
+            }
+        }
+
+        for node in &nodes {
+            let Some(syntax) = enumeration(node) else {
+                continue;
+            };
+            write_enum(out, node, syntax, cp);
+        }
+
+        for node in &nodes {
+            if node.kind == NodeKind::Row {
+                self.row(out, node, &nodes)?;
+            }
+        }
+
+        writeln!(out, "}}").unwrap();
+        Ok(())
+    }
+
+    fn row(&self, out: &mut String, row: &Node, nodes: &[&Node]) -> Result<(), MibError> {
+        let index = self
+            .tree
+            .table_index(&format!("{}::{}", row.module, row.name))?;
+
+        let columns: Vec<&Node> = nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::Column && n.oid[..n.oid.len() - 1] == row.oid[..])
+            .cloned()
+            .collect();
+
+        let mut fields = Vec::new();
+        for (position, object) in index.objects.iter().enumerate() {
+            fields.push(index_field(object, position, &columns));
+        }
+        for column in &columns {
+            if fields
+                .iter()
+                .any(|f| f.column == Some(*column.oid.last().unwrap()))
+            {
+                continue;
+            }
+            fields.push(Field::column(column, None));
+        }
+
+        let ty = camel_case(&row.name);
+        let accessible: Vec<u32> = fields
+            .iter()
+            .filter(|f| f.accessible)
+            .filter_map(|f| f.column)
+            .collect();
+        let writable: Vec<u32> = fields
+            .iter()
+            .filter(|f| f.writable)
+            .filter_map(|f| f.column)
+            .collect();
+        let index_fields: Vec<&Field> = fields.iter().filter(|f| f.index.is_some()).collect();
+
+        writeln!(out).unwrap();
+        writeln!(out, "    /// A row of {}::{}.", row.module, row.name).unwrap();
+        writeln!(out, "    #[derive(Clone, Debug, PartialEq)]").unwrap();
+        writeln!(out, "    pub struct {} {{", ty).unwrap();
+        for field in &fields {
+            if field.index.is_some() {
+                writeln!(out, "        pub {}: {},", field.name, field.ty.rust_type()).unwrap();
+            } else {
+                writeln!(
+                    out,
+                    "        pub {}: Option<{}>,",
+                    field.name,
+                    field.ty.rust_type()
+                )
+                .unwrap();
+            }
+        }
+        writeln!(out, "    }}").unwrap();
+
+        writeln!(out).unwrap();
+        writeln!(out, "    impl {} {{", ty).unwrap();
+        writeln!(
+            out,
+            "        pub const ENTRY: &'static [u32] = {};",
+            snake_case(&row.name).to_uppercase()
+        )
+        .unwrap();
+        writeln!(out, "        /// The accessible columns.").unwrap();
+        writeln!(
+            out,
+            "        pub const COLUMNS: &'static [u32] = &{:?};",
+            accessible
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        pub const WRITABLE: &'static [u32] = &{:?};",
+            writable
+        )
+        .unwrap();
+
+        // new
+        let params: Vec<String> = index_fields
+            .iter()
+            .map(|f| format!("{}: {}", f.name, f.ty.rust_type()))
+            .collect();
+        writeln!(out).unwrap();
+        writeln!(out, "        pub fn new({}) -> Self {{", params.join(", ")).unwrap();
+        writeln!(out, "            {} {{", ty).unwrap();
+        for field in &fields {
+            if field.index.is_some() {
+                writeln!(out, "                {},", field.name).unwrap();
+            } else {
+                writeln!(out, "                {}: None,", field.name).unwrap();
+            }
+        }
+        writeln!(out, "            }}").unwrap();
+        writeln!(out, "        }}").unwrap();
+
+        // index decoding
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "        /// The row with the given index and no column values."
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        pub fn from_index(index: &[u32]) -> Option<Self> {{"
+        )
+        .unwrap();
+        writeln!(out, "            let mut rest = index;").unwrap();
+        for field in &index_fields {
+            writeln!(
+                out,
+                "            let {} = {};",
+                field.name,
+                field.decode_index()
+            )
+            .unwrap();
+        }
+        writeln!(out, "            if !rest.is_empty() {{").unwrap();
+        writeln!(out, "                return None;").unwrap();
+        writeln!(out, "            }}").unwrap();
+        let names: Vec<&str> = index_fields.iter().map(|f| &f.name[..]).collect();
+        writeln!(out, "            Some({}::new({}))", ty, names.join(", ")).unwrap();
+        writeln!(out, "        }}").unwrap();
+
+        // index encoding
+        writeln!(out).unwrap();
+        writeln!(out, "        pub fn index(&self) -> Vec<u32> {{").unwrap();
+        writeln!(out, "            let mut index = Vec::new();").unwrap();
+        for field in &index_fields {
+            writeln!(out, "            {};", field.encode_index()).unwrap();
+        }
+        writeln!(out, "            index").unwrap();
+        writeln!(out, "        }}").unwrap();
+
+        // column values
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "        /// Sets the field of a column from its value. Returns whether the"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        /// column is known and the value of the right type."
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        pub fn set_column(&mut self, column: u32, value: &OwnedValue) -> bool {{"
+        )
+        .unwrap();
+        writeln!(out, "            match column {{").unwrap();
+        for field in fields.iter().filter(|f| f.index.is_none()) {
+            let Some(column) = field.column else {
+                continue;
+            };
+            writeln!(out, "                {} => {{", column).unwrap();
+            writeln!(
+                out,
+                "                    self.{} = {};",
+                field.name,
+                field.ty.value_to_field("value")
+            )
+            .unwrap();
+            writeln!(out, "                    self.{}.is_some()", field.name).unwrap();
+            writeln!(out, "                }}").unwrap();
+        }
+        writeln!(out, "                _ => false,").unwrap();
+        writeln!(out, "            }}").unwrap();
+        writeln!(out, "        }}").unwrap();
+
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "        /// The values of the accessible columns that are set, as"
+        )
+        .unwrap();
+        writeln!(out, "        /// `TableHandler::insert_row` takes them.").unwrap();
+        writeln!(
+            out,
+            "        pub fn values(&self) -> Vec<(u32, OwnedValue)> {{"
+        )
+        .unwrap();
+        writeln!(out, "            let mut values = Vec::new();").unwrap();
+        for field in fields.iter().filter(|f| f.accessible) {
+            let column = field.column.unwrap();
+            if field.index.is_some() {
+                let value = field.ty.to_value(&format!("(&self.{})", field.name));
+                writeln!(out, "            values.push(({}, {}));", column, value).unwrap();
+            } else {
+                writeln!(
+                    out,
+                    "            if let Some(ref v) = self.{} {{",
+                    field.name
+                )
+                .unwrap();
+                writeln!(
+                    out,
+                    "                values.push(({}, {}));",
+                    column,
+                    field.ty.to_value("v")
+                )
+                .unwrap();
+                writeln!(out, "            }}").unwrap();
+            }
+        }
+        writeln!(out, "            values").unwrap();
+        writeln!(out, "        }}").unwrap();
+
+        // walks and agent tables
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "        /// Collects rows, in index order, from instance varbinds such as"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        /// those of a walk. Varbinds of other objects are ignored."
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        pub fn from_varbinds<I>(varbinds: I) -> Vec<Self>"
+        )
+        .unwrap();
+        writeln!(out, "        where").unwrap();
+        writeln!(
+            out,
+            "            I: IntoIterator<Item = (Vec<u32>, OwnedValue)>,"
+        )
+        .unwrap();
+        writeln!(out, "        {{").unwrap();
+        writeln!(
+            out,
+            "            let mut rows = std::collections::BTreeMap::new();"
+        )
+        .unwrap();
+        writeln!(out, "            for (oid, value) in varbinds {{").unwrap();
+        writeln!(
+            out,
+            "                let Some(rest) = oid.strip_prefix(Self::ENTRY) else {{"
+        )
+        .unwrap();
+        writeln!(out, "                    continue;").unwrap();
+        writeln!(out, "                }};").unwrap();
+        writeln!(
+            out,
+            "                let Some((&column, index)) = rest.split_first() else {{"
+        )
+        .unwrap();
+        writeln!(out, "                    continue;").unwrap();
+        writeln!(out, "                }};").unwrap();
+        writeln!(out, "                if !rows.contains_key(index) {{").unwrap();
+        writeln!(out, "                    match Self::from_index(index) {{").unwrap();
+        writeln!(
+            out,
+            "                        Some(row) => rows.insert(index.to_vec(), row),"
+        )
+        .unwrap();
+        writeln!(out, "                        None => continue,").unwrap();
+        writeln!(out, "                    }};").unwrap();
+        writeln!(out, "                }}").unwrap();
+        writeln!(
+            out,
+            "                rows.get_mut(index).unwrap().set_column(column, &value);"
+        )
+        .unwrap();
+        writeln!(out, "            }}").unwrap();
+        writeln!(out, "            rows.into_values().collect()").unwrap();
+        writeln!(out, "        }}").unwrap();
+
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "        /// An empty table of these rows, to register at `ENTRY`."
+        )
+        .unwrap();
+        writeln!(out, "        pub fn table_handler() -> TableHandler {{").unwrap();
+        writeln!(
+            out,
+            "            let mut table = TableHandler::new(Self::ENTRY, Self::COLUMNS);"
+        )
+        .unwrap();
+        writeln!(out, "            table.set_writable(Self::WRITABLE);").unwrap();
+        writeln!(out, "            table").unwrap();
+        writeln!(out, "        }}").unwrap();
+
+        writeln!(out).unwrap();
+        writeln!(out, "        /// Adds or replaces the row in `table`.").unwrap();
+        writeln!(
+            out,
+            "        pub fn insert_into(&self, table: &mut TableHandler) {{"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "            table.insert_row(&self.index(), self.values());"
+        )
+        .unwrap();
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+        Ok(())
+    }
+}
+
+fn index_field(object: &IndexObject, position: usize, columns: &[&Node]) -> Field {
+    let spec = Some((object.implied, position));
+    if let Some(column) = columns.iter().find(|c| c.name == object.name) {
+        return Field::column(column, spec);
+    }
+
+    // an object of another table, or an SMIv1 type name
+    let name = if object.name.starts_with(|c: char| c.is_ascii_lowercase()) {
+        snake_case(&object.name)
+    } else {
+        format!("index_{}", position + 1)
+    };
+    let ty = match object.ty {
+        IndexType::Integer => FieldType::Integer,
+        IndexType::OctetString(size) => FieldType::Octets(size),
+        IndexType::ObjectIdentifier => FieldType::Oid,
+        IndexType::IpAddress => FieldType::IpAddress,
+    };
+    Field {
+        name: ident(&name),
+        column: None,
+        accessible: false,
+        writable: false,
+        ty,
+        index: spec,
+    }
+}
+
+/// A field of a generated row struct.
+struct Field {
+    name: String,
+    column: Option<u32>,
+    accessible: bool,
+    writable: bool,
+    ty: FieldType,
+    /// IMPLIED and position, for index fields.
+    index: Option<(bool, usize)>,
+}
+
+impl Field {
+    fn column(node: &Node, index: Option<(bool, usize)>) -> Field {
+        let enum_type = enumeration(node).map(|_| camel_case(&node.name));
+        let syntax = node.syntax.as_ref();
+        Field {
+            name: ident(&snake_case(&node.name)),
+            column: node.oid.last().cloned(),
+            accessible: !matches!(
+                node.access,
+                Some(Access::NotAccessible) | Some(Access::AccessibleForNotify)
+            ),
+            writable: matches!(
+                node.access,
+                Some(Access::ReadWrite) | Some(Access::ReadCreate) | Some(Access::WriteOnly)
+            ),
+            ty: syntax.map_or(FieldType::Octets(None), |s| FieldType::of(s, enum_type)),
+            index,
+        }
+    }
+
+    fn decode_index(&self) -> String {
+        let implied = self.index.is_some_and(|(implied, _)| implied);
+        match self.ty {
+            FieldType::Integer => "index::decode_integer(&mut rest)?".to_string(),
+            FieldType::Enum(ref ty) => {
+                format!("{}::from_i64(index::decode_integer(&mut rest)?)?", ty)
+            }
+            FieldType::Unsigned(_) => "index::decode_integer(&mut rest)? as u32".to_string(),
+            FieldType::Counter64 => "index::decode_integer(&mut rest)? as u64".to_string(),
+            FieldType::Octets(size) | FieldType::Opaque(size) => {
+                format!("index::decode_octets(&mut rest, {:?}, {})?", size, implied)
+            }
+            FieldType::Text(size) => format!(
+                "String::from_utf8_lossy(&index::decode_octets(&mut rest, {:?}, {})?).into_owned()",
+                size, implied
+            ),
+            FieldType::Oid => format!("index::decode_oid(&mut rest, {})?", implied),
+            FieldType::IpAddress => "index::decode_ip_address(&mut rest)?".to_string(),
+        }
+    }
+
+    fn encode_index(&self) -> String {
+        let implied = self.index.is_some_and(|(implied, _)| implied);
+        let name = &self.name;
+        match self.ty {
+            FieldType::Integer => format!("index::encode_integer(&mut index, self.{})", name),
+            FieldType::Enum(_) | FieldType::Unsigned(_) | FieldType::Counter64 => {
+                format!("index::encode_integer(&mut index, self.{} as i64)", name)
+            }
+            FieldType::Octets(size) | FieldType::Opaque(size) => format!(
+                "index::encode_octets(&mut index, &self.{}, {}, {})",
+                name,
+                size.is_some(),
+                implied
+            ),
+            FieldType::Text(size) => format!(
+                "index::encode_octets(&mut index, self.{}.as_bytes(), {}, {})",
+                name,
+                size.is_some(),
+                implied
+            ),
+            FieldType::Oid => format!("index::encode_oid(&mut index, &self.{}, {})", name, implied),
+            FieldType::IpAddress => format!("index::encode_ip_address(&mut index, self.{})", name),
+        }
+    }
+}
+
+/// The Rust representation of a syntax.
+enum FieldType {
+    Integer,
+    Enum(String),
+    /// The `OwnedValue` variant of Counter32, Gauge32 or TimeTicks values.
+    Unsigned(&'static str),
+    Counter64,
+    /// With the size of fixed-size strings.
+    Octets(Option<usize>),
+    /// DisplayString and other strings with a text display hint.
+    Text(Option<usize>),
+    Opaque(Option<usize>),
+    Oid,
+    IpAddress,
+}
+
+impl FieldType {
+    fn of(syntax: &Syntax, enum_type: Option<String>) -> FieldType {
+        let size = match syntax.sizes[..] {
+            [(min, max)] if min == max && min >= 0 => Some(min as usize),
+            _ => None,
+        };
+        match syntax.base {
+            BaseType::Integer => match enum_type {
+                Some(ty) => FieldType::Enum(ty),
+                None => FieldType::Integer,
+            },
+            BaseType::OctetString => match syntax.display_hint.as_deref() {
+                Some("255a") | Some("255t") => FieldType::Text(size),
+                _ => FieldType::Octets(size),
+            },
+            BaseType::Bits | BaseType::Sequence | BaseType::SequenceOf => FieldType::Octets(size),
+            BaseType::Opaque => FieldType::Opaque(size),
+            BaseType::ObjectIdentifier => FieldType::Oid,
+            BaseType::IpAddress => FieldType::IpAddress,
+            BaseType::Counter32 => FieldType::Unsigned("Counter32"),
+            BaseType::Gauge32 | BaseType::Unsigned32 => FieldType::Unsigned("Unsigned32"),
+            BaseType::TimeTicks => FieldType::Unsigned("Timeticks"),
+            BaseType::Counter64 => FieldType::Counter64,
+        }
+    }
+
+    fn rust_type(&self) -> String {
+        match *self {
+            FieldType::Integer => "i64".to_string(),
+            FieldType::Enum(ref ty) => ty.clone(),
+            FieldType::Unsigned(_) => "u32".to_string(),
+            FieldType::Counter64 => "u64".to_string(),
+            FieldType::Octets(_) | FieldType::Opaque(_) => "Vec<u8>".to_string(),
+            FieldType::Text(_) => "String".to_string(),
+            FieldType::Oid => "Vec<u32>".to_string(),
+            FieldType::IpAddress => "[u8; 4]".to_string(),
+        }
+    }
+
+    /// The `OwnedValue` of a reference `v` to a field value.
+    fn to_value(&self, v: &str) -> String {
+        match *self {
+            FieldType::Integer => format!("OwnedValue::Integer(*{})", v),
+            FieldType::Enum(_) => format!("OwnedValue::Integer(*{} as i64)", v),
+            FieldType::Unsigned(variant) => format!("OwnedValue::{}(*{})", variant, v),
+            FieldType::Counter64 => format!("OwnedValue::Counter64(*{})", v),
+            FieldType::Octets(_) => format!("OwnedValue::OctetString({}.clone())", v),
+            FieldType::Text(_) => format!("OwnedValue::OctetString({}.as_bytes().to_vec())", v),
+            FieldType::Opaque(_) => format!("OwnedValue::Opaque({}.clone())", v),
+            FieldType::Oid => format!("OwnedValue::ObjectIdentifier({}.clone())", v),
+            FieldType::IpAddress => format!("OwnedValue::IpAddress(*{})", v),
+        }
+    }
+
+    /// An `Option` of the field value from a reference `value` to an
+    /// `OwnedValue`.
+    fn value_to_field(&self, value: &str) -> String {
+        let (pattern, expr) = match *self {
+            FieldType::Integer => ("Integer(v)".to_string(), "Some(*v)".to_string()),
+            FieldType::Enum(ref ty) => ("Integer(v)".to_string(), format!("{}::from_i64(*v)", ty)),
+            FieldType::Unsigned(variant) => (format!("{}(v)", variant), "Some(*v)".to_string()),
+            FieldType::Counter64 => ("Counter64(v)".to_string(), "Some(*v)".to_string()),
+            FieldType::Octets(_) => ("OctetString(v)".to_string(), "Some(v.clone())".to_string()),
+            FieldType::Text(_) => (
+                "OctetString(v)".to_string(),
+                "Some(String::from_utf8_lossy(v).into_owned())".to_string(),
+            ),
+            FieldType::Opaque(_) => ("Opaque(v)".to_string(), "Some(v.clone())".to_string()),
+            FieldType::Oid => (
+                "ObjectIdentifier(v)".to_string(),
+                "Some(v.clone())".to_string(),
+            ),
+            FieldType::IpAddress => ("IpAddress(v)".to_string(), "Some(*v)".to_string()),
+        };
+        format!(
+            "match {} {{ OwnedValue::{} => {}, _ => None }}",
+            value, pattern, expr
+        )
+    }
+}
+
+/// The syntax of nodes that get an enum: INTEGER enumerations of objects.
+fn enumeration(node: &Node) -> Option<&Syntax> {
+    let syntax = node.syntax.as_ref()?;
+    let object = matches!(node.kind, NodeKind::Scalar | NodeKind::Column);
+    (object && syntax.base == BaseType::Integer && !syntax.enums.is_empty()).then_some(syntax)
+}
+
+fn write_enum(out: &mut String, node: &Node, syntax: &Syntax, cp: &str) {
+    let ty = camel_case(&node.name);
+    let mut variants: Vec<(String, &str, i64)> = Vec::new();
+    for (label, value) in &syntax.enums {
+        let mut variant = camel_case(label);
+        if variants.iter().any(|(v, _, _)| *v == variant) {
+            variant = format!("{}{}", variant, value);
+        }
+        variants.push((variant, label, *value));
+    }
+
+    writeln!(out).unwrap();
+    writeln!(out, "    /// The values of {}::{}.", node.module, node.name).unwrap();
+    writeln!(
+        out,
+        "    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]"
+    )
+    .unwrap();
+    writeln!(out, "    #[repr(i64)]").unwrap();
+    writeln!(out, "    pub enum {} {{", ty).unwrap();
+    for (variant, _, value) in &variants {
+        writeln!(out, "        {} = {},", variant, value).unwrap();
+    }
+    writeln!(out, "    }}").unwrap();
+
+    writeln!(out).unwrap();
+    writeln!(out, "    impl {} {{", ty).unwrap();
+    writeln!(
+        out,
+        "        pub fn from_i64(value: i64) -> Option<Self> {{"
+    )
+    .unwrap();
+    writeln!(out, "            match value {{").unwrap();
+    for (variant, _, value) in &variants {
+        writeln!(
+            out,
+            "                {} => Some({}::{}),",
+            value, ty, variant
+        )
+        .unwrap();
+    }
+    writeln!(out, "                _ => None,").unwrap();
+    writeln!(out, "            }}").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "        /// The label of the value in the MIB.").unwrap();
+    writeln!(out, "        pub fn label(self) -> &'static str {{").unwrap();
+    writeln!(out, "            match self {{").unwrap();
+    for (variant, label, _) in &variants {
+        writeln!(out, "                {}::{} => {:?},", ty, variant, label).unwrap();
+    }
+    writeln!(out, "            }}").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+
+    writeln!(out).unwrap();
+    writeln!(out, "    impl From<{}> for OwnedValue {{", ty).unwrap();
+    writeln!(out, "        fn from(value: {}) -> OwnedValue {{", ty).unwrap();
+    writeln!(out, "            OwnedValue::Integer(value as i64)").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+
+    writeln!(out).unwrap();
+    writeln!(out, "    impl TryFrom<&OwnedValue> for {} {{", ty).unwrap();
+    writeln!(out, "        type Error = {}::SnmpError;", cp).unwrap();
+    writeln!(
+        out,
+        "        fn try_from(value: &OwnedValue) -> Result<Self, Self::Error> {{"
+    )
+    .unwrap();
+    writeln!(out, "            match *value {{").unwrap();
+    writeln!(
+        out,
+        "                OwnedValue::Integer(v) => {}::from_i64(v).ok_or({}::SnmpError::ValueOutOfRange),",
+        ty, cp
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "                _ => Err({}::SnmpError::AsnWrongType),",
+        cp
+    )
+    .unwrap();
+    writeln!(out, "            }}").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+}
+
+/// `ifHCInOctets` to `if_hc_in_octets`, `IF-MIB` to `if_mib`.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '-' || c == '_' {
+            if !out.ends_with('_') {
+                out.push('_');
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            let boundary = prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_lower);
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+/// `ifEntry` to `IfEntry`, `iso88023-csmacd` to `Iso88023Csmacd`.
+fn camel_case(name: &str) -> String {
+    let mut out = String::new();
+    let mut upper = true;
+    for c in name.chars() {
+        if c == '-' || c == '_' {
+            upper = true;
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    if out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, 'V');
+    }
+    out
+}
+
+fn ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
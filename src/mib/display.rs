@@ -0,0 +1,714 @@
+//! Formatting values for display according to their SYNTAX, and parsing
+//! such text back into values.
+//!
+//! Octet strings follow the DISPLAY-HINT of their textual convention
+//! (RFC 2579 section 3.1), which covers DisplayString, PhysAddress,
+//! MacAddress and DateAndTime. Integers are shown by enumeration label or
+//! by their DISPLAY-HINT, and BITS by the names of the bits that are set.
+
+use std::fmt::Write;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::tree::{Access, BaseType, MibTree, NodeKind, Syntax};
+use super::MibError;
+use crate::OwnedValue;
+
+impl Syntax {
+    /// Formats `value` for display. Object identifiers are shown
+    /// numerically; use `MibTree::format_value` to name them.
+    pub fn format(&self, value: &OwnedValue) -> String {
+        self.format_as(value, None)
+    }
+
+    /// Formats `value`, reading an InetAddress as of type `addr_type`, or
+    /// guessing the type from its length when that is `None`.
+    fn format_as(&self, value: &OwnedValue, addr_type: Option<i64>) -> String {
+        match *value {
+            OwnedValue::Integer(n) => self.format_integer(n),
+            OwnedValue::Counter32(n) | OwnedValue::Unsigned32(n) | OwnedValue::Timeticks(n) => {
+                self.format_integer(n as i64)
+            }
+            OwnedValue::Counter64(n) => match i64::try_from(n) {
+                Ok(n) => self.format_integer(n),
+                Err(_) => n.to_string(),
+            },
+            OwnedValue::OctetString(ref octets) => self.format_octets(octets, addr_type),
+            OwnedValue::ObjectIdentifier(ref oid) => dotted(oid),
+            OwnedValue::IpAddress(ip) => Ipv4Addr::from(ip).to_string(),
+            OwnedValue::Opaque(ref octets) => hex(octets),
+            OwnedValue::Boolean(b) => b.to_string(),
+            OwnedValue::Null => "NULL".to_string(),
+            OwnedValue::EndOfMibView => "endOfMibView".to_string(),
+            OwnedValue::NoSuchObject => "noSuchObject".to_string(),
+            OwnedValue::NoSuchInstance => "noSuchInstance".to_string(),
+        }
+    }
+
+    /// Parses text in the form `format` produces, or a plain number or
+    /// label, into a value of this syntax. Object identifiers must be
+    /// numeric; use `MibTree::parse_value` to resolve names.
+    pub fn parse(&self, text: &str) -> Result<OwnedValue, MibError> {
+        self.parse_as(text, None)
+    }
+
+    /// Parses `text`, reading an InetAddress as of type `addr_type`, or as
+    /// an IPv4 or IPv6 address or plain text when that is `None`.
+    fn parse_as(&self, text: &str, addr_type: Option<i64>) -> Result<OwnedValue, MibError> {
+        let invalid = || MibError::InvalidValue(text.to_string());
+        let text = text.trim();
+        let value = match self.base {
+            BaseType::Integer => OwnedValue::Integer(self.parse_integer(text).ok_or_else(invalid)?),
+            BaseType::Counter32 => {
+                OwnedValue::Counter32(self.parse_unsigned(text).ok_or_else(invalid)?)
+            }
+            BaseType::Gauge32 | BaseType::Unsigned32 => {
+                OwnedValue::Unsigned32(self.parse_unsigned(text).ok_or_else(invalid)?)
+            }
+            BaseType::TimeTicks => {
+                OwnedValue::Timeticks(self.parse_unsigned(text).ok_or_else(invalid)?)
+            }
+            BaseType::Counter64 => OwnedValue::Counter64(text.parse().map_err(|_| invalid())?),
+            BaseType::OctetString => {
+                OwnedValue::OctetString(self.parse_octets(text, addr_type).ok_or_else(invalid)?)
+            }
+            BaseType::Bits => OwnedValue::OctetString(self.parse_bits(text).ok_or_else(invalid)?),
+            BaseType::ObjectIdentifier => {
+                let oid: Result<Vec<u32>, _> = text
+                    .trim_start_matches('.')
+                    .split('.')
+                    .map(str::parse)
+                    .collect();
+                OwnedValue::ObjectIdentifier(oid.map_err(|_| invalid())?)
+            }
+            BaseType::IpAddress => {
+                let ip: Ipv4Addr = text.parse().map_err(|_| invalid())?;
+                OwnedValue::IpAddress(ip.octets())
+            }
+            BaseType::Opaque => OwnedValue::Opaque(parse_hex(text).ok_or_else(invalid)?),
+            BaseType::Sequence | BaseType::SequenceOf => return Err(invalid()),
+        };
+        Ok(value)
+    }
+
+    /// A syntax with no constraints or hint, for values of unknown objects.
+    fn untyped(value: &OwnedValue) -> Syntax {
+        let base = match *value {
+            OwnedValue::OctetString(_) => BaseType::OctetString,
+            OwnedValue::Opaque(_) => BaseType::Opaque,
+            _ => BaseType::Integer,
+        };
+        Syntax {
+            base,
+            tc: None,
+            display_hint: None,
+            enums: Vec::new(),
+            ranges: Vec::new(),
+            sizes: Vec::new(),
+        }
+    }
+
+    fn format_integer(&self, n: i64) -> String {
+        if let Some(label) = self.label(n) {
+            return label.to_string();
+        }
+        match self.display_hint.as_deref() {
+            Some(hint) => format_integer_hint(hint, n),
+            None => n.to_string(),
+        }
+    }
+
+    fn parse_integer(&self, text: &str) -> Option<i64> {
+        // `up`, `up(1)` or `1`
+        let label = text.split('(').next().unwrap_or(text);
+        if let Some(value) = self.value(label) {
+            return Some(value);
+        }
+        if let Some(number) = text
+            .strip_prefix(label)
+            .and_then(|rest| rest.strip_prefix('('))
+        {
+            return number.strip_suffix(')')?.parse().ok();
+        }
+        match self.display_hint.as_deref() {
+            Some(hint) => parse_integer_hint(hint, text),
+            None => text.parse().ok(),
+        }
+    }
+
+    fn parse_unsigned(&self, text: &str) -> Option<u32> {
+        u32::try_from(self.parse_integer(text)?).ok()
+    }
+
+    fn format_octets(&self, octets: &[u8], addr_type: Option<i64>) -> String {
+        if self.base == BaseType::Bits {
+            return self.format_bits(octets);
+        }
+        if let Some(hint) = self.display_hint.as_deref() {
+            if let Some(specs) = OctetSpec::parse_hint(hint) {
+                return format_octets_hint(&specs, octets);
+            }
+        }
+        if self.tc.as_deref() == Some("InetAddress") {
+            let addr_type = addr_type.unwrap_or_else(|| inet_address_type(octets.len()));
+            if let Some(text) = format_inet_address(addr_type, octets) {
+                return text;
+            }
+        }
+        if is_printable(octets) {
+            String::from_utf8_lossy(octets).into_owned()
+        } else {
+            hex(octets)
+        }
+    }
+
+    fn parse_octets(&self, text: &str, addr_type: Option<i64>) -> Option<Vec<u8>> {
+        if let Some(hint) = self.display_hint.as_deref() {
+            if let Some(specs) = OctetSpec::parse_hint(hint) {
+                return parse_octets_hint(&specs, text);
+            }
+        }
+        if self.tc.as_deref() == Some("InetAddress") {
+            if let Some(addr_type) = addr_type {
+                return parse_inet_address(addr_type, text);
+            }
+            if let Some(octets) =
+                parse_inet_address(1, text).or_else(|| parse_inet_address(2, text))
+            {
+                return Some(octets);
+            }
+        }
+        match parse_hex(text) {
+            Some(octets) if text.starts_with("0x") => Some(octets),
+            _ => Some(text.as_bytes().to_vec()),
+        }
+    }
+
+    /// The names of the bits set, with unnamed bits by number.
+    fn format_bits(&self, octets: &[u8]) -> String {
+        let mut names = Vec::new();
+        for (i, octet) in octets.iter().enumerate() {
+            for bit in 0..8 {
+                if octet & (0x80 >> bit) != 0 {
+                    let number = (i * 8 + bit) as i64;
+                    names.push(match self.label(number) {
+                        Some(label) => label.to_string(),
+                        None => number.to_string(),
+                    });
+                }
+            }
+        }
+        names.join(" ")
+    }
+
+    fn parse_bits(&self, text: &str) -> Option<Vec<u8>> {
+        let mut octets = Vec::new();
+        for name in text.split(|c: char| c.is_whitespace() || c == ',' || c == '{' || c == '}') {
+            if name.is_empty() {
+                continue;
+            }
+            let label = name.split('(').next().unwrap_or(name);
+            let number = match self.value(label) {
+                Some(number) => number,
+                None => name.parse().ok()?,
+            };
+            let number = usize::try_from(number).ok()?;
+            if octets.len() <= number / 8 {
+                octets.resize(number / 8 + 1, 0);
+            }
+            octets[number / 8] |= 0x80 >> (number % 8);
+        }
+        Some(octets)
+    }
+}
+
+impl Syntax {
+    /// Checks `value` against the ranges, sizes and enumeration of this
+    /// syntax.
+    pub fn check(&self, value: &OwnedValue) -> Result<(), MibError> {
+        let number = match *value {
+            OwnedValue::Integer(n) => Some(n as i128),
+            OwnedValue::Counter32(n) | OwnedValue::Unsigned32(n) | OwnedValue::Timeticks(n) => {
+                Some(n as i128)
+            }
+            OwnedValue::Counter64(n) => Some(n as i128),
+            _ => None,
+        };
+        if let Some(n) = number {
+            if self.base == BaseType::Integer && !self.enums.is_empty() {
+                if self.label(n as i64).is_none() {
+                    return Err(MibError::Constraint(format!(
+                        "{} is not a valid enumeration",
+                        n
+                    )));
+                }
+            } else if !in_ranges(&self.ranges, n) {
+                return Err(MibError::Constraint(format!(
+                    "{} is outside {}",
+                    n,
+                    format_ranges(&self.ranges)
+                )));
+            }
+        }
+        if let OwnedValue::OctetString(ref octets) | OwnedValue::Opaque(ref octets) = *value {
+            if self.base != BaseType::Bits && !in_ranges(&self.sizes, octets.len() as i128) {
+                return Err(MibError::Constraint(format!(
+                    "size {} is outside {}",
+                    octets.len(),
+                    format_ranges(&self.sizes)
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn in_ranges(ranges: &[(i128, i128)], n: i128) -> bool {
+    ranges.is_empty() || ranges.iter().any(|&(min, max)| min <= n && n <= max)
+}
+
+fn format_ranges(ranges: &[(i128, i128)]) -> String {
+    let parts: Vec<String> = ranges
+        .iter()
+        .map(|&(min, max)| {
+            if min == max {
+                min.to_string()
+            } else {
+                format!("{}..{}", min, max)
+            }
+        })
+        .collect();
+    format!("({})", parts.join(" | "))
+}
+
+impl MibTree {
+    /// Parses `text` into a value to SET instance `oid` to, checking that
+    /// the object is writable and the value within its constraints.
+    pub fn value_for_set(&self, oid: &[u32], text: &str) -> Result<OwnedValue, MibError> {
+        self.value_for_set_as(oid, text, None)
+    }
+
+    /// As `value_for_set`, reading an InetAddress column as of the type
+    /// given by `addr_type`, the value of the row's InetAddressType column.
+    pub fn value_for_row_set(
+        &self,
+        oid: &[u32],
+        text: &str,
+        addr_type: &OwnedValue,
+    ) -> Result<OwnedValue, MibError> {
+        self.value_for_set_as(oid, text, inet_type_value(addr_type))
+    }
+
+    fn value_for_set_as(
+        &self,
+        oid: &[u32],
+        text: &str,
+        addr_type: Option<i64>,
+    ) -> Result<OwnedValue, MibError> {
+        let node = self
+            .lookup(oid)
+            .map(|(node, _)| node)
+            .filter(|node| matches!(node.kind, NodeKind::Scalar | NodeKind::Column))
+            .ok_or_else(|| MibError::UnknownName(dotted(oid)))?;
+        if !matches!(
+            node.access,
+            Some(Access::ReadWrite) | Some(Access::ReadCreate) | Some(Access::WriteOnly)
+        ) {
+            return Err(MibError::NotWritable(format!(
+                "{}::{}",
+                node.module, node.name
+            )));
+        }
+        let value = self.parse_value_as(oid, text, addr_type)?;
+        if let Some(ref syntax) = node.syntax {
+            syntax.check(&value).map_err(|err| {
+                MibError::Constraint(format!("{}::{}: {}", node.module, node.name, err))
+            })?;
+        }
+        Ok(value)
+    }
+
+    /// Formats the value of instance `oid` according to the syntax of its
+    /// object, naming object identifier values.
+    pub fn format_value(&self, oid: &[u32], value: &OwnedValue) -> String {
+        self.format_value_as(oid, value, None)
+    }
+
+    /// As `format_value`, showing an InetAddress column as of the type
+    /// given by `addr_type`, the value of the row's InetAddressType column.
+    /// Without it the type of an InetAddress is guessed from its length,
+    /// which misreads `dns(16)` names of 4, 8, 16 or 20 octets.
+    pub fn format_row_value(
+        &self,
+        oid: &[u32],
+        value: &OwnedValue,
+        addr_type: &OwnedValue,
+    ) -> String {
+        self.format_value_as(oid, value, inet_type_value(addr_type))
+    }
+
+    fn format_value_as(&self, oid: &[u32], value: &OwnedValue, addr_type: Option<i64>) -> String {
+        if let OwnedValue::ObjectIdentifier(ref value) = *value {
+            return self.format_oid(value);
+        }
+        match self.lookup(oid).and_then(|(node, _)| node.syntax.as_ref()) {
+            Some(syntax) => syntax.format_as(value, addr_type),
+            None => Syntax::untyped(value).format(value),
+        }
+    }
+
+    /// Parses `text` into a value for instance `oid`, according to the
+    /// syntax of its object.
+    pub fn parse_value(&self, oid: &[u32], text: &str) -> Result<OwnedValue, MibError> {
+        self.parse_value_as(oid, text, None)
+    }
+
+    /// As `parse_value`, reading an InetAddress column as of the type
+    /// given by `addr_type`, the value of the row's InetAddressType column.
+    pub fn parse_row_value(
+        &self,
+        oid: &[u32],
+        text: &str,
+        addr_type: &OwnedValue,
+    ) -> Result<OwnedValue, MibError> {
+        self.parse_value_as(oid, text, inet_type_value(addr_type))
+    }
+
+    fn parse_value_as(
+        &self,
+        oid: &[u32],
+        text: &str,
+        addr_type: Option<i64>,
+    ) -> Result<OwnedValue, MibError> {
+        let syntax = self
+            .lookup(oid)
+            .and_then(|(node, _)| node.syntax.as_ref())
+            .ok_or_else(|| MibError::UnknownName(dotted(oid)))?;
+        match syntax.base {
+            BaseType::ObjectIdentifier => Ok(OwnedValue::ObjectIdentifier(
+                self.resolve(text.trim())
+                    .map_err(|_| MibError::InvalidValue(text.to_string()))?,
+            )),
+            _ => syntax.parse_as(text, addr_type),
+        }
+    }
+}
+
+/// The InetAddressType held in `value`, if it is an integer.
+fn inet_type_value(value: &OwnedValue) -> Option<i64> {
+    match *value {
+        OwnedValue::Integer(n) => Some(n),
+        _ => None,
+    }
+}
+
+/// The InetAddressType (INET-ADDRESS-MIB) for an InetAddress of `len`
+/// octets, for when the type object is not at hand.
+fn inet_address_type(len: usize) -> i64 {
+    match len {
+        4 => 1,
+        16 => 2,
+        8 => 3,
+        20 => 4,
+        _ => 0,
+    }
+}
+
+/// Formats an InetAddress given the value of its InetAddressType:
+/// `ipv4(1)`, `ipv6(2)`, `ipv4z(3)`, `ipv6z(4)` or `dns(16)`.
+pub fn format_inet_address(addr_type: i64, octets: &[u8]) -> Option<String> {
+    let zone = |octets: &[u8]| u32::from_be_bytes([octets[0], octets[1], octets[2], octets[3]]);
+    let text = match (addr_type, octets.len()) {
+        (1, 4) => Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]).to_string(),
+        (2, 16) => Ipv6Addr::from(<[u8; 16]>::try_from(octets).ok()?).to_string(),
+        (3, 8) => format!(
+            "{}%{}",
+            format_inet_address(1, &octets[..4])?,
+            zone(&octets[4..])
+        ),
+        (4, 20) => format!(
+            "{}%{}",
+            format_inet_address(2, &octets[..16])?,
+            zone(&octets[16..])
+        ),
+        (16, _) => String::from_utf8(octets.to_vec()).ok()?,
+        (0, 0) => String::new(),
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// Parses an InetAddress of the given InetAddressType.
+pub fn parse_inet_address(addr_type: i64, text: &str) -> Option<Vec<u8>> {
+    let (address, zone) = match text.split_once('%') {
+        Some((address, zone)) => (address, Some(zone.parse::<u32>().ok()?)),
+        None => (text, None),
+    };
+    let mut octets = match (addr_type, zone) {
+        (1, None) | (3, Some(_)) => address.parse::<Ipv4Addr>().ok()?.octets().to_vec(),
+        (2, None) | (4, Some(_)) => address.parse::<Ipv6Addr>().ok()?.octets().to_vec(),
+        (16, None) => return Some(text.as_bytes().to_vec()),
+        (0, None) if text.is_empty() => return Some(Vec::new()),
+        _ => return None,
+    };
+    if let Some(zone) = zone {
+        octets.extend_from_slice(&zone.to_be_bytes());
+    }
+    Some(octets)
+}
+
+/// Whether `octets` read as text: UTF-8 without control characters other
+/// than whitespace.
+pub(crate) fn is_printable(octets: &[u8]) -> bool {
+    match std::str::from_utf8(octets) {
+        Ok(text) => !text.chars().any(|c| c.is_control() && !c.is_whitespace()),
+        Err(_) => false,
+    }
+}
+
+/// `0x` followed by the octets in hex.
+pub(crate) fn hex(octets: &[u8]) -> String {
+    let mut out = "0x".to_string();
+    for octet in octets {
+        let _ = write!(out, "{:02x}", octet);
+    }
+    out
+}
+
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+    if !digits.len().is_multiple_of(2) || !digits.is_ascii() {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
+
+fn dotted(oid: &[u32]) -> String {
+    let parts: Vec<String> = oid.iter().map(|subid| subid.to_string()).collect();
+    parts.join(".")
+}
+
+/// Applies an INTEGER DISPLAY-HINT: `d-N` for an implied decimal point,
+/// `x`, `o` or `b` for hex, octal or binary.
+fn format_integer_hint(hint: &str, n: i64) -> String {
+    match hint.as_bytes().first() {
+        Some(b'd') => match hint[1..]
+            .strip_prefix('-')
+            .and_then(|places| places.parse::<usize>().ok())
+        {
+            Some(places) if places > 0 => {
+                let digits = format!("{:0width$}", n.unsigned_abs(), width = places + 1);
+                let (whole, fraction) = digits.split_at(digits.len() - places);
+                let sign = if n < 0 { "-" } else { "" };
+                format!("{}{}.{}", sign, whole, fraction)
+            }
+            _ => n.to_string(),
+        },
+        Some(b'x') => format!("{:x}", n),
+        Some(b'o') => format!("{:o}", n),
+        Some(b'b') => format!("{:b}", n),
+        _ => n.to_string(),
+    }
+}
+
+fn parse_integer_hint(hint: &str, text: &str) -> Option<i64> {
+    match hint.as_bytes().first() {
+        Some(b'd') => {
+            let places: usize = hint[1..]
+                .strip_prefix('-')
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(0);
+            let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+            if fraction.len() > places || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let digits = format!(
+                "{}{}{}",
+                whole,
+                fraction,
+                "0".repeat(places - fraction.len())
+            );
+            digits.parse().ok()
+        }
+        Some(b'x') => i64::from_str_radix(text, 16).ok(),
+        Some(b'o') => i64::from_str_radix(text, 8).ok(),
+        Some(b'b') => i64::from_str_radix(text, 2).ok(),
+        _ => text.parse().ok(),
+    }
+}
+
+/// One octet-format specification of an OCTET STRING DISPLAY-HINT.
+#[derive(Debug)]
+struct OctetSpec {
+    /// Whether the first octet is a repeat count (`*`).
+    repeat: bool,
+    len: usize,
+    /// `x`, `d`, `o`, `a` or `t`.
+    format: u8,
+    separator: Option<char>,
+    terminator: Option<char>,
+}
+
+impl OctetSpec {
+    fn parse_hint(hint: &str) -> Option<Vec<OctetSpec>> {
+        let mut specs = Vec::new();
+        let mut chars = hint.chars().peekable();
+        while chars.peek().is_some() {
+            let repeat = chars.next_if_eq(&'*').is_some();
+            let mut len = String::new();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                len.push(digit);
+            }
+            let format = match chars.next()? {
+                c @ ('x' | 'd' | 'o' | 'a' | 't') => c as u8,
+                _ => return None,
+            };
+            // numbers wider than 128 bits are not supported
+            let len: usize = len.parse().ok()?;
+            if len == 0 || len > 16 && !matches!(format, b'a' | b't') {
+                return None;
+            }
+            let delimiter = |c: &char| !c.is_ascii_digit() && *c != '*';
+            let separator = chars.next_if(delimiter);
+            let terminator = if repeat {
+                chars.next_if(delimiter)
+            } else {
+                None
+            };
+            specs.push(OctetSpec {
+                repeat,
+                len,
+                format,
+                separator,
+                terminator,
+            });
+        }
+        if specs.is_empty() {
+            None
+        } else {
+            Some(specs)
+        }
+    }
+}
+
+fn format_octets_hint(specs: &[OctetSpec], mut octets: &[u8]) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while !octets.is_empty() {
+        // the last specification applies to the rest of the value
+        let spec = &specs[i.min(specs.len() - 1)];
+        i += 1;
+        let count = if spec.repeat {
+            let count = octets[0] as usize;
+            octets = &octets[1..];
+            count
+        } else {
+            1
+        };
+        for n in 0..count {
+            if octets.is_empty() {
+                break;
+            }
+            let (field, rest) = octets.split_at(spec.len.min(octets.len()));
+            octets = rest;
+            match spec.format {
+                b'a' | b't' => out.push_str(&String::from_utf8_lossy(field)),
+                format => {
+                    let value = field
+                        .iter()
+                        .fold(0u128, |acc, &octet| acc << 8 | octet as u128);
+                    let _ = match format {
+                        b'x' => write!(out, "{:0width$x}", value, width = field.len() * 2),
+                        b'o' => write!(out, "{:o}", value),
+                        _ => write!(out, "{}", value),
+                    };
+                }
+            }
+            let last = n + 1 == count;
+            if let Some(separator) = spec.separator {
+                if !octets.is_empty() && (!last || spec.terminator.is_none()) {
+                    out.push(separator);
+                }
+            }
+        }
+        if let Some(terminator) = spec.terminator {
+            if !octets.is_empty() {
+                out.push(terminator);
+            }
+        }
+    }
+    out
+}
+
+fn parse_octets_hint(specs: &[OctetSpec], mut text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while !text.is_empty() {
+        let spec = &specs[i.min(specs.len() - 1)];
+        i += 1;
+        let count_at = out.len();
+        if spec.repeat {
+            out.push(0);
+        }
+        let mut count = 0;
+        loop {
+            let stop = |c: char| Some(c) == spec.separator || Some(c) == spec.terminator;
+            let end = match spec.format {
+                b'a' | b't' => {
+                    // up to `len` octets, or to a delimiter when there is one
+                    let mut end = 0;
+                    for (at, c) in text.char_indices() {
+                        if at + c.len_utf8() > spec.len
+                            || (spec.separator.is_some() || spec.terminator.is_some()) && stop(c)
+                        {
+                            break;
+                        }
+                        end = at + c.len_utf8();
+                    }
+                    out.extend_from_slice(&text.as_bytes()[..end]);
+                    end
+                }
+                format => {
+                    let radix = match format {
+                        b'x' => 16,
+                        b'o' => 8,
+                        _ => 10,
+                    };
+                    let end = text
+                        .find(|c: char| !c.is_digit(radix))
+                        .unwrap_or(text.len());
+                    let value = u128::from_str_radix(&text[..end], radix).ok()?;
+                    if spec.len < 16 && value >> (spec.len * 8) != 0 {
+                        return None;
+                    }
+                    out.extend((0..spec.len).rev().map(|byte| (value >> (byte * 8)) as u8));
+                    end
+                }
+            };
+            if end == 0 && spec.len > 0 {
+                return None;
+            }
+            text = &text[end..];
+            count += 1;
+            match text.chars().next() {
+                Some(c) if spec.repeat && Some(c) == spec.terminator => {
+                    text = &text[c.len_utf8()..];
+                    break;
+                }
+                Some(c) if Some(c) == spec.separator => {
+                    text = &text[c.len_utf8()..];
+                    if !spec.repeat {
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+        if spec.repeat {
+            out[count_at] = u8::try_from(count).ok()?;
+        }
+    }
+    Some(out)
+}
@@ -0,0 +1,242 @@
+//! Table indices in instance OIDs (RFC 2578 section 7.7).
+//!
+//! Decoders consume their part of the index from the front of `suffix`
+//! and return `None` when it is malformed. `TableIndex` applies them to
+//! the whole INDEX clause of a row, e.g. with
+//! `mibs.table_index("ipNetToMediaTable")?.decode(suffix)`.
+
+use super::parser::IndexPart;
+use super::tree::{BaseType, MibTree, Node, NodeKind};
+use super::MibError;
+
+/// How an index object is encoded in instance OIDs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexType {
+    /// INTEGER, Unsigned32 and the other types encoded as a single
+    /// sub-identifier.
+    Integer,
+    /// OCTET STRING and BITS, with the size of fixed-size strings.
+    OctetString(Option<usize>),
+    ObjectIdentifier,
+    IpAddress,
+}
+
+/// An object of an INDEX clause.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexObject {
+    pub name: String,
+    pub ty: IndexType,
+    pub implied: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IndexValue {
+    Integer(i64),
+    OctetString(Vec<u8>),
+    ObjectIdentifier(Vec<u32>),
+    IpAddress([u8; 4]),
+}
+
+/// The index of a table's rows, for decoding instance OID suffixes into
+/// index values and back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableIndex {
+    pub objects: Vec<IndexObject>,
+}
+
+impl TableIndex {
+    pub fn new(objects: Vec<IndexObject>) -> TableIndex {
+        TableIndex { objects }
+    }
+
+    /// Decodes the index part of an instance OID, i.e. what follows the
+    /// column. Returns `None` when it is malformed or too long.
+    pub fn decode(&self, mut suffix: &[u32]) -> Option<Vec<IndexValue>> {
+        let mut values = Vec::with_capacity(self.objects.len());
+        for object in &self.objects {
+            let value = match object.ty {
+                IndexType::Integer => IndexValue::Integer(decode_integer(&mut suffix)?),
+                IndexType::OctetString(size) => {
+                    IndexValue::OctetString(decode_octets(&mut suffix, size, object.implied)?)
+                }
+                IndexType::ObjectIdentifier => {
+                    IndexValue::ObjectIdentifier(decode_oid(&mut suffix, object.implied)?)
+                }
+                IndexType::IpAddress => IndexValue::IpAddress(decode_ip_address(&mut suffix)?),
+            };
+            values.push(value);
+        }
+        if suffix.is_empty() {
+            Some(values)
+        } else {
+            None
+        }
+    }
+
+    /// Encodes index values, one per index object and of its type.
+    /// Returns `None` when they do not match the index.
+    pub fn encode(&self, values: &[IndexValue]) -> Option<Vec<u32>> {
+        if values.len() != self.objects.len() {
+            return None;
+        }
+        let mut oid = Vec::new();
+        for (object, value) in self.objects.iter().zip(values) {
+            match (object.ty, value) {
+                (IndexType::Integer, &IndexValue::Integer(n))
+                    if (0..=u32::MAX as i64).contains(&n) =>
+                {
+                    encode_integer(&mut oid, n)
+                }
+                (IndexType::OctetString(size), IndexValue::OctetString(octets)) => {
+                    if size.is_some_and(|size| size != octets.len()) {
+                        return None;
+                    }
+                    encode_octets(&mut oid, octets, size.is_some(), object.implied)
+                }
+                (IndexType::ObjectIdentifier, IndexValue::ObjectIdentifier(value)) => {
+                    encode_oid(&mut oid, value, object.implied)
+                }
+                (IndexType::IpAddress, &IndexValue::IpAddress(ip)) => {
+                    encode_ip_address(&mut oid, ip)
+                }
+                _ => return None,
+            }
+        }
+        Some(oid)
+    }
+}
+
+impl MibTree {
+    /// The index of a table, given the table, its row or one of its
+    /// columns. Rows that AUGMENT another take its index.
+    pub fn table_index(&self, name: &str) -> Result<TableIndex, MibError> {
+        let unknown = || MibError::UnknownName(name.to_string());
+        let mut row = self.node(name).ok_or_else(unknown)?;
+        if row.kind == NodeKind::Table || row.kind == NodeKind::Column {
+            let oid = match row.kind {
+                NodeKind::Table => [&row.oid[..], &[1]].concat(),
+                _ => row.oid[..row.oid.len() - 1].to_vec(),
+            };
+            row = self.node_by_oid(&oid).ok_or_else(unknown)?;
+        }
+        if row.kind != NodeKind::Row {
+            return Err(unknown());
+        }
+        let objects = self
+            .row_index(row)?
+            .iter()
+            .map(|part| self.index_object(row, part))
+            .collect::<Result<_, _>>()?;
+        Ok(TableIndex::new(objects))
+    }
+
+    fn index_object(&self, row: &Node, part: &IndexPart) -> Result<IndexObject, MibError> {
+        let object = self
+            .node(&format!("{}::{}", row.module, part.name))
+            .or_else(|| self.node(&part.name));
+        let ty = match object.and_then(|object| object.syntax.as_ref()) {
+            Some(syntax) => match syntax.base {
+                BaseType::OctetString | BaseType::Bits | BaseType::Opaque => {
+                    let size = match syntax.sizes[..] {
+                        [(min, max)] if min == max && min >= 0 => Some(min as usize),
+                        _ => None,
+                    };
+                    IndexType::OctetString(size)
+                }
+                BaseType::ObjectIdentifier => IndexType::ObjectIdentifier,
+                BaseType::IpAddress => IndexType::IpAddress,
+                BaseType::Sequence | BaseType::SequenceOf => {
+                    return Err(MibError::UnknownName(format!(
+                        "{}::{}",
+                        row.module, part.name
+                    )))
+                }
+                _ => IndexType::Integer,
+            },
+            // SMIv1 allows type names in INDEX clauses
+            None => match &part.name[..] {
+                "INTEGER" | "Integer32" => IndexType::Integer,
+                "OCTET STRING" => IndexType::OctetString(None),
+                "OBJECT IDENTIFIER" => IndexType::ObjectIdentifier,
+                "IpAddress" | "NetworkAddress" => IndexType::IpAddress,
+                _ => {
+                    return Err(MibError::UnknownName(format!(
+                        "{}::{}",
+                        row.module, part.name
+                    )))
+                }
+            },
+        };
+        Ok(IndexObject {
+            name: part.name.clone(),
+            ty,
+            implied: part.implied,
+        })
+    }
+}
+
+/// An INTEGER or unsigned index, as a single sub-identifier.
+pub fn decode_integer(suffix: &mut &[u32]) -> Option<i64> {
+    let (&first, rest) = suffix.split_first()?;
+    *suffix = rest;
+    Some(first as i64)
+}
+
+/// An OCTET STRING index: `size` sub-identifiers for fixed-size strings,
+/// the rest of the index for IMPLIED ones, and otherwise a length followed
+/// by the octets.
+pub fn decode_octets(suffix: &mut &[u32], size: Option<usize>, implied: bool) -> Option<Vec<u8>> {
+    let subids = take(suffix, size, implied)?;
+    subids
+        .iter()
+        .map(|&subid| u8::try_from(subid).ok())
+        .collect()
+}
+
+/// An OBJECT IDENTIFIER index, length-prefixed unless IMPLIED.
+pub fn decode_oid(suffix: &mut &[u32], implied: bool) -> Option<Vec<u32>> {
+    take(suffix, None, implied).map(|subids| subids.to_vec())
+}
+
+pub fn decode_ip_address(suffix: &mut &[u32]) -> Option<[u8; 4]> {
+    let octets = decode_octets(suffix, Some(4), false)?;
+    Some([octets[0], octets[1], octets[2], octets[3]])
+}
+
+fn take<'a>(suffix: &mut &'a [u32], size: Option<usize>, implied: bool) -> Option<&'a [u32]> {
+    let len = match size {
+        Some(size) => size,
+        None if implied => suffix.len(),
+        None => decode_integer(suffix)? as usize,
+    };
+    if len > suffix.len() {
+        return None;
+    }
+    let (taken, rest) = suffix.split_at(len);
+    *suffix = rest;
+    Some(taken)
+}
+
+pub fn encode_integer(oid: &mut Vec<u32>, value: i64) {
+    oid.push(value as u32);
+}
+
+/// Appends an OCTET STRING index. `fixed` is for fixed-size strings, which
+/// have no length prefix.
+pub fn encode_octets(oid: &mut Vec<u32>, value: &[u8], fixed: bool, implied: bool) {
+    if !fixed && !implied {
+        oid.push(value.len() as u32);
+    }
+    oid.extend(value.iter().map(|&octet| octet as u32));
+}
+
+pub fn encode_oid(oid: &mut Vec<u32>, value: &[u32], implied: bool) {
+    if !implied {
+        oid.push(value.len() as u32);
+    }
+    oid.extend_from_slice(value);
+}
+
+pub fn encode_ip_address(oid: &mut Vec<u32>, value: [u8; 4]) {
+    encode_octets(oid, &value, true, false);
+}
@@ -0,0 +1,142 @@
+//! Tokenizer for ASN.1 MIB modules.
+
+use super::MibError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    /// Identifiers and keywords, including hyphenated ones such as
+    /// `OBJECT-TYPE`.
+    Word(String),
+    Number(i128),
+    /// A quoted string without the quotes.
+    Str(String),
+    /// `'...'H` and `'...'B` strings, as their value.
+    Bytes(Vec<u8>, bool),
+    /// `::=`, `..` or a single punctuation character.
+    Punct(&'static str),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spanned {
+    pub token: Token,
+    pub line: usize,
+}
+
+const PUNCT: &[&str] = &[
+    "::=", "..", "{", "}", "(", ")", "[", "]", ",", ";", "|", ".", ":",
+];
+
+pub fn tokenize(text: &str) -> Result<Vec<Spanned>, MibError> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        let start_line = line;
+
+        if c == b'\n' {
+            line += 1;
+            pos += 1;
+            continue;
+        }
+        if c.is_ascii_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        // comments run to the end of the line or the next "--"
+        if bytes[pos..].starts_with(b"--") {
+            pos += 2;
+            while pos < bytes.len() && bytes[pos] != b'\n' {
+                if bytes[pos..].starts_with(b"--") {
+                    pos += 2;
+                    break;
+                }
+                pos += 1;
+            }
+            continue;
+        }
+
+        let token = if c == b'"' {
+            let end = text[pos + 1..]
+                .find('"')
+                .map(|i| pos + 1 + i)
+                .ok_or_else(|| MibError::parse(start_line, "unterminated string"))?;
+            let s = &text[pos + 1..end];
+            line += s.matches('\n').count();
+            pos = end + 1;
+            Token::Str(s.to_string())
+        } else if c == b'\'' {
+            let end = text[pos + 1..]
+                .find('\'')
+                .map(|i| pos + 1 + i)
+                .ok_or_else(|| MibError::parse(start_line, "unterminated quoted value"))?;
+            let digits: String = text[pos + 1..end]
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect();
+            let radix = bytes.get(end + 1).map(|b| b.to_ascii_uppercase());
+            pos = end + 2;
+            match radix {
+                Some(b'H') => Token::Bytes(parse_radix(&digits, 16, start_line)?, true),
+                Some(b'B') => Token::Bytes(parse_radix(&digits, 2, start_line)?, false),
+                _ => return Err(MibError::parse(start_line, "expected 'H or 'B suffix")),
+            }
+        } else if c.is_ascii_digit()
+            || (c == b'-' && bytes.get(pos + 1).is_some_and(|b| b.is_ascii_digit()))
+        {
+            let start = pos;
+            pos += 1;
+            while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                pos += 1;
+            }
+            let n = text[start..pos]
+                .parse()
+                .map_err(|_| MibError::parse(start_line, "number out of range"))?;
+            Token::Number(n)
+        } else if c.is_ascii_alphabetic() {
+            let start = pos;
+            while pos < bytes.len()
+                && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_' || bytes[pos] == b'-')
+                && !bytes[pos..].starts_with(b"--")
+            {
+                pos += 1;
+            }
+            Token::Word(text[start..pos].to_string())
+        } else if let Some(&p) = PUNCT.iter().find(|p| text[pos..].starts_with(*p)) {
+            pos += p.len();
+            Token::Punct(p)
+        } else {
+            let c = text[pos..].chars().next().unwrap();
+            return Err(MibError::parse(
+                start_line,
+                &format!("unexpected character {:?}", c),
+            ));
+        };
+
+        tokens.push(Spanned {
+            token,
+            line: start_line,
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// The octets of a hex or binary string, left-aligned as in BITS values.
+fn parse_radix(digits: &str, radix: u32, line: usize) -> Result<Vec<u8>, MibError> {
+    let per_octet = if radix == 16 { 2 } else { 8 };
+    let mut out = Vec::new();
+    for chunk in digits.as_bytes().chunks(per_octet) {
+        let mut chunk = String::from_utf8_lossy(chunk).into_owned();
+        while chunk.len() < per_octet {
+            chunk.push('0');
+        }
+        let octet = u8::from_str_radix(&chunk, radix)
+            .map_err(|_| MibError::parse(line, "invalid digit in quoted value"))?;
+        out.push(octet);
+    }
+    Ok(out)
+}
@@ -0,0 +1,465 @@
+//! Validation of MIB modules, for checking vendor MIBs before loading them.
+//!
+//! Linting reports all the problems found rather than stopping at the
+//! first: unresolved imports and names, OID conflicts, DEFVALs that are
+//! not valid values of their syntax and violations of SMIv2 rules.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use super::parser::{parse, Definition, Module, OidComponent, SyntaxDef};
+use super::tree::{Access, BaseType, MibTree, Node, NodeKind, Syntax};
+use super::MibError;
+use crate::OwnedValue;
+
+/// Names the parser itself knows, which the SMI base modules export
+/// without defining them.
+const SMI_NAMES: &[&str] = &[
+    "MODULE-IDENTITY",
+    "OBJECT-IDENTITY",
+    "OBJECT-TYPE",
+    "NOTIFICATION-TYPE",
+    "TRAP-TYPE",
+    "TEXTUAL-CONVENTION",
+    "OBJECT-GROUP",
+    "NOTIFICATION-GROUP",
+    "MODULE-COMPLIANCE",
+    "AGENT-CAPABILITIES",
+    "ObjectName",
+    "NotificationName",
+    "ObjectSyntax",
+    "SimpleSyntax",
+    "ApplicationSyntax",
+    "ExtUTCTime",
+    "iso",
+    "ccitt",
+    "joint-iso-ccitt",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A problem found in a MIB file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: String,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.file, self.line, severity, self.message
+        )
+    }
+}
+
+impl MibTree {
+    /// Checks the modules of a MIB file's text against the loaded modules
+    /// and those of the search path, without loading them. `file` names the
+    /// text in the diagnostics, which are sorted by line.
+    pub fn lint_str(&self, text: &str, file: &str) -> Vec<Diagnostic> {
+        let mut lint = Lint {
+            file,
+            diagnostics: Vec::new(),
+        };
+        let modules = match parse(text) {
+            Ok(modules) => modules,
+            Err(MibError::Parse { line, message }) => {
+                lint.error(line, message);
+                return lint.diagnostics;
+            }
+            Err(err) => {
+                lint.error(0, err.to_string());
+                return lint.diagnostics;
+            }
+        };
+
+        let mut found = Vec::new();
+        for module in &modules {
+            self.lint_imports(&mut lint, module, &modules, &mut found);
+            lint_names(&mut lint, module);
+        }
+
+        // the semantic checks need the modules resolved, so on errors they
+        // run on the definitions that can be
+        let mut tree = self.clone();
+        if let Err(err) = tree.load_str(text) {
+            if !lint
+                .diagnostics
+                .iter()
+                .any(|d| d.severity == Severity::Error)
+            {
+                let line = modules.first().map_or(0, |m| m.line);
+                lint.error(line, err.to_string());
+            }
+            tree = self.clone();
+            if tree.load_str_partial(text).is_err() {
+                lint.diagnostics.sort_by_key(|d| d.line);
+                return lint.diagnostics;
+            }
+        }
+        for module in &modules {
+            tree.lint_definitions(&mut lint, module);
+        }
+
+        lint.diagnostics.sort_by_key(|d| d.line);
+        lint.diagnostics
+    }
+
+    /// Checks the modules of a MIB file, as `lint_str`.
+    pub fn lint_file<P: AsRef<Path>>(&self, path: P) -> Result<Vec<Diagnostic>, MibError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        Ok(self.lint_str(&text, &path.display().to_string()))
+    }
+
+    /// Checks that imported modules exist and define the imported symbols.
+    /// Modules read from the search path are kept in `found`.
+    fn lint_imports(
+        &self,
+        lint: &mut Lint,
+        module: &Module,
+        modules: &[Module],
+        found: &mut Vec<Module>,
+    ) {
+        for import in &module.imports {
+            if import.module == module.name {
+                continue;
+            }
+            let known = modules
+                .iter()
+                .chain(self.modules())
+                .chain(found.iter())
+                .any(|m| m.name == import.module);
+            if !known {
+                match self
+                    .find_module(&import.module)
+                    .and_then(|text| parse(&text))
+                {
+                    Ok(parsed) => found.extend(parsed),
+                    Err(MibError::UnknownModule(_)) => {
+                        let message = match self.misnamed_module_file(&import.module) {
+                            Some(path) => format!(
+                                "unresolved import: {} does not define module {}",
+                                path.display(),
+                                import.module
+                            ),
+                            None => {
+                                format!("unresolved import: module {} not found", import.module)
+                            }
+                        };
+                        lint.error(import.line, message);
+                        continue;
+                    }
+                    Err(err) => {
+                        lint.error(
+                            import.line,
+                            format!("cannot read module {}: {}", import.module, err),
+                        );
+                        continue;
+                    }
+                }
+            }
+            let Some(imported) = modules
+                .iter()
+                .chain(self.modules())
+                .chain(found.iter())
+                .find(|m| m.name == import.module)
+            else {
+                continue;
+            };
+            for symbol in &import.symbols {
+                let defined = imported.definitions.iter().any(|d| d.name == *symbol)
+                    || SMI_NAMES.contains(&&symbol[..])
+                    || BaseType::from_name(symbol).is_some();
+                if !defined {
+                    lint.error(
+                        import.line,
+                        format!(
+                            "unresolved import: {} is not defined in {}",
+                            symbol, import.module
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// The checks of definitions that need their OIDs and syntaxes.
+    fn lint_definitions(&self, lint: &mut Lint, module: &Module) {
+        let smiv2 = module.imports.iter().any(|i| i.module == "SNMPv2-SMI");
+
+        // the definitions of all modules by OID, for conflicts
+        let mut by_oid: HashMap<&[u32], Vec<(&str, &Definition)>> = HashMap::new();
+        for m in self.modules() {
+            for def in &m.definitions {
+                if let Some(node) = self.node(&format!("{}::{}", m.name, def.name)) {
+                    by_oid.entry(&node.oid).or_default().push((&m.name, def));
+                }
+            }
+        }
+
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for def in &module.definitions {
+            if let Some(&line) = seen.get(&def.name[..]) {
+                lint.error(
+                    def.line,
+                    format!("{} is defined twice, first on line {}", def.name, line),
+                );
+                continue;
+            }
+            seen.insert(&def.name, def.line);
+
+            let Some(node) = self.node(&format!("{}::{}", module.name, def.name)) else {
+                continue;
+            };
+            for &(other_module, other) in &by_oid[&node.oid[..]] {
+                if other.name == def.name {
+                    continue;
+                }
+                if other_module == module.name {
+                    // reported once, at the later definition
+                    if other.line < def.line {
+                        lint.error(
+                            def.line,
+                            format!(
+                                "{} has the same OID as {} on line {}",
+                                def.name, other.name, other.line
+                            ),
+                        );
+                    }
+                } else {
+                    lint.warning(
+                        def.line,
+                        format!(
+                            "OID of {} is also assigned to {}::{}",
+                            def.name, other_module, other.name
+                        ),
+                    );
+                }
+            }
+            // the node of a conflicting OID may be the other definition's
+            if node.name != def.name || node.module != module.name {
+                continue;
+            }
+
+            self.lint_defval(lint, node, def);
+            if smiv2 {
+                self.lint_smiv2(lint, node, def);
+            }
+            if node.kind == NodeKind::Row && node.index.is_empty() && node.augments.is_none() {
+                lint.error(
+                    def.line,
+                    format!("row {} has no INDEX or AUGMENTS", def.name),
+                );
+            }
+        }
+    }
+
+    fn lint_defval(&self, lint: &mut Lint, node: &Node, def: &Definition) {
+        let (Some(text), Some(syntax)) = (def.defval.as_deref(), node.syntax.as_ref()) else {
+            return;
+        };
+        let value = match defval_value(syntax, text) {
+            Some(value) => value,
+            None => return,
+        };
+        if let Err(err) = value.and_then(|value| syntax.check(&value)) {
+            lint.error(def.line, format!("invalid DEFVAL of {}: {}", def.name, err));
+        }
+    }
+
+    fn lint_smiv2(&self, lint: &mut Lint, node: &Node, def: &Definition) {
+        let name = &def.name;
+        match node.kind {
+            NodeKind::Table | NodeKind::Row if node.access != Some(Access::NotAccessible) => {
+                lint.error(
+                    def.line,
+                    format!("{} {} must be not-accessible", node.kind.name(), name),
+                );
+            }
+            NodeKind::Row if !node.index.is_empty() => {
+                let columns: Vec<&Node> = self
+                    .subtree(&node.oid)
+                    .filter(|n| n.kind == NodeKind::Column)
+                    .collect();
+                let all_indexes = columns
+                    .iter()
+                    .all(|c| node.index.iter().any(|part| part.name == c.name));
+                for part in &node.index {
+                    let Some(column) = columns.iter().find(|c| c.name == part.name) else {
+                        continue;
+                    };
+                    if !all_indexes && column.access != Some(Access::NotAccessible) {
+                        lint.warning(
+                            def.line,
+                            format!(
+                                "index object {} of {} should be not-accessible",
+                                part.name, name
+                            ),
+                        );
+                    }
+                }
+            }
+            NodeKind::Notification => {
+                for object in &node.objects {
+                    let accessible = self
+                        .node(&format!("{}::{}", node.module, object))
+                        .or_else(|| self.node(object))
+                        .map(|object| object.access);
+                    if accessible == Some(Some(Access::NotAccessible)) {
+                        lint.warning(
+                            def.line,
+                            format!(
+                                "object {} of notification {} is not-accessible",
+                                object, name
+                            ),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+        if node.access == Some(Access::WriteOnly) {
+            lint.error(
+                def.line,
+                format!("{} is write-only, which SMIv2 does not allow", name),
+            );
+        }
+    }
+}
+
+struct Lint<'a> {
+    file: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Lint<'_> {
+    fn error(&mut self, line: usize, message: String) {
+        self.push(Severity::Error, line, message);
+    }
+
+    fn warning(&mut self, line: usize, message: String) {
+        self.push(Severity::Warning, line, message);
+    }
+
+    fn push(&mut self, severity: Severity, line: usize, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            file: self.file.to_string(),
+            line,
+            message,
+        });
+    }
+}
+
+/// Checks that the names a module uses are defined in it or imported.
+fn lint_names(lint: &mut Lint, module: &Module) {
+    let known = |name: &str| {
+        module.definitions.iter().any(|d| d.name == name)
+            || module
+                .imports
+                .iter()
+                .any(|i| i.symbols.iter().any(|s| s == name))
+            || SMI_NAMES.contains(&name)
+            || BaseType::from_name(name).is_some()
+    };
+    for def in &module.definitions {
+        let mut used = Vec::new();
+        if let Some(OidComponent::Name(ref name)) = def.oid.first() {
+            used.push(&name[..]);
+        }
+        if let Some(ref syntax) = def.syntax {
+            syntax_names(syntax, &mut used);
+        }
+        used.extend(def.index.iter().map(|part| &part.name[..]));
+        used.extend(def.augments.as_deref());
+        used.extend(def.objects.iter().map(|object| &object[..]));
+        used.extend(def.enterprise.as_deref());
+
+        let mut reported = Vec::new();
+        for name in used {
+            if !known(name) && !reported.contains(&name) {
+                lint.error(
+                    def.line,
+                    format!("{} is neither defined nor imported", name),
+                );
+                reported.push(name);
+            }
+        }
+    }
+}
+
+/// The type names a SYNTAX refers to.
+fn syntax_names<'a>(syntax: &'a SyntaxDef, names: &mut Vec<&'a str>) {
+    if BaseType::from_name(&syntax.name).is_none() {
+        names.push(&syntax.name);
+    }
+    names.extend(syntax.of.as_deref());
+    for (_, member) in &syntax.members {
+        syntax_names(member, names);
+    }
+}
+
+/// The value of a DEFVAL clause as written, or `None` if it is not checked.
+fn defval_value(syntax: &Syntax, text: &str) -> Option<Result<OwnedValue, MibError>> {
+    let invalid = || Err(MibError::InvalidValue(text.to_string()));
+    let value = match syntax.base {
+        BaseType::Integer
+        | BaseType::Counter32
+        | BaseType::Gauge32
+        | BaseType::Unsigned32
+        | BaseType::TimeTicks
+        | BaseType::Counter64
+        | BaseType::Bits => {
+            // DEFVALs are plain numbers, whatever the display hint
+            let mut plain = syntax.clone();
+            plain.display_hint = None;
+            plain.parse(text)
+        }
+        BaseType::OctetString | BaseType::Opaque => match defval_octets(text) {
+            Some(octets) => Ok(OwnedValue::OctetString(octets)),
+            None => invalid(),
+        },
+        BaseType::IpAddress => match defval_octets(text) {
+            Some(octets) if octets.len() == 4 && !text.starts_with('"') => {
+                Ok(OwnedValue::IpAddress([
+                    octets[0], octets[1], octets[2], octets[3],
+                ]))
+            }
+            _ => invalid(),
+        },
+        BaseType::ObjectIdentifier | BaseType::Sequence | BaseType::SequenceOf => return None,
+    };
+    Some(value)
+}
+
+/// The octets of a quoted string or of a hex or binary string.
+fn defval_octets(text: &str) -> Option<Vec<u8>> {
+    if let Some(string) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+        return Some(string.as_bytes().to_vec());
+    }
+    let (digits, radix, width) = if let Some(hex) = text.strip_suffix("'H") {
+        (hex.strip_prefix('\'')?, 16, 2)
+    } else {
+        (text.strip_suffix("'B")?.strip_prefix('\'')?, 2, 8)
+    };
+    let digits = digits.as_bytes();
+    digits
+        .chunks(width)
+        .map(|chunk| u8::from_str_radix(std::str::from_utf8(chunk).ok()?, radix).ok())
+        .collect()
+}
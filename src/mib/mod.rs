@@ -0,0 +1,116 @@
+//! MIB modules: parsing SMIv1 and SMIv2 module files and resolving symbolic
+//! names.
+//!
+//! A `MibTree` always knows the SMI base modules (SNMPv2-SMI, SNMPv2-TC,
+//! SNMPv2-CONF, RFC1155-SMI, RFC-1212 and RFC-1215). Other modules are
+//! loaded from text or files, and their imports are looked up in the
+//! directories added with `add_path`.
+//!
+//! ```no_run
+//! use snmp::mib::MibTree;
+//!
+//! let mut mibs = MibTree::new();
+//! mibs.add_path("/usr/share/snmp/mibs");
+//! mibs.load_module("IF-MIB").unwrap();
+//!
+//! let oid = mibs.resolve("IF-MIB::ifHCInOctets.3").unwrap();
+//! assert_eq!(mibs.format_oid(&oid), "IF-MIB::ifHCInOctets.3");
+//! ```
+//!
+//! With the `mibs` feature, the names of the common IETF modules are also
+//! compiled in (see `bundled`), and `ObjectIdentifier` displays as
+//! `SNMPv2-MIB::sysUpTime.0` instead of `1.3.6.1.2.1.1.3.0`.
+//!
+//! `MibTree::format_value` and `MibTree::parse_value` convert values to and
+//! from text according to the SYNTAX of their object, e.g. `00:1a:2b:3c:4d:5e`
+//! for a MacAddress or `up` for an ifOperStatus.
+//!
+//! `MibTree::table_index` gives the `index::TableIndex` of a table, which
+//! decodes the index part of instance OIDs into typed values and back.
+//!
+//! For browsing, `MibTree` also lists the `children` of a node, the
+//! `columns` of a table and the `notifications` of a module, and `search`es
+//! names. `Node::to_json` and `nodes_to_json` serialize the results.
+//!
+//! `MibTree::lint_file` validates a MIB file without loading it, reporting
+//! `Diagnostic`s such as unresolved imports, OID conflicts and invalid
+//! DEFVALs with their line numbers.
+
+use std::error;
+use std::fmt;
+use std::io;
+
+mod browse;
+mod builtin;
+#[cfg(feature = "mibs")]
+pub mod bundled;
+mod codegen;
+mod display;
+pub mod index;
+mod lexer;
+mod lint;
+mod parser;
+mod tree;
+
+pub use self::browse::nodes_to_json;
+pub use self::codegen::Generator;
+pub use self::display::{format_inet_address, parse_inet_address};
+pub(crate) use self::display::{hex, is_printable};
+pub use self::lint::{Diagnostic, Severity};
+pub use self::parser::{
+    parse, Definition, DefinitionKind, Import, IndexPart, Module, OidComponent, SyntaxDef,
+};
+pub use self::tree::{Access, BaseType, MibTree, Node, NodeKind, Status, Syntax};
+
+#[derive(Debug)]
+pub enum MibError {
+    /// A syntax error in a module, with its line number.
+    Parse {
+        line: usize,
+        message: String,
+    },
+    /// An imported module that was neither loaded nor found in the search
+    /// path.
+    UnknownModule(String),
+    /// A name that could not be resolved, qualified with the module it was
+    /// used in where known.
+    UnknownName(String),
+    /// Text that does not parse as a value of an object's syntax.
+    InvalidValue(String),
+    /// A value outside the ranges, sizes or enumeration of its syntax.
+    Constraint(String),
+    /// An object that cannot be written, by name.
+    NotWritable(String),
+    Io(io::Error),
+}
+
+impl MibError {
+    pub(crate) fn parse(line: usize, message: &str) -> Self {
+        MibError::Parse {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for MibError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MibError::Parse { line, ref message } => write!(f, "line {}: {}", line, message),
+            MibError::UnknownModule(ref name) => write!(f, "unknown module {}", name),
+            MibError::UnknownName(ref name) => write!(f, "unknown name {}", name),
+            MibError::InvalidValue(ref text) => write!(f, "invalid value {:?}", text),
+            MibError::Constraint(ref message) => f.write_str(message),
+            MibError::NotWritable(ref name) => write!(f, "{} is not writable", name),
+            MibError::Io(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl error::Error for MibError {}
+
+impl From<io::Error> for MibError {
+    fn from(err: io::Error) -> Self {
+        MibError::Io(err)
+    }
+}
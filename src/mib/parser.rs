@@ -0,0 +1,652 @@
+//! Parser for SMIv1 (RFC 1155, 1212, 1215) and SMIv2 (RFC 2578, 2579,
+//! 2580) MIB modules.
+//!
+//! The parser is lenient where real-world MIBs tend to be sloppy: unknown
+//! clauses are skipped, and so are MACRO definitions and the bodies of
+//! MODULE-COMPLIANCE and AGENT-CAPABILITIES.
+
+use super::lexer::{tokenize, Spanned, Token};
+use super::MibError;
+
+/// A parsed MIB module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    pub name: String,
+    pub imports: Vec<Import>,
+    pub definitions: Vec<Definition>,
+    /// The line of the module header.
+    pub line: usize,
+}
+
+/// The symbols imported from one module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Import {
+    pub module: String,
+    pub symbols: Vec<String>,
+    pub line: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OidComponent {
+    Name(String),
+    Number(u32),
+    /// `name(number)`, as in `{ iso org(3) dod(6) }`.
+    NamedNumber(String, u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DefinitionKind {
+    /// `name OBJECT IDENTIFIER ::= { ... }`
+    ObjectIdentifier,
+    ModuleIdentity,
+    ObjectIdentity,
+    ObjectType,
+    NotificationType,
+    /// SMIv1 TRAP-TYPE.
+    TrapType,
+    ObjectGroup,
+    NotificationGroup,
+    ModuleCompliance,
+    AgentCapabilities,
+    TextualConvention,
+    /// A plain type assignment such as `IfEntry ::= SEQUENCE { ... }`.
+    Type,
+}
+
+/// A SYNTAX clause or the right-hand side of a type assignment, before
+/// type names are resolved.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SyntaxDef {
+    /// The type name as written, with multi-word names joined by a space:
+    /// `INTEGER`, `OCTET STRING`, `OBJECT IDENTIFIER`, `BITS`, `SEQUENCE`,
+    /// `SEQUENCE OF`, `CHOICE` or the name of a defined type.
+    pub name: String,
+    /// Named numbers of INTEGER enumerations and BITS.
+    pub enums: Vec<(String, i64)>,
+    /// Value range constraints.
+    pub ranges: Vec<(i128, i128)>,
+    /// SIZE constraints.
+    pub sizes: Vec<(i128, i128)>,
+    /// The entry type of a `SEQUENCE OF`.
+    pub of: Option<String>,
+    /// The members of a `SEQUENCE`.
+    pub members: Vec<(String, SyntaxDef)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexPart {
+    pub name: String,
+    pub implied: bool,
+}
+
+/// A top-level assignment with the clauses that matter for name resolution
+/// and value handling.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Definition {
+    pub name: String,
+    pub kind: DefinitionKind,
+    pub line: usize,
+    /// The assigned OID, for everything but types.
+    pub oid: Vec<OidComponent>,
+    pub syntax: Option<SyntaxDef>,
+    pub access: Option<String>,
+    pub status: Option<String>,
+    pub description: Option<String>,
+    pub reference: Option<String>,
+    pub units: Option<String>,
+    pub display_hint: Option<String>,
+    pub index: Vec<IndexPart>,
+    pub augments: Option<String>,
+    /// The DEFVAL clause as written, without the braces.
+    pub defval: Option<String>,
+    /// OBJECTS, NOTIFICATIONS or VARIABLES.
+    pub objects: Vec<String>,
+    /// ENTERPRISE of a TRAP-TYPE, whose value is the trap number.
+    pub enterprise: Option<String>,
+    pub trap_number: Option<u32>,
+}
+
+impl Definition {
+    fn new(name: String, kind: DefinitionKind, line: usize) -> Self {
+        Definition {
+            name,
+            kind,
+            line,
+            oid: Vec::new(),
+            syntax: None,
+            access: None,
+            status: None,
+            description: None,
+            reference: None,
+            units: None,
+            display_hint: None,
+            index: Vec::new(),
+            augments: None,
+            defval: None,
+            objects: Vec::new(),
+            enterprise: None,
+            trap_number: None,
+        }
+    }
+}
+
+/// Parses all modules of a MIB file.
+pub fn parse(text: &str) -> Result<Vec<Module>, MibError> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+    };
+    let mut modules = Vec::new();
+    while !parser.at_end() {
+        modules.push(parser.module()?);
+    }
+    Ok(modules)
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+}
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn line(&self) -> usize {
+        match self.tokens.get(self.pos) {
+            Some(t) => t.line,
+            None => self.tokens.last().map_or(1, |t| t.line),
+        }
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, MibError> {
+        Err(MibError::parse(self.line(), message))
+    }
+
+    fn next(&mut self) -> Result<Token, MibError> {
+        match self.tokens.get(self.pos) {
+            Some(t) => {
+                self.pos += 1;
+                Ok(t.token.clone())
+            }
+            None => self.error("unexpected end of file"),
+        }
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w == word)
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let found = self.is_word(word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let found = self.is_punct(punct);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), MibError> {
+        if self.eat_word(word) {
+            Ok(())
+        } else {
+            self.error(&format!("expected {}", word))
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<(), MibError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            self.error(&format!("expected \"{}\"", punct))
+        }
+    }
+
+    fn word(&mut self) -> Result<String, MibError> {
+        match self.next()? {
+            Token::Word(w) => Ok(w),
+            _ => {
+                self.pos -= 1;
+                self.error("expected an identifier")
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, MibError> {
+        match self.next()? {
+            Token::Str(s) => Ok(s),
+            _ => {
+                self.pos -= 1;
+                self.error("expected a string")
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<i128, MibError> {
+        match self.next()? {
+            Token::Number(n) => Ok(n),
+            Token::Bytes(bytes, _) if bytes.len() <= 8 => {
+                Ok(bytes.iter().fold(0, |n, &b| (n << 8) | b as i128))
+            }
+            // MIN and MAX are not SMI, but common
+            Token::Word(w) if w == "MIN" => Ok(i64::MIN as i128),
+            Token::Word(w) if w == "MAX" => Ok(u64::MAX as i128),
+            _ => {
+                self.pos -= 1;
+                self.error("expected a number")
+            }
+        }
+    }
+
+    /// Skips a balanced `{ ... }` or `( ... )` group, or a single token.
+    fn skip(&mut self) -> Result<(), MibError> {
+        let close = match self.next()? {
+            Token::Punct("{") => "}",
+            Token::Punct("(") => ")",
+            _ => return Ok(()),
+        };
+        while !self.is_punct(close) {
+            self.skip()?;
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn module(&mut self) -> Result<Module, MibError> {
+        let line = self.line();
+        let name = self.word()?;
+        // an optional module OID, as in some SMIv1 modules
+        if self.is_punct("{") {
+            self.skip()?;
+        }
+        self.expect_word("DEFINITIONS")?;
+        // tagging defaults, as in "DEFINITIONS IMPLICIT TAGS ::="
+        while !self.eat_punct("::=") {
+            self.word()?;
+        }
+        self.expect_word("BEGIN")?;
+
+        let mut module = Module {
+            name,
+            imports: Vec::new(),
+            definitions: Vec::new(),
+            line,
+        };
+
+        loop {
+            if self.eat_word("END") {
+                return Ok(module);
+            } else if self.eat_word("IMPORTS") {
+                module.imports = self.imports()?;
+            } else if self.eat_word("EXPORTS") {
+                while !self.eat_punct(";") {
+                    self.next()?;
+                }
+            } else if let Some(definition) = self.assignment()? {
+                module.definitions.push(definition);
+            }
+        }
+    }
+
+    fn imports(&mut self) -> Result<Vec<Import>, MibError> {
+        let mut imports = Vec::new();
+        let mut symbols = Vec::new();
+        let mut line = self.line();
+
+        while !self.eat_punct(";") {
+            if self.eat_word("FROM") {
+                imports.push(Import {
+                    module: self.word()?,
+                    symbols: std::mem::take(&mut symbols),
+                    line,
+                });
+                line = self.line();
+            } else if self.eat_punct(",") {
+                continue;
+            } else {
+                symbols.push(self.word()?);
+            }
+        }
+
+        if !symbols.is_empty() {
+            return self.error("imported symbols without FROM");
+        }
+        Ok(imports)
+    }
+
+    /// Parses one assignment. Returns `None` for MACRO definitions.
+    fn assignment(&mut self) -> Result<Option<Definition>, MibError> {
+        let line = self.line();
+        let name = self.word()?;
+
+        if self.eat_word("MACRO") {
+            self.expect_punct("::=")?;
+            while !self.eat_word("END") {
+                self.next()?;
+            }
+            return Ok(None);
+        }
+
+        // type assignments
+        if self.eat_punct("::=") {
+            if self.eat_word("TEXTUAL-CONVENTION") {
+                let mut definition = Definition::new(name, DefinitionKind::TextualConvention, line);
+                self.clauses(&mut definition, None)?;
+                return Ok(Some(definition));
+            }
+            let mut definition = Definition::new(name, DefinitionKind::Type, line);
+            definition.syntax = Some(self.syntax()?);
+            return Ok(Some(definition));
+        }
+
+        let macro_name = self.word()?;
+        let kind = match &macro_name[..] {
+            "OBJECT" => {
+                self.expect_word("IDENTIFIER")?;
+                DefinitionKind::ObjectIdentifier
+            }
+            "MODULE-IDENTITY" => DefinitionKind::ModuleIdentity,
+            "OBJECT-IDENTITY" => DefinitionKind::ObjectIdentity,
+            "OBJECT-TYPE" => DefinitionKind::ObjectType,
+            "NOTIFICATION-TYPE" => DefinitionKind::NotificationType,
+            "TRAP-TYPE" => DefinitionKind::TrapType,
+            "OBJECT-GROUP" => DefinitionKind::ObjectGroup,
+            "NOTIFICATION-GROUP" => DefinitionKind::NotificationGroup,
+            "MODULE-COMPLIANCE" => DefinitionKind::ModuleCompliance,
+            "AGENT-CAPABILITIES" => DefinitionKind::AgentCapabilities,
+            other => {
+                self.pos -= 1;
+                return self.error(&format!("unknown construct {} for {}", other, name));
+            }
+        };
+
+        let mut definition = Definition::new(name, kind, line);
+        match kind {
+            DefinitionKind::ObjectIdentifier => self.expect_punct("::=")?,
+            // their clauses refer to other modules' objects and are of
+            // no use for name resolution
+            DefinitionKind::ModuleCompliance | DefinitionKind::AgentCapabilities => {
+                while !self.eat_punct("::=") {
+                    self.skip()?;
+                }
+            }
+            _ => self.clauses(&mut definition, Some(&macro_name))?,
+        }
+
+        if kind == DefinitionKind::TrapType {
+            let number = self.number()?;
+            let number =
+                u32::try_from(number).or_else(|_| self.error("trap number out of range"))?;
+            definition.trap_number = Some(number);
+        } else {
+            definition.oid = self.oid_value()?;
+        }
+        Ok(Some(definition))
+    }
+
+    /// Parses the clauses of a macro invocation up to and including `::=`,
+    /// or to the end of a TEXTUAL-CONVENTION.
+    fn clauses(
+        &mut self,
+        definition: &mut Definition,
+        macro_name: Option<&str>,
+    ) -> Result<(), MibError> {
+        loop {
+            if macro_name.is_some() && self.eat_punct("::=") {
+                return Ok(());
+            }
+            let Some(Token::Word(word)) = self.peek().cloned() else {
+                if macro_name.is_none() {
+                    return self.error("expected SYNTAX");
+                }
+                self.skip()?;
+                continue;
+            };
+            self.pos += 1;
+
+            match &word[..] {
+                "SYNTAX" => {
+                    definition.syntax = Some(self.syntax()?);
+                    // SYNTAX ends a TEXTUAL-CONVENTION
+                    if macro_name.is_none() {
+                        return Ok(());
+                    }
+                }
+                "MAX-ACCESS" | "ACCESS" => definition.access = Some(self.word()?),
+                "STATUS" => definition.status = Some(self.word()?),
+                "DESCRIPTION" => {
+                    // the first one, not those of REVISION clauses
+                    let description = self.string()?;
+                    definition.description.get_or_insert(description);
+                }
+                "REFERENCE" => definition.reference = Some(self.string()?),
+                "UNITS" => definition.units = Some(self.string()?),
+                "DISPLAY-HINT" => definition.display_hint = Some(self.string()?),
+                "INDEX" => definition.index = self.index()?,
+                "AUGMENTS" => {
+                    self.expect_punct("{")?;
+                    definition.augments = Some(self.word()?);
+                    self.expect_punct("}")?;
+                }
+                "DEFVAL" => definition.defval = Some(self.defval()?),
+                "OBJECTS" | "NOTIFICATIONS" | "VARIABLES" => definition.objects = self.names()?,
+                "ENTERPRISE" => definition.enterprise = Some(self.word()?),
+                // LAST-UPDATED, ORGANIZATION, CONTACT-INFO, REVISION and
+                // whatever else have a single string or name argument
+                _ if macro_name.is_some() => {
+                    if !self.is_punct("::=") {
+                        self.skip()?;
+                    }
+                }
+                _ => {
+                    self.pos -= 1;
+                    return self.error(&format!("unexpected {} in TEXTUAL-CONVENTION", word));
+                }
+            }
+        }
+    }
+
+    fn index(&mut self) -> Result<Vec<IndexPart>, MibError> {
+        self.expect_punct("{")?;
+        let mut index = Vec::new();
+        loop {
+            let implied = self.eat_word("IMPLIED");
+            // SMIv1 allows type names such as INTEGER as index
+            let mut name = self.word()?;
+            if name == "OCTET" || name == "OBJECT" {
+                name = format!("{} {}", name, self.word()?);
+            }
+            index.push(IndexPart { name, implied });
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct("}")?;
+        Ok(index)
+    }
+
+    fn names(&mut self) -> Result<Vec<String>, MibError> {
+        self.expect_punct("{")?;
+        let mut names = Vec::new();
+        while !self.eat_punct("}") {
+            if !self.eat_punct(",") {
+                names.push(self.word()?);
+            }
+        }
+        Ok(names)
+    }
+
+    fn defval(&mut self) -> Result<String, MibError> {
+        self.expect_punct("{")?;
+        let mut depth = 0;
+        let mut parts: Vec<String> = Vec::new();
+        loop {
+            let token = self.next()?;
+            match token {
+                Token::Punct("{") => depth += 1,
+                Token::Punct("}") if depth == 0 => break,
+                Token::Punct("}") => depth -= 1,
+                _ => {}
+            }
+            parts.push(match token {
+                Token::Word(w) => w,
+                Token::Number(n) => n.to_string(),
+                Token::Str(s) => format!("\"{}\"", s),
+                Token::Bytes(bytes, true) => {
+                    let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+                    format!("'{}'H", hex)
+                }
+                Token::Bytes(bytes, false) => {
+                    let bin: String = bytes.iter().map(|b| format!("{:08b}", b)).collect();
+                    format!("'{}'B", bin)
+                }
+                Token::Punct(p) => p.to_string(),
+            });
+        }
+        Ok(parts.join(" "))
+    }
+
+    fn oid_value(&mut self) -> Result<Vec<OidComponent>, MibError> {
+        self.expect_punct("{")?;
+        let mut oid = Vec::new();
+        while !self.eat_punct("}") {
+            let component = match self.next()? {
+                Token::Number(n) => OidComponent::Number(self.subid(n)?),
+                Token::Word(name) if self.eat_punct("(") => {
+                    let n = self.number()?;
+                    self.expect_punct(")")?;
+                    OidComponent::NamedNumber(name, self.subid(n)?)
+                }
+                Token::Word(name) => OidComponent::Name(name),
+                _ => {
+                    self.pos -= 1;
+                    return self.error("expected an OID component");
+                }
+            };
+            oid.push(component);
+        }
+        if oid.is_empty() {
+            return self.error("empty OID value");
+        }
+        Ok(oid)
+    }
+
+    fn subid(&self, n: i128) -> Result<u32, MibError> {
+        u32::try_from(n).or_else(|_| self.error("sub-identifier out of range"))
+    }
+
+    fn syntax(&mut self) -> Result<SyntaxDef, MibError> {
+        let mut syntax = SyntaxDef::default();
+
+        // tagged types of the SMI definitions themselves
+        if self.eat_punct("[") {
+            while !self.eat_punct("]") {
+                self.next()?;
+            }
+            self.eat_word("IMPLICIT");
+        }
+
+        let word = self.word()?;
+        syntax.name = match &word[..] {
+            "OCTET" => {
+                self.expect_word("STRING")?;
+                "OCTET STRING".to_string()
+            }
+            "OBJECT" => {
+                self.expect_word("IDENTIFIER")?;
+                "OBJECT IDENTIFIER".to_string()
+            }
+            "SEQUENCE" if self.eat_word("OF") => {
+                syntax.of = Some(self.word()?);
+                return Ok(with_name(syntax, "SEQUENCE OF"));
+            }
+            "SEQUENCE" => {
+                self.expect_punct("{")?;
+                while !self.eat_punct("}") {
+                    if self.eat_punct(",") {
+                        continue;
+                    }
+                    let member = self.word()?;
+                    let member_syntax = self.syntax()?;
+                    syntax.members.push((member, member_syntax));
+                }
+                return Ok(with_name(syntax, "SEQUENCE"));
+            }
+            "CHOICE" => {
+                self.skip()?;
+                return Ok(with_name(syntax, "CHOICE"));
+            }
+            _ => word,
+        };
+
+        if self.is_punct("{") {
+            syntax.enums = self.named_numbers()?;
+        }
+        if self.eat_punct("(") {
+            if self.eat_word("SIZE") {
+                self.expect_punct("(")?;
+                syntax.sizes = self.ranges()?;
+                self.expect_punct(")")?;
+            } else {
+                syntax.ranges = self.ranges()?;
+            }
+            self.expect_punct(")")?;
+        }
+        Ok(syntax)
+    }
+
+    fn named_numbers(&mut self) -> Result<Vec<(String, i64)>, MibError> {
+        self.expect_punct("{")?;
+        let mut named = Vec::new();
+        while !self.eat_punct("}") {
+            if self.eat_punct(",") {
+                continue;
+            }
+            let name = self.word()?;
+            self.expect_punct("(")?;
+            let n = self.number()?;
+            let n = i64::try_from(n).or_else(|_| self.error("enumeration value out of range"))?;
+            self.expect_punct(")")?;
+            named.push((name, n));
+        }
+        Ok(named)
+    }
+
+    /// `a..b | c | ...` up to the closing parenthesis.
+    fn ranges(&mut self) -> Result<Vec<(i128, i128)>, MibError> {
+        let mut ranges = Vec::new();
+        loop {
+            let min = self.number()?;
+            let max = if self.eat_punct("..") {
+                self.number()?
+            } else {
+                min
+            };
+            ranges.push((min, max));
+            if !self.eat_punct("|") {
+                return Ok(ranges);
+            }
+        }
+    }
+}
+
+fn with_name(mut syntax: SyntaxDef, name: &str) -> SyntaxDef {
+    syntax.name = name.to_string();
+    syntax
+}
@@ -0,0 +1,786 @@
+//! The OID tree built from loaded modules.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+
+use super::builtin;
+use super::parser::{
+    parse, Definition, DefinitionKind, IndexPart, Module, OidComponent, SyntaxDef,
+};
+use super::MibError;
+
+/// File name extensions tried when looking up a module in the search path.
+const EXTENSIONS: &[&str] = &["", ".txt", ".mib", ".my", ".MIB"];
+
+/// Bound on the nesting of type definitions, against cycles.
+const MAX_TYPE_DEPTH: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    /// A plain `OBJECT IDENTIFIER` assignment.
+    ObjectIdentifier,
+    ModuleIdentity,
+    ObjectIdentity,
+    Table,
+    Row,
+    Column,
+    Scalar,
+    Notification,
+    /// An SMIv1 TRAP-TYPE, at `enterprise.0.number`.
+    Trap,
+    Group,
+    Compliance,
+    Capabilities,
+}
+
+impl NodeKind {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            NodeKind::ObjectIdentifier => "object-identifier",
+            NodeKind::ModuleIdentity => "module-identity",
+            NodeKind::ObjectIdentity => "object-identity",
+            NodeKind::Table => "table",
+            NodeKind::Row => "row",
+            NodeKind::Column => "column",
+            NodeKind::Scalar => "scalar",
+            NodeKind::Notification => "notification",
+            NodeKind::Trap => "trap",
+            NodeKind::Group => "group",
+            NodeKind::Compliance => "compliance",
+            NodeKind::Capabilities => "capabilities",
+        }
+    }
+}
+
+/// The SMI base types values are encoded as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BaseType {
+    Integer,
+    OctetString,
+    ObjectIdentifier,
+    Bits,
+    IpAddress,
+    Counter32,
+    Gauge32,
+    Unsigned32,
+    TimeTicks,
+    Opaque,
+    Counter64,
+    /// The syntax of rows.
+    Sequence,
+    /// The syntax of tables.
+    SequenceOf,
+}
+
+impl BaseType {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            BaseType::Integer => "INTEGER",
+            BaseType::OctetString => "OCTET STRING",
+            BaseType::ObjectIdentifier => "OBJECT IDENTIFIER",
+            BaseType::Bits => "BITS",
+            BaseType::IpAddress => "IpAddress",
+            BaseType::Counter32 => "Counter32",
+            BaseType::Gauge32 => "Gauge32",
+            BaseType::Unsigned32 => "Unsigned32",
+            BaseType::TimeTicks => "TimeTicks",
+            BaseType::Opaque => "Opaque",
+            BaseType::Counter64 => "Counter64",
+            BaseType::Sequence => "SEQUENCE",
+            BaseType::SequenceOf => "SEQUENCE OF",
+        }
+    }
+
+    /// The base type named `name`, including the SMIv1 names.
+    pub(super) fn from_name(name: &str) -> Option<BaseType> {
+        let base = match name {
+            "INTEGER" | "Integer32" => BaseType::Integer,
+            "OCTET STRING" => BaseType::OctetString,
+            "OBJECT IDENTIFIER" => BaseType::ObjectIdentifier,
+            "BITS" => BaseType::Bits,
+            "IpAddress" | "NetworkAddress" => BaseType::IpAddress,
+            "Counter32" | "Counter" => BaseType::Counter32,
+            "Gauge32" | "Gauge" => BaseType::Gauge32,
+            "Unsigned32" => BaseType::Unsigned32,
+            "TimeTicks" => BaseType::TimeTicks,
+            "Opaque" => BaseType::Opaque,
+            "Counter64" => BaseType::Counter64,
+            "SEQUENCE" | "CHOICE" => BaseType::Sequence,
+            "SEQUENCE OF" => BaseType::SequenceOf,
+            _ => return None,
+        };
+        Some(base)
+    }
+}
+
+/// A resolved SYNTAX: the base type with the constraints and display hint
+/// of the textual conventions it was defined through.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Syntax {
+    pub base: BaseType,
+    /// The outermost textual convention or defined type, e.g.
+    /// `DisplayString`.
+    pub tc: Option<String>,
+    pub display_hint: Option<String>,
+    /// Enumeration labels of INTEGER and bit names of BITS.
+    pub enums: Vec<(String, i64)>,
+    pub ranges: Vec<(i128, i128)>,
+    pub sizes: Vec<(i128, i128)>,
+}
+
+impl Syntax {
+    /// The label of an enumeration value.
+    pub fn label(&self, value: i64) -> Option<&str> {
+        self.enums
+            .iter()
+            .find(|&&(_, v)| v == value)
+            .map(|(label, _)| &label[..])
+    }
+
+    /// The value of an enumeration label.
+    pub fn value(&self, label: &str) -> Option<i64> {
+        self.enums.iter().find(|(l, _)| l == label).map(|&(_, v)| v)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    NotAccessible,
+    AccessibleForNotify,
+    ReadOnly,
+    ReadWrite,
+    ReadCreate,
+    /// SMIv1 only.
+    WriteOnly,
+}
+
+impl Access {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Access::NotAccessible => "not-accessible",
+            Access::AccessibleForNotify => "accessible-for-notify",
+            Access::ReadOnly => "read-only",
+            Access::ReadWrite => "read-write",
+            Access::ReadCreate => "read-create",
+            Access::WriteOnly => "write-only",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Access> {
+        let access = match name {
+            "not-accessible" => Access::NotAccessible,
+            "accessible-for-notify" => Access::AccessibleForNotify,
+            "read-only" => Access::ReadOnly,
+            "read-write" => Access::ReadWrite,
+            "read-create" => Access::ReadCreate,
+            "write-only" => Access::WriteOnly,
+            _ => return None,
+        };
+        Some(access)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Current,
+    Deprecated,
+    Obsolete,
+    /// SMIv1 only.
+    Mandatory,
+    /// SMIv1 only.
+    Optional,
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Status::Current => "current",
+            Status::Deprecated => "deprecated",
+            Status::Obsolete => "obsolete",
+            Status::Mandatory => "mandatory",
+            Status::Optional => "optional",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Status> {
+        let status = match name {
+            "current" => Status::Current,
+            "deprecated" => Status::Deprecated,
+            "obsolete" => Status::Obsolete,
+            "mandatory" => Status::Mandatory,
+            "optional" => Status::Optional,
+            _ => return None,
+        };
+        Some(status)
+    }
+}
+
+/// A named OID with the definition it was assigned by.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub name: String,
+    pub module: String,
+    pub oid: Vec<u32>,
+    pub kind: NodeKind,
+    pub syntax: Option<Syntax>,
+    pub access: Option<Access>,
+    pub status: Option<Status>,
+    pub description: Option<String>,
+    pub reference: Option<String>,
+    pub units: Option<String>,
+    /// INDEX of a row, as written.
+    pub index: Vec<IndexPart>,
+    /// The row a row AUGMENTS.
+    pub augments: Option<String>,
+    pub defval: Option<String>,
+    /// OBJECTS of notifications and groups, VARIABLES of traps.
+    pub objects: Vec<String>,
+    /// The line of the definition in its module.
+    pub line: usize,
+}
+
+/// Loaded MIB modules and the tree of their named OIDs.
+#[derive(Clone)]
+pub struct MibTree {
+    paths: Vec<PathBuf>,
+    modules: Vec<Module>,
+    nodes: BTreeMap<Vec<u32>, Node>,
+    /// OIDs by module and name.
+    names: HashMap<(String, String), Vec<u32>>,
+    /// OIDs by name, of the module loaded first.
+    global: HashMap<String, Vec<u32>>,
+}
+
+impl Default for MibTree {
+    fn default() -> Self {
+        MibTree::new()
+    }
+}
+
+impl MibTree {
+    /// A tree of the SMI base modules.
+    pub fn new() -> Self {
+        let mut tree = MibTree {
+            paths: Vec::new(),
+            modules: Vec::new(),
+            nodes: BTreeMap::new(),
+            names: HashMap::new(),
+            global: HashMap::new(),
+        };
+        tree.load_str(builtin::MODULES)
+            .expect("invalid builtin modules");
+        tree
+    }
+
+    /// Adds a directory to look up imported modules in.
+    pub fn add_path<P: Into<PathBuf>>(&mut self, dir: P) {
+        self.paths.push(dir.into());
+    }
+
+    /// Loads the modules of a MIB file's text and the modules they import,
+    /// replacing modules of the same names. Returns the names of the modules
+    /// in `text`. Nothing is loaded on errors.
+    pub fn load_str(&mut self, text: &str) -> Result<Vec<String>, MibError> {
+        self.load(text, false)
+    }
+
+    /// Loads modules as `load_str`, but leaves out what cannot be resolved:
+    /// imported modules that are not found or not valid, and the
+    /// definitions that depend on them.
+    pub(super) fn load_str_partial(&mut self, text: &str) -> Result<Vec<String>, MibError> {
+        self.load(text, true)
+    }
+
+    fn load(&mut self, text: &str, partial: bool) -> Result<Vec<String>, MibError> {
+        let parsed = parse(text)?;
+        let names = parsed.iter().map(|m| m.name.clone()).collect();
+
+        let mut modules = self.modules.clone();
+        let mut queue = parsed;
+        // each module is looked up once, so a file that does not define
+        // the module it was read for cannot be queued again and again
+        let mut requested = HashSet::new();
+        while let Some(module) = queue.pop() {
+            for import in &module.imports {
+                let known = modules
+                    .iter()
+                    .chain(&queue)
+                    .any(|m| m.name == import.module);
+                if !known && import.module != module.name {
+                    let found = if requested.insert(import.module.clone()) {
+                        self.find_module(&import.module)
+                            .and_then(|text| parse(&text))
+                    } else {
+                        Err(MibError::UnknownModule(import.module.clone()))
+                    };
+                    match found {
+                        Ok(imported) => queue.extend(imported),
+                        Err(_) if partial => {}
+                        Err(err) => return Err(err),
+                    }
+                }
+            }
+            match modules.iter_mut().find(|m| m.name == module.name) {
+                Some(m) => *m = module,
+                None => modules.push(module),
+            }
+        }
+
+        let (nodes, names_by_module, global) = build(&modules, partial)?;
+        self.modules = modules;
+        self.nodes = nodes;
+        self.names = names_by_module;
+        self.global = global;
+        Ok(names)
+    }
+
+    /// Loads the modules of a MIB file, as `load_str`.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<Vec<String>, MibError> {
+        let text = fs::read_to_string(path)?;
+        self.load_str(&text)
+    }
+
+    /// Loads a module from the search path, unless it is loaded already.
+    pub fn load_module(&mut self, name: &str) -> Result<(), MibError> {
+        if self.has_module(name) {
+            return Ok(());
+        }
+        let text = self.find_module(name)?;
+        self.load_str(&text).map(|_| ())
+    }
+
+    pub fn has_module(&self, name: &str) -> bool {
+        self.modules.iter().any(|m| m.name == name)
+    }
+
+    /// The loaded modules, in load order.
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
+
+    /// Reads the text of a module from the search path: files named after
+    /// the module first, then any file defining it. Files named after the
+    /// module that define another one are passed over.
+    pub(super) fn find_module(&self, name: &str) -> Result<String, MibError> {
+        for dir in &self.paths {
+            for ext in EXTENSIONS {
+                if let Ok(text) = fs::read_to_string(dir.join(format!("{}{}", name, ext))) {
+                    if defines_module(&text, name) {
+                        return Ok(text);
+                    }
+                }
+            }
+        }
+        for dir in &self.paths {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let Ok(text) = fs::read_to_string(entry.path()) else {
+                    continue;
+                };
+                if defines_module(&text, name) {
+                    return Ok(text);
+                }
+            }
+        }
+        Err(MibError::UnknownModule(name.to_string()))
+    }
+
+    /// A file of the search path named after the module `name` that defines
+    /// another module instead.
+    pub(super) fn misnamed_module_file(&self, name: &str) -> Option<PathBuf> {
+        self.paths
+            .iter()
+            .flat_map(|dir| {
+                EXTENSIONS
+                    .iter()
+                    .map(move |ext| dir.join(format!("{}{}", name, ext)))
+            })
+            .find(|path| fs::read_to_string(path).is_ok_and(|text| !defines_module(&text, name)))
+    }
+
+    /// The node of a name, optionally qualified as `MODULE::name`.
+    pub fn node(&self, name: &str) -> Option<&Node> {
+        let oid = match name.split_once("::") {
+            Some((module, name)) => self.names.get(&(module.to_string(), name.to_string())),
+            None => self.global.get(name),
+        }?;
+        self.nodes.get(oid)
+    }
+
+    /// The INDEX clause of a row, or of the row it AUGMENTS.
+    pub fn row_index<'a>(&'a self, row: &'a Node) -> Result<&'a [IndexPart], MibError> {
+        match row.augments {
+            Some(ref augmented) => {
+                let augmented = self
+                    .node(&format!("{}::{}", row.module, augmented))
+                    .or_else(|| self.node(augmented))
+                    .ok_or_else(|| MibError::UnknownName(augmented.clone()))?;
+                Ok(&augmented.index[..])
+            }
+            None => Ok(&row.index[..]),
+        }
+    }
+
+    /// The node at exactly `oid`.
+    pub fn node_by_oid(&self, oid: &[u32]) -> Option<&Node> {
+        self.nodes.get(oid)
+    }
+
+    /// The node with the longest OID that is a prefix of `oid`, and the rest
+    /// of `oid`, e.g. the instance part.
+    pub fn lookup<'a>(&self, oid: &'a [u32]) -> Option<(&Node, &'a [u32])> {
+        (1..=oid.len())
+            .rev()
+            .find_map(|len| self.nodes.get(&oid[..len]).map(|node| (node, &oid[len..])))
+    }
+
+    /// All nodes in OID order.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.values()
+    }
+
+    /// The nodes below `oid`, not including it, in OID order.
+    pub fn subtree<'a, 'b>(
+        &'a self,
+        oid: &'b [u32],
+    ) -> impl Iterator<Item = &'a Node> + use<'a, 'b> {
+        self.nodes
+            .range::<[u32], _>((Bound::Excluded(oid), Bound::Unbounded))
+            .take_while(move |(node, _)| node.starts_with(oid))
+            .map(|(_, node)| node)
+    }
+
+    /// Resolves `IF-MIB::ifHCInOctets.3`, `ifHCInOctets.3` or
+    /// `1.3.6.1.2.1.31.1.1.1.6.3` to an OID.
+    pub fn resolve(&self, name: &str) -> Result<Vec<u32>, MibError> {
+        let unknown = || MibError::UnknownName(name.to_string());
+        let trimmed = name.trim_start_matches('.');
+
+        // the name ends at the first dot after the module separator
+        let module_len = trimmed.find("::").map_or(0, |i| i + 2);
+        let (symbol, suffix) = match trimmed[module_len..].find('.') {
+            Some(i) => trimmed.split_at(module_len + i),
+            None => (trimmed, ""),
+        };
+
+        let mut oid = match symbol.parse::<u32>() {
+            Ok(subid) => vec![subid],
+            Err(_) => self.node(symbol).ok_or_else(unknown)?.oid.clone(),
+        };
+        for part in suffix.split('.').skip(1) {
+            oid.push(part.parse().map_err(|_| unknown())?);
+        }
+        Ok(oid)
+    }
+
+    /// Formats `oid` as `MODULE::name.suffix`, or numerically when no
+    /// prefix of it is named.
+    pub fn format_oid(&self, oid: &[u32]) -> String {
+        let (mut out, suffix) = match self.lookup(oid) {
+            Some((node, suffix)) => (format!("{}::{}", node.module, node.name), suffix),
+            None => (String::new(), oid),
+        };
+        for (i, subid) in suffix.iter().enumerate() {
+            if i > 0 || !out.is_empty() {
+                out.push('.');
+            }
+            out.push_str(&subid.to_string());
+        }
+        out
+    }
+}
+
+/// Whether `text` contains the header of module `name`.
+fn defines_module(text: &str, name: &str) -> bool {
+    text.match_indices(name).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + name.len()..].trim_start();
+        !before.is_some_and(|c| c.is_ascii_alphanumeric() || c == '-')
+            && after.starts_with("DEFINITIONS")
+    })
+}
+
+type Built = (
+    BTreeMap<Vec<u32>, Node>,
+    HashMap<(String, String), Vec<u32>>,
+    HashMap<String, Vec<u32>>,
+);
+
+/// Symbol lookup across modules.
+struct Scope<'a> {
+    modules: &'a [Module],
+    definitions: HashMap<(&'a str, &'a str), &'a Definition>,
+}
+
+impl<'a> Scope<'a> {
+    /// The module that `name`, as used in `module`, refers to: the module
+    /// itself, the module it is imported from, or else the first module
+    /// defining it.
+    fn owner(&self, module: &'a Module, name: &str) -> Option<&'a str> {
+        if self.definitions.contains_key(&(&module.name[..], name)) {
+            return Some(&module.name);
+        }
+        let imported = module
+            .imports
+            .iter()
+            .find(|i| i.symbols.iter().any(|s| s == name))
+            .map(|i| &i.module[..]);
+        if let Some(imported) = imported {
+            if self.definitions.contains_key(&(imported, name)) {
+                return Some(imported);
+            }
+        }
+        self.modules
+            .iter()
+            .find(|m| self.definitions.contains_key(&(&m.name[..], name)))
+            .map(|m| &m.name[..])
+    }
+
+    fn module(&self, name: &str) -> &'a Module {
+        self.modules.iter().find(|m| m.name == name).unwrap()
+    }
+
+    /// Resolves a SYNTAX through the type definitions it refers to.
+    fn syntax(
+        &self,
+        module: &'a Module,
+        def: &SyntaxDef,
+        depth: usize,
+    ) -> Result<Syntax, MibError> {
+        let mut syntax = match BaseType::from_name(&def.name) {
+            Some(base) => Syntax {
+                base,
+                tc: None,
+                display_hint: None,
+                enums: Vec::new(),
+                ranges: Vec::new(),
+                sizes: Vec::new(),
+            },
+            None => {
+                let unknown = || MibError::UnknownName(format!("{}::{}", module.name, def.name));
+                let owner = self.owner(module, &def.name).ok_or_else(unknown)?;
+                let typedef = self.definitions[&(owner, &def.name[..])];
+                let inner = match typedef.syntax {
+                    Some(ref inner) if depth < MAX_TYPE_DEPTH => inner,
+                    _ => return Err(unknown()),
+                };
+                let mut syntax = self.syntax(self.module(owner), inner, depth + 1)?;
+                syntax.tc = Some(def.name.clone());
+                if typedef.display_hint.is_some() {
+                    syntax.display_hint = typedef.display_hint.clone();
+                }
+                syntax
+            }
+        };
+
+        // refinements of the SYNTAX clause replace those of the type
+        if !def.enums.is_empty() {
+            syntax.enums = def.enums.clone();
+        }
+        if !def.ranges.is_empty() {
+            syntax.ranges = def.ranges.clone();
+        }
+        if !def.sizes.is_empty() {
+            syntax.sizes = def.sizes.clone();
+        }
+        Ok(syntax)
+    }
+}
+
+/// Resolves the OIDs of all definitions and builds their nodes. `partial`
+/// skips the definitions that cannot be resolved instead of failing.
+fn build(modules: &[Module], partial: bool) -> Result<Built, MibError> {
+    let mut definitions = HashMap::new();
+    for module in modules {
+        for def in &module.definitions {
+            definitions.insert((&module.name[..], &def.name[..]), def);
+        }
+    }
+    let scope = Scope {
+        modules,
+        definitions,
+    };
+
+    // resolve OIDs until no more can be, as definitions may refer to
+    // those of later ones and of other modules
+    let mut oids: HashMap<(&str, &str), Vec<u32>> = HashMap::new();
+    loop {
+        let mut progress = false;
+        let mut unresolved = None;
+        for module in modules {
+            for def in &module.definitions {
+                let key = (&module.name[..], &def.name[..]);
+                if !has_oid(def) || oids.contains_key(&key) {
+                    continue;
+                }
+                match resolve_oid(&scope, &oids, module, def) {
+                    Ok(oid) => {
+                        oids.insert(key, oid);
+                        progress = true;
+                    }
+                    Err(name) => {
+                        unresolved.get_or_insert(name);
+                    }
+                }
+            }
+        }
+        match unresolved {
+            None => break,
+            Some(_) if !progress && partial => break,
+            Some(name) if !progress => return Err(MibError::UnknownName(name)),
+            Some(_) => {}
+        }
+    }
+
+    let mut nodes = BTreeMap::new();
+    let mut names = HashMap::new();
+    let mut global = HashMap::new();
+    for module in modules {
+        for def in &module.definitions {
+            let Some(oid) = oids.get(&(&module.name[..], &def.name[..])) else {
+                continue;
+            };
+            let node = match make_node(&scope, module, def, oid.clone()) {
+                Ok(node) => node,
+                Err(_) if partial => continue,
+                Err(err) => return Err(err),
+            };
+            names.insert((module.name.clone(), def.name.clone()), oid.clone());
+            global
+                .entry(def.name.clone())
+                .or_insert_with(|| oid.clone());
+            nodes.entry(oid.clone()).or_insert(node);
+        }
+    }
+
+    // object types are columns when their parent is a row
+    let columns: Vec<Vec<u32>> = nodes
+        .values()
+        .filter(|n| n.kind == NodeKind::Scalar)
+        .filter(|n| {
+            let parent = &n.oid[..n.oid.len() - 1];
+            nodes.get(parent).is_some_and(|p| p.kind == NodeKind::Row)
+        })
+        .map(|n| n.oid.clone())
+        .collect();
+    for oid in columns {
+        nodes.get_mut(&oid).unwrap().kind = NodeKind::Column;
+    }
+
+    Ok((nodes, names, global))
+}
+
+fn has_oid(def: &Definition) -> bool {
+    !matches!(
+        def.kind,
+        DefinitionKind::TextualConvention | DefinitionKind::Type
+    )
+}
+
+/// The OID of `def`, or the qualified name that is not resolved yet.
+fn resolve_oid<'a>(
+    scope: &Scope<'a>,
+    oids: &HashMap<(&str, &str), Vec<u32>>,
+    module: &'a Module,
+    def: &Definition,
+) -> Result<Vec<u32>, String> {
+    let lookup = |name: &str| -> Result<Vec<u32>, String> {
+        let owner = scope.owner(module, name);
+        match owner.and_then(|owner| oids.get(&(owner, name))) {
+            Some(oid) => Ok(oid.clone()),
+            None if owner.is_none() => match name {
+                "ccitt" => Ok(vec![0]),
+                "iso" => Ok(vec![1]),
+                "joint-iso-ccitt" => Ok(vec![2]),
+                _ => Err(format!("{}::{}", module.name, name)),
+            },
+            None => Err(format!("{}::{}", module.name, name)),
+        }
+    };
+
+    if def.kind == DefinitionKind::TrapType {
+        let enterprise = def.enterprise.as_deref().unwrap_or("");
+        let mut oid = lookup(enterprise)?;
+        oid.push(0);
+        oid.push(def.trap_number.unwrap_or(0));
+        return Ok(oid);
+    }
+
+    let mut oid = Vec::new();
+    for (i, component) in def.oid.iter().enumerate() {
+        match *component {
+            OidComponent::Name(ref name) if i == 0 => oid = lookup(name)?,
+            OidComponent::Name(ref name) => return Err(format!("{}::{}", module.name, name)),
+            OidComponent::Number(n) | OidComponent::NamedNumber(_, n) => oid.push(n),
+        }
+    }
+    Ok(oid)
+}
+
+fn make_node(
+    scope: &Scope,
+    module: &Module,
+    def: &Definition,
+    oid: Vec<u32>,
+) -> Result<Node, MibError> {
+    let error = |message: String| Err(MibError::parse(def.line, &message));
+
+    let syntax = match def.syntax {
+        Some(ref syntax) => Some(scope.syntax(module, syntax, 0)?),
+        None => None,
+    };
+    let access = match def.access.as_deref() {
+        Some(access) => match Access::from_name(access) {
+            Some(access) => Some(access),
+            None => return error(format!("invalid access {} of {}", access, def.name)),
+        },
+        None => None,
+    };
+    let status = match def.status.as_deref() {
+        Some(status) => match Status::from_name(status) {
+            Some(status) => Some(status),
+            None => return error(format!("invalid status {} of {}", status, def.name)),
+        },
+        None => None,
+    };
+
+    let kind = match def.kind {
+        DefinitionKind::ObjectIdentifier => NodeKind::ObjectIdentifier,
+        DefinitionKind::ModuleIdentity => NodeKind::ModuleIdentity,
+        DefinitionKind::ObjectIdentity => NodeKind::ObjectIdentity,
+        DefinitionKind::ObjectType => match syntax.as_ref().map(|s| s.base) {
+            Some(BaseType::SequenceOf) => NodeKind::Table,
+            Some(BaseType::Sequence) => NodeKind::Row,
+            // columns are told apart once all nodes are known
+            _ => NodeKind::Scalar,
+        },
+        DefinitionKind::NotificationType => NodeKind::Notification,
+        DefinitionKind::TrapType => NodeKind::Trap,
+        DefinitionKind::ObjectGroup | DefinitionKind::NotificationGroup => NodeKind::Group,
+        DefinitionKind::ModuleCompliance => NodeKind::Compliance,
+        DefinitionKind::AgentCapabilities => NodeKind::Capabilities,
+        DefinitionKind::TextualConvention | DefinitionKind::Type => unreachable!(),
+    };
+
+    Ok(Node {
+        name: def.name.clone(),
+        module: module.name.clone(),
+        oid,
+        kind,
+        syntax,
+        access,
+        status,
+        description: def.description.clone(),
+        reference: def.reference.clone(),
+        units: def.units.clone(),
+        index: def.index.clone(),
+        augments: def.augments.clone(),
+        defval: def.defval.clone(),
+        objects: def.objects.clone(),
+        line: def.line,
+    })
+}
@@ -0,0 +1,348 @@
+//! Version independent notifications.
+//!
+//! SNMPv1 Trap-PDUs are translated into SNMPv2-style notifications, and back,
+//! following RFC 3584 section 3.
+//!
+//! With MIBs loaded, `Notification::named` matches the notification to its
+//! NOTIFICATION-TYPE or TRAP-TYPE definition and names its objects.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
+use crate::mib::{MibTree, NodeKind};
+use crate::pdu;
+use crate::snmp;
+use crate::OwnedValue;
+use crate::SnmpError;
+use crate::SnmpMessageType;
+use crate::SnmpPdu;
+use crate::SnmpResult;
+use crate::SnmpTrapV1Pdu;
+use crate::Varbinds;
+use crate::{is_encodable_oid, is_encodable_varbind};
+
+/// SNMPv2-MIB::sysUpTime.0
+pub const SYS_UPTIME: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 3, 0];
+/// SNMPv2-MIB::snmpTrapOID.0
+pub const SNMP_TRAP_OID: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0];
+/// SNMPv2-MIB::snmpTrapEnterprise.0
+pub const SNMP_TRAP_ENTERPRISE: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 4, 3, 0];
+/// SNMPv2-MIB::snmpTraps, parent of the generic traps.
+pub const SNMP_TRAPS: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 5];
+/// SNMP-COMMUNITY-MIB::snmpTrapAddress.0
+pub const SNMP_TRAP_ADDRESS: &[u32] = &[1, 3, 6, 1, 6, 3, 18, 1, 3, 0];
+/// SNMP-COMMUNITY-MIB::snmpTrapCommunity.0
+pub const SNMP_TRAP_COMMUNITY: &[u32] = &[1, 3, 6, 1, 6, 3, 18, 1, 4, 0];
+
+/// generic-trap value of an SNMPv1 enterprise specific trap.
+pub const GENERIC_TRAP_ENTERPRISE_SPECIFIC: i64 = 6;
+
+/// An owned SNMPv2-style notification.
+///
+/// `varbinds` holds everything after the mandatory `sysUpTime.0` and
+/// `snmpTrapOID.0` varbinds, which are available as `uptime` and `trap_oid`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    /// Version of the message the notification was received as.
+    pub version: i64,
+    pub community: Vec<u8>,
+    /// Sender of the message, filled in by the receiving session.
+    pub source: Option<SocketAddr>,
+    /// Local endpoint the message was received on, filled in by the
+    /// receiving session.
+    pub local: Option<SocketAddr>,
+    pub uptime: u32,
+    pub trap_oid: Vec<u32>,
+    pub varbinds: Vec<(Vec<u32>, OwnedValue)>,
+}
+
+/// An owned SNMPv1 trap, the result of translating a `Notification` for a
+/// v1-only manager.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrapV1 {
+    pub enterprise: Vec<u32>,
+    pub agent_addr: [u8; 4],
+    pub generic_trap: i64,
+    pub specific_trap: i64,
+    pub timestamp: u32,
+    pub varbinds: Vec<(Vec<u32>, OwnedValue)>,
+}
+
+/// A notification matched to its definition in the loaded MIBs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedNotification {
+    /// The definition, as `MODULE::name`, e.g. `IF-MIB::linkDown`.
+    pub name: String,
+    /// Values of the OBJECTS (VARIABLES of SMIv1 traps) the notification
+    /// carries, by the names the definition lists them with.
+    pub objects: BTreeMap<String, OwnedValue>,
+    /// OBJECTS the notification does not carry a varbind of.
+    pub missing: Vec<String>,
+}
+
+impl NamedNotification {
+    /// Whether the notification carries all the OBJECTS of its definition.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+pub(crate) fn owned_varbinds(varbinds: Varbinds) -> SnmpResult<Vec<(Vec<u32>, OwnedValue)>> {
+    let mut buf = [0; 128];
+    let mut out = Vec::new();
+    for (name, value) in varbinds {
+        let name = name.read_name(&mut buf)?;
+        // names that could not be encoded again, e.g. 3.0
+        if !is_encodable_oid(name) {
+            return Err(SnmpError::AsnParseError);
+        }
+        let name = name.to_vec();
+        let value = OwnedValue::from_value(&value).ok_or(SnmpError::AsnUnsupportedType)?;
+        out.push((name, value));
+    }
+    Ok(out)
+}
+
+impl Notification {
+    /// Decodes an SNMPv1 Trap-PDU, SNMPv2-Trap-PDU or InformRequest-PDU.
+    pub fn from_bytes(bytes: &[u8]) -> SnmpResult<Notification> {
+        match crate::peek_version(bytes)? {
+            snmp::VERSION_1 => Notification::from_v1(&SnmpTrapV1Pdu::from_bytes(bytes)?),
+            snmp::VERSION_2 => Notification::from_pdu(&SnmpPdu::from_bytes(bytes)?),
+            _ => Err(SnmpError::UnsupportedVersion),
+        }
+    }
+
+    /// Converts an SNMPv2-Trap-PDU or InformRequest-PDU.
+    pub fn from_pdu(pdu: &SnmpPdu) -> SnmpResult<Notification> {
+        if pdu.message_type != SnmpMessageType::Trap
+            && pdu.message_type != SnmpMessageType::InformRequest
+        {
+            return Err(SnmpError::AsnWrongType);
+        }
+
+        let mut varbinds = owned_varbinds(pdu.varbinds.clone())?.into_iter();
+
+        let uptime = match varbinds.next() {
+            Some((name, OwnedValue::Timeticks(tt))) if name == SYS_UPTIME => tt,
+            _ => return Err(SnmpError::AsnParseError),
+        };
+
+        let trap_oid = match varbinds.next() {
+            Some((name, OwnedValue::ObjectIdentifier(oid))) if name == SNMP_TRAP_OID => oid,
+            _ => return Err(SnmpError::AsnParseError),
+        };
+
+        Ok(Notification {
+            version: pdu.version,
+            community: pdu.community.to_vec(),
+            source: None,
+            local: None,
+            uptime,
+            trap_oid,
+            varbinds: varbinds.collect(),
+        })
+    }
+
+    /// Translates an SNMPv1 trap as described in RFC 3584 section 3.1.
+    ///
+    /// `snmpTrapAddress.0`, `snmpTrapCommunity.0` and `snmpTrapEnterprise.0`
+    /// are appended unless the trap already carries them.
+    pub fn from_v1(pdu: &SnmpTrapV1Pdu) -> SnmpResult<Notification> {
+        let mut buf = [0; 128];
+        let enterprise = pdu.enterprise.read_name(&mut buf)?.to_vec();
+        if !is_encodable_oid(&enterprise) {
+            return Err(SnmpError::AsnParseError);
+        }
+
+        let trap_oid = if pdu.generic_trap == GENERIC_TRAP_ENTERPRISE_SPECIFIC {
+            // a negative specific-trap has no sub-identifier to map to
+            let specific_trap =
+                u32::try_from(pdu.specific_trap).map_err(|_| SnmpError::ValueOutOfRange)?;
+            let mut oid = enterprise.clone();
+            oid.push(0);
+            oid.push(specific_trap);
+            oid
+        } else if (0..GENERIC_TRAP_ENTERPRISE_SPECIFIC).contains(&pdu.generic_trap) {
+            let mut oid = SNMP_TRAPS.to_vec();
+            oid.push(pdu.generic_trap as u32 + 1);
+            oid
+        } else {
+            return Err(SnmpError::ValueOutOfRange);
+        };
+
+        let mut notification = Notification {
+            version: snmp::VERSION_1,
+            community: pdu.community.to_vec(),
+            source: None,
+            local: None,
+            uptime: pdu.timestamp,
+            trap_oid,
+            varbinds: owned_varbinds(pdu.varbinds.clone())?,
+        };
+
+        notification.append_missing(SNMP_TRAP_ADDRESS, OwnedValue::IpAddress(pdu.agent_addr));
+        notification.append_missing(
+            SNMP_TRAP_COMMUNITY,
+            OwnedValue::OctetString(pdu.community.to_vec()),
+        );
+        notification.append_missing(
+            SNMP_TRAP_ENTERPRISE,
+            OwnedValue::ObjectIdentifier(enterprise),
+        );
+
+        Ok(notification)
+    }
+
+    /// Whether all the OIDs of the notification can be encoded, as those of
+    /// received notifications always can.
+    pub fn is_encodable(&self) -> bool {
+        is_encodable_oid(&self.trap_oid)
+            && self
+                .varbinds
+                .iter()
+                .all(|(name, value)| is_encodable_varbind(name, value))
+    }
+
+    /// Translates the notification for an SNMPv1 manager as described in
+    /// RFC 3584 section 3.2.
+    ///
+    /// Counter64 varbinds cannot be represented in SNMPv1 and are dropped.
+    pub fn to_v1(&self) -> TrapV1 {
+        let trap_oid = &self.trap_oid[..];
+        let (enterprise, generic_trap, specific_trap) = match self.generic_trap() {
+            Some(generic_trap) => {
+                let enterprise = match self.varbind(SNMP_TRAP_ENTERPRISE) {
+                    Some(OwnedValue::ObjectIdentifier(oid)) => oid.clone(),
+                    _ => SNMP_TRAPS.to_vec(),
+                };
+                (enterprise, generic_trap, 0)
+            }
+            None => {
+                let (head, last) = trap_oid.split_at(trap_oid.len().saturating_sub(1));
+                let enterprise = match head.split_last() {
+                    Some((0, parent)) => parent.to_vec(),
+                    _ => head.to_vec(),
+                };
+                let specific_trap = last.first().copied().unwrap_or(0) as i64;
+                (enterprise, GENERIC_TRAP_ENTERPRISE_SPECIFIC, specific_trap)
+            }
+        };
+
+        let agent_addr = match self.varbind(SNMP_TRAP_ADDRESS) {
+            Some(OwnedValue::IpAddress(ip)) => *ip,
+            _ => [0; 4],
+        };
+
+        let varbinds = self
+            .varbinds
+            .iter()
+            .filter(|(_, value)| !matches!(value, OwnedValue::Counter64(_)))
+            .cloned()
+            .collect();
+
+        TrapV1 {
+            enterprise,
+            agent_addr,
+            generic_trap,
+            specific_trap,
+            timestamp: self.uptime,
+            varbinds,
+        }
+    }
+
+    /// The SNMPv1 generic-trap number if `trap_oid` is one of the standard
+    /// `snmpTraps`.
+    pub fn generic_trap(&self) -> Option<i64> {
+        match self.trap_oid.split_last() {
+            Some((&last, parent)) if parent == SNMP_TRAPS && (1..=6).contains(&last) => {
+                Some(last as i64 - 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Looks up the value of a varbind by name.
+    pub fn varbind(&self, name: &[u32]) -> Option<&OwnedValue> {
+        self.varbinds
+            .iter()
+            .find(|(n, _)| &n[..] == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Matches `trap_oid` to the NOTIFICATION-TYPE or TRAP-TYPE defining it
+    /// and picks out the varbinds of its OBJECTS, which are instances of
+    /// the objects. Returns `None` for notifications the MIBs do not define.
+    pub fn named(&self, mibs: &MibTree) -> Option<NamedNotification> {
+        let node = mibs
+            .node_by_oid(&self.trap_oid)
+            .filter(|node| matches!(node.kind, NodeKind::Notification | NodeKind::Trap))?;
+
+        let mut objects = BTreeMap::new();
+        let mut missing = Vec::new();
+        for object in &node.objects {
+            let value = mibs
+                .node(&format!("{}::{}", node.module, object))
+                .or_else(|| mibs.node(object))
+                .and_then(|object| {
+                    self.varbinds
+                        .iter()
+                        .find(|(name, _)| name.starts_with(&object.oid))
+                });
+            match value {
+                Some((_, value)) => {
+                    objects.insert(object.clone(), value.clone());
+                }
+                None => missing.push(object.clone()),
+            }
+        }
+
+        Some(NamedNotification {
+            name: format!("{}::{}", node.module, node.name),
+            objects,
+            missing,
+        })
+    }
+
+    fn append_missing(&mut self, name: &[u32], value: OwnedValue) {
+        if self.varbind(name).is_none() {
+            self.varbinds.push((name.to_vec(), value));
+        }
+    }
+
+    /// All varbinds as sent on the wire, starting with `sysUpTime.0` and
+    /// `snmpTrapOID.0`.
+    pub fn wire_varbinds(&self) -> Vec<(Vec<u32>, OwnedValue)> {
+        let mut out = Vec::with_capacity(self.varbinds.len() + 2);
+        out.push((SYS_UPTIME.to_vec(), OwnedValue::Timeticks(self.uptime)));
+        out.push((
+            SNMP_TRAP_OID.to_vec(),
+            OwnedValue::ObjectIdentifier(self.trap_oid.clone()),
+        ));
+        out.extend(self.varbinds.iter().cloned());
+        out
+    }
+
+    /// Encodes the notification as an SNMPv2-Trap-PDU. Fails on object
+    /// identifiers that cannot be encoded.
+    pub fn build_trap(&self, community: &[u8], req_id: i32, buf: &mut pdu::Buf) -> SnmpResult<()> {
+        pdu::build_trap(community, req_id, &self.wire_varbinds(), buf)
+    }
+}
+
+impl TrapV1 {
+    /// Encodes the trap as an SNMPv1 Trap-PDU, failing like
+    /// `Notification::build_trap`.
+    pub fn build_trap(&self, community: &[u8], buf: &mut pdu::Buf) -> SnmpResult<()> {
+        pdu::build_trap_v1(
+            community,
+            &self.enterprise,
+            &self.agent_addr,
+            self.generic_trap,
+            self.specific_trap,
+            self.timestamp,
+            &self.varbinds,
+            buf,
+        )
+    }
+}
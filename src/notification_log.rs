@@ -0,0 +1,298 @@
+//! Bounded in-memory notification history, modeled on NOTIFICATION-LOG-MIB
+//! (RFC 3014).
+//!
+//! Every source address keeps its own ring buffer, so a chatty device cannot
+//! push the history of quieter ones out of the log.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::notification::Notification;
+use crate::pdu;
+use crate::BUFFER_SIZE;
+
+const FILE_MAGIC: &[u8; 8] = b"SNMPNLG2";
+/// Files written before local addresses were recorded.
+const FILE_MAGIC_V1: &[u8; 8] = b"SNMPNLG1";
+
+/// A logged notification, the equivalent of an nlmLogEntry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    /// Log-wide sequence number, nlmLogIndex.
+    pub index: u32,
+    /// Local time the notification was logged at, nlmLogDateAndTime.
+    pub received: SystemTime,
+    pub notification: Notification,
+}
+
+/// Selects entries from the log. Unset fields match everything.
+#[derive(Clone, Debug, Default)]
+pub struct LogQuery {
+    pub source: Option<IpAddr>,
+    /// Matches notifications whose trap OID starts with this prefix.
+    pub trap_oid: Option<Vec<u32>>,
+    /// Inclusive lower bound of `LogEntry::received`.
+    pub since: Option<SystemTime>,
+    /// Exclusive upper bound of `LogEntry::received`.
+    pub until: Option<SystemTime>,
+    /// Returns only the most recent `limit` matches.
+    pub limit: Option<usize>,
+}
+
+impl LogQuery {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if self.source.is_some() && self.source != entry.notification.source.map(|s| s.ip()) {
+            return false;
+        }
+        if let Some(ref prefix) = self.trap_oid {
+            if !entry.notification.trap_oid.starts_with(prefix) {
+                return false;
+            }
+        }
+        if let Some(since) = self.since {
+            if entry.received < since {
+                return false;
+            }
+        }
+        if let Some(until) = self.until {
+            if entry.received >= until {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Global counters, nlmStatsGlobalNotificationsLogged and
+/// nlmStatsGlobalNotificationsBumped.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogStats {
+    pub logged: u64,
+    pub bumped: u64,
+}
+
+pub struct NotificationLog {
+    entries_per_source: usize,
+    sources: HashMap<Option<IpAddr>, VecDeque<LogEntry>>,
+    next_index: u32,
+    stats: LogStats,
+}
+
+impl NotificationLog {
+    /// Creates a log keeping at most `entries_per_source` notifications for
+    /// every source address.
+    pub fn new(entries_per_source: usize) -> Self {
+        NotificationLog {
+            entries_per_source: entries_per_source.max(1),
+            sources: HashMap::new(),
+            next_index: 1,
+            stats: LogStats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> &LogStats {
+        &self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.values().map(|q| q.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn log(&mut self, notification: Notification) {
+        self.log_at(notification, SystemTime::now());
+    }
+
+    /// Like `log`, with an explicit receive time.
+    pub fn log_at(&mut self, notification: Notification, received: SystemTime) {
+        let index = self.next_index;
+        // nlmLogIndex wraps to 1, never 0.
+        self.next_index = self.next_index.checked_add(1).unwrap_or(1);
+        self.push(LogEntry {
+            index,
+            received,
+            notification,
+        });
+        self.stats.logged += 1;
+    }
+
+    fn push(&mut self, entry: LogEntry) {
+        let queue = self
+            .sources
+            .entry(entry.notification.source.map(|s| s.ip()))
+            .or_default();
+
+        if queue.len() == self.entries_per_source {
+            queue.pop_front();
+            self.stats.bumped += 1;
+        }
+        queue.push_back(entry);
+    }
+
+    /// Matching entries, oldest first.
+    pub fn query(&self, query: &LogQuery) -> Vec<&LogEntry> {
+        let mut out: Vec<&LogEntry> = match query.source {
+            Some(ip) => self
+                .sources
+                .get(&Some(ip))
+                .into_iter()
+                .flatten()
+                .filter(|e| query.matches(e))
+                .collect(),
+            None => self
+                .sources
+                .values()
+                .flatten()
+                .filter(|e| query.matches(e))
+                .collect(),
+        };
+        out.sort_by_key(|e| (e.received, e.index));
+
+        if let Some(limit) = query.limit {
+            let skip = out.len().saturating_sub(limit);
+            out.drain(..skip);
+        }
+        out
+    }
+
+    /// The last `n` notifications received from `source`, oldest first.
+    pub fn last(&self, source: IpAddr, n: usize) -> Vec<&LogEntry> {
+        self.query(&LogQuery {
+            source: Some(source),
+            limit: Some(n),
+            ..LogQuery::default()
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.sources.clear();
+    }
+
+    /// Writes all entries to `path`. Notifications are stored as encoded
+    /// SNMPv2-Trap-PDUs.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let mut buf = pdu::Buf::default();
+
+        out.write_all(FILE_MAGIC)?;
+        for entry in self.query(&LogQuery::default()) {
+            let n = &entry.notification;
+            let received = entry
+                .received
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            let source = n.source.map(|s| s.to_string()).unwrap_or_default();
+            let local = n.local.map(|s| s.to_string()).unwrap_or_default();
+            // notifications added with OIDs that cannot be encoded are
+            // left out
+            if n.build_trap(&n.community, 0, &mut buf).is_err() {
+                continue;
+            }
+
+            out.write_all(&entry.index.to_be_bytes())?;
+            out.write_all(&received.as_secs().to_be_bytes())?;
+            out.write_all(&received.subsec_nanos().to_be_bytes())?;
+            out.write_all(&[n.version as u8, source.len() as u8, local.len() as u8])?;
+            out.write_all(source.as_bytes())?;
+            out.write_all(local.as_bytes())?;
+            out.write_all(&(buf.len() as u32).to_be_bytes())?;
+            out.write_all(&buf[..])?;
+        }
+        out.flush()
+    }
+
+    /// Reads a log written by `save`, or by versions that did not record
+    /// local addresses. Entries beyond `entries_per_source` are bumped as if
+    /// they had been received live.
+    pub fn load<P: AsRef<Path>>(path: P, entries_per_source: usize) -> io::Result<Self> {
+        let mut input = BufReader::new(File::open(path)?);
+        let mut log = NotificationLog::new(entries_per_source);
+
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic)?;
+        let has_local = match &magic {
+            FILE_MAGIC => true,
+            FILE_MAGIC_V1 => false,
+            _ => return Err(invalid_data("not a notification log file")),
+        };
+
+        let mut max_index = 0;
+        loop {
+            let mut index = [0u8; 4];
+            match input.read_exact(&mut index) {
+                Ok(()) => {}
+                Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            }
+            let index = u32::from_be_bytes(index);
+            let secs = u64::from_be_bytes(read_array(&mut input)?);
+            let nanos = u32::from_be_bytes(read_array(&mut input)?);
+            let [version, source_len] = read_array(&mut input)?;
+            let local_len = if has_local {
+                read_array::<_, 1>(&mut input)?[0]
+            } else {
+                0
+            };
+            let source = read_addr(&mut input, source_len)?;
+            let local = read_addr(&mut input, local_len)?;
+
+            // notifications are received into buffers of BUFFER_SIZE, so a
+            // longer PDU is a corrupt length
+            let pdu_len = u32::from_be_bytes(read_array(&mut input)?) as usize;
+            if pdu_len > BUFFER_SIZE {
+                return Err(invalid_data("notification longer than a message"));
+            }
+            let mut bytes = vec![0; pdu_len];
+            input.read_exact(&mut bytes)?;
+
+            let mut notification = Notification::from_bytes(&bytes)
+                .map_err(|err| invalid_data(&format!("invalid notification: {:?}", err)))?;
+            notification.version = version as i64;
+            notification.source = source;
+            notification.local = local;
+
+            log.push(LogEntry {
+                index,
+                received: UNIX_EPOCH + Duration::new(secs, nanos),
+                notification,
+            });
+            log.stats.logged += 1;
+            // entries are saved by receive time, so the last is not
+            // necessarily the newest index
+            max_index = max_index.max(index);
+        }
+        log.next_index = max_index.checked_add(1).unwrap_or(1);
+
+        Ok(log)
+    }
+}
+
+fn read_array<R: Read, const N: usize>(input: &mut R) -> io::Result<[u8; N]> {
+    let mut out = [0u8; N];
+    input.read_exact(&mut out)?;
+    Ok(out)
+}
+
+fn read_addr<R: Read>(input: &mut R, len: u8) -> io::Result<Option<SocketAddr>> {
+    if len == 0 {
+        return Ok(None);
+    }
+
+    let mut addr = vec![0; len as usize];
+    input.read_exact(&mut addr)?;
+    String::from_utf8_lossy(&addr)
+        .parse()
+        .map(Some)
+        .map_err(|_| invalid_data("invalid socket address"))
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
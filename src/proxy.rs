@@ -0,0 +1,263 @@
+//! Proxy forwarding of requests to other agents (RFC 3413 section 3.5).
+//!
+//! Configuration follows SNMP-PROXY-MIB: a proxied community selects a
+//! context, as `snmpCommunityContextName` does, and forwarding entries map
+//! contexts to targets. Targets may speak SNMPv1, in which case requests and
+//! responses are translated as described in RFC 3584 section 4.
+//!
+//! An `Agent` proxies requests of SNMPv2c managers by community, and
+//! SNMPv3 requests by their contextName once it has a `Usm`. SNMPv3 is
+//! limited to the noAuthNoPriv security level.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::notification::owned_varbinds;
+use crate::snmp;
+use crate::sync_session::SyncSession;
+use crate::OwnedValue;
+use crate::SnmpMessageType;
+
+type Varbinds = Vec<(Vec<u32>, OwnedValue)>;
+
+/// The requests a forwarding entry applies to, as `snmpProxyType`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProxyType {
+    Read,
+    Write,
+}
+
+/// An agent requests are forwarded to.
+#[derive(Clone, Debug)]
+pub struct ProxyTarget {
+    pub address: SocketAddr,
+    /// `snmp::VERSION_1` or `snmp::VERSION_2`.
+    pub version: i64,
+    pub community: Vec<u8>,
+    pub timeout: Duration,
+    pub retries: u32,
+}
+
+impl ProxyTarget {
+    /// A target with the defaults of SNMP-TARGET-MIB: a timeout of 1.5
+    /// seconds and three retries.
+    pub fn new(address: SocketAddr, version: i64, community: &[u8]) -> Self {
+        ProxyTarget {
+            address,
+            version,
+            community: community.to_vec(),
+            timeout: Duration::from_millis(1500),
+            retries: 3,
+        }
+    }
+
+    fn forward(
+        &self,
+        session: &mut SyncSession,
+        message_type: &SnmpMessageType,
+        error_status: u32,
+        error_index: u32,
+        varbinds: &[(Vec<u32>, OwnedValue)],
+    ) -> Option<(u32, u32, Varbinds)> {
+        use SnmpMessageType::*;
+        let v1 = self.version == snmp::VERSION_1;
+        let (pdu_type, error_status, error_index) = match *message_type {
+            GetRequest => (snmp::MSG_GET, 0, 0),
+            GetNextRequest => (snmp::MSG_GET_NEXT, 0, 0),
+            GetBulkRequest if v1 => (snmp::MSG_GET_NEXT, 0, 0),
+            GetBulkRequest => (snmp::MSG_GET_BULK, error_status, error_index),
+            SetRequest => (snmp::MSG_SET, 0, 0),
+            _ => return None,
+        };
+        // SNMPv1 has no Counter64
+        if v1
+            && varbinds
+                .iter()
+                .any(|(_, v)| matches!(v, OwnedValue::Counter64(_)))
+        {
+            return None;
+        }
+
+        if !v1 {
+            return self.exchange(session, pdu_type, error_status, error_index, varbinds);
+        }
+
+        // a noSuchName error of an SNMPv1 retrieval marks the varbind it
+        // points at, and the remaining varbinds are requested again
+        let mut values = varbinds.to_vec();
+        let mut pending: Vec<usize> = (0..values.len()).collect();
+        loop {
+            let request: Varbinds = pending.iter().map(|&i| values[i].clone()).collect();
+            let (status, index, response) = self.exchange(session, pdu_type, 0, 0, &request)?;
+            let index = index as usize;
+
+            match status {
+                snmp::ERRSTATUS_NOERROR if response.len() == pending.len() => {
+                    for (&i, varbind) in pending.iter().zip(response) {
+                        values[i] = varbind;
+                    }
+                    return Some((0, 0, values));
+                }
+                snmp::ERRSTATUS_NOSUCHNAME
+                    if pdu_type != snmp::MSG_SET && (1..=pending.len()).contains(&index) =>
+                {
+                    let i = pending.remove(index - 1);
+                    values[i].1 = match pdu_type {
+                        snmp::MSG_GET => OwnedValue::NoSuchObject,
+                        _ => OwnedValue::EndOfMibView,
+                    };
+                    if pending.is_empty() {
+                        return Some((0, 0, values));
+                    }
+                }
+                snmp::ERRSTATUS_TOOBIG => return Some((snmp::ERRSTATUS_TOOBIG, 0, Vec::new())),
+                status => {
+                    let index = match index.checked_sub(1).and_then(|i| pending.get(i)) {
+                        Some(&i) => i as u32 + 1,
+                        None => 0,
+                    };
+                    let status = match pdu_type {
+                        snmp::MSG_SET => v1_set_error_to_v2(status),
+                        _ => snmp::ERRSTATUS_GENERR,
+                    };
+                    return Some((status, index, values));
+                }
+            }
+        }
+    }
+
+    fn exchange(
+        &self,
+        session: &mut SyncSession,
+        pdu_type: u8,
+        error_status: u32,
+        error_index: u32,
+        varbinds: &[(Vec<u32>, OwnedValue)],
+    ) -> Option<(u32, u32, Varbinds)> {
+        for _ in 0..=self.retries {
+            let Ok(resp) =
+                session.request(self.version, pdu_type, error_status, error_index, varbinds)
+            else {
+                continue;
+            };
+            let values = owned_varbinds(resp.varbinds).ok()?;
+            return Some((resp.error_status, resp.error_index, values));
+        }
+        None
+    }
+}
+
+/// Error-status of an SNMPv1 response to a SET as seen by an SNMPv2
+/// manager (RFC 3584 section 4.4).
+fn v1_set_error_to_v2(status: u32) -> u32 {
+    match status {
+        snmp::ERRSTATUS_NOSUCHNAME | snmp::ERRSTATUS_READONLY => snmp::ERRSTATUS_NOTWRITABLE,
+        snmp::ERRSTATUS_BADVALUE => snmp::ERRSTATUS_WRONGVALUE,
+        _ => snmp::ERRSTATUS_GENERR,
+    }
+}
+
+struct Forwarding {
+    proxy_type: ProxyType,
+    context: String,
+    target: String,
+}
+
+/// The proxy configuration of an `Agent`.
+#[derive(Default)]
+pub struct Proxy {
+    communities: Vec<(Vec<u8>, String)>,
+    targets: HashMap<String, ProxyTarget>,
+    // by target name, opened on first use
+    sessions: HashMap<String, SyncSession>,
+    forwardings: Vec<Forwarding>,
+}
+
+impl Proxy {
+    pub fn new() -> Self {
+        Proxy::default()
+    }
+
+    /// Proxies requests carrying `community` for `context`.
+    pub fn add_community(&mut self, community: &[u8], context: &str) {
+        self.communities.retain(|(c, _)| c != community);
+        self.communities
+            .push((community.to_vec(), context.to_string()));
+    }
+
+    pub fn add_target(&mut self, name: &str, target: ProxyTarget) {
+        self.sessions.remove(name);
+        self.targets.insert(name.to_string(), target);
+    }
+
+    /// Forwards requests of `proxy_type` for `context` to the target named
+    /// `target`. The first matching entry wins.
+    pub fn add_forwarding(&mut self, proxy_type: ProxyType, context: &str, target: &str) {
+        self.forwardings.push(Forwarding {
+            proxy_type,
+            context: context.to_string(),
+            target: target.to_string(),
+        });
+    }
+
+    /// The context selected by `community`.
+    pub fn context(&self, community: &[u8]) -> Option<&str> {
+        self.communities
+            .iter()
+            .find(|(c, _)| c == community)
+            .map(|(_, context)| &context[..])
+    }
+
+    /// The name of the target requests of `message_type` for `context` are
+    /// forwarded to, `None` if they are not proxied.
+    pub fn target_for_context(
+        &self,
+        context: &str,
+        message_type: &SnmpMessageType,
+    ) -> Option<&str> {
+        use SnmpMessageType::*;
+        let proxy_type = match *message_type {
+            GetRequest | GetNextRequest | GetBulkRequest => ProxyType::Read,
+            SetRequest => ProxyType::Write,
+            _ => return None,
+        };
+        self.forwardings
+            .iter()
+            .find(|f| f.proxy_type == proxy_type && f.context == context)
+            .map(|f| &f.target[..])
+    }
+
+    /// The target a request carrying `community` is forwarded to, `None` if
+    /// it is not proxied.
+    pub fn target(&self, community: &[u8], message_type: &SnmpMessageType) -> Option<&ProxyTarget> {
+        let name = self.target_for_context(self.context(community)?, message_type)?;
+        self.targets.get(name)
+    }
+
+    /// Forwards a request to the target named `target` and translates the
+    /// response. `None` if the request cannot be translated for the target
+    /// or the target does not answer, in which case the request is dropped.
+    ///
+    /// For GETBULK, `error_status` and `error_index` carry non-repeaters and
+    /// max-repetitions. SNMPv1 targets get a single GETNEXT instead.
+    pub fn forward(
+        &mut self,
+        target: &str,
+        message_type: &SnmpMessageType,
+        error_status: u32,
+        error_index: u32,
+        varbinds: &[(Vec<u32>, OwnedValue)],
+    ) -> Option<(u32, u32, Varbinds)> {
+        let config = self.targets.get(target)?;
+        let session = match self.sessions.entry(target.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(
+                SyncSession::new(config.address, &config.community, Some(config.timeout), 1)
+                    .ok()?,
+            ),
+        };
+        config.forward(session, message_type, error_status, error_index, varbinds)
+    }
+}
@@ -1,10 +1,20 @@
+use std::collections::BTreeMap;
 use std::io;
 use std::net::ToSocketAddrs;
 use std::net::UdpSocket;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use crate::agent::Agent;
+use crate::mib::{MibError, MibTree, NodeKind};
+use crate::notification::{NamedNotification, Notification, TrapV1};
+use crate::notification_log::NotificationLog;
 use crate::pdu;
+use crate::snmp;
+use crate::trap_filter::{Suppressor, Verdict};
+use crate::IntoOid;
+use crate::OwnedValue;
 use crate::SnmpError;
 use crate::SnmpMessageType;
 use crate::SnmpPdu;
@@ -14,15 +24,23 @@ use crate::BUFFER_SIZE;
 
 /// Synchronous SNMPv2 client.
 ///
-///
+/// Requests accept names as anything `IntoOid`, e.g.
+/// `"IF-MIB::ifDescr.1"` once a MIB tree is set with `set_mibs`.
 pub struct SyncSession {
     socket: UdpSocket,
     community: Vec<u8>,
     req_id: i32,
     send_pdu: pdu::Buf,
     recv_buf: [u8; BUFFER_SIZE],
+    mibs: Option<Arc<MibTree>>,
 }
 
+/// Rows of a table by index, each with its values by column.
+pub type Rows = BTreeMap<Vec<u32>, BTreeMap<u32, OwnedValue>>;
+
+/// How many varbinds `walk` asks for per GETBULK request.
+const WALK_REPETITIONS: u32 = 16;
+
 impl SyncSession {
     pub fn new<SA>(
         destination: SA,
@@ -48,9 +66,19 @@ impl SyncSession {
             req_id,
             send_pdu: pdu::Buf::default(),
             recv_buf: [0; 4096],
+            mibs: None,
         })
     }
 
+    /// Sets the MIB tree symbolic names are resolved in.
+    pub fn set_mibs(&mut self, mibs: Option<Arc<MibTree>>) {
+        self.mibs = mibs;
+    }
+
+    pub fn mibs(&self) -> Option<&MibTree> {
+        self.mibs.as_deref()
+    }
+
     fn send_and_recv(socket: &UdpSocket, pdu: &pdu::Buf, out: &mut [u8]) -> SnmpResult<usize> {
         if socket.send(&pdu[..]).is_err() {
             return Err(SnmpError::SendError);
@@ -63,11 +91,12 @@ impl SyncSession {
         Ok(len)
     }
 
-    pub fn get(&mut self, name: &[u32]) -> SnmpResult<SnmpPdu> {
+    pub fn get<N: IntoOid>(&mut self, name: N) -> SnmpResult<SnmpPdu<'_>> {
+        let name = name.into_oid(self.mibs())?;
         pdu::build_get(
             self.community.as_slice(),
             self.req_id,
-            name,
+            &name,
             &mut self.send_pdu,
         );
 
@@ -93,11 +122,12 @@ impl SyncSession {
         Ok(resp)
     }
 
-    pub fn getnext(&mut self, name: &[u32]) -> SnmpResult<SnmpPdu> {
+    pub fn getnext<N: IntoOid>(&mut self, name: N) -> SnmpResult<SnmpPdu<'_>> {
+        let name = name.into_oid(self.mibs())?;
         pdu::build_getnext(
             self.community.as_slice(),
             self.req_id,
-            name,
+            &name,
             &mut self.send_pdu,
         );
 
@@ -123,16 +153,21 @@ impl SyncSession {
         Ok(resp)
     }
 
-    pub fn getbulk(
+    pub fn getbulk<N: IntoOid + Clone>(
         &mut self,
-        names: &[&[u32]],
+        names: &[N],
         non_repeaters: u32,
         max_repetitions: u32,
-    ) -> SnmpResult<SnmpPdu> {
+    ) -> SnmpResult<SnmpPdu<'_>> {
+        let names = names
+            .iter()
+            .map(|name| name.clone().into_oid(self.mibs()))
+            .collect::<SnmpResult<Vec<_>>>()?;
+        let names: Vec<&[u32]> = names.iter().map(|name| &name[..]).collect();
         pdu::build_getbulk(
             self.community.as_slice(),
             self.req_id,
-            names,
+            &names,
             non_repeaters,
             max_repetitions,
             &mut self.send_pdu,
@@ -160,6 +195,66 @@ impl SyncSession {
         Ok(resp)
     }
 
+    /// Retrieves every instance in the subtree `name` with GETBULK
+    /// requests, in OID order. A response with an error-status fails with
+    /// `SnmpError::ErrorStatus`.
+    pub fn walk<N: IntoOid>(&mut self, name: N) -> SnmpResult<Vec<(Vec<u32>, OwnedValue)>> {
+        let root = name.into_oid(self.mibs())?;
+        let mut instances: Vec<(Vec<u32>, OwnedValue)> = Vec::new();
+        loop {
+            let last = instances
+                .last()
+                .map_or(&root[..], |(name, _)| &name[..])
+                .to_vec();
+            let resp = self.getbulk(&[&last], 0, WALK_REPETITIONS)?;
+            if resp.error_status != 0 {
+                return Err(SnmpError::ErrorStatus(resp.error_status, resp.error_index));
+            }
+            let varbinds = resp.owned_varbinds()?;
+            if varbinds.is_empty() {
+                return Ok(instances);
+            }
+            let mut previous = last;
+            for (name, value) in varbinds {
+                // the end of the subtree, or an agent that does not advance
+                if value == OwnedValue::EndOfMibView || !name.starts_with(&root) || name <= previous
+                {
+                    return Ok(instances);
+                }
+                previous.clone_from(&name);
+                instances.push((name, value));
+            }
+        }
+    }
+
+    /// Retrieves the rows of a table, given the table or its row. The
+    /// result of `walk` on the row can also be passed to the
+    /// `from_varbinds` of generated row structs.
+    ///
+    /// Rows are only recognized with a MIB tree that defines them. Without
+    /// one, `name` is taken as the table and `.1` is appended, so it must
+    /// not be the OID of the row.
+    pub fn table<N: IntoOid>(&mut self, name: N) -> SnmpResult<Rows> {
+        let mut entry = name.into_oid(self.mibs())?;
+        let is_row = self
+            .mibs()
+            .and_then(|mibs| mibs.node_by_oid(&entry))
+            .is_some_and(|node| node.kind == NodeKind::Row);
+        if !is_row {
+            entry.push(1);
+        }
+
+        let mut rows = Rows::new();
+        for (name, value) in self.walk(&entry)? {
+            if let Some((&column, index)) = name[entry.len()..].split_first() {
+                rows.entry(index.to_vec())
+                    .or_default()
+                    .insert(column, value);
+            }
+        }
+        Ok(rows)
+    }
+
     /// # Panics if any of the values are not one of these supported types:
     ///   - `Boolean`
     ///   - `Null`
@@ -172,11 +267,20 @@ impl SyncSession {
     ///   - `Timeticks`
     ///   - `Opaque`
     ///   - `Counter64`
-    pub fn set(&mut self, values: &[(&[u32], Value)]) -> SnmpResult<SnmpPdu> {
+    pub fn set<N: IntoOid + Clone>(&mut self, values: &[(N, Value)]) -> SnmpResult<SnmpPdu<'_>> {
+        let names = values
+            .iter()
+            .map(|(name, _)| name.clone().into_oid(self.mibs()))
+            .collect::<SnmpResult<Vec<_>>>()?;
+        let values: Vec<(&[u32], Value)> = names
+            .iter()
+            .zip(values)
+            .map(|(name, (_, value))| (&name[..], value.clone()))
+            .collect();
         pdu::build_set(
             self.community.as_slice(),
             self.req_id,
-            values,
+            &values,
             &mut self.send_pdu,
         );
 
@@ -201,11 +305,78 @@ impl SyncSession {
 
         Ok(resp)
     }
+
+    /// Sets `name` to `value` given as text, e.g. `"down"` for an
+    /// ifAdminStatus or `"00:1a:2b:3c:4d:5e"` for a MacAddress.
+    ///
+    /// The value is parsed according to the object's SYNTAX in the loaded
+    /// MIBs and checked against its constraints and access before anything
+    /// is sent.
+    pub fn set_str<N: IntoOid>(&mut self, name: N, value: &str) -> SnmpResult<SnmpPdu<'_>> {
+        let name = name.into_oid(self.mibs())?;
+        let Some(mibs) = self.mibs() else {
+            let subids: Vec<String> = name.iter().map(|subid| subid.to_string()).collect();
+            return Err(SnmpError::UnknownName(subids.join(".")));
+        };
+        let value = mibs.value_for_set(&name, value).map_err(|err| match err {
+            MibError::UnknownName(name) => SnmpError::UnknownName(name),
+            MibError::NotWritable(name) => SnmpError::NotWritable(name),
+            err => SnmpError::InvalidValue(err.to_string()),
+        })?;
+        self.request(snmp::VERSION_2, snmp::MSG_SET, 0, 0, &[(name, value)])
+    }
+
+    /// Sends a request of any type and version, e.g. one forwarded by a
+    /// proxy. For GETBULK, `error_status` and `error_index` carry
+    /// non-repeaters and max-repetitions.
+    pub fn request(
+        &mut self,
+        version: i64,
+        message_type: u8,
+        error_status: u32,
+        error_index: u32,
+        values: &[(Vec<u32>, OwnedValue)],
+    ) -> SnmpResult<SnmpPdu<'_>> {
+        pdu::build_owned_request(
+            version,
+            self.community.as_slice(),
+            self.req_id,
+            message_type,
+            error_status,
+            error_index,
+            values,
+            &mut self.send_pdu,
+        )?;
+
+        let recv_len = Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf[..])?;
+        let req_id = self.req_id;
+        self.req_id = self.req_id.wrapping_add(1);
+
+        let pdu_bytes = &self.recv_buf[..recv_len];
+        let resp = SnmpPdu::from_bytes_with_version(pdu_bytes, version)?;
+
+        if resp.message_type != SnmpMessageType::Response {
+            return Err(SnmpError::AsnWrongType);
+        }
+
+        if resp.req_id != req_id {
+            return Err(SnmpError::RequestIdMismatch);
+        }
+
+        if resp.community != &self.community[..] {
+            return Err(SnmpError::CommunityMismatch);
+        }
+
+        Ok(resp)
+    }
 }
 
 pub struct SyncTrapSession {
     socket: UdpSocket,
     recv_buf: [u8; BUFFER_SIZE],
+    suppressor: Option<Suppressor>,
+    log: Option<Arc<Mutex<NotificationLog>>>,
+    mibs: Option<Arc<MibTree>>,
 }
 
 impl SyncTrapSession {
@@ -218,9 +389,31 @@ impl SyncTrapSession {
         Ok(SyncTrapSession {
             socket,
             recv_buf: [0; 4096],
+            suppressor: None,
+            log: None,
+            mibs: None,
         })
     }
 
+    /// Installs a suppression stage applied by `recv_notification`.
+    pub fn set_suppressor(&mut self, suppressor: Option<Suppressor>) {
+        self.suppressor = suppressor;
+    }
+
+    pub fn suppressor(&self) -> Option<&Suppressor> {
+        self.suppressor.as_ref()
+    }
+
+    /// Records every notification returned by `recv_notification` in `log`.
+    pub fn set_log(&mut self, log: Option<Arc<Mutex<NotificationLog>>>) {
+        self.log = log;
+    }
+
+    /// Loaded MIBs for `recv_named_notification` to name notifications by.
+    pub fn set_mibs(&mut self, mibs: Option<Arc<MibTree>>) {
+        self.mibs = mibs;
+    }
+
     fn recv(&mut self) -> SnmpResult<usize> {
         let Ok(len) = self.socket.recv(&mut self.recv_buf[..]) else {
             return Err(SnmpError::ReceiveError);
@@ -229,6 +422,14 @@ impl SyncTrapSession {
         Ok(len)
     }
 
+    fn recv_from(&mut self) -> SnmpResult<(usize, SocketAddr)> {
+        let Ok(res) = self.socket.recv_from(&mut self.recv_buf[..]) else {
+            return Err(SnmpError::ReceiveError);
+        };
+
+        Ok(res)
+    }
+
     pub fn recv_trap(&mut self) -> SnmpResult<SnmpPdu> {
         let len = self.recv()?;
         let pdu_bytes = &self.recv_buf[..len];
@@ -240,4 +441,165 @@ impl SyncTrapSession {
 
         Ok(message)
     }
+
+    /// Receives an SNMPv1 or SNMPv2c trap and returns it as an SNMPv2-style
+    /// notification. SNMPv1 traps are translated per RFC 3584.
+    ///
+    /// Notifications rejected by the suppressor are skipped.
+    pub fn recv_notification(&mut self) -> SnmpResult<Notification> {
+        loop {
+            let (len, source) = self.recv_from()?;
+            let mut notification = Notification::from_bytes(&self.recv_buf[..len])?;
+            notification.source = Some(source);
+            notification.local = self.socket.local_addr().ok();
+
+            if let Some(ref mut suppressor) = self.suppressor {
+                if suppressor.check(&notification) != Verdict::Pass {
+                    continue;
+                }
+            }
+
+            if let Some(ref log) = self.log {
+                if let Ok(mut log) = log.lock() {
+                    log.log(notification.clone());
+                }
+            }
+
+            return Ok(notification);
+        }
+    }
+
+    /// Receives a notification as `recv_notification` and names it with the
+    /// MIBs set with `set_mibs`, e.g. `IF-MIB::linkDown` with its OBJECTS by
+    /// name. The name is `None` for notifications the MIBs do not define.
+    pub fn recv_named_notification(
+        &mut self,
+    ) -> SnmpResult<(Notification, Option<NamedNotification>)> {
+        let notification = self.recv_notification()?;
+        let named = self
+            .mibs
+            .as_deref()
+            .and_then(|mibs| notification.named(mibs));
+        Ok((notification, named))
+    }
+}
+
+/// Sends notifications to a single manager.
+pub struct SyncTrapSender {
+    socket: UdpSocket,
+    community: Vec<u8>,
+    req_id: i32,
+    send_pdu: pdu::Buf,
+}
+
+impl SyncTrapSender {
+    pub fn new<SA>(destination: SA, community: &[u8], req_id: i32) -> io::Result<Self>
+    where
+        SA: ToSocketAddrs,
+    {
+        let socket = match destination.to_socket_addrs()?.next() {
+            Some(SocketAddr::V4(_)) => UdpSocket::bind((Ipv4Addr::new(0, 0, 0, 0), 0))?,
+            Some(SocketAddr::V6(_)) => UdpSocket::bind((Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0), 0))?,
+            None => panic!("empty list of socket addrs"),
+        };
+
+        socket.connect(destination)?;
+
+        Ok(SyncTrapSender {
+            socket,
+            community: community.to_vec(),
+            req_id,
+            send_pdu: pdu::Buf::default(),
+        })
+    }
+
+    fn send(&mut self) -> SnmpResult<()> {
+        if self.socket.send(&self.send_pdu[..]).is_err() {
+            return Err(SnmpError::SendError);
+        }
+
+        Ok(())
+    }
+
+    /// Sends an SNMPv2-Trap-PDU. `community` overrides the sender's
+    /// community string.
+    pub fn send_trap(
+        &mut self,
+        notification: &Notification,
+        community: Option<&[u8]>,
+    ) -> SnmpResult<()> {
+        let community = community.unwrap_or(&self.community[..]);
+        notification.build_trap(community, self.req_id, &mut self.send_pdu)?;
+        self.req_id = self.req_id.wrapping_add(1);
+
+        self.send()
+    }
+
+    /// Sends an SNMPv1 Trap-PDU. `community` overrides the sender's
+    /// community string.
+    pub fn send_trap_v1(&mut self, trap: &TrapV1, community: Option<&[u8]>) -> SnmpResult<()> {
+        let community = community.unwrap_or(&self.community[..]);
+        trap.build_trap(community, &mut self.send_pdu)?;
+
+        self.send()
+    }
+}
+
+/// Serves an `Agent` over UDP.
+pub struct SyncAgent {
+    socket: UdpSocket,
+    agent: Agent,
+    send_pdu: pdu::Buf,
+    recv_buf: [u8; BUFFER_SIZE],
+}
+
+impl SyncAgent {
+    pub fn new<SA>(addr: SA, agent: Agent) -> io::Result<Self>
+    where
+        SA: ToSocketAddrs,
+    {
+        let socket = UdpSocket::bind(addr)?;
+
+        Ok(SyncAgent {
+            socket,
+            agent,
+            send_pdu: pdu::Buf::default(),
+            recv_buf: [0; 4096],
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub fn agent(&mut self) -> &mut Agent {
+        &mut self.agent
+    }
+
+    /// Receives and answers a single request.
+    pub fn serve_one(&mut self) -> SnmpResult<()> {
+        let Ok((len, source)) = self.socket.recv_from(&mut self.recv_buf[..]) else {
+            return Err(SnmpError::ReceiveError);
+        };
+
+        if self
+            .agent
+            .process(&self.recv_buf[..len], &mut self.send_pdu)
+            && self.socket.send_to(&self.send_pdu[..], source).is_err()
+        {
+            return Err(SnmpError::SendError);
+        }
+
+        Ok(())
+    }
+
+    /// Answers requests until receiving fails.
+    pub fn serve(&mut self) -> SnmpResult<()> {
+        loop {
+            match self.serve_one() {
+                Err(SnmpError::ReceiveError) => return Err(SnmpError::ReceiveError),
+                _ => continue,
+            }
+        }
+    }
 }
@@ -0,0 +1,665 @@
+/// Generated from IF-MIB.
+#[allow(dead_code, unused_imports, clippy::all)]
+pub mod if_mib {
+    use crate::agent::TableHandler;
+    use crate::mib::index;
+    use crate::OwnedValue;
+
+    /// IF-MIB::interfaces
+    pub const INTERFACES: &[u32] = &[1, 3, 6, 1, 2, 1, 2];
+
+    /// IF-MIB::ifNumber
+    pub const IF_NUMBER: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 1];
+    pub const IF_NUMBER_0: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 1, 0];
+
+    /// IF-MIB::ifTable
+    pub const IF_TABLE: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2];
+
+    /// IF-MIB::ifEntry
+    pub const IF_ENTRY: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1];
+
+    /// IF-MIB::ifIndex
+    pub const IF_INDEX: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 1];
+
+    /// IF-MIB::ifDescr
+    pub const IF_DESCR: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 2];
+
+    /// IF-MIB::ifPhysAddress
+    pub const IF_PHYS_ADDRESS: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 6];
+
+    /// IF-MIB::ifAdminStatus
+    pub const IF_ADMIN_STATUS: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 7];
+
+    /// IF-MIB::ifInOctets
+    pub const IF_IN_OCTETS: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 10];
+
+    /// IF-MIB::ifMIB
+    pub const IF_MIB: &[u32] = &[1, 3, 6, 1, 2, 1, 31];
+
+    /// IF-MIB::ifMIBObjects
+    pub const IF_MIB_OBJECTS: &[u32] = &[1, 3, 6, 1, 2, 1, 31, 1];
+
+    /// IF-MIB::ifXTable
+    pub const IF_X_TABLE: &[u32] = &[1, 3, 6, 1, 2, 1, 31, 1, 1];
+
+    /// IF-MIB::ifXEntry
+    pub const IF_X_ENTRY: &[u32] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1];
+
+    /// IF-MIB::ifName
+    pub const IF_NAME: &[u32] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 1];
+
+    /// IF-MIB::ifHCInOctets
+    pub const IF_HC_IN_OCTETS: &[u32] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6];
+
+    /// IF-MIB::ifPromiscuousMode
+    pub const IF_PROMISCUOUS_MODE: &[u32] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 16];
+
+    /// IF-MIB::snmpTraps
+    pub const SNMP_TRAPS: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 5];
+
+    /// IF-MIB::linkDown
+    pub const LINK_DOWN: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 5, 3];
+
+    /// The values of IF-MIB::ifAdminStatus.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[repr(i64)]
+    pub enum IfAdminStatus {
+        Up = 1,
+        Down = 2,
+        Testing = 3,
+    }
+
+    impl IfAdminStatus {
+        pub fn from_i64(value: i64) -> Option<Self> {
+            match value {
+                1 => Some(IfAdminStatus::Up),
+                2 => Some(IfAdminStatus::Down),
+                3 => Some(IfAdminStatus::Testing),
+                _ => None,
+            }
+        }
+
+        /// The label of the value in the MIB.
+        pub fn label(self) -> &'static str {
+            match self {
+                IfAdminStatus::Up => "up",
+                IfAdminStatus::Down => "down",
+                IfAdminStatus::Testing => "testing",
+            }
+        }
+    }
+
+    impl From<IfAdminStatus> for OwnedValue {
+        fn from(value: IfAdminStatus) -> OwnedValue {
+            OwnedValue::Integer(value as i64)
+        }
+    }
+
+    impl TryFrom<&OwnedValue> for IfAdminStatus {
+        type Error = crate::SnmpError;
+        fn try_from(value: &OwnedValue) -> Result<Self, Self::Error> {
+            match *value {
+                OwnedValue::Integer(v) => IfAdminStatus::from_i64(v).ok_or(crate::SnmpError::ValueOutOfRange),
+                _ => Err(crate::SnmpError::AsnWrongType),
+            }
+        }
+    }
+
+    /// The values of IF-MIB::ifPromiscuousMode.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[repr(i64)]
+    pub enum IfPromiscuousMode {
+        True = 1,
+        False = 2,
+    }
+
+    impl IfPromiscuousMode {
+        pub fn from_i64(value: i64) -> Option<Self> {
+            match value {
+                1 => Some(IfPromiscuousMode::True),
+                2 => Some(IfPromiscuousMode::False),
+                _ => None,
+            }
+        }
+
+        /// The label of the value in the MIB.
+        pub fn label(self) -> &'static str {
+            match self {
+                IfPromiscuousMode::True => "true",
+                IfPromiscuousMode::False => "false",
+            }
+        }
+    }
+
+    impl From<IfPromiscuousMode> for OwnedValue {
+        fn from(value: IfPromiscuousMode) -> OwnedValue {
+            OwnedValue::Integer(value as i64)
+        }
+    }
+
+    impl TryFrom<&OwnedValue> for IfPromiscuousMode {
+        type Error = crate::SnmpError;
+        fn try_from(value: &OwnedValue) -> Result<Self, Self::Error> {
+            match *value {
+                OwnedValue::Integer(v) => IfPromiscuousMode::from_i64(v).ok_or(crate::SnmpError::ValueOutOfRange),
+                _ => Err(crate::SnmpError::AsnWrongType),
+            }
+        }
+    }
+
+    /// A row of IF-MIB::ifEntry.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct IfEntry {
+        pub if_index: i64,
+        pub if_descr: Option<String>,
+        pub if_phys_address: Option<Vec<u8>>,
+        pub if_admin_status: Option<IfAdminStatus>,
+        pub if_in_octets: Option<u32>,
+    }
+
+    impl IfEntry {
+        pub const ENTRY: &'static [u32] = IF_ENTRY;
+        /// The accessible columns.
+        pub const COLUMNS: &'static [u32] = &[1, 2, 6, 7, 10];
+        pub const WRITABLE: &'static [u32] = &[7];
+
+        pub fn new(if_index: i64) -> Self {
+            IfEntry {
+                if_index,
+                if_descr: None,
+                if_phys_address: None,
+                if_admin_status: None,
+                if_in_octets: None,
+            }
+        }
+
+        /// The row with the given index and no column values.
+        pub fn from_index(index: &[u32]) -> Option<Self> {
+            let mut rest = index;
+            let if_index = index::decode_integer(&mut rest)?;
+            if !rest.is_empty() {
+                return None;
+            }
+            Some(IfEntry::new(if_index))
+        }
+
+        pub fn index(&self) -> Vec<u32> {
+            let mut index = Vec::new();
+            index::encode_integer(&mut index, self.if_index);
+            index
+        }
+
+        /// Sets the field of a column from its value. Returns whether the
+        /// column is known and the value of the right type.
+        pub fn set_column(&mut self, column: u32, value: &OwnedValue) -> bool {
+            match column {
+                2 => {
+                    self.if_descr = match value { OwnedValue::OctetString(v) => Some(String::from_utf8_lossy(v).into_owned()), _ => None };
+                    self.if_descr.is_some()
+                }
+                6 => {
+                    self.if_phys_address = match value { OwnedValue::OctetString(v) => Some(v.clone()), _ => None };
+                    self.if_phys_address.is_some()
+                }
+                7 => {
+                    self.if_admin_status = match value { OwnedValue::Integer(v) => IfAdminStatus::from_i64(*v), _ => None };
+                    self.if_admin_status.is_some()
+                }
+                10 => {
+                    self.if_in_octets = match value { OwnedValue::Counter32(v) => Some(*v), _ => None };
+                    self.if_in_octets.is_some()
+                }
+                _ => false,
+            }
+        }
+
+        /// The values of the accessible columns that are set, as
+        /// `TableHandler::insert_row` takes them.
+        pub fn values(&self) -> Vec<(u32, OwnedValue)> {
+            let mut values = Vec::new();
+            values.push((1, OwnedValue::Integer(*(&self.if_index))));
+            if let Some(ref v) = self.if_descr {
+                values.push((2, OwnedValue::OctetString(v.as_bytes().to_vec())));
+            }
+            if let Some(ref v) = self.if_phys_address {
+                values.push((6, OwnedValue::OctetString(v.clone())));
+            }
+            if let Some(ref v) = self.if_admin_status {
+                values.push((7, OwnedValue::Integer(*v as i64)));
+            }
+            if let Some(ref v) = self.if_in_octets {
+                values.push((10, OwnedValue::Counter32(*v)));
+            }
+            values
+        }
+
+        /// Collects rows, in index order, from instance varbinds such as
+        /// those of a walk. Varbinds of other objects are ignored.
+        pub fn from_varbinds<I>(varbinds: I) -> Vec<Self>
+        where
+            I: IntoIterator<Item = (Vec<u32>, OwnedValue)>,
+        {
+            let mut rows = std::collections::BTreeMap::new();
+            for (oid, value) in varbinds {
+                let Some(rest) = oid.strip_prefix(Self::ENTRY) else {
+                    continue;
+                };
+                let Some((&column, index)) = rest.split_first() else {
+                    continue;
+                };
+                if !rows.contains_key(index) {
+                    match Self::from_index(index) {
+                        Some(row) => rows.insert(index.to_vec(), row),
+                        None => continue,
+                    };
+                }
+                rows.get_mut(index).unwrap().set_column(column, &value);
+            }
+            rows.into_values().collect()
+        }
+
+        /// An empty table of these rows, to register at `ENTRY`.
+        pub fn table_handler() -> TableHandler {
+            let mut table = TableHandler::new(Self::ENTRY, Self::COLUMNS);
+            table.set_writable(Self::WRITABLE);
+            table
+        }
+
+        /// Adds or replaces the row in `table`.
+        pub fn insert_into(&self, table: &mut TableHandler) {
+            table.insert_row(&self.index(), self.values());
+        }
+    }
+
+    /// A row of IF-MIB::ifXEntry.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct IfXEntry {
+        pub if_index: i64,
+        pub if_name: Option<String>,
+        pub if_hc_in_octets: Option<u64>,
+        pub if_promiscuous_mode: Option<IfPromiscuousMode>,
+    }
+
+    impl IfXEntry {
+        pub const ENTRY: &'static [u32] = IF_X_ENTRY;
+        /// The accessible columns.
+        pub const COLUMNS: &'static [u32] = &[1, 6, 16];
+        pub const WRITABLE: &'static [u32] = &[16];
+
+        pub fn new(if_index: i64) -> Self {
+            IfXEntry {
+                if_index,
+                if_name: None,
+                if_hc_in_octets: None,
+                if_promiscuous_mode: None,
+            }
+        }
+
+        /// The row with the given index and no column values.
+        pub fn from_index(index: &[u32]) -> Option<Self> {
+            let mut rest = index;
+            let if_index = index::decode_integer(&mut rest)?;
+            if !rest.is_empty() {
+                return None;
+            }
+            Some(IfXEntry::new(if_index))
+        }
+
+        pub fn index(&self) -> Vec<u32> {
+            let mut index = Vec::new();
+            index::encode_integer(&mut index, self.if_index);
+            index
+        }
+
+        /// Sets the field of a column from its value. Returns whether the
+        /// column is known and the value of the right type.
+        pub fn set_column(&mut self, column: u32, value: &OwnedValue) -> bool {
+            match column {
+                1 => {
+                    self.if_name = match value { OwnedValue::OctetString(v) => Some(String::from_utf8_lossy(v).into_owned()), _ => None };
+                    self.if_name.is_some()
+                }
+                6 => {
+                    self.if_hc_in_octets = match value { OwnedValue::Counter64(v) => Some(*v), _ => None };
+                    self.if_hc_in_octets.is_some()
+                }
+                16 => {
+                    self.if_promiscuous_mode = match value { OwnedValue::Integer(v) => IfPromiscuousMode::from_i64(*v), _ => None };
+                    self.if_promiscuous_mode.is_some()
+                }
+                _ => false,
+            }
+        }
+
+        /// The values of the accessible columns that are set, as
+        /// `TableHandler::insert_row` takes them.
+        pub fn values(&self) -> Vec<(u32, OwnedValue)> {
+            let mut values = Vec::new();
+            if let Some(ref v) = self.if_name {
+                values.push((1, OwnedValue::OctetString(v.as_bytes().to_vec())));
+            }
+            if let Some(ref v) = self.if_hc_in_octets {
+                values.push((6, OwnedValue::Counter64(*v)));
+            }
+            if let Some(ref v) = self.if_promiscuous_mode {
+                values.push((16, OwnedValue::Integer(*v as i64)));
+            }
+            values
+        }
+
+        /// Collects rows, in index order, from instance varbinds such as
+        /// those of a walk. Varbinds of other objects are ignored.
+        pub fn from_varbinds<I>(varbinds: I) -> Vec<Self>
+        where
+            I: IntoIterator<Item = (Vec<u32>, OwnedValue)>,
+        {
+            let mut rows = std::collections::BTreeMap::new();
+            for (oid, value) in varbinds {
+                let Some(rest) = oid.strip_prefix(Self::ENTRY) else {
+                    continue;
+                };
+                let Some((&column, index)) = rest.split_first() else {
+                    continue;
+                };
+                if !rows.contains_key(index) {
+                    match Self::from_index(index) {
+                        Some(row) => rows.insert(index.to_vec(), row),
+                        None => continue,
+                    };
+                }
+                rows.get_mut(index).unwrap().set_column(column, &value);
+            }
+            rows.into_values().collect()
+        }
+
+        /// An empty table of these rows, to register at `ENTRY`.
+        pub fn table_handler() -> TableHandler {
+            let mut table = TableHandler::new(Self::ENTRY, Self::COLUMNS);
+            table.set_writable(Self::WRITABLE);
+            table
+        }
+
+        /// Adds or replaces the row in `table`.
+        pub fn insert_into(&self, table: &mut TableHandler) {
+            table.insert_row(&self.index(), self.values());
+        }
+    }
+}
+/// Generated from ACME-PEER-MIB.
+#[allow(dead_code, unused_imports, clippy::all)]
+pub mod acme_peer_mib {
+    use crate::agent::TableHandler;
+    use crate::mib::index;
+    use crate::OwnedValue;
+
+    /// ACME-PEER-MIB::peers
+    pub const PEERS: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9];
+
+    /// ACME-PEER-MIB::peerTable
+    pub const PEER_TABLE: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9, 1];
+
+    /// ACME-PEER-MIB::peerEntry
+    pub const PEER_ENTRY: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9, 1, 1];
+
+    /// ACME-PEER-MIB::peerAddress
+    pub const PEER_ADDRESS: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9, 1, 1, 1];
+
+    /// ACME-PEER-MIB::peerName
+    pub const PEER_NAME: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9, 1, 1, 2];
+
+    /// ACME-PEER-MIB::peerState
+    pub const PEER_STATE: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9, 1, 1, 3];
+
+    /// ACME-PEER-MIB::peerRouteTable
+    pub const PEER_ROUTE_TABLE: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9, 2];
+
+    /// ACME-PEER-MIB::peerRouteEntry
+    pub const PEER_ROUTE_ENTRY: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9, 2, 1];
+
+    /// ACME-PEER-MIB::peerRouteCount
+    pub const PEER_ROUTE_COUNT: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9, 2, 1, 1];
+
+    /// The values of ACME-PEER-MIB::peerState.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[repr(i64)]
+    pub enum PeerState {
+        Idle = 1,
+        Established = 2,
+    }
+
+    impl PeerState {
+        pub fn from_i64(value: i64) -> Option<Self> {
+            match value {
+                1 => Some(PeerState::Idle),
+                2 => Some(PeerState::Established),
+                _ => None,
+            }
+        }
+
+        /// The label of the value in the MIB.
+        pub fn label(self) -> &'static str {
+            match self {
+                PeerState::Idle => "idle",
+                PeerState::Established => "established",
+            }
+        }
+    }
+
+    impl From<PeerState> for OwnedValue {
+        fn from(value: PeerState) -> OwnedValue {
+            OwnedValue::Integer(value as i64)
+        }
+    }
+
+    impl TryFrom<&OwnedValue> for PeerState {
+        type Error = crate::SnmpError;
+        fn try_from(value: &OwnedValue) -> Result<Self, Self::Error> {
+            match *value {
+                OwnedValue::Integer(v) => PeerState::from_i64(v).ok_or(crate::SnmpError::ValueOutOfRange),
+                _ => Err(crate::SnmpError::AsnWrongType),
+            }
+        }
+    }
+
+    /// A row of ACME-PEER-MIB::peerEntry.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct PeerEntry {
+        pub peer_address: [u8; 4],
+        pub peer_name: Vec<u8>,
+        pub peer_state: Option<PeerState>,
+    }
+
+    impl PeerEntry {
+        pub const ENTRY: &'static [u32] = PEER_ENTRY;
+        /// The accessible columns.
+        pub const COLUMNS: &'static [u32] = &[1, 2, 3];
+        pub const WRITABLE: &'static [u32] = &[3];
+
+        pub fn new(peer_address: [u8; 4], peer_name: Vec<u8>) -> Self {
+            PeerEntry {
+                peer_address,
+                peer_name,
+                peer_state: None,
+            }
+        }
+
+        /// The row with the given index and no column values.
+        pub fn from_index(index: &[u32]) -> Option<Self> {
+            let mut rest = index;
+            let peer_address = index::decode_ip_address(&mut rest)?;
+            let peer_name = index::decode_octets(&mut rest, None, true)?;
+            if !rest.is_empty() {
+                return None;
+            }
+            Some(PeerEntry::new(peer_address, peer_name))
+        }
+
+        pub fn index(&self) -> Vec<u32> {
+            let mut index = Vec::new();
+            index::encode_ip_address(&mut index, self.peer_address);
+            index::encode_octets(&mut index, &self.peer_name, false, true);
+            index
+        }
+
+        /// Sets the field of a column from its value. Returns whether the
+        /// column is known and the value of the right type.
+        pub fn set_column(&mut self, column: u32, value: &OwnedValue) -> bool {
+            match column {
+                3 => {
+                    self.peer_state = match value { OwnedValue::Integer(v) => PeerState::from_i64(*v), _ => None };
+                    self.peer_state.is_some()
+                }
+                _ => false,
+            }
+        }
+
+        /// The values of the accessible columns that are set, as
+        /// `TableHandler::insert_row` takes them.
+        pub fn values(&self) -> Vec<(u32, OwnedValue)> {
+            let mut values = Vec::new();
+            values.push((1, OwnedValue::IpAddress(*(&self.peer_address))));
+            values.push((2, OwnedValue::OctetString((&self.peer_name).clone())));
+            if let Some(ref v) = self.peer_state {
+                values.push((3, OwnedValue::Integer(*v as i64)));
+            }
+            values
+        }
+
+        /// Collects rows, in index order, from instance varbinds such as
+        /// those of a walk. Varbinds of other objects are ignored.
+        pub fn from_varbinds<I>(varbinds: I) -> Vec<Self>
+        where
+            I: IntoIterator<Item = (Vec<u32>, OwnedValue)>,
+        {
+            let mut rows = std::collections::BTreeMap::new();
+            for (oid, value) in varbinds {
+                let Some(rest) = oid.strip_prefix(Self::ENTRY) else {
+                    continue;
+                };
+                let Some((&column, index)) = rest.split_first() else {
+                    continue;
+                };
+                if !rows.contains_key(index) {
+                    match Self::from_index(index) {
+                        Some(row) => rows.insert(index.to_vec(), row),
+                        None => continue,
+                    };
+                }
+                rows.get_mut(index).unwrap().set_column(column, &value);
+            }
+            rows.into_values().collect()
+        }
+
+        /// An empty table of these rows, to register at `ENTRY`.
+        pub fn table_handler() -> TableHandler {
+            let mut table = TableHandler::new(Self::ENTRY, Self::COLUMNS);
+            table.set_writable(Self::WRITABLE);
+            table
+        }
+
+        /// Adds or replaces the row in `table`.
+        pub fn insert_into(&self, table: &mut TableHandler) {
+            table.insert_row(&self.index(), self.values());
+        }
+    }
+
+    /// A row of ACME-PEER-MIB::peerRouteEntry.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct PeerRouteEntry {
+        pub peer_address: [u8; 4],
+        pub index_2: i64,
+        pub peer_route_count: Option<u32>,
+    }
+
+    impl PeerRouteEntry {
+        pub const ENTRY: &'static [u32] = PEER_ROUTE_ENTRY;
+        /// The accessible columns.
+        pub const COLUMNS: &'static [u32] = &[1];
+        pub const WRITABLE: &'static [u32] = &[];
+
+        pub fn new(peer_address: [u8; 4], index_2: i64) -> Self {
+            PeerRouteEntry {
+                peer_address,
+                index_2,
+                peer_route_count: None,
+            }
+        }
+
+        /// The row with the given index and no column values.
+        pub fn from_index(index: &[u32]) -> Option<Self> {
+            let mut rest = index;
+            let peer_address = index::decode_ip_address(&mut rest)?;
+            let index_2 = index::decode_integer(&mut rest)?;
+            if !rest.is_empty() {
+                return None;
+            }
+            Some(PeerRouteEntry::new(peer_address, index_2))
+        }
+
+        pub fn index(&self) -> Vec<u32> {
+            let mut index = Vec::new();
+            index::encode_ip_address(&mut index, self.peer_address);
+            index::encode_integer(&mut index, self.index_2);
+            index
+        }
+
+        /// Sets the field of a column from its value. Returns whether the
+        /// column is known and the value of the right type.
+        pub fn set_column(&mut self, column: u32, value: &OwnedValue) -> bool {
+            match column {
+                1 => {
+                    self.peer_route_count = match value { OwnedValue::Counter32(v) => Some(*v), _ => None };
+                    self.peer_route_count.is_some()
+                }
+                _ => false,
+            }
+        }
+
+        /// The values of the accessible columns that are set, as
+        /// `TableHandler::insert_row` takes them.
+        pub fn values(&self) -> Vec<(u32, OwnedValue)> {
+            let mut values = Vec::new();
+            if let Some(ref v) = self.peer_route_count {
+                values.push((1, OwnedValue::Counter32(*v)));
+            }
+            values
+        }
+
+        /// Collects rows, in index order, from instance varbinds such as
+        /// those of a walk. Varbinds of other objects are ignored.
+        pub fn from_varbinds<I>(varbinds: I) -> Vec<Self>
+        where
+            I: IntoIterator<Item = (Vec<u32>, OwnedValue)>,
+        {
+            let mut rows = std::collections::BTreeMap::new();
+            for (oid, value) in varbinds {
+                let Some(rest) = oid.strip_prefix(Self::ENTRY) else {
+                    continue;
+                };
+                let Some((&column, index)) = rest.split_first() else {
+                    continue;
+                };
+                if !rows.contains_key(index) {
+                    match Self::from_index(index) {
+                        Some(row) => rows.insert(index.to_vec(), row),
+                        None => continue,
+                    };
+                }
+                rows.get_mut(index).unwrap().set_column(column, &value);
+            }
+            rows.into_values().collect()
+        }
+
+        /// An empty table of these rows, to register at `ENTRY`.
+        pub fn table_handler() -> TableHandler {
+            let mut table = TableHandler::new(Self::ENTRY, Self::COLUMNS);
+            table.set_writable(Self::WRITABLE);
+            table
+        }
+
+        /// Adds or replaces the row in `table`.
+        pub fn insert_into(&self, table: &mut TableHandler) {
+            table.insert_row(&self.index(), self.values());
+        }
+    }
+}
@@ -77,3 +77,3401 @@ fn asn_parse_getnext_pdu() {
         })
         .unwrap();
 }
+
+#[test]
+fn v1_trap_to_notification() {
+    use super::notification::{self, Notification};
+    use super::OwnedValue;
+
+    let enterprise = [1, 3, 6, 1, 4, 1, 9999];
+    let mut buf = pdu::Buf::default();
+    pdu::build_trap_v1(
+        b"public",
+        &enterprise,
+        &[192, 0, 2, 1],
+        2,
+        0,
+        4242,
+        &[(
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 3],
+            OwnedValue::Integer(3),
+        )],
+        &mut buf,
+    )
+    .unwrap();
+
+    let n = Notification::from_bytes(&buf[..]).unwrap();
+    assert_eq!(n.version, snmp::VERSION_1);
+    assert_eq!(n.uptime, 4242);
+    assert_eq!(n.trap_oid, [1, 3, 6, 1, 6, 3, 1, 1, 5, 3]);
+    assert_eq!(n.varbinds.len(), 4);
+    assert_eq!(
+        n.varbind(notification::SNMP_TRAP_ADDRESS),
+        Some(&OwnedValue::IpAddress([192, 0, 2, 1]))
+    );
+    assert_eq!(
+        n.varbind(notification::SNMP_TRAP_COMMUNITY),
+        Some(&OwnedValue::OctetString(b"public".to_vec()))
+    );
+    assert_eq!(
+        n.varbind(notification::SNMP_TRAP_ENTERPRISE),
+        Some(&OwnedValue::ObjectIdentifier(enterprise.to_vec()))
+    );
+
+    let v1 = n.to_v1();
+    assert_eq!(v1.enterprise, enterprise);
+    assert_eq!(v1.agent_addr, [192, 0, 2, 1]);
+    assert_eq!((v1.generic_trap, v1.specific_trap), (2, 0));
+}
+
+#[test]
+fn enterprise_specific_trap_round_trip() {
+    use super::notification::Notification;
+    use super::OwnedValue;
+
+    let mut buf = pdu::Buf::default();
+    pdu::build_trap_v1(
+        b"public",
+        &[1, 3, 6, 1, 4, 1, 9999, 1],
+        &[10, 0, 0, 1],
+        6,
+        17,
+        1,
+        &[],
+        &mut buf,
+    )
+    .unwrap();
+    let n = Notification::from_bytes(&buf[..]).unwrap();
+    assert_eq!(n.trap_oid, [1, 3, 6, 1, 4, 1, 9999, 1, 0, 17]);
+
+    let mut v2 = n.clone();
+    v2.varbinds
+        .push((vec![1, 3, 6, 1, 4, 1, 9999, 2], OwnedValue::Counter64(1)));
+    v2.build_trap(b"public", 7, &mut buf).unwrap();
+    let decoded = Notification::from_bytes(&buf[..]).unwrap();
+    assert_eq!(decoded.trap_oid, n.trap_oid);
+    assert_eq!(decoded.varbinds, v2.varbinds);
+
+    let v1 = decoded.to_v1();
+    assert_eq!(v1.enterprise, [1, 3, 6, 1, 4, 1, 9999, 1]);
+    assert_eq!((v1.generic_trap, v1.specific_trap), (6, 17));
+    assert!(v1
+        .varbinds
+        .iter()
+        .all(|(_, v)| !matches!(v, OwnedValue::Counter64(_))));
+
+    pdu::build_trap_v1(
+        b"public",
+        &[1, 3, 6, 1, 4, 1, 9999, 1],
+        &[10, 0, 0, 1],
+        6,
+        -1,
+        1,
+        &[],
+        &mut buf,
+    )
+    .unwrap();
+    assert_eq!(
+        Notification::from_bytes(&buf[..]),
+        Err(super::SnmpError::ValueOutOfRange)
+    );
+}
+
+#[tokio::test]
+async fn relay_rewrites_and_converts() {
+    use super::notification::{Notification, SNMP_TRAP_ADDRESS};
+    use super::trap_relay::{RelayTarget, RelayVersion, TrapRelay};
+    use super::{OwnedValue, SnmpTrapV1Pdu};
+    use tokio::net::UdpSocket;
+
+    let v2_target = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let v1_target = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+    let mut v2 = RelayTarget::new(v2_target.local_addr().unwrap());
+    v2.community = Some(b"downstream".to_vec());
+    v2.add_trap_address = true;
+    let mut v1 = RelayTarget::new(v1_target.local_addr().unwrap());
+    v1.version = RelayVersion::V1;
+
+    let relay = TrapRelay::new(vec![v2, v1]).await.unwrap();
+    relay.forward(Notification {
+        version: snmp::VERSION_2,
+        community: b"public".to_vec(),
+        source: Some("192.0.2.7:162".parse().unwrap()),
+        local: None,
+        uptime: 100,
+        trap_oid: vec![1, 3, 6, 1, 6, 3, 1, 1, 5, 4],
+        varbinds: vec![],
+    });
+
+    let mut buf = [0u8; 1500];
+    let len = v2_target.recv(&mut buf).await.unwrap();
+    let n = Notification::from_bytes(&buf[..len]).unwrap();
+    assert_eq!(n.community, b"downstream");
+    assert_eq!(
+        n.varbind(SNMP_TRAP_ADDRESS),
+        Some(&OwnedValue::IpAddress([192, 0, 2, 7]))
+    );
+
+    let len = v1_target.recv(&mut buf).await.unwrap();
+    let trap = SnmpTrapV1Pdu::from_bytes(&buf[..len]).unwrap();
+    assert_eq!(trap.community, b"public");
+    assert_eq!(trap.generic_trap, 3);
+    assert_eq!(trap.agent_addr, [192, 0, 2, 7]);
+
+    // an OID that cannot be encoded is dropped without stopping delivery
+    let mut bad = Notification {
+        version: snmp::VERSION_2,
+        community: b"public".to_vec(),
+        source: None,
+        local: None,
+        uptime: 101,
+        trap_oid: vec![1, 3, 6, 1, 6, 3, 1, 1, 5, 3],
+        varbinds: vec![(vec![3, 0, 1], OwnedValue::Integer(1))],
+    };
+    relay.forward(bad.clone());
+    bad.varbinds = vec![(
+        vec![1, 3, 6, 1, 2, 1, 1, 2, 0],
+        OwnedValue::ObjectIdentifier(vec![2]),
+    )];
+    relay.forward(bad.clone());
+    bad.varbinds.clear();
+    relay.forward(bad);
+    let len = v2_target.recv(&mut buf).await.unwrap();
+    assert_eq!(Notification::from_bytes(&buf[..len]).unwrap().uptime, 101);
+    for (_, stats) in relay.stats() {
+        assert_eq!(stats.dropped.load(std::sync::atomic::Ordering::Relaxed), 2);
+    }
+
+    // and is refused on receipt: an SNMPv1 trap from enterprise 3.0.1
+    let mut out = super::pdu::Buf::default();
+    super::pdu::build_trap_v1(b"public", &[1, 3, 1], &[0; 4], 0, 0, 0, &[], &mut out).unwrap();
+    let mut bytes = out[..].to_vec();
+    let at = bytes.windows(4).position(|w| w == [6, 2, 0x2b, 1]).unwrap();
+    bytes[at + 2] = 0x78;
+    assert_eq!(
+        Notification::from_bytes(&bytes).err(),
+        Some(super::SnmpError::AsnParseError)
+    );
+}
+
+#[test]
+fn suppressor_dedup_and_rate_limit() {
+    use super::notification::Notification;
+    use super::trap_filter::{SuppressionConfig, Suppressor, Verdict};
+    use super::OwnedValue;
+    use std::time::{Duration, Instant};
+
+    const IF_INDEX: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 1];
+    let link_down = |if_index: i64, source: &str| Notification {
+        version: snmp::VERSION_2,
+        community: b"public".to_vec(),
+        source: Some(source.parse().unwrap()),
+        local: None,
+        uptime: 0,
+        trap_oid: vec![1, 3, 6, 1, 6, 3, 1, 1, 5, 3],
+        varbinds: vec![(
+            [IF_INDEX, &[if_index as u32]].concat(),
+            OwnedValue::Integer(if_index),
+        )],
+    };
+
+    let mut s = Suppressor::new(SuppressionConfig {
+        dedup_window: Some(Duration::from_secs(5)),
+        dedup_varbinds: vec![IF_INDEX.to_vec()],
+        rate_limit: Some(1.0),
+        burst: 2,
+    });
+    let t0 = Instant::now();
+
+    assert_eq!(
+        s.check_at(&link_down(1, "192.0.2.1:162"), t0),
+        Verdict::Pass
+    );
+    assert_eq!(
+        s.check_at(&link_down(1, "192.0.2.1:162"), t0),
+        Verdict::Duplicate
+    );
+    assert_eq!(
+        s.check_at(&link_down(2, "192.0.2.1:162"), t0),
+        Verdict::Pass
+    );
+    assert_eq!(
+        s.check_at(&link_down(3, "192.0.2.1:162"), t0),
+        Verdict::RateLimited
+    );
+    assert_eq!(
+        s.check_at(&link_down(3, "192.0.2.2:162"), t0),
+        Verdict::Pass
+    );
+    // the rate-limited trap was not delivered, so its retransmission is not
+    // a duplicate
+    assert_eq!(
+        s.check_at(&link_down(3, "192.0.2.1:162"), t0 + Duration::from_secs(1)),
+        Verdict::Pass
+    );
+
+    let t1 = t0 + Duration::from_secs(6);
+    assert_eq!(
+        s.check_at(&link_down(1, "192.0.2.1:162"), t1),
+        Verdict::Pass
+    );
+
+    let stats = s.stats();
+    assert_eq!(
+        (stats.passed, stats.duplicates, stats.rate_limited),
+        (5, 1, 1)
+    );
+    assert_eq!(stats.suppressed(), 2);
+}
+
+#[test]
+fn notification_log_bounds_queries_and_reloads() {
+    use super::notification::Notification;
+    use super::notification_log::{LogQuery, NotificationLog};
+    use super::OwnedValue;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let trap = |source: &str, last: u32| Notification {
+        version: snmp::VERSION_2,
+        community: b"public".to_vec(),
+        source: Some(source.parse().unwrap()),
+        local: None,
+        uptime: 5,
+        trap_oid: vec![1, 3, 6, 1, 6, 3, 1, 1, 5, last],
+        varbinds: vec![(
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 1],
+            OwnedValue::Integer(1),
+        )],
+    };
+    let t = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+
+    let mut log = NotificationLog::new(2);
+    log.log_at(trap("192.0.2.1:162", 3), t(10));
+    log.log_at(trap("192.0.2.1:162", 4), t(20));
+    log.log_at(trap("192.0.2.1:162", 3), t(30));
+    log.log_at(trap("[2001:db8::1]:162", 1), t(40));
+
+    assert_eq!(log.len(), 3);
+    assert_eq!(log.stats().bumped, 1);
+
+    let source = "192.0.2.1".parse().unwrap();
+    let last: Vec<u32> = log.last(source, 1).iter().map(|e| e.index).collect();
+    assert_eq!(last, [3]);
+
+    let link_down = log.query(&LogQuery {
+        trap_oid: Some(vec![1, 3, 6, 1, 6, 3, 1, 1, 5, 3]),
+        ..LogQuery::default()
+    });
+    assert_eq!(link_down.len(), 1);
+
+    let window = log.query(&LogQuery {
+        since: Some(t(20)),
+        until: Some(t(40)),
+        ..LogQuery::default()
+    });
+    assert_eq!(window.len(), 2);
+
+    let path = std::env::temp_dir().join(format!("snmp-nlog-{}", std::process::id()));
+    log.save(&path).unwrap();
+    let reloaded = NotificationLog::load(&path, 2).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        reloaded.query(&LogQuery::default()),
+        log.query(&LogQuery::default())
+    );
+
+    // indices continue after the highest saved, whatever the receive times
+    let mut log = NotificationLog::new(2);
+    log.log_at(trap("192.0.2.1:162", 3), t(50));
+    log.log_at(trap("192.0.2.1:162", 4), t(10));
+    log.save(&path).unwrap();
+    let mut reloaded = NotificationLog::load(&path, 2).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    reloaded.log_at(trap("192.0.2.2:162", 3), t(60));
+    let last: Vec<u32> = reloaded
+        .last("192.0.2.2".parse().unwrap(), 1)
+        .iter()
+        .map(|e| e.index)
+        .collect();
+    assert_eq!(last, [3]);
+
+    // files from before local addresses were recorded
+    let mut buf = pdu::Buf::default();
+    let n = trap("192.0.2.1:162", 3);
+    n.build_trap(b"public", 0, &mut buf).unwrap();
+    let mut v1 = b"SNMPNLG1".to_vec();
+    v1.extend(7u32.to_be_bytes());
+    v1.extend(10u64.to_be_bytes());
+    v1.extend(0u32.to_be_bytes());
+    v1.extend([snmp::VERSION_2 as u8, 13]);
+    v1.extend(b"192.0.2.1:162");
+    v1.extend((buf.len() as u32).to_be_bytes());
+    v1.extend(&buf[..]);
+    std::fs::write(&path, v1).unwrap();
+    let reloaded = NotificationLog::load(&path, 2).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let entries = reloaded.query(&LogQuery::default());
+    assert_eq!(entries.len(), 1);
+    assert_eq!((entries[0].index, entries[0].received), (7, t(10)));
+    assert_eq!(entries[0].notification.source, n.source);
+    assert_eq!(entries[0].notification.local, None);
+
+    // a corrupt PDU length is refused before anything is allocated for it
+    let mut corrupt = b"SNMPNLG1".to_vec();
+    corrupt.extend(7u32.to_be_bytes());
+    corrupt.extend(10u64.to_be_bytes());
+    corrupt.extend(0u32.to_be_bytes());
+    corrupt.extend([snmp::VERSION_2 as u8, 0]);
+    corrupt.extend(u32::MAX.to_be_bytes());
+    std::fs::write(&path, corrupt).unwrap();
+    let err = NotificationLog::load(&path, 2).err().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[tokio::test]
+async fn trap_listener_merges_sockets() {
+    use super::async_session::{AsyncTrapListener, AsyncTrapSender};
+    use super::notification::Notification;
+
+    let mut listener = AsyncTrapListener::new(["127.0.0.1:0", "127.0.0.1:0"])
+        .await
+        .unwrap();
+    let locals = listener.local_addrs().to_vec();
+    assert_eq!(locals.len(), 2);
+
+    let notification = Notification {
+        version: snmp::VERSION_2,
+        community: b"public".to_vec(),
+        source: None,
+        local: None,
+        uptime: 1,
+        trap_oid: vec![1, 3, 6, 1, 6, 3, 1, 1, 5, 1],
+        varbinds: vec![],
+    };
+
+    for local in &locals {
+        let mut sender = AsyncTrapSender::new(local, b"public", 0).await.unwrap();
+        sender.send_trap(&notification, None).await.unwrap();
+        let received = listener.recv_notification().await.unwrap();
+        assert_eq!(received.local, Some(*local));
+        assert_eq!(received.trap_oid, notification.trap_oid);
+    }
+}
+
+/// Serves a fixed, sorted list of instances.
+struct FixedHandler(Vec<(Vec<u32>, super::OwnedValue)>);
+
+impl super::agent::MibHandler for FixedHandler {
+    fn get(&mut self, oid: &[u32]) -> super::OwnedValue {
+        match self.0.iter().find(|(n, _)| &n[..] == oid) {
+            Some((_, v)) => v.clone(),
+            None => super::OwnedValue::NoSuchInstance,
+        }
+    }
+
+    fn get_next(&mut self, oid: &[u32]) -> Option<(Vec<u32>, super::OwnedValue)> {
+        self.0.iter().find(|(n, _)| &n[..] > oid).cloned()
+    }
+}
+
+fn response_varbinds(bytes: &[u8]) -> Vec<(Vec<u32>, super::OwnedValue)> {
+    let resp = super::SnmpPdu::from_bytes(bytes).unwrap();
+    assert_eq!(resp.message_type, super::SnmpMessageType::Response);
+    let mut buf = [0; 128];
+    resp.varbinds
+        .map(|(n, v)| {
+            (
+                n.read_name(&mut buf).unwrap().to_vec(),
+                super::OwnedValue::from_value(&v).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn agent_get_getnext_getbulk() {
+    use super::agent::Agent;
+    use super::OwnedValue;
+
+    let mut agent = Agent::new(b"public");
+    agent.register(
+        &[1, 3, 6, 1, 2, 1, 1],
+        FixedHandler(vec![
+            (
+                vec![1, 3, 6, 1, 2, 1, 1, 1, 0],
+                OwnedValue::OctetString(b"box".to_vec()),
+            ),
+            (vec![1, 3, 6, 1, 2, 1, 1, 3, 0], OwnedValue::Timeticks(42)),
+        ]),
+    );
+    agent.register(
+        &[1, 3, 6, 1, 2, 1, 2],
+        FixedHandler(vec![(
+            vec![1, 3, 6, 1, 2, 1, 2, 1, 0],
+            OwnedValue::Integer(2),
+        )]),
+    );
+    // end the MIB view after the interfaces handler
+    agent.registry().unregister(super::agent::SNMP);
+
+    let mut req = pdu::Buf::default();
+    let mut resp = pdu::Buf::default();
+
+    pdu::build_get(b"public", 1, &[1, 3, 6, 1, 2, 1, 1, 2, 0], &mut req);
+    assert!(agent.process(&req[..], &mut resp));
+    assert_eq!(
+        response_varbinds(&resp[..])[0].1,
+        OwnedValue::NoSuchInstance
+    );
+
+    pdu::build_get(b"public", 1, &[1, 3, 6, 1, 4, 1], &mut req);
+    assert!(agent.process(&req[..], &mut resp));
+    assert_eq!(response_varbinds(&resp[..])[0].1, OwnedValue::NoSuchObject);
+
+    pdu::build_get(b"private", 1, &[1, 3, 6, 1, 2, 1, 1, 1, 0], &mut req);
+    assert!(!agent.process(&req[..], &mut resp));
+
+    // crosses from the system handler into the interfaces handler
+    pdu::build_getnext(b"public", 2, &[1, 3, 6, 1, 2, 1, 1, 3, 0], &mut req);
+    assert!(agent.process(&req[..], &mut resp));
+    assert_eq!(
+        response_varbinds(&resp[..]),
+        [(vec![1, 3, 6, 1, 2, 1, 2, 1, 0], OwnedValue::Integer(2))]
+    );
+
+    pdu::build_getbulk(
+        b"public",
+        3,
+        &[&[1, 3, 6, 1, 2, 1, 1, 1, 0], &[1, 3, 6, 1, 2, 1, 1]],
+        1,
+        5,
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    let values: Vec<_> = response_varbinds(&resp[..])
+        .into_iter()
+        .map(|(_, v)| v)
+        .collect();
+    assert_eq!(
+        values,
+        [
+            OwnedValue::Timeticks(42),
+            OwnedValue::OctetString(b"box".to_vec()),
+            OwnedValue::Timeticks(42),
+            OwnedValue::Integer(2),
+            OwnedValue::EndOfMibView,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn async_agent_answers_session() {
+    use super::agent::Agent;
+    use super::async_session::{AsyncAgent, AsyncSession};
+    use super::OwnedValue;
+    use std::time::Duration;
+
+    let mut agent = Agent::new(b"public");
+    agent.register(
+        &[1, 3, 6, 1, 2, 1, 1],
+        FixedHandler(vec![(
+            vec![1, 3, 6, 1, 2, 1, 1, 5, 0],
+            OwnedValue::OctetString(b"r1".to_vec()),
+        )]),
+    );
+    let mut agent = AsyncAgent::new("127.0.0.1:0", agent).await.unwrap();
+    let addr = agent.local_addr().unwrap();
+    tokio::spawn(async move { agent.serve().await });
+
+    let mut session = AsyncSession::new(addr, b"public", Duration::from_secs(2), 0)
+        .await
+        .unwrap();
+    let resp = session.get(&[1, 3, 6, 1, 2, 1, 1, 5, 0]).await.unwrap();
+    let (_, value) = resp.varbinds.clone().next().unwrap();
+    assert_eq!(
+        OwnedValue::from_value(&value),
+        Some(OwnedValue::OctetString(b"r1".to_vec()))
+    );
+}
+
+#[test]
+fn registry_overlap_and_helpers() {
+    use super::agent::{MibRegistry, ScalarHandler, TableHandler};
+    use super::OwnedValue;
+
+    const MIB2: &[u32] = &[1, 3, 6, 1, 2, 1];
+    const IF_ENTRY: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1];
+
+    let mut registry = MibRegistry::new();
+    // a catch-all that also claims instances below ifEntry
+    registry.register(
+        MIB2,
+        Box::new(FixedHandler(vec![
+            (
+                vec![1, 3, 6, 1, 2, 1, 1, 5, 0],
+                OwnedValue::OctetString(b"r1".to_vec()),
+            ),
+            (
+                vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 1],
+                OwnedValue::OctetString(b"stale".to_vec()),
+            ),
+            (
+                vec![1, 3, 6, 1, 2, 1, 2, 2, 1, u32::MAX, 1],
+                OwnedValue::Integer(0),
+            ),
+            // right after ifEntry's subtree
+            (vec![1, 3, 6, 1, 2, 1, 2, 2, 2], OwnedValue::Integer(0)),
+            (vec![1, 3, 6, 1, 2, 1, 4, 1, 0], OwnedValue::Integer(1)),
+        ])),
+    );
+
+    let mut if_table = TableHandler::new(IF_ENTRY, &[1, 2]);
+    if_table.insert_row(
+        &[1],
+        vec![
+            (1, OwnedValue::Integer(1)),
+            (2, OwnedValue::OctetString(b"eth0".to_vec())),
+        ],
+    );
+    if_table.insert_row(
+        &[2],
+        vec![
+            (1, OwnedValue::Integer(2)),
+            (2, OwnedValue::OctetString(b"eth1".to_vec())),
+        ],
+    );
+    if_table.set_writable(&[2]);
+    registry.register(IF_ENTRY, Box::new(if_table));
+    registry.register(
+        &[1, 3, 6, 1, 2, 1, 2, 1],
+        Box::new(ScalarHandler::constant(
+            &[1, 3, 6, 1, 2, 1, 2, 1],
+            OwnedValue::Integer(2),
+        )),
+    );
+
+    assert_eq!(
+        registry.get(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 1]),
+        OwnedValue::OctetString(b"eth0".to_vec())
+    );
+    assert_eq!(
+        registry.get(&[1, 3, 6, 1, 2, 1, 2, 1, 1]),
+        OwnedValue::NoSuchInstance
+    );
+
+    let mut walk = Vec::new();
+    let mut oid = MIB2.to_vec();
+    while let Some((next, _)) = registry.get_next(&oid) {
+        walk.push(next.clone());
+        oid = next;
+    }
+    assert_eq!(
+        walk,
+        [
+            vec![1, 3, 6, 1, 2, 1, 1, 5, 0],
+            vec![1, 3, 6, 1, 2, 1, 2, 1, 0],
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 1],
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 2],
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 1],
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 2],
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 2],
+            vec![1, 3, 6, 1, 2, 1, 4, 1, 0],
+        ]
+    );
+
+    assert_eq!(
+        registry.set(&[(
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 2],
+            OwnedValue::OctetString(b"wan".to_vec())
+        )]),
+        Ok(())
+    );
+    assert_eq!(
+        registry.get(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 2]),
+        OwnedValue::OctetString(b"wan".to_vec())
+    );
+    assert_eq!(
+        registry.set(&[(
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 2],
+            OwnedValue::Integer(3)
+        )]),
+        Err((snmp::ERRSTATUS_NOTWRITABLE, 1))
+    );
+    assert_eq!(
+        registry.set(&[(
+            vec![1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 9],
+            OwnedValue::OctetString(vec![])
+        )]),
+        Err((snmp::ERRSTATUS_NOCREATION, 1))
+    );
+}
+
+#[test]
+fn agent_set_is_atomic() {
+    use super::agent::{Agent, ScalarHandler, TableHandler};
+    use super::{OwnedValue, SnmpPdu, Value};
+    use std::sync::{Arc, Mutex};
+
+    const IF_ALIAS: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 1];
+    const CONTACT: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 4, 0];
+
+    let mut if_table = TableHandler::new(&[1, 3, 6, 1, 2, 1, 2, 2, 1], &[1, 2]);
+    if_table.insert_row(
+        &[1],
+        vec![
+            (1, OwnedValue::Integer(1)),
+            (2, OwnedValue::OctetString(b"eth0".to_vec())),
+        ],
+    );
+    if_table.set_writable(&[2]);
+
+    // refuses to commit an empty contact
+    let contact = Arc::new(Mutex::new(OwnedValue::OctetString(b"noc".to_vec())));
+    let (get, set) = (contact.clone(), contact.clone());
+    let sys_contact = ScalarHandler::read_write(
+        &[1, 3, 6, 1, 2, 1, 1, 4],
+        move || get.lock().unwrap().clone(),
+        move |value| {
+            if *value == OwnedValue::OctetString(vec![]) {
+                return Err(snmp::ERRSTATUS_GENERR);
+            }
+            *set.lock().unwrap() = value.clone();
+            Ok(())
+        },
+    );
+
+    let mut agent = Agent::new(b"private");
+    agent.register(&[1, 3, 6, 1, 2, 1, 2, 2, 1], if_table);
+    agent.register(&[1, 3, 6, 1, 2, 1, 1, 4], sys_contact);
+
+    let mut req = pdu::Buf::default();
+    let mut resp = pdu::Buf::default();
+
+    // the second commit fails, so the first one is undone
+    pdu::build_set(
+        b"private",
+        1,
+        &[
+            (IF_ALIAS, Value::OctetString(b"uplink")),
+            (CONTACT, Value::OctetString(b"")),
+        ],
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    let pdu = SnmpPdu::from_bytes(&resp[..]).unwrap();
+    assert_eq!(pdu.error_status, snmp::ERRSTATUS_COMMITFAILED);
+    assert_eq!(pdu.error_index, 2);
+    assert_eq!(
+        agent.get(IF_ALIAS),
+        OwnedValue::OctetString(b"eth0".to_vec())
+    );
+
+    // a failed test leaves everything untouched
+    pdu::build_set(
+        b"private",
+        2,
+        &[
+            (CONTACT, Value::OctetString(b"ops")),
+            (IF_ALIAS, Value::Integer(7)),
+        ],
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    let pdu = SnmpPdu::from_bytes(&resp[..]).unwrap();
+    assert_eq!(pdu.error_status, snmp::ERRSTATUS_WRONGTYPE);
+    assert_eq!(pdu.error_index, 2);
+    assert_eq!(
+        *contact.lock().unwrap(),
+        OwnedValue::OctetString(b"noc".to_vec())
+    );
+
+    // a scalar named twice is restored to its value from before the request
+    pdu::build_set(
+        b"private",
+        2,
+        &[
+            (CONTACT, Value::OctetString(b"ops")),
+            (CONTACT, Value::OctetString(b"dev")),
+            (CONTACT, Value::OctetString(b"")),
+        ],
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    let pdu = SnmpPdu::from_bytes(&resp[..]).unwrap();
+    assert_eq!(
+        (pdu.error_status, pdu.error_index),
+        (snmp::ERRSTATUS_COMMITFAILED, 3)
+    );
+    assert_eq!(
+        *contact.lock().unwrap(),
+        OwnedValue::OctetString(b"noc".to_vec())
+    );
+
+    pdu::build_set(
+        b"private",
+        3,
+        &[
+            (CONTACT, Value::OctetString(b"ops")),
+            (IF_ALIAS, Value::OctetString(b"uplink")),
+        ],
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    let pdu = SnmpPdu::from_bytes(&resp[..]).unwrap();
+    assert_eq!(pdu.error_status, 0);
+    assert_eq!(
+        response_varbinds(&resp[..])[1].1,
+        OwnedValue::OctetString(b"uplink".to_vec())
+    );
+    assert_eq!(
+        agent.get(IF_ALIAS),
+        OwnedValue::OctetString(b"uplink".to_vec())
+    );
+    assert_eq!(agent.get(CONTACT), OwnedValue::OctetString(b"ops".to_vec()));
+}
+
+#[test]
+fn response_with_error_and_too_big() {
+    use super::agent::{Agent, ScalarHandler};
+    use super::{OwnedValue, SnmpPdu, Value};
+
+    const SYS_DESCR: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 1, 0];
+    let descr = [b'x'; 600];
+    let mut buf = pdu::Buf::default();
+
+    assert!(pdu::build_response_with_error(
+        b"public",
+        7,
+        snmp::ERRSTATUS_NOTWRITABLE,
+        1,
+        &[(SYS_DESCR, Value::OctetString(b"box"))],
+        484,
+        &mut buf,
+    )
+    .unwrap());
+    let resp = SnmpPdu::from_bytes(&buf[..]).unwrap();
+    assert_eq!(resp.req_id, 7);
+    assert_eq!(resp.error_status, snmp::ERRSTATUS_NOTWRITABLE);
+    assert_eq!(resp.error_index, 1);
+    assert_eq!(resp.varbinds.count(), 1);
+
+    assert!(!pdu::build_response_with_error(
+        b"public",
+        8,
+        0,
+        0,
+        &[(SYS_DESCR, Value::OctetString(&descr))],
+        484,
+        &mut buf,
+    )
+    .unwrap());
+    let resp = SnmpPdu::from_bytes(&buf[..]).unwrap();
+    assert_eq!(resp.req_id, 8);
+    assert_eq!(resp.error_status, snmp::ERRSTATUS_TOOBIG);
+    assert_eq!(resp.error_index, 0);
+    assert_eq!(resp.varbinds.count(), 0);
+
+    let mut agent = Agent::new(b"public");
+    agent.register(
+        &[1, 3, 6, 1, 2, 1, 1, 1],
+        ScalarHandler::constant(
+            &[1, 3, 6, 1, 2, 1, 1, 1],
+            OwnedValue::OctetString(descr.to_vec()),
+        ),
+    );
+    let mut req = pdu::Buf::default();
+
+    pdu::build_get(b"public", 9, SYS_DESCR, &mut req);
+    assert!(agent.process(&req[..], &mut buf));
+    assert_eq!(
+        SnmpPdu::from_bytes(&buf[..]).unwrap().error_status,
+        snmp::ERRSTATUS_NOERROR
+    );
+
+    agent.set_max_message_size(484);
+    assert!(agent.process(&req[..], &mut buf));
+    let resp = SnmpPdu::from_bytes(&buf[..]).unwrap();
+    assert_eq!(resp.error_status, snmp::ERRSTATUS_TOOBIG);
+    assert_eq!(resp.varbinds.count(), 0);
+
+    // responses are sized by their encoding, so small varbinds fill the
+    // message up to the limit
+    const COUNTERS: &[u32] = &[1, 3, 6, 1, 4, 1, 99, 2];
+    let mut agent = Agent::new(b"public");
+    agent.set_max_message_size(484);
+    let mut names = Vec::new();
+    for i in 1..=100 {
+        let mut base = COUNTERS.to_vec();
+        base.push(i);
+        agent.register(
+            &base,
+            ScalarHandler::constant(&base, OwnedValue::Counter32(i * 1000)),
+        );
+        base.push(0);
+        names.push((base, OwnedValue::Null));
+    }
+
+    pdu::build_getbulk(b"public", 10, &[COUNTERS], 0, 100, &mut req);
+    assert!(agent.process(&req[..], &mut buf));
+    assert!(buf.len() <= 484 && buf.len() > 484 - 17);
+    let resp = SnmpPdu::from_bytes(&buf[..]).unwrap();
+    assert_eq!(resp.error_status, snmp::ERRSTATUS_NOERROR);
+    assert_eq!(resp.varbinds.count(), 26);
+
+    pdu::build_owned_request(
+        snmp::VERSION_2,
+        b"public",
+        11,
+        snmp::MSG_GET,
+        0,
+        0,
+        &names[..26],
+        &mut req,
+    )
+    .unwrap();
+    assert!(agent.process(&req[..], &mut buf));
+    let resp = SnmpPdu::from_bytes(&buf[..]).unwrap();
+    assert_eq!(resp.error_status, snmp::ERRSTATUS_NOERROR);
+    assert_eq!(resp.varbinds.count(), 26);
+
+    pdu::build_owned_request(
+        snmp::VERSION_2,
+        b"public",
+        12,
+        snmp::MSG_GET,
+        0,
+        0,
+        &names[..27],
+        &mut req,
+    )
+    .unwrap();
+    assert!(agent.process(&req[..], &mut buf));
+    assert_eq!(
+        SnmpPdu::from_bytes(&buf[..]).unwrap().error_status,
+        snmp::ERRSTATUS_TOOBIG
+    );
+}
+
+#[test]
+fn row_status_table_state_machine() {
+    use super::agent::{Column, MibRegistry, RowStatus, RowStatusTable};
+    use super::OwnedValue;
+
+    // a table with a mandatory target address, an optional port and the
+    // RowStatus in column 9
+    const ENTRY: &[u32] = &[1, 3, 6, 1, 4, 1, 99, 1, 1];
+    fn oid(column: u32, index: u32) -> Vec<u32> {
+        let mut oid = ENTRY.to_vec();
+        oid.extend([column, index]);
+        oid
+    }
+    fn status(s: RowStatus) -> OwnedValue {
+        s.to_value()
+    }
+
+    let mut registry = MibRegistry::new();
+    registry.register(
+        ENTRY,
+        Box::new(RowStatusTable::new(
+            ENTRY,
+            9,
+            vec![
+                Column::new(2, None),
+                Column::new(3, Some(OwnedValue::Integer(162))),
+            ],
+        )),
+    );
+    let addr = OwnedValue::OctetString(b"10.0.0.1".to_vec());
+
+    // createAndGo needs every mandatory column in the same request
+    assert_eq!(
+        registry.set(&[(oid(9, 1), status(RowStatus::CreateAndGo))]),
+        Err((snmp::ERRSTATUS_INCONSISTENTVALUE, 1))
+    );
+    assert_eq!(
+        registry.set(&[
+            (oid(9, 1), status(RowStatus::CreateAndGo)),
+            (oid(2, 1), addr.clone()),
+        ]),
+        Ok(())
+    );
+    assert_eq!(registry.get(&oid(9, 1)), status(RowStatus::Active));
+    assert_eq!(registry.get(&oid(3, 1)), OwnedValue::Integer(162));
+    assert_eq!(
+        registry.set(&[(oid(9, 1), status(RowStatus::CreateAndWait))]),
+        Err((snmp::ERRSTATUS_INCONSISTENTVALUE, 1))
+    );
+
+    // createAndWait, then fill in the row column by column
+    assert_eq!(
+        registry.set(&[(oid(9, 2), status(RowStatus::CreateAndWait))]),
+        Ok(())
+    );
+    assert_eq!(registry.get(&oid(9, 2)), status(RowStatus::NotReady));
+    assert_eq!(
+        registry.set(&[(oid(9, 2), status(RowStatus::Active))]),
+        Err((snmp::ERRSTATUS_INCONSISTENTVALUE, 1))
+    );
+    assert_eq!(registry.set(&[(oid(2, 2), addr.clone())]), Ok(()));
+    assert_eq!(registry.get(&oid(9, 2)), status(RowStatus::NotInService));
+    assert_eq!(
+        registry.set(&[(oid(9, 2), status(RowStatus::Active))]),
+        Ok(())
+    );
+
+    // values are type checked, unknown rows cannot be written without a
+    // create request and notReady cannot be written at all
+    assert_eq!(
+        registry.set(&[(oid(3, 2), OwnedValue::OctetString(vec![]))]),
+        Err((snmp::ERRSTATUS_WRONGTYPE, 1))
+    );
+    assert_eq!(
+        registry.set(&[(oid(2, 3), addr.clone())]),
+        Err((snmp::ERRSTATUS_NOCREATION, 1))
+    );
+    assert_eq!(
+        registry.set(&[(oid(9, 3), status(RowStatus::Active))]),
+        Err((snmp::ERRSTATUS_INCONSISTENTVALUE, 1))
+    );
+    assert_eq!(
+        registry.set(&[(oid(9, 2), status(RowStatus::NotReady))]),
+        Err((snmp::ERRSTATUS_WRONGVALUE, 1))
+    );
+
+    // a failed request creates no rows
+    assert_eq!(
+        registry.set(&[
+            (oid(9, 3), status(RowStatus::CreateAndGo)),
+            (oid(2, 3), addr.clone()),
+            (oid(9, 1), status(RowStatus::CreateAndGo)),
+        ]),
+        Err((snmp::ERRSTATUS_INCONSISTENTVALUE, 3))
+    );
+    assert_eq!(registry.get(&oid(9, 3)), OwnedValue::NoSuchInstance);
+
+    assert_eq!(
+        registry.set(&[(oid(9, 1), status(RowStatus::Destroy))]),
+        Ok(())
+    );
+    assert_eq!(registry.get(&oid(2, 1)), OwnedValue::NoSuchInstance);
+    assert_eq!(
+        registry.get_next(ENTRY).map(|(oid, _)| oid),
+        Some(oid(2, 2))
+    );
+}
+
+#[test]
+fn agent_system_and_snmp_groups() {
+    use super::agent::Agent;
+    use super::{OwnedValue, SnmpPdu, Value};
+
+    const SYS_CONTACT: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 4, 0];
+    const SYS_OR_DESCR_1: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 9, 1, 3, 1];
+    const SNMP_IN_PKTS: &[u32] = &[1, 3, 6, 1, 2, 1, 11, 1, 0];
+
+    let mut agent = Agent::new(b"public");
+    // the sysORTable columns exist before any entry is added
+    assert_eq!(agent.get(SYS_OR_DESCR_1), OwnedValue::NoSuchInstance);
+    assert_eq!(
+        agent.get(&[1, 3, 6, 1, 2, 1, 1, 9, 1, 5, 1]),
+        OwnedValue::NoSuchObject
+    );
+    agent.system().name = "r1".to_string();
+    agent
+        .system()
+        .add_or_entry(&[1, 3, 6, 1, 6, 3, 1], "SNMPv2-MIB");
+
+    assert_eq!(
+        agent.get(&[1, 3, 6, 1, 2, 1, 1, 5, 0]),
+        OwnedValue::OctetString(b"r1".to_vec())
+    );
+    assert_eq!(
+        agent.get(&[1, 3, 6, 1, 2, 1, 1, 7, 0]),
+        OwnedValue::Integer(72)
+    );
+    assert_eq!(
+        agent.get(SYS_OR_DESCR_1),
+        OwnedValue::OctetString(b"SNMPv2-MIB".to_vec())
+    );
+    assert_eq!(
+        agent.get(&[1, 3, 6, 1, 2, 1, 1, 9, 1, 3, 2]),
+        OwnedValue::NoSuchInstance
+    );
+
+    let mut req = pdu::Buf::default();
+    let mut resp = pdu::Buf::default();
+
+    pdu::build_set(
+        b"public",
+        1,
+        &[(SYS_CONTACT, Value::OctetString(b"noc@example.com"))],
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    assert_eq!(agent.system().contact, "noc@example.com");
+
+    pdu::build_set(
+        b"public",
+        2,
+        &[(&[1, 3, 6, 1, 2, 1, 1, 1, 0], Value::OctetString(b"x"))],
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    assert_eq!(
+        SnmpPdu::from_bytes(&resp[..]).unwrap().error_status,
+        snmp::ERRSTATUS_NOTWRITABLE
+    );
+
+    pdu::build_get(b"private", 3, SNMP_IN_PKTS, &mut req);
+    assert!(!agent.process(&req[..], &mut resp));
+    assert!(!agent.process(b"\x30\x03\x02\x01", &mut resp));
+
+    pdu::build_get(b"public", 4, SNMP_IN_PKTS, &mut req);
+    assert!(agent.process(&req[..], &mut resp));
+    assert_eq!(response_varbinds(&resp[..])[0].1, OwnedValue::Counter32(5));
+
+    let stats = agent.stats();
+    let load = |c: &std::sync::atomic::AtomicU32| c.load(std::sync::atomic::Ordering::Relaxed);
+    assert_eq!(load(&stats.in_bad_community_names), 1);
+    assert_eq!(load(&stats.in_asn_parse_errs), 1);
+    assert_eq!(load(&stats.in_set_requests), 2);
+    assert_eq!(load(&stats.in_total_set_vars), 1);
+    assert_eq!(load(&stats.in_get_requests), 1);
+    assert_eq!(load(&stats.in_total_req_vars), 1);
+    assert_eq!(load(&stats.out_get_responses), 3);
+}
+
+#[test]
+fn agent_rejects_unencodable_oids() {
+    use super::agent::Agent;
+    use super::{OwnedValue, SnmpPdu};
+
+    let mut agent = Agent::new(b"public");
+    agent.register(
+        &[1, 3, 6, 1, 4, 1, 99],
+        FixedHandler(vec![(
+            vec![1, 3, 6, 1, 4, 1, 99, 1, 0],
+            OwnedValue::ObjectIdentifier(vec![3, 0]),
+        )]),
+    );
+    let mut req = pdu::Buf::default();
+    let mut resp = pdu::Buf::default();
+
+    // 06 02 78 01 decodes to 3.0.1, and 06 03 81 34 01 (2.100.1) to 3.9.52.1
+    pdu::build_get(b"public", 1, &[1, 3, 1], &mut req);
+    let mut bytes = req[..].to_vec();
+    let at = bytes.windows(4).position(|w| w == [6, 2, 0x2b, 1]).unwrap();
+    bytes[at + 2] = 0x78;
+    assert!(!agent.process(&bytes, &mut resp));
+    pdu::build_get(b"public", 2, &[1, 3, 1, 1], &mut req);
+    let mut bytes = req[..].to_vec();
+    let at = bytes
+        .windows(5)
+        .position(|w| w == [6, 3, 0x2b, 1, 1])
+        .unwrap();
+    bytes[at + 2..at + 4].copy_from_slice(&[0x81, 0x34]);
+    assert!(!agent.process(&bytes, &mut resp));
+    let stats = agent.stats();
+    assert_eq!(
+        stats
+            .in_asn_parse_errs
+            .load(std::sync::atomic::Ordering::Relaxed),
+        2
+    );
+
+    // a value that cannot be encoded fails the request instead of the agent
+    pdu::build_get(b"public", 3, &[1, 3, 6, 1, 4, 1, 99, 1, 0], &mut req);
+    assert!(agent.process(&req[..], &mut resp));
+    let resp = SnmpPdu::from_bytes(&resp[..]).unwrap();
+    assert_eq!(
+        (resp.error_status, resp.error_index),
+        (snmp::ERRSTATUS_GENERR, 1)
+    );
+
+    // 2.100 itself is valid, with a multi-octet first subidentifier
+    pdu::build_owned_request(
+        snmp::VERSION_2,
+        b"public",
+        4,
+        snmp::MSG_GET,
+        0,
+        0,
+        &[(vec![2, 100, 1], OwnedValue::Null)],
+        &mut req,
+    )
+    .unwrap();
+    assert!(req[..].windows(5).any(|w| w == [6, 3, 0x81, 0x34, 1]));
+    let mut buf = pdu::Buf::default();
+    assert!(pdu::build_owned_request(
+        snmp::VERSION_2,
+        b"public",
+        5,
+        snmp::MSG_GET,
+        0,
+        0,
+        &[(vec![1, 40], OwnedValue::Null)],
+        &mut buf,
+    )
+    .is_err());
+}
+
+#[test]
+fn vacm_views_and_agent_enforcement() {
+    use super::agent::{Agent, SecurityLevel, SecurityModel, Vacm, VacmError, ViewType};
+    use super::{OwnedValue, SnmpPdu, Value};
+
+    const SYS_DESCR: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 1, 0];
+    const SYS_CONTACT: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 4, 0];
+    const SNMP_IN_PKTS: &[u32] = &[1, 3, 6, 1, 2, 1, 11, 1, 0];
+
+    let vacm = Vacm::parse(
+        r#"
+        # tenant A sees the system group only and may change sysContact
+        com2sec tenantA default secretA
+        com2sec nobody  default guest
+        group   tenants v2c     tenantA
+        group   guests  v2c     nobody
+        view    system  included .1.3.6.1.2.1.1
+        view    contact included .1.3.6.1.2.1.1.4
+        view    ifRow1  included .1.3.6.1.2.1.2.2.1.0.1 ff:a0
+        access  tenants ""      any   noauth exact system contact none
+        access  guests  ""      any   auth   exact system none    none
+        "#,
+    )
+    .unwrap();
+
+    let allowed = |name, oid| {
+        vacm.is_access_allowed(
+            SecurityModel::V2c,
+            name,
+            SecurityLevel::NoAuthNoPriv,
+            ViewType::Read,
+            "",
+            oid,
+        )
+    };
+    assert_eq!(allowed("tenantA", SYS_DESCR), Ok(()));
+    assert_eq!(allowed("tenantA", SNMP_IN_PKTS), Err(VacmError::NotInView));
+    // guests need authentication
+    assert_eq!(allowed("nobody", SYS_DESCR), Err(VacmError::NoAccessEntry));
+    assert_eq!(allowed("eve", SYS_DESCR), Err(VacmError::NoGroupName));
+
+    // masks wildcard the column, longer subtrees win
+    let mut ctx = Vacm::new();
+    ctx.add_view("v", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 0, 1], &[0xff, 0xa0], true);
+    ctx.add_view("v", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 1], &[], false);
+    ctx.add_community(b"c", "s");
+    ctx.add_group(SecurityModel::V2c, "s", "g");
+    let mut access = super::agent::Access::new("g");
+    access.read_view = "v".to_string();
+    ctx.add_access(access);
+    let view = ctx
+        .view(
+            SecurityModel::V2c,
+            "s",
+            SecurityLevel::AuthPriv,
+            ViewType::Read,
+            "",
+        )
+        .unwrap();
+    assert!(view.contains(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 1]));
+    assert!(view.contains(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 3, 1, 0]));
+    assert!(!view.contains(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 2, 1]));
+    assert!(!view.contains(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 1, 2]));
+
+    assert!(Vacm::parse("com2sec a 10.0.0.0/8 public").is_err());
+    assert!(Vacm::parse("view v sideways .1.3").is_err());
+
+    let mut agent = Agent::new(b"public");
+    agent.set_vacm(vacm);
+    let mut req = pdu::Buf::default();
+    let mut resp = pdu::Buf::default();
+
+    // the agent community is no longer accepted
+    pdu::build_get(b"public", 1, SYS_DESCR, &mut req);
+    assert!(!agent.process(&req[..], &mut resp));
+
+    pdu::build_get(b"secretA", 2, SNMP_IN_PKTS, &mut req);
+    assert!(agent.process(&req[..], &mut resp));
+    assert_eq!(response_varbinds(&resp[..])[0].1, OwnedValue::NoSuchObject);
+
+    // the walk ends at the edge of the view
+    pdu::build_getnext(b"secretA", 3, &[1, 3, 6, 1, 2, 1, 1, 8, 0], &mut req);
+    assert!(agent.process(&req[..], &mut resp));
+    assert_eq!(
+        response_varbinds(&resp[..]),
+        [(vec![1, 3, 6, 1, 2, 1, 1, 8, 0], OwnedValue::EndOfMibView)]
+    );
+
+    pdu::build_set(
+        b"secretA",
+        4,
+        &[
+            (SYS_CONTACT, Value::OctetString(b"a@example.com")),
+            (&[1, 3, 6, 1, 2, 1, 1, 5, 0], Value::OctetString(b"r1")),
+        ],
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    let pdu = SnmpPdu::from_bytes(&resp[..]).unwrap();
+    assert_eq!(
+        (pdu.error_status, pdu.error_index),
+        (snmp::ERRSTATUS_NOACCESS, 2)
+    );
+    assert_eq!(agent.system().contact, "");
+
+    pdu::build_get(b"guest", 5, SYS_DESCR, &mut req);
+    assert!(agent.process(&req[..], &mut resp));
+    let pdu = SnmpPdu::from_bytes(&resp[..]).unwrap();
+    assert_eq!(
+        (pdu.error_status, pdu.error_index),
+        (snmp::ERRSTATUS_AUTHORIZATIONERROR, 1)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn agentx_pdu_and_subagent() {
+    use super::agent::ScalarHandler;
+    use super::agentx::pdu::{self as ax, Payload, Pdu, SearchRange};
+    use super::agentx::{MasterAddress, Subagent};
+    use super::OwnedValue;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixListener;
+    use std::sync::{Arc, Mutex};
+
+    const SCALAR: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 7];
+    const INSTANCE: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 7, 0];
+
+    let range = |start: &[u32], include, end: &[u32]| SearchRange {
+        start: start.to_vec(),
+        include,
+        end: end.to_vec(),
+    };
+
+    // round trip, with the internet prefix compressed and the context padded
+    let mut get = Pdu::new(7, 9, Payload::Get(vec![range(INSTANCE, false, &[])]));
+    get.context = Some(b"ctx".to_vec());
+    get.flags |= ax::FLAG_NON_DEFAULT_CONTEXT;
+    let bytes = get.encode();
+    assert_eq!(bytes.len() % 4, 0);
+    assert_eq!(
+        Pdu::len_from_header(&bytes[..ax::HEADER_LEN]),
+        Ok(bytes.len())
+    );
+    let decoded = Pdu::decode(&bytes).unwrap();
+    assert_eq!(decoded.context, get.context);
+    assert_eq!(decoded.payload, get.payload);
+
+    let path = std::env::temp_dir().join(format!("snmp-agentx-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+
+    // a scripted master agent
+    let master = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let recv = |stream: &mut std::os::unix::net::UnixStream| {
+            let mut bytes = vec![0; ax::HEADER_LEN];
+            stream.read_exact(&mut bytes).unwrap();
+            bytes.resize(Pdu::len_from_header(&bytes).unwrap(), 0);
+            stream.read_exact(&mut bytes[ax::HEADER_LEN..]).unwrap();
+            Pdu::decode(&bytes).unwrap()
+        };
+
+        let open = recv(&mut stream);
+        assert!(matches!(open.payload, Payload::Open { .. }));
+        let mut resp = open.response(0, 0, 0, vec![]);
+        resp.session_id = 42;
+        stream.write_all(&resp.encode()).unwrap();
+
+        let register = recv(&mut stream);
+        assert_eq!(register.session_id, 42);
+        match register.payload {
+            Payload::Register { ref subtree, .. } => assert_eq!(subtree, SCALAR),
+            ref other => panic!("{:?}", other),
+        }
+        stream
+            .write_all(&register.response(0, 0, 0, vec![]).encode())
+            .unwrap();
+
+        let requests = vec![
+            Payload::Get(vec![range(INSTANCE, false, &[]), range(SCALAR, false, &[])]),
+            Payload::GetNext(vec![
+                range(SCALAR, false, &[]),
+                range(SCALAR, false, INSTANCE),
+            ]),
+            Payload::TestSet(vec![(
+                INSTANCE.to_vec(),
+                OwnedValue::OctetString(b"x".to_vec()),
+            )]),
+            Payload::CleanupSet,
+            // the handler refuses the value on commit
+            Payload::TestSet(vec![(INSTANCE.to_vec(), OwnedValue::Integer(1))]),
+            Payload::CommitSet,
+            Payload::UndoSet,
+            Payload::CleanupSet,
+            Payload::TestSet(vec![(INSTANCE.to_vec(), OwnedValue::Integer(5))]),
+            Payload::CommitSet,
+            Payload::CleanupSet,
+            Payload::Get(vec![range(INSTANCE, false, &[])]),
+            Payload::Close {
+                reason: ax::CLOSE_SHUTDOWN,
+            },
+        ];
+        let mut responses = Vec::new();
+        for (packet_id, payload) in requests.into_iter().enumerate() {
+            let answered = !matches!(payload, Payload::CleanupSet | Payload::Close { .. });
+            let pdu = Pdu::new(42, packet_id as u32 + 10, payload);
+            stream.write_all(&pdu.encode()).unwrap();
+            if answered {
+                let resp = recv(&mut stream);
+                assert_eq!(resp.packet_id, pdu.packet_id);
+                responses.push(resp.payload);
+            }
+        }
+        responses
+    });
+
+    let counter = Arc::new(Mutex::new(3));
+    let (get, set) = (counter.clone(), counter.clone());
+    let mut subagent = Subagent::new(
+        MasterAddress::Unix(path.clone()),
+        &[1, 3, 6, 1, 4, 1, 32473],
+        "test",
+    );
+    subagent.register(
+        SCALAR,
+        ScalarHandler::read_write(
+            SCALAR,
+            move || OwnedValue::Integer(*get.lock().unwrap()),
+            move |value| match *value {
+                OwnedValue::Integer(v) if v > 1 => {
+                    *set.lock().unwrap() = v;
+                    Ok(())
+                }
+                _ => Err(snmp::ERRSTATUS_WRONGVALUE),
+            },
+        ),
+    );
+    subagent.connect().unwrap();
+    assert_eq!(subagent.session_id(), Some(42));
+    while subagent.serve_one().is_ok() {}
+    assert!(!subagent.is_connected());
+
+    let responses = master.join().unwrap();
+    let _ = std::fs::remove_file(&path);
+    let results: Vec<_> = responses
+        .into_iter()
+        .map(|payload| match payload {
+            Payload::Response {
+                error,
+                index,
+                varbinds,
+                ..
+            } => (error, index, varbinds),
+            other => panic!("{:?}", other),
+        })
+        .collect();
+
+    assert_eq!(
+        results[0].2,
+        [
+            (INSTANCE.to_vec(), OwnedValue::Integer(3)),
+            (SCALAR.to_vec(), OwnedValue::NoSuchInstance),
+        ]
+    );
+    // the second range ends before the instance
+    assert_eq!(
+        results[1].2,
+        [
+            (INSTANCE.to_vec(), OwnedValue::Integer(3)),
+            (SCALAR.to_vec(), OwnedValue::EndOfMibView),
+        ]
+    );
+    let status = |i: usize| (results[i].0 as u32, results[i].1);
+    assert_eq!(status(2), (snmp::ERRSTATUS_WRONGTYPE, 1));
+    assert_eq!(status(3), (0, 0));
+    assert_eq!(status(4), (snmp::ERRSTATUS_COMMITFAILED, 1));
+    assert_eq!((status(5), status(6), status(7)), ((0, 0), (0, 0), (0, 0)));
+    assert_eq!(results[8].2, [(INSTANCE.to_vec(), OwnedValue::Integer(5))]);
+    assert_eq!(*counter.lock().unwrap(), 5);
+}
+
+#[test]
+fn agentx_pdu_limits() {
+    use super::agentx::pdu::{Payload, Pdu, SearchRange};
+    use super::agentx::{MasterAddress, Subagent};
+    use std::io::{ErrorKind, Read, Write};
+    use std::net::TcpListener;
+
+    // 1.3.6.1.0 is not an internet prefix that can be compressed
+    let range = SearchRange {
+        start: vec![1, 3, 6, 1, 0, 5],
+        include: false,
+        end: vec![],
+    };
+    let bytes = Pdu::new(1, 2, Payload::Get(vec![range.clone()])).encode();
+    assert_eq!(bytes[20..22], [6, 0]);
+    assert_eq!(
+        Pdu::decode(&bytes).unwrap().payload,
+        Payload::Get(vec![range])
+    );
+
+    // a master announcing a 1 GiB PDU is refused before anything is read
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let master = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut open = [0; 20];
+        stream.read_exact(&mut open).unwrap();
+        let mut header = Pdu::new(1, 1, Payload::CleanupSet).encode();
+        header[16..20].copy_from_slice(&(1u32 << 30).to_be_bytes());
+        stream.write_all(&header).unwrap();
+    });
+
+    let mut subagent = Subagent::new(MasterAddress::Tcp(addr), &[1, 3, 6, 1, 4, 1, 32473], "test");
+    assert_eq!(
+        subagent.connect().unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    master.join().unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn agentx_master_forwards_to_subagents() {
+    use super::agent::{Agent, ScalarHandler};
+    use super::agentx::pdu::{self as ax, Payload, Pdu};
+    use super::agentx::{Master, MasterAddress, Subagent};
+    use super::{OwnedValue, SnmpPdu, Value};
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    const A: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 8, 1];
+    const B: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 8, 2];
+    const SILENT: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 9];
+
+    let path = std::env::temp_dir().join(format!("snmp-agentx-master-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let master = Master::bind(&path).unwrap();
+    master.set_timeout(Duration::from_millis(200));
+
+    let wait_for = |n: usize| {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while master.registrations().len() != n {
+            assert!(Instant::now() < deadline, "{:?}", master.registrations());
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    };
+
+    let scalar = |oid: &'static [u32], initial: i64| {
+        let value = Arc::new(Mutex::new(initial));
+        let (get, set) = (value.clone(), value.clone());
+        let handler = ScalarHandler::read_write(
+            oid,
+            move || OwnedValue::Integer(*get.lock().unwrap()),
+            move |value| match *value {
+                OwnedValue::Integer(v) if v >= 0 => {
+                    *set.lock().unwrap() = v;
+                    Ok(())
+                }
+                _ => Err(snmp::ERRSTATUS_WRONGVALUE),
+            },
+        );
+        (handler, value)
+    };
+
+    let (a, a_value) = scalar(A, 1);
+    let (b, b_value) = scalar(B, 2);
+    let mut first = Subagent::new(MasterAddress::Unix(path.clone()), &[1], "first");
+    first.register(A, a);
+    first.connect().unwrap();
+    std::thread::spawn(move || first.serve());
+    wait_for(1);
+
+    // a second registration of A with a worse priority is shadowed
+    let mut second = Subagent::new(MasterAddress::Unix(path.clone()), &[2], "second");
+    second.register_with_priority(A, 200, ScalarHandler::constant(A, OwnedValue::Integer(9)));
+    second.register(B, b);
+    second.connect().unwrap();
+    std::thread::spawn(move || second.serve());
+    wait_for(3);
+
+    // a subagent that registers a range and then never answers
+    let mut silent = UnixStream::connect(&path).unwrap();
+    let mut exchange = |pdu: Pdu| {
+        silent.write_all(&pdu.encode()).unwrap();
+        let mut bytes = vec![0; ax::HEADER_LEN];
+        silent.read_exact(&mut bytes).unwrap();
+        bytes.resize(Pdu::len_from_header(&bytes).unwrap(), 0);
+        silent.read_exact(&mut bytes[ax::HEADER_LEN..]).unwrap();
+        Pdu::decode(&bytes).unwrap()
+    };
+    let open = Payload::Open {
+        timeout: 0,
+        id: vec![3],
+        descr: b"silent".to_vec(),
+    };
+    let session_id = exchange(Pdu::new(0, 1, open)).session_id;
+    let register = Payload::Register {
+        timeout: 0,
+        priority: 127,
+        range_subid: SILENT.len() as u8 + 1,
+        subtree: [SILENT, &[1]].concat(),
+        upper_bound: 3,
+    };
+    assert!(matches!(
+        exchange(Pdu::new(session_id, 2, register.clone())).payload,
+        Payload::Response { error: 0, .. }
+    ));
+    assert!(matches!(
+        exchange(Pdu::new(session_id, 3, register)).payload,
+        Payload::Response {
+            error: ax::ERR_DUPLICATE_REGISTRATION,
+            ..
+        }
+    ));
+    wait_for(6);
+
+    let mut agent = Agent::new(b"public");
+    agent.register(&[], master.handler());
+
+    let instance = |oid: &[u32]| [oid, &[0]].concat();
+    assert_eq!(agent.get(&instance(A)), OwnedValue::Integer(1));
+    assert_eq!(agent.get_next(A), (instance(A), OwnedValue::Integer(1)));
+    assert_eq!(
+        agent.get_next(&instance(A)),
+        (instance(B), OwnedValue::Integer(2))
+    );
+    // the walk skips the silent subagent once it times out
+    let started = Instant::now();
+    assert_eq!(
+        agent.get_next(&instance(B)),
+        (instance(B), OwnedValue::EndOfMibView)
+    );
+    assert!(started.elapsed() < Duration::from_secs(3));
+    assert_eq!(
+        agent.get(&[SILENT, &[2, 0]].concat()),
+        OwnedValue::NoSuchObject
+    );
+    // three timeouts close the session and drop its registrations
+    wait_for(3);
+
+    // one SET spanning both subagents
+    let mut req = pdu::Buf::default();
+    let mut resp = pdu::Buf::default();
+    pdu::build_set(
+        b"public",
+        1,
+        &[
+            (&instance(A), Value::Integer(10)),
+            (&instance(B), Value::Integer(20)),
+        ],
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    let pdu = SnmpPdu::from_bytes(&resp[..]).unwrap();
+    assert_eq!((pdu.error_status, pdu.error_index), (0, 0));
+    assert_eq!(
+        (*a_value.lock().unwrap(), *b_value.lock().unwrap()),
+        (10, 20)
+    );
+
+    // B refuses its value on commit, so A is undone
+    pdu::build_set(
+        b"public",
+        2,
+        &[
+            (&instance(A), Value::Integer(11)),
+            (&instance(B), Value::Integer(-1)),
+        ],
+        &mut req,
+    );
+    assert!(agent.process(&req[..], &mut resp));
+    let pdu = SnmpPdu::from_bytes(&resp[..]).unwrap();
+    assert_eq!(
+        (pdu.error_status, pdu.error_index),
+        (snmp::ERRSTATUS_COMMITFAILED, 2)
+    );
+    assert_eq!(
+        (*a_value.lock().unwrap(), *b_value.lock().unwrap()),
+        (10, 20)
+    );
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn proxy_forwards_and_translates_v1() {
+    use super::agent::Agent;
+    use super::notification::owned_varbinds;
+    use super::proxy::{Proxy, ProxyTarget, ProxyType};
+    use super::sync_session::SyncAgent;
+    use super::{OwnedValue, SnmpMessageType, SnmpPdu};
+    use std::collections::HashSet;
+    use std::net::UdpSocket;
+    use std::sync::atomic::Ordering;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    const SYS_DESCR: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 1, 0];
+    const SYS_NAME: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 5, 0];
+    const MISSING: &[u32] = &[1, 3, 6, 1, 4, 1, 32473, 1, 0];
+
+    let backend = Agent::new(b"backend");
+    backend.system().descr = "v2c device".to_string();
+    let mut backend = SyncAgent::new("127.0.0.1:0", backend).unwrap();
+    let v2c_addr = backend.local_addr().unwrap();
+    std::thread::spawn(move || backend.serve());
+
+    // a read-only SNMPv1 agent with two objects
+    let v1_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let v1_addr = v1_socket.local_addr().unwrap();
+    let v1_sources = Arc::new(Mutex::new(HashSet::new()));
+    let sources = v1_sources.clone();
+    std::thread::spawn(move || {
+        let objects = [
+            (
+                SYS_DESCR.to_vec(),
+                OwnedValue::OctetString(b"v1 device".to_vec()),
+            ),
+            (SYS_NAME.to_vec(), OwnedValue::OctetString(b"old".to_vec())),
+        ];
+        let mut buf = [0; 4096];
+        let mut out = pdu::Buf::default();
+        loop {
+            let (len, source) = v1_socket.recv_from(&mut buf).unwrap();
+            let req = SnmpPdu::from_bytes_with_version(&buf[..len], snmp::VERSION_1).unwrap();
+            sources.lock().unwrap().insert(source);
+            let varbinds = owned_varbinds(req.varbinds.clone()).unwrap();
+
+            let mut values = Vec::new();
+            let mut error = (0, 0);
+            for (i, (name, _)) in varbinds.iter().enumerate() {
+                let found = match req.message_type {
+                    SnmpMessageType::GetRequest => objects.iter().find(|(n, _)| n == name),
+                    SnmpMessageType::GetNextRequest => objects.iter().find(|(n, _)| n > name),
+                    _ => {
+                        error = (snmp::ERRSTATUS_READONLY, i as u32 + 1);
+                        break;
+                    }
+                };
+                match found {
+                    Some(varbind) => values.push(varbind.clone()),
+                    None => {
+                        error = (snmp::ERRSTATUS_NOSUCHNAME, i as u32 + 1);
+                        break;
+                    }
+                }
+            }
+            if error.0 != 0 {
+                values = varbinds;
+            }
+            pdu::build_owned_request(
+                snmp::VERSION_1,
+                b"legacy",
+                req.req_id,
+                snmp::MSG_RESPONSE,
+                error.0,
+                error.1,
+                &values,
+                &mut out,
+            )
+            .unwrap();
+            v1_socket.send_to(&out[..], source).unwrap();
+        }
+    });
+
+    let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut unreachable = ProxyTarget::new(silent.local_addr().unwrap(), snmp::VERSION_2, b"x");
+    unreachable.timeout = Duration::from_millis(50);
+    unreachable.retries = 1;
+
+    let mut proxy = Proxy::new();
+    proxy.add_community(b"dev2", "v2c");
+    proxy.add_community(b"dev1", "v1");
+    proxy.add_community(b"gone", "gone");
+    proxy.add_target(
+        "v2c",
+        ProxyTarget::new(v2c_addr, snmp::VERSION_2, b"backend"),
+    );
+    proxy.add_target("v1", ProxyTarget::new(v1_addr, snmp::VERSION_1, b"legacy"));
+    proxy.add_target("gone", unreachable);
+    proxy.add_forwarding(ProxyType::Read, "v2c", "v2c");
+    proxy.add_forwarding(ProxyType::Read, "v1", "v1");
+    proxy.add_forwarding(ProxyType::Write, "v1", "v1");
+    proxy.add_forwarding(ProxyType::Read, "gone", "gone");
+    assert_eq!(
+        proxy.target_for_context("v1", &SnmpMessageType::SetRequest),
+        Some("v1")
+    );
+    assert_eq!(
+        proxy.target_for_context("v2c", &SnmpMessageType::SetRequest),
+        None
+    );
+
+    let mut agent = Agent::new(b"public");
+    agent.system().descr = "proxy".to_string();
+    agent.set_proxy(proxy);
+
+    let mut req = pdu::Buf::default();
+    let mut resp = pdu::Buf::default();
+    let mut request = |community: &[u8], pdu_type, fields: (u32, u32), names: &[&[u32]]| {
+        let varbinds: Vec<_> = names
+            .iter()
+            .map(|name| (name.to_vec(), OwnedValue::Null))
+            .collect();
+        pdu::build_owned_request(
+            snmp::VERSION_2,
+            community,
+            7,
+            pdu_type,
+            fields.0,
+            fields.1,
+            &varbinds,
+            &mut req,
+        )
+        .unwrap();
+        if !agent.process(&req[..], &mut resp) {
+            return None;
+        }
+        let pdu = SnmpPdu::from_bytes(&resp[..]).unwrap();
+        assert_eq!(pdu.req_id, 7);
+        let varbinds = owned_varbinds(pdu.varbinds.clone()).unwrap();
+        Some((pdu.error_status, pdu.error_index, varbinds))
+    };
+    let octets = |s: &[u8]| OwnedValue::OctetString(s.to_vec());
+
+    // the proxy's own MIB for its own community
+    let (_, _, values) = request(b"public", snmp::MSG_GET, (0, 0), &[SYS_DESCR]).unwrap();
+    assert_eq!(values[0].1, octets(b"proxy"));
+
+    let (_, _, values) = request(b"dev2", snmp::MSG_GET, (0, 0), &[SYS_DESCR]).unwrap();
+    assert_eq!(values[0].1, octets(b"v2c device"));
+
+    // noSuchName only fails the missing varbind
+    let (status, _, values) =
+        request(b"dev1", snmp::MSG_GET, (0, 0), &[MISSING, SYS_DESCR]).unwrap();
+    assert_eq!(status, 0);
+    assert_eq!(
+        values,
+        [
+            (MISSING.to_vec(), OwnedValue::NoSuchObject),
+            (SYS_DESCR.to_vec(), octets(b"v1 device")),
+        ]
+    );
+
+    // GETBULK becomes a single GETNEXT
+    let (_, _, values) =
+        request(b"dev1", snmp::MSG_GET_BULK, (0, 10), &[SYS_DESCR, SYS_NAME]).unwrap();
+    assert_eq!(
+        values,
+        [
+            (SYS_NAME.to_vec(), octets(b"old")),
+            (SYS_NAME.to_vec(), OwnedValue::EndOfMibView),
+        ]
+    );
+
+    let (status, index, _) = request(b"dev1", snmp::MSG_SET, (0, 0), &[SYS_NAME]).unwrap();
+    assert_eq!((status, index), (snmp::ERRSTATUS_NOTWRITABLE, 1));
+
+    // no forwarding entry for writes to the v2c device
+    assert!(request(b"dev2", snmp::MSG_SET, (0, 0), &[SYS_NAME]).is_none());
+
+    assert!(request(b"gone", snmp::MSG_GET, (0, 0), &[SYS_DESCR]).is_none());
+    assert_eq!(agent.stats().proxy_drops.load(Ordering::Relaxed), 1);
+
+    // every request to a target goes through the target's one session
+    assert_eq!(v1_sources.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn proxy_forwards_snmpv3_by_context() {
+    use super::agent::{
+        Agent, Usm, USM_STATS_UNKNOWN_ENGINE_IDS, USM_STATS_UNKNOWN_USER_NAMES,
+        USM_STATS_UNSUPPORTED_SEC_LEVELS,
+    };
+    use super::proxy::{Proxy, ProxyTarget, ProxyType};
+    use super::sync_session::SyncAgent;
+    use super::{OwnedValue, SnmpMessageType, SnmpV3Message, V3Header};
+    use std::sync::atomic::Ordering;
+
+    const SYS_DESCR: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 1, 0];
+    const ENGINE_ID: &[u8] = b"\x80\x00\x7e\x69\x04proxy";
+
+    let backend = Agent::new(b"backend");
+    backend.system().descr = "v2c device".to_string();
+    let mut backend = SyncAgent::new("127.0.0.1:0", backend).unwrap();
+    let backend_addr = backend.local_addr().unwrap();
+    std::thread::spawn(move || backend.serve());
+
+    let mut proxy = Proxy::new();
+    proxy.add_target(
+        "dev",
+        ProxyTarget::new(backend_addr, snmp::VERSION_2, b"backend"),
+    );
+    proxy.add_forwarding(ProxyType::Read, "dev", "dev");
+    let mut usm = Usm::new(ENGINE_ID, 7);
+    usm.add_user(b"manager");
+
+    let mut agent = Agent::new(b"public");
+    agent.set_proxy(proxy);
+    agent.set_usm(usm);
+
+    let mut req = pdu::Buf::default();
+    let mut resp = pdu::Buf::default();
+    let mut request = |engine_id: &[u8], user: &[u8], flags, context: &[u8]| {
+        let header = V3Header {
+            msg_id: 42,
+            max_size: 1500,
+            flags,
+            engine_id,
+            engine_boots: 0,
+            engine_time: 0,
+            user_name: user,
+        };
+        let varbinds = [(SYS_DESCR.to_vec(), OwnedValue::Null)];
+        pdu::build_v3(
+            &header,
+            engine_id,
+            context,
+            snmp::MSG_GET,
+            9,
+            0,
+            0,
+            &varbinds,
+            &mut req,
+        )
+        .unwrap();
+        if !agent.process(&req[..], &mut resp) {
+            return None;
+        }
+        let message = SnmpV3Message::from_bytes(&resp[..]).unwrap();
+        assert_eq!(message.header.msg_id, 42);
+        assert_eq!(message.header.engine_id, ENGINE_ID);
+        assert_eq!(message.header.engine_boots, 7);
+        assert_eq!(message.header.user_name, user);
+        let scoped = message.scoped_pdu().unwrap();
+        assert_eq!(scoped.pdu.req_id, 9);
+        assert_eq!(scoped.context_name, context);
+        let varbinds = scoped.pdu.owned_varbinds().unwrap();
+        Some((scoped.pdu.message_type, varbinds))
+    };
+    let reportable = snmp::MSG_FLAG_REPORTABLE;
+
+    // discovery of the engine ID, then the security failures
+    let failures = [
+        (&b""[..], &b""[..], reportable, USM_STATS_UNKNOWN_ENGINE_IDS),
+        (
+            ENGINE_ID,
+            b"intruder",
+            reportable,
+            USM_STATS_UNKNOWN_USER_NAMES,
+        ),
+        (
+            ENGINE_ID,
+            b"manager",
+            reportable | snmp::MSG_FLAG_AUTH,
+            USM_STATS_UNSUPPORTED_SEC_LEVELS,
+        ),
+    ];
+    for (engine_id, user, flags, counter) in failures {
+        let (message_type, varbinds) = request(engine_id, user, flags, b"dev").unwrap();
+        assert_eq!(message_type, SnmpMessageType::Report);
+        assert_eq!(varbinds, [(counter.to_vec(), OwnedValue::Counter32(1))]);
+    }
+    // no report unless asked for
+    assert!(request(b"", b"", 0, b"dev").is_none());
+
+    let (message_type, varbinds) = request(ENGINE_ID, b"manager", reportable, b"dev").unwrap();
+    assert_eq!(message_type, SnmpMessageType::Response);
+    assert_eq!(
+        varbinds,
+        [(
+            SYS_DESCR.to_vec(),
+            OwnedValue::OctetString(b"v2c device".to_vec())
+        )]
+    );
+
+    // contexts without a forwarding entry are not served
+    assert!(request(ENGINE_ID, b"manager", reportable, b"").is_none());
+    assert_eq!(agent.stats().proxy_drops.load(Ordering::Relaxed), 1);
+    let usm = agent.usm().unwrap();
+    assert_eq!(usm.stats().unknown_engine_ids, 2);
+}
+
+/// An excerpt of IF-MIB (RFC 2863) with an SMIv1 trap module.
+const IF_MIB: &str = r#"
+IF-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    MODULE-IDENTITY, OBJECT-TYPE, Counter32, Counter64, Integer32,
+    NOTIFICATION-TYPE, mib-2                  FROM SNMPv2-SMI
+    TEXTUAL-CONVENTION, DisplayString,
+    PhysAddress, TruthValue, TimeStamp        FROM SNMPv2-TC
+    NOTIFICATION-GROUP                        FROM SNMPv2-CONF;
+
+ifMIB MODULE-IDENTITY
+    LAST-UPDATED "200006140000Z"
+    ORGANIZATION "IETF Interfaces MIB Working Group"
+    CONTACT-INFO "-- see RFC 2863 --"
+    DESCRIPTION  "The MIB module to describe generic objects for network
+                 interface sub-layers."
+    REVISION     "200006140000Z"
+    DESCRIPTION  "Clarifications."
+    ::= { mib-2 31 }
+
+ifMIBObjects OBJECT IDENTIFIER ::= { ifMIB 1 }
+interfaces   OBJECT IDENTIFIER ::= { mib-2 2 }
+
+InterfaceIndex ::= TEXTUAL-CONVENTION
+    DISPLAY-HINT "d"
+    STATUS       current
+    DESCRIPTION  "A unique value, greater than zero, for each interface."
+    SYNTAX       Integer32 (1..2147483647)
+
+ifNumber OBJECT-TYPE
+    SYNTAX      Integer32
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The number of network interfaces."
+    ::= { interfaces 1 }
+
+ifTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF IfEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "A list of interface entries."
+    ::= { interfaces 2 }
+
+ifEntry OBJECT-TYPE
+    SYNTAX      IfEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "An entry containing management information."
+    INDEX       { ifIndex }
+    ::= { ifTable 1 }
+
+IfEntry ::=
+    SEQUENCE {
+        ifIndex                 InterfaceIndex,
+        ifDescr                 DisplayString,
+        ifPhysAddress           PhysAddress,
+        ifAdminStatus           INTEGER,
+        ifInOctets              Counter32
+    }
+
+ifIndex OBJECT-TYPE
+    SYNTAX      InterfaceIndex
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "A unique value for each interface."
+    ::= { ifEntry 1 }
+
+ifDescr OBJECT-TYPE
+    SYNTAX      DisplayString (SIZE (0..255))
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "A textual string containing information about the
+                interface."
+    ::= { ifEntry 2 }
+
+ifPhysAddress OBJECT-TYPE
+    SYNTAX      PhysAddress
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The interface's address at its protocol sub-layer."
+    ::= { ifEntry 6 }
+
+ifAdminStatus OBJECT-TYPE
+    SYNTAX  INTEGER {
+                up(1),       -- ready to pass packets
+                down(2),
+                testing(3)   -- in some test mode
+            }
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "The desired state of the interface."
+    DEFVAL      { up }
+    ::= { ifEntry 7 }
+
+ifInOctets OBJECT-TYPE
+    SYNTAX      Counter32
+    UNITS       "octets"
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The total number of octets received on the interface."
+    ::= { ifEntry 10 }
+
+ifXTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF IfXEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "A list of interface entries."
+    ::= { ifMIBObjects 1 }
+
+ifXEntry OBJECT-TYPE
+    SYNTAX      IfXEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "An entry containing additional management information."
+    AUGMENTS    { ifEntry }
+    ::= { ifXTable 1 }
+
+IfXEntry ::= SEQUENCE { ifName DisplayString, ifHCInOctets Counter64 }
+
+ifName OBJECT-TYPE
+    SYNTAX      DisplayString
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The textual name of the interface."
+    ::= { ifXEntry 1 }
+
+ifHCInOctets OBJECT-TYPE
+    SYNTAX      Counter64
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The total number of octets received on the interface."
+    ::= { ifXEntry 6 }
+
+ifPromiscuousMode OBJECT-TYPE
+    SYNTAX      TruthValue
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "Whether the interface only accepts packets addressed to it."
+    ::= { ifXEntry 16 }
+
+snmpTraps OBJECT IDENTIFIER ::= { 1 3 6 1 6 3 1 1 5 }
+
+linkDown NOTIFICATION-TYPE
+    OBJECTS { ifIndex, ifAdminStatus }
+    STATUS  current
+    DESCRIPTION "A linkDown trap signifies that a communication link is
+                about to go down."
+    ::= { snmpTraps 3 }
+
+END
+
+ACME-TRAP-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    enterprises FROM RFC1155-SMI
+    TRAP-TYPE   FROM RFC-1215
+    ifIndex     FROM IF-MIB;
+
+acme OBJECT IDENTIFIER ::= { enterprises 32473 }
+
+acmeLinkFlap TRAP-TYPE
+    ENTERPRISE  acme
+    VARIABLES   { ifIndex }
+    DESCRIPTION "A link went down and up again."
+    ::= 7
+
+END
+"#;
+
+#[test]
+fn mib_parse_and_resolve() {
+    use super::mib::{Access, BaseType, MibError, MibTree, NodeKind, Status};
+
+    let mut mibs = MibTree::new();
+    assert_eq!(
+        mibs.load_str(IF_MIB).unwrap(),
+        vec!["IF-MIB".to_string(), "ACME-TRAP-MIB".to_string()]
+    );
+
+    // names to OIDs and back
+    let hc_in_octets = [1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 3];
+    assert_eq!(
+        mibs.resolve("IF-MIB::ifHCInOctets.3").unwrap(),
+        hc_in_octets
+    );
+    assert_eq!(mibs.resolve("ifHCInOctets.3").unwrap(), hc_in_octets);
+    assert_eq!(
+        mibs.resolve(".1.3.6.1.2.1.31.1.1.1.6.3").unwrap(),
+        hc_in_octets
+    );
+    assert_eq!(mibs.format_oid(&hc_in_octets), "IF-MIB::ifHCInOctets.3");
+    assert_eq!(
+        mibs.format_oid(&[1, 3, 6, 1, 4, 1, 32473, 0, 7]),
+        "ACME-TRAP-MIB::acmeLinkFlap"
+    );
+    assert_eq!(mibs.format_oid(&[1, 3, 6, 1, 2, 1]), "SNMPv2-SMI::mib-2");
+    assert_eq!(mibs.format_oid(&[0, 0, 5]), "SNMPv2-SMI::zeroDotZero.5");
+    assert_eq!(mibs.format_oid(&[3, 1]), "3.1");
+    assert!(matches!(
+        mibs.resolve("IF-MIB::ifFoo.1"),
+        Err(MibError::UnknownName(_))
+    ));
+
+    let (node, suffix) = mibs.lookup(&hc_in_octets).unwrap();
+    assert_eq!((&node.name[..], suffix), ("ifHCInOctets", &[3][..]));
+
+    // kinds, syntax, access and index
+    let kind = |name| mibs.node(name).unwrap().kind;
+    assert_eq!(kind("IF-MIB::ifMIB"), NodeKind::ModuleIdentity);
+    assert_eq!(kind("ifNumber"), NodeKind::Scalar);
+    assert_eq!(kind("ifTable"), NodeKind::Table);
+    assert_eq!(kind("ifEntry"), NodeKind::Row);
+    assert_eq!(kind("ifDescr"), NodeKind::Column);
+    assert_eq!(kind("linkDown"), NodeKind::Notification);
+    assert_eq!(kind("acmeLinkFlap"), NodeKind::Trap);
+
+    let mib_module = mibs.node("ifMIB").unwrap();
+    assert!(mib_module
+        .description
+        .as_ref()
+        .unwrap()
+        .starts_with("The MIB module"));
+
+    let entry = mibs.node("ifEntry").unwrap();
+    assert_eq!(entry.index.len(), 1);
+    assert_eq!(
+        (&entry.index[0].name[..], entry.index[0].implied),
+        ("ifIndex", false)
+    );
+    assert_eq!(
+        mibs.node("ifXEntry").unwrap().augments.as_deref(),
+        Some("ifEntry")
+    );
+
+    let if_index = mibs.node("ifIndex").unwrap();
+    let syntax = if_index.syntax.as_ref().unwrap();
+    assert_eq!(syntax.base, BaseType::Integer);
+    assert_eq!(syntax.tc.as_deref(), Some("InterfaceIndex"));
+    assert_eq!(syntax.display_hint.as_deref(), Some("d"));
+    assert_eq!(syntax.ranges, vec![(1, 2147483647)]);
+    assert_eq!(if_index.access, Some(Access::ReadOnly));
+    assert_eq!(if_index.status, Some(Status::Current));
+
+    let descr = mibs.node("ifDescr").unwrap().syntax.clone().unwrap();
+    assert_eq!(
+        (descr.base, descr.display_hint.as_deref()),
+        (BaseType::OctetString, Some("255a"))
+    );
+    assert_eq!(descr.sizes, vec![(0, 255)]);
+
+    let admin = mibs.node("ifAdminStatus").unwrap();
+    let syntax = admin.syntax.as_ref().unwrap();
+    assert_eq!(syntax.label(2), Some("down"));
+    assert_eq!(syntax.value("testing"), Some(3));
+    assert_eq!(admin.access, Some(Access::ReadWrite));
+    assert_eq!(admin.defval.as_deref(), Some("up"));
+
+    let promiscuous = mibs
+        .node("ifPromiscuousMode")
+        .unwrap()
+        .syntax
+        .clone()
+        .unwrap();
+    assert_eq!(promiscuous.tc.as_deref(), Some("TruthValue"));
+    assert_eq!(promiscuous.label(1), Some("true"));
+
+    let in_octets = mibs.node("ifInOctets").unwrap();
+    assert_eq!(in_octets.syntax.as_ref().unwrap().base, BaseType::Counter32);
+    assert_eq!(in_octets.units.as_deref(), Some("octets"));
+    assert_eq!(
+        mibs.node("ifHCInOctets")
+            .unwrap()
+            .syntax
+            .as_ref()
+            .unwrap()
+            .base,
+        BaseType::Counter64
+    );
+    assert_eq!(
+        mibs.node("linkDown").unwrap().objects,
+        vec!["ifIndex", "ifAdminStatus"]
+    );
+    assert_eq!(mibs.node("acmeLinkFlap").unwrap().objects, vec!["ifIndex"]);
+
+    // errors name the line, and leave the tree as it was
+    let broken = "BROKEN-MIB DEFINITIONS ::= BEGIN\nfoo OBJECT IDENTIFIER\n    ::= { mib-2 }\nbar OBJECT-TYPE\n    SYNTAX INTEGER {\n    ::= { foo 1 }\nEND\n";
+    match mibs.load_str(broken) {
+        Err(MibError::Parse { line, .. }) => assert_eq!(line, 6),
+        other => panic!("{:?}", other),
+    }
+    let missing = "MISSING-MIB DEFINITIONS ::= BEGIN\nIMPORTS foo FROM NOWHERE-MIB;\nEND\n";
+    assert!(
+        matches!(mibs.load_str(missing), Err(MibError::UnknownModule(ref m)) if m == "NOWHERE-MIB")
+    );
+    let dangling =
+        "DANGLING-MIB DEFINITIONS ::= BEGIN\nfoo OBJECT IDENTIFIER ::= { nowhere 1 }\nEND\n";
+    assert!(matches!(
+        mibs.load_str(dangling),
+        Err(MibError::UnknownName(_))
+    ));
+    assert!(!mibs.has_module("DANGLING-MIB"));
+    assert!(mibs.node("ifIndex").is_some());
+    // non-ASCII comments, and an enumeration followed by a constraint
+    let extra = "EXTRA-MIB DEFINITIONS ::= BEGIN\nIMPORTS mib-2 FROM SNMPv2-SMI;\n-- Gerät für Zürich -- extra OBJECT IDENTIFIER ::= { mib-2 999 } -- ünd\nMode ::= INTEGER { on(1), off(2) } (1..2)\nEND\n";
+    assert_eq!(mibs.load_str(extra).unwrap(), vec!["EXTRA-MIB".to_string()]);
+    assert_eq!(
+        mibs.resolve("EXTRA-MIB::extra").unwrap(),
+        [1, 3, 6, 1, 2, 1, 999]
+    );
+}
+
+#[test]
+fn mib_search_path_misnamed_files() {
+    use super::mib::{MibError, MibTree};
+
+    // FOO-MIB.txt defines BAR-MIB, which imports from FOO-MIB
+    let dir = std::env::temp_dir().join(format!("snmp-mibs-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("FOO-MIB.txt"),
+        "BAR-MIB DEFINITIONS ::= BEGIN\n\
+         IMPORTS fooRoot FROM FOO-MIB;\n\
+         bar OBJECT IDENTIFIER ::= { fooRoot 1 }\n\
+         END\n",
+    )
+    .unwrap();
+    let mut mibs = MibTree::new();
+    mibs.add_path(&dir);
+    let text = "BAZ-MIB DEFINITIONS ::= BEGIN\n\
+                IMPORTS bar FROM BAR-MIB fooRoot FROM FOO-MIB;\n\
+                baz OBJECT IDENTIFIER ::= { bar 1 }\n\
+                END\n";
+    assert!(matches!(
+        mibs.load_str(text),
+        Err(MibError::UnknownModule(name)) if name == "FOO-MIB"
+    ));
+    assert!(matches!(
+        mibs.load_module("FOO-MIB"),
+        Err(MibError::UnknownModule(_))
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// An SMIv1 module with string, IMPLIED, foreign and type-name indexes.
+const ACME_PEER_MIB: &str = r#"
+ACME-PEER-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    enterprises, IpAddress, Counter FROM RFC1155-SMI
+    OBJECT-TYPE                     FROM RFC-1212;
+
+peers OBJECT IDENTIFIER ::= { enterprises 32473 9 }
+
+peerTable OBJECT-TYPE
+    SYNTAX  SEQUENCE OF PeerEntry
+    ACCESS  not-accessible
+    STATUS  mandatory
+    ::= { peers 1 }
+
+peerEntry OBJECT-TYPE
+    SYNTAX  PeerEntry
+    ACCESS  not-accessible
+    STATUS  mandatory
+    INDEX   { peerAddress, IMPLIED peerName }
+    ::= { peerTable 1 }
+
+PeerEntry ::= SEQUENCE {
+    peerAddress IpAddress,
+    peerName    OCTET STRING,
+    peerState   INTEGER
+}
+
+peerAddress OBJECT-TYPE
+    SYNTAX  IpAddress
+    ACCESS  read-only
+    STATUS  mandatory
+    ::= { peerEntry 1 }
+
+peerName OBJECT-TYPE
+    SYNTAX  OCTET STRING (SIZE (0..32))
+    ACCESS  read-only
+    STATUS  mandatory
+    ::= { peerEntry 2 }
+
+peerState OBJECT-TYPE
+    SYNTAX  INTEGER { idle(1), established(2) }
+    ACCESS  read-write
+    STATUS  mandatory
+    ::= { peerEntry 3 }
+
+peerRouteTable OBJECT-TYPE
+    SYNTAX  SEQUENCE OF PeerRouteEntry
+    ACCESS  not-accessible
+    STATUS  mandatory
+    ::= { peers 2 }
+
+peerRouteEntry OBJECT-TYPE
+    SYNTAX  PeerRouteEntry
+    ACCESS  not-accessible
+    STATUS  mandatory
+    INDEX   { peerAddress, INTEGER }
+    ::= { peerRouteTable 1 }
+
+PeerRouteEntry ::= SEQUENCE {
+    peerRouteCount Counter
+}
+
+peerRouteCount OBJECT-TYPE
+    SYNTAX  Counter
+    ACCESS  read-only
+    STATUS  mandatory
+    ::= { peerRouteEntry 1 }
+
+END
+"#;
+
+#[test]
+fn mib_codegen_and_index_encoding() {
+    use super::mib::{index, Generator, MibTree};
+
+    // index encoding (RFC 2578 section 7.7)
+    let mut oid = Vec::new();
+    index::encode_integer(&mut oid, 3);
+    index::encode_ip_address(&mut oid, [10, 0, 0, 1]);
+    index::encode_octets(&mut oid, b"ab", false, false);
+    index::encode_oid(&mut oid, &[1, 3], true);
+    assert_eq!(oid, [3, 10, 0, 0, 1, 2, 97, 98, 1, 3]);
+
+    let mut rest = &oid[..];
+    assert_eq!(index::decode_integer(&mut rest), Some(3));
+    assert_eq!(index::decode_ip_address(&mut rest), Some([10, 0, 0, 1]));
+    assert_eq!(
+        index::decode_octets(&mut rest, None, false),
+        Some(b"ab".to_vec())
+    );
+    assert_eq!(index::decode_oid(&mut rest, true), Some(vec![1, 3]));
+    assert!(rest.is_empty());
+    assert_eq!(index::decode_octets(&mut &[5, 1][..], None, false), None);
+    assert_eq!(index::decode_octets(&mut &[256][..], Some(1), false), None);
+
+    let mut mibs = MibTree::new();
+    mibs.load_str(IF_MIB).unwrap();
+    let code = Generator::new(&mibs, &["IF-MIB"]).generate().unwrap();
+
+    for expected in [
+        "pub mod if_mib {",
+        "pub const IF_HC_IN_OCTETS: &[u32] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6];",
+        "pub const IF_NUMBER_0: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 1, 0];",
+        "pub enum IfAdminStatus {",
+        "        Testing = 3,",
+        "pub struct IfEntry {",
+        "        pub if_index: i64,",
+        "        pub if_descr: Option<String>,",
+        "        pub if_admin_status: Option<IfAdminStatus>,",
+        "        pub if_in_octets: Option<u32>,",
+        "pub const COLUMNS: &'static [u32] = &[1, 2, 6, 7, 10];",
+        "pub const WRITABLE: &'static [u32] = &[7];",
+        "let if_index = index::decode_integer(&mut rest)?;",
+        // the augmenting row takes the index of ifEntry
+        "pub struct IfXEntry {\n        pub if_index: i64,",
+        "pub if_hc_in_octets: Option<u64>,",
+    ] {
+        assert!(
+            code.contains(expected),
+            "missing {:?} in\n{}",
+            expected,
+            code
+        );
+    }
+    assert!(Generator::new(&mibs, &["NO-SUCH-MIB"]).generate().is_err());
+
+    // the generated code compiled into `generated` is current
+    mibs.load_str(ACME_PEER_MIB).unwrap();
+    let mut generator = Generator::new(&mibs, &["IF-MIB", "ACME-PEER-MIB"]);
+    generator.crate_path = "crate".to_string();
+    assert_eq!(
+        generator.generate().unwrap(),
+        include_str!("testdata/codegen.rs")
+    );
+}
+
+/// The output of `Generator` for IF-MIB and ACME-PEER-MIB.
+mod generated {
+    include!("testdata/codegen.rs");
+}
+
+#[test]
+fn mib_generated_rows() {
+    use super::OwnedValue;
+    use generated::acme_peer_mib::{PeerEntry, PeerRouteEntry, PeerState, PEER_ENTRY};
+    use generated::if_mib::{IfAdminStatus, IfEntry, IF_ADMIN_STATUS, IF_DESCR, IF_IN_OCTETS};
+
+    let mut peer = PeerEntry::new([10, 0, 0, 1], b"edge".to_vec());
+    // the IMPLIED name has no length prefix
+    assert_eq!(peer.index(), [10, 0, 0, 1, 101, 100, 103, 101]);
+    assert_eq!(PeerEntry::from_index(&peer.index()), Some(peer.clone()));
+    assert_eq!(PeerEntry::from_index(&[10, 0, 0]), None);
+
+    peer.peer_state = Some(PeerState::Established);
+    assert_eq!(
+        peer.values(),
+        [
+            (1, OwnedValue::IpAddress([10, 0, 0, 1])),
+            (2, OwnedValue::OctetString(b"edge".to_vec())),
+            (3, OwnedValue::Integer(2)),
+        ]
+    );
+
+    // an index object of another table, and an SMIv1 type name
+    let route = PeerRouteEntry::from_index(&[10, 0, 0, 1, 4]).unwrap();
+    assert_eq!((route.peer_address, route.index_2), ([10, 0, 0, 1], 4));
+    assert_eq!(route.index(), [10, 0, 0, 1, 4]);
+    assert_eq!(PeerRouteEntry::from_index(&[10, 0, 0, 1, 4, 5]), None);
+
+    let instance = |column: &[u32], index: &[u32]| [column, index].concat();
+    let rows = IfEntry::from_varbinds(vec![
+        (
+            instance(IF_DESCR, &[2]),
+            OwnedValue::OctetString(b"eth1".to_vec()),
+        ),
+        (
+            instance(IF_DESCR, &[1]),
+            OwnedValue::OctetString(b"lo".to_vec()),
+        ),
+        (instance(IF_ADMIN_STATUS, &[2]), OwnedValue::Integer(2)),
+        // the wrong type leaves the column unset
+        (instance(IF_IN_OCTETS, &[2]), OwnedValue::Integer(5)),
+        (
+            instance(PEER_ENTRY, &[3, 10, 0, 0, 1]),
+            OwnedValue::Integer(1),
+        ),
+    ]);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        (rows[0].if_index, rows[0].if_descr.as_deref()),
+        (1, Some("lo"))
+    );
+    assert_eq!(rows[1].if_index, 2);
+    assert_eq!(rows[1].if_descr.as_deref(), Some("eth1"));
+    assert_eq!(rows[1].if_admin_status, Some(IfAdminStatus::Down));
+    assert_eq!(rows[1].if_in_octets, None);
+}
+
+#[cfg(feature = "mibs")]
+#[test]
+fn bundled_mibs_name_oids() {
+    use super::mib::{bundled, MibTree};
+
+    let mut pdu = pdu::Buf::default();
+    pdu::build_get(b"public", 1, &[1, 3, 6, 1, 2, 1, 1, 3, 0], &mut pdu);
+    let mut varbinds = super::SnmpPdu::from_bytes(&pdu[..]).unwrap().varbinds;
+    let (name, _) = varbinds.next().unwrap();
+    assert_eq!(name.to_string(), "SNMPv2-MIB::sysUpTime.0");
+
+    assert_eq!(
+        bundled::resolve("IF-MIB::ifHCInOctets.3").unwrap(),
+        [1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 3]
+    );
+    assert_eq!(
+        bundled::resolve("lldpRemSysName").unwrap(),
+        [1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 9]
+    );
+    assert!(bundled::resolve("IP-MIB::ifDescr").is_none());
+    assert_eq!(
+        bundled::format_oid(&[1, 3, 6, 1, 6, 3, 1, 1, 5, 4]),
+        "IF-MIB::linkUp"
+    );
+    assert_eq!(
+        bundled::format_oid(&[1, 3, 6, 1, 4, 1, 32473, 1]),
+        "SNMPv2-SMI::enterprises.32473.1"
+    );
+    assert_eq!(bundled::format_oid(&[2, 5]), "2.5");
+
+    // the bundled names agree with the parsed excerpt
+    let mut mibs = MibTree::new();
+    mibs.load_str(IF_MIB).unwrap();
+    for node in mibs.nodes().filter(|node| node.module == "IF-MIB") {
+        let (_, _, oid) = bundled::node(&node.name).expect(&node.name);
+        assert_eq!(oid, &node.oid[..], "{}", node.name);
+    }
+}
+
+const ACME_DISPLAY_MIB: &str = r#"
+ACME-DISPLAY-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    OBJECT-TYPE, Integer32, enterprises     FROM SNMPv2-SMI
+    TEXTUAL-CONVENTION, DateAndTime,
+    MacAddress, TruthValue                  FROM SNMPv2-TC;
+
+Tenths ::= TEXTUAL-CONVENTION
+    DISPLAY-HINT "d-1"
+    STATUS       current
+    DESCRIPTION  "A value in tenths."
+    SYNTAX       Integer32
+
+InetAddress ::= TEXTUAL-CONVENTION
+    STATUS       current
+    DESCRIPTION  "An Internet address."
+    SYNTAX       OCTET STRING (SIZE (0..255))
+
+acmeDisplay OBJECT IDENTIFIER ::= { enterprises 32473 2 }
+
+acmeClock OBJECT-TYPE
+    SYNTAX      DateAndTime
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "The clock."
+    ::= { acmeDisplay 1 }
+
+acmeMac OBJECT-TYPE
+    SYNTAX      MacAddress
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The base MAC address."
+    ::= { acmeDisplay 2 }
+
+acmeFeatures OBJECT-TYPE
+    SYNTAX      BITS { routing(0), switching(1), wireless(9) }
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "The enabled features."
+    ::= { acmeDisplay 3 }
+
+acmeTemperature OBJECT-TYPE
+    SYNTAX      Tenths
+    UNITS       "degrees Celsius"
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The temperature."
+    ::= { acmeDisplay 4 }
+
+acmeManager OBJECT-TYPE
+    SYNTAX      InetAddress
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "The manager address."
+    ::= { acmeDisplay 5 }
+
+acmeLocked OBJECT-TYPE
+    SYNTAX      TruthValue
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "Whether the configuration is locked."
+    ::= { acmeDisplay 6 }
+
+END
+"#;
+
+#[test]
+fn mib_format_and_parse_values() {
+    use super::mib::{format_inet_address, parse_inet_address, MibTree};
+    use super::OwnedValue;
+
+    let mut mibs = MibTree::new();
+    mibs.load_str(IF_MIB).unwrap();
+    mibs.load_str(ACME_DISPLAY_MIB).unwrap();
+    let oid = |name: &str| mibs.resolve(name).unwrap();
+
+    let cases: &[(&str, OwnedValue, &str)] = &[
+        (
+            "ifDescr.1",
+            OwnedValue::OctetString(b"eth0".to_vec()),
+            "eth0",
+        ),
+        (
+            "ifPhysAddress.1",
+            OwnedValue::OctetString(vec![0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]),
+            "00:1a:2b:3c:4d:5e",
+        ),
+        ("ifAdminStatus.1", OwnedValue::Integer(2), "down"),
+        ("acmeLocked.0", OwnedValue::Integer(1), "true"),
+        (
+            "acmeClock.0",
+            OwnedValue::OctetString(vec![0x07, 0xea, 10, 19, 13, 30, 15, 0, b'+', 2, 0]),
+            "2026-10-19,13:30:15.0,+2:0",
+        ),
+        (
+            "acmeFeatures.0",
+            OwnedValue::OctetString(vec![0x80, 0x40]),
+            "routing wireless",
+        ),
+        ("acmeTemperature.0", OwnedValue::Integer(-15), "-1.5"),
+        (
+            "acmeManager.0",
+            OwnedValue::OctetString(vec![192, 0, 2, 1]),
+            "192.0.2.1",
+        ),
+        (
+            "acmeMac.0",
+            OwnedValue::OctetString(vec![0xff; 6]),
+            "ff:ff:ff:ff:ff:ff",
+        ),
+    ];
+    for (name, value, text) in cases {
+        assert_eq!(mibs.format_value(&oid(name), value), *text, "{}", name);
+        assert_eq!(
+            mibs.parse_value(&oid(name), text).unwrap(),
+            *value,
+            "{}",
+            name
+        );
+    }
+
+    // labels with their numbers, plain numbers and unknown enumerations
+    let admin = oid("ifAdminStatus.1");
+    assert_eq!(
+        mibs.parse_value(&admin, "up(1)").unwrap(),
+        OwnedValue::Integer(1)
+    );
+    assert_eq!(
+        mibs.parse_value(&admin, "3").unwrap(),
+        OwnedValue::Integer(3)
+    );
+    assert_eq!(mibs.format_value(&admin, &OwnedValue::Integer(7)), "7");
+    assert!(mibs.parse_value(&admin, "sideways").is_err());
+    assert_eq!(
+        mibs.parse_value(&oid("acmeFeatures.0"), "switching, 2")
+            .unwrap(),
+        OwnedValue::OctetString(vec![0x60])
+    );
+    assert!(mibs.parse_value(&oid("acmeMac.0"), "00:zz").is_err());
+    assert_eq!(
+        mibs.parse_value(&oid("acmeClock.0"), "2026-10-19,13:30:15.0")
+            .unwrap(),
+        OwnedValue::OctetString(vec![0x07, 0xea, 10, 19, 13, 30, 15, 0])
+    );
+
+    // OID values are named, and binary strings of unknown objects shown in hex
+    assert_eq!(
+        mibs.format_value(
+            &[1, 3, 6, 1, 2, 1, 1, 2, 0],
+            &OwnedValue::ObjectIdentifier(oid("acmeDisplay"))
+        ),
+        "ACME-DISPLAY-MIB::acmeDisplay"
+    );
+    assert_eq!(
+        mibs.format_value(&[1, 3, 9], &OwnedValue::OctetString(vec![0, 1, 0xfe])),
+        "0x0001fe"
+    );
+
+    // an InetAddress given its InetAddressType
+    let v6 = parse_inet_address(4, "fe80::1%3").unwrap();
+    assert_eq!(v6.len(), 20);
+    assert_eq!(format_inet_address(4, &v6).unwrap(), "fe80::1%3");
+    assert_eq!(format_inet_address(1, &[10, 0, 0, 1]).unwrap(), "10.0.0.1");
+    assert!(format_inet_address(1, &[10, 0, 0]).is_none());
+
+    // a dns(16) name of four octets is not an IPv4 address once the row's
+    // InetAddressType is known
+    let manager = oid("acmeManager.0");
+    let name = OwnedValue::OctetString(b"mgmt".to_vec());
+    assert_eq!(
+        mibs.format_row_value(&manager, &name, &OwnedValue::Integer(16)),
+        "mgmt"
+    );
+    assert_eq!(
+        mibs.parse_row_value(&manager, "mgmt", &OwnedValue::Integer(16))
+            .unwrap(),
+        name
+    );
+    assert_eq!(
+        mibs.value_for_row_set(&manager, "192.0.2.1", &OwnedValue::Integer(16))
+            .unwrap(),
+        OwnedValue::OctetString(b"192.0.2.1".to_vec())
+    );
+    assert_eq!(
+        mibs.format_row_value(
+            &manager,
+            &OwnedValue::OctetString(vec![192, 0, 2, 1]),
+            &OwnedValue::Integer(1)
+        ),
+        "192.0.2.1"
+    );
+    assert!(mibs
+        .parse_row_value(&manager, "192.0.2.1", &OwnedValue::Integer(2))
+        .is_err());
+}
+
+const ACME_INDEX_MIB: &str = r#"
+ACME-INDEX-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    OBJECT-TYPE, IpAddress, Integer32, enterprises FROM SNMPv2-SMI
+    DisplayString, MacAddress                      FROM SNMPv2-TC
+    ifIndex                                        FROM IF-MIB;
+
+acmeIndex OBJECT IDENTIFIER ::= { enterprises 32473 3 }
+
+acmeNeighborTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF AcmeNeighborEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "Neighbors by interface, address and MAC address."
+    ::= { acmeIndex 1 }
+
+acmeNeighborEntry OBJECT-TYPE
+    SYNTAX      AcmeNeighborEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "A neighbor."
+    INDEX       { ifIndex, acmeNeighborAddress, acmeNeighborMac }
+    ::= { acmeNeighborTable 1 }
+
+AcmeNeighborEntry ::= SEQUENCE {
+    acmeNeighborAddress IpAddress,
+    acmeNeighborMac     MacAddress,
+    acmeNeighborAge     Integer32
+}
+
+acmeNeighborAddress OBJECT-TYPE
+    SYNTAX      IpAddress
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "The neighbor's address."
+    ::= { acmeNeighborEntry 1 }
+
+acmeNeighborMac OBJECT-TYPE
+    SYNTAX      MacAddress
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "The neighbor's MAC address."
+    ::= { acmeNeighborEntry 2 }
+
+acmeNeighborAge OBJECT-TYPE
+    SYNTAX      Integer32
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "Seconds since the neighbor was last seen."
+    ::= { acmeNeighborEntry 3 }
+
+acmeUserTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF AcmeUserEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "Users by realm and name."
+    ::= { acmeIndex 2 }
+
+acmeUserEntry OBJECT-TYPE
+    SYNTAX      AcmeUserEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "A user."
+    INDEX       { acmeUserRealm, IMPLIED acmeUserName }
+    ::= { acmeUserTable 1 }
+
+AcmeUserEntry ::= SEQUENCE {
+    acmeUserRealm OBJECT IDENTIFIER,
+    acmeUserName  DisplayString
+}
+
+acmeUserRealm OBJECT-TYPE
+    SYNTAX      OBJECT IDENTIFIER
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "The realm."
+    ::= { acmeUserEntry 1 }
+
+acmeUserName OBJECT-TYPE
+    SYNTAX      DisplayString (SIZE (1..32))
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "The user name."
+    ::= { acmeUserEntry 2 }
+
+END
+"#;
+
+#[test]
+fn mib_table_index_codec() {
+    use super::mib::index::{IndexType, IndexValue};
+    use super::mib::MibTree;
+
+    let mut mibs = MibTree::new();
+    mibs.load_str(IF_MIB).unwrap();
+    mibs.load_str(ACME_INDEX_MIB).unwrap();
+
+    let index = mibs.table_index("acmeNeighborTable").unwrap();
+    let types: Vec<IndexType> = index.objects.iter().map(|o| o.ty).collect();
+    assert_eq!(
+        types,
+        [
+            IndexType::Integer,
+            IndexType::IpAddress,
+            IndexType::OctetString(Some(6))
+        ]
+    );
+    let suffix = [3, 192, 0, 2, 1, 0, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e];
+    let values = vec![
+        IndexValue::Integer(3),
+        IndexValue::IpAddress([192, 0, 2, 1]),
+        IndexValue::OctetString(vec![0, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]),
+    ];
+    assert_eq!(index.decode(&suffix).unwrap(), values);
+    assert_eq!(index.encode(&values).unwrap(), suffix);
+    // too short, too long, or values of the wrong type or size
+    assert!(index.decode(&suffix[..10]).is_none());
+    assert!(index.decode(&[&suffix[..], &[1]].concat()).is_none());
+    assert!(index.encode(&values[..2]).is_none());
+    let mut short_mac = values.clone();
+    short_mac[2] = IndexValue::OctetString(vec![0; 5]);
+    assert!(index.encode(&short_mac).is_none());
+
+    // columns find the index of their row
+    let user = mibs.table_index("ACME-INDEX-MIB::acmeUserName").unwrap();
+    assert!(user.objects[1].implied);
+    let values = vec![
+        IndexValue::ObjectIdentifier(vec![1, 3, 6]),
+        IndexValue::OctetString(b"bob".to_vec()),
+    ];
+    let suffix = user.encode(&values).unwrap();
+    assert_eq!(suffix, [3, 1, 3, 6, b'b' as u32, b'o' as u32, b'b' as u32]);
+    assert_eq!(user.decode(&suffix).unwrap(), values);
+
+    // augmenting rows take the augmented row's index
+    let if_x = mibs.table_index("ifXEntry").unwrap();
+    assert_eq!(if_x.objects[0].name, "ifIndex");
+    assert!(mibs.table_index("ifNumber").is_err());
+}
+
+#[test]
+fn session_symbolic_names_walk_and_table() {
+    use super::agent::{Agent, ScalarHandler, TableHandler};
+    use super::mib::MibTree;
+    use super::notification::owned_varbinds;
+    use super::sync_session::{SyncAgent, SyncSession};
+    use super::{OwnedValue, SnmpError, SnmpPdu};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const IF_ENTRY: &[u32] = &[1, 3, 6, 1, 2, 1, 2, 2, 1];
+
+    let mut agent = Agent::new(b"public");
+    agent.register(
+        &[1, 3, 6, 1, 2, 1, 2, 1],
+        ScalarHandler::new(&[1, 3, 6, 1, 2, 1, 2, 1], || OwnedValue::Integer(20)),
+    );
+    let mut if_table = TableHandler::new(IF_ENTRY, &[2, 7]);
+    for index in 1..=20 {
+        if_table.insert_row(
+            &[index],
+            vec![
+                (
+                    2,
+                    OwnedValue::OctetString(format!("eth{}", index).into_bytes()),
+                ),
+                (7, OwnedValue::Integer(1)),
+            ],
+        );
+    }
+    agent.register(&[1, 3, 6, 1, 2, 1, 2, 2], if_table);
+    let mut agent = SyncAgent::new("127.0.0.1:0", agent).unwrap();
+    let addr = agent.local_addr().unwrap();
+    std::thread::spawn(move || agent.serve());
+
+    let mut session = SyncSession::new(addr, b"public", Some(Duration::from_secs(2)), 0).unwrap();
+
+    // numeric names work without MIBs, symbolic ones only when bundled
+    assert!(session.get("1.3.6.1.2.1.2.1.0").is_ok());
+    #[cfg(not(feature = "mibs"))]
+    assert_eq!(
+        session.get("ifNumber.0").err(),
+        Some(SnmpError::UnknownName("ifNumber.0".to_string()))
+    );
+
+    let mut mibs = MibTree::new();
+    mibs.load_str(IF_MIB).unwrap();
+    session.set_mibs(Some(Arc::new(mibs)));
+
+    let resp = session.get("IF-MIB::ifDescr.3").unwrap();
+    let (_, value) = resp.owned_varbinds().unwrap().remove(0);
+    assert_eq!(value, OwnedValue::OctetString(b"eth3".to_vec()));
+    let resp = session.getnext("ifNumber").unwrap();
+    assert_eq!(resp.owned_varbinds().unwrap()[0].1, OwnedValue::Integer(20));
+    assert_eq!(
+        session.get("ifBogus.1").err(),
+        Some(SnmpError::UnknownName("ifBogus.1".to_string()))
+    );
+
+    // walks span several GETBULK requests and stop at the end of the subtree
+    let descrs = session.walk("ifDescr").unwrap();
+    assert_eq!(descrs.len(), 20);
+    assert_eq!(descrs[19].0, [IF_ENTRY, &[2, 20]].concat());
+    assert_eq!(
+        session.walk(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 7]).unwrap().len(),
+        20
+    );
+
+    for name in ["ifTable", "IF-MIB::ifEntry"] {
+        let rows = session.table(name).unwrap();
+        assert_eq!(rows.len(), 20);
+        assert_eq!(
+            rows[&vec![12]][&2],
+            OwnedValue::OctetString(b"eth12".to_vec())
+        );
+        assert_eq!(rows[&vec![12]][&7], OwnedValue::Integer(1));
+    }
+
+    let resp = session.getbulk(&["ifNumber", "ifDescr"], 1, 3).unwrap();
+    let varbinds = resp.owned_varbinds().unwrap();
+    assert_eq!(varbinds.len(), 4);
+    assert_eq!(varbinds[3].0, [IF_ENTRY, &[2, 3]].concat());
+
+    // an agent failing the request fails the walk instead of ending it
+    let failing = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let failing_addr = failing.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let mut buf = [0; 4096];
+        let mut out = pdu::Buf::default();
+        loop {
+            let (len, source) = failing.recv_from(&mut buf).unwrap();
+            let req = SnmpPdu::from_bytes(&buf[..len]).unwrap();
+            let values = owned_varbinds(req.varbinds.clone()).unwrap();
+            pdu::build_owned_response(
+                req.community,
+                req.req_id,
+                snmp::ERRSTATUS_GENERR,
+                1,
+                &values,
+                4096,
+                &mut out,
+            )
+            .unwrap();
+            failing.send_to(&out[..], source).unwrap();
+        }
+    });
+    let mut session =
+        SyncSession::new(failing_addr, b"public", Some(Duration::from_secs(2)), 0).unwrap();
+    assert_eq!(
+        session.walk("1.3.6.1.2.1.2").err(),
+        Some(SnmpError::ErrorStatus(snmp::ERRSTATUS_GENERR, 1))
+    );
+}
+
+#[test]
+fn session_set_str_checks_syntax() {
+    use super::agent::{Agent, TableHandler};
+    use super::mib::{MibError, MibTree};
+    use super::sync_session::{SyncAgent, SyncSession};
+    use super::{OwnedValue, SnmpError};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let mut mibs = MibTree::new();
+    mibs.load_str(IF_MIB).unwrap();
+    mibs.load_str(ACME_DISPLAY_MIB).unwrap();
+    let oid = |name: &str| mibs.resolve(name).unwrap();
+
+    // constraints are checked before anything is sent
+    assert!(matches!(
+        mibs.value_for_set(&oid("ifAdminStatus.1"), "7"),
+        Err(MibError::Constraint(_))
+    ));
+    let err = mibs
+        .value_for_set(&oid("acmeClock.0"), "2026-10-19")
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "ACME-DISPLAY-MIB::acmeClock: size 4 is outside (8 | 11)"
+    );
+    assert!(matches!(
+        mibs.value_for_set(&oid("acmeMac.0"), "00:00:00:00:00:01"),
+        Err(MibError::NotWritable(_))
+    ));
+    assert!(matches!(
+        mibs.value_for_set(&oid("ifIndex.1"), "1"),
+        Err(MibError::NotWritable(_))
+    ));
+    assert_eq!(
+        mibs.value_for_set(&oid("acmeFeatures.0"), "routing")
+            .unwrap(),
+        OwnedValue::OctetString(vec![0x80])
+    );
+
+    let mut agent = Agent::new(b"private");
+    let mut if_table = TableHandler::new(&[1, 3, 6, 1, 2, 1, 2, 2, 1], &[7]);
+    if_table.set_writable(&[7]);
+    if_table.insert_row(&[1], vec![(7, OwnedValue::Integer(1))]);
+    agent.register(&[1, 3, 6, 1, 2, 1, 2, 2], if_table);
+    let mut agent = SyncAgent::new("127.0.0.1:0", agent).unwrap();
+    let addr = agent.local_addr().unwrap();
+    std::thread::spawn(move || agent.serve());
+
+    let mut session = SyncSession::new(addr, b"private", Some(Duration::from_secs(2)), 0).unwrap();
+    assert_eq!(
+        session.set_str("1.3.6.1.2.1.2.2.1.7.1", "down").err(),
+        Some(SnmpError::UnknownName("1.3.6.1.2.1.2.2.1.7.1".to_string()))
+    );
+    session.set_mibs(Some(Arc::new(mibs)));
+
+    let resp = session.set_str("ifAdminStatus.1", "down").unwrap();
+    assert_eq!(resp.error_status, 0);
+    let resp = session.get("ifAdminStatus.1").unwrap();
+    assert_eq!(resp.owned_varbinds().unwrap()[0].1, OwnedValue::Integer(2));
+
+    assert_eq!(
+        session.set_str("ifAdminStatus.1", "sideways").err(),
+        Some(SnmpError::InvalidValue(
+            "invalid value \"sideways\"".to_string()
+        ))
+    );
+    assert_eq!(
+        session.set_str("acmeMac.0", "00:00:00:00:00:01").err(),
+        Some(SnmpError::NotWritable(
+            "ACME-DISPLAY-MIB::acmeMac".to_string()
+        ))
+    );
+}
+
+#[test]
+fn mib_browse_search_and_json() {
+    use super::mib::{nodes_to_json, MibTree};
+
+    let mut mibs = MibTree::new();
+    mibs.load_str(IF_MIB).unwrap();
+    mibs.load_str(ACME_DISPLAY_MIB).unwrap();
+    let names = |nodes: Vec<&super::mib::Node>| -> Vec<String> {
+        nodes.iter().map(|node| node.name.clone()).collect()
+    };
+
+    let interfaces = mibs.resolve("interfaces").unwrap();
+    assert_eq!(
+        names(mibs.children(&interfaces).collect()),
+        ["ifNumber", "ifTable"]
+    );
+    let if_entry = mibs.resolve("ifEntry").unwrap();
+    assert_eq!(
+        names(mibs.children(&if_entry).collect()),
+        [
+            "ifIndex",
+            "ifDescr",
+            "ifPhysAddress",
+            "ifAdminStatus",
+            "ifInOctets"
+        ]
+    );
+    // unnamed arcs are skipped over, as the 0 of SMIv1 traps
+    let acme = mibs.resolve("acme").unwrap();
+    assert_eq!(
+        names(mibs.children(&acme).collect()),
+        ["acmeLinkFlap", "acmeDisplay"]
+    );
+    assert_eq!(mibs.parent(&if_entry).unwrap().name, "ifTable");
+
+    assert_eq!(
+        names(mibs.columns("ifXTable").unwrap()),
+        ["ifName", "ifHCInOctets", "ifPromiscuousMode"]
+    );
+    assert!(mibs.columns("ifNumber").is_err());
+    assert_eq!(names(mibs.notifications("IF-MIB").collect()), ["linkDown"]);
+    assert_eq!(
+        names(mibs.notifications("ACME-TRAP-MIB").collect()),
+        ["acmeLinkFlap"]
+    );
+    assert_eq!(mibs.module_nodes("ACME-DISPLAY-MIB").count(), 7);
+
+    // exact, prefix, substring and subsequence matches, in that order
+    assert_eq!(names(mibs.search("ifdescr", 3)), ["ifDescr"]);
+    assert_eq!(
+        names(mibs.search("ifin", 10)),
+        ["ifIndex", "ifInOctets", "ifHCInOctets", "ifAdminStatus"]
+    );
+    assert_eq!(
+        names(mibs.search("octets", 10)),
+        ["ifInOctets", "ifHCInOctets"]
+    );
+    assert_eq!(names(mibs.search("hcoct", 10)), ["ifHCInOctets"]);
+    assert_eq!(names(mibs.search("ACME-DISPLAY-MIB::mac", 10)), ["acmeMac"]);
+    assert_eq!(mibs.search("if", 2).len(), 2);
+
+    let temperature = mibs.node("acmeTemperature").unwrap();
+    let json = temperature.to_json();
+    for expected in [
+        "{\"name\":\"acmeTemperature\",\"module\":\"ACME-DISPLAY-MIB\",",
+        "\"oid\":\"1.3.6.1.4.1.32473.2.4\",\"kind\":\"scalar\",",
+        "\"syntax\":{\"base\":\"INTEGER\",\"tc\":\"Tenths\",\"displayHint\":\"d-1\",\"enums\":{},",
+        "\"access\":\"read-only\",\"status\":\"current\",",
+        "\"units\":\"degrees Celsius\",\"index\":[],\"augments\":null,",
+    ] {
+        assert!(
+            json.contains(expected),
+            "missing {:?} in {}",
+            expected,
+            json
+        );
+    }
+    let json = nodes_to_json(mibs.search("ifAdminStatus", 1));
+    assert!(json.starts_with("[{\"name\":\"ifAdminStatus\""));
+    assert!(json.contains("\"enums\":{\"up\":1,\"down\":2,\"testing\":3}"));
+    assert!(json.ends_with("}]"));
+    assert_eq!(nodes_to_json(mibs.search("nothing like it", 5)), "[]");
+
+    // bounds beyond 2^53 would lose precision as JavaScript numbers
+    mibs.load_str(
+        "ACME-BOUNDS-MIB DEFINITIONS ::= BEGIN
+        IMPORTS OBJECT-TYPE, Counter64, enterprises FROM SNMPv2-SMI;
+        acmeOffset OBJECT-TYPE
+            SYNTAX      INTEGER (MIN..-1 | 1..9007199254740991)
+            MAX-ACCESS  read-only
+            STATUS      current
+            DESCRIPTION \"Offset.\"
+            ::= { enterprises 32473 9 1 }
+        acmeTotal OBJECT-TYPE
+            SYNTAX      Counter64 (0..MAX)
+            MAX-ACCESS  read-only
+            STATUS      current
+            DESCRIPTION \"Total.\"
+            ::= { enterprises 32473 9 2 }
+        END",
+    )
+    .unwrap();
+    let json = mibs.node("acmeOffset").unwrap().to_json();
+    assert!(json.contains("\"ranges\":[[\"-9223372036854775808\",-1],[1,9007199254740991]]"));
+    let json = mibs.node("acmeTotal").unwrap().to_json();
+    assert!(json.contains("\"ranges\":[[0,\"18446744073709551615\"]]"));
+}
+
+const ACME_LINT_MIB: &str = r#"
+ACME-LINT-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    MODULE-IDENTITY, OBJECT-TYPE, Integer32, Gauge32, enterprises
+        FROM SNMPv2-SMI
+    DisplayString
+        FROM SNMPv2-TC;
+
+acmeLint MODULE-IDENTITY
+    LAST-UPDATED "202401010000Z"
+    ORGANIZATION "Acme"
+    CONTACT-INFO "noc@acme.example"
+    DESCRIPTION  "Test MIB with semantic errors."
+    ::= { enterprises 32473 9 }
+
+acmeLevel OBJECT-TYPE
+    SYNTAX      Integer32 (1..5)
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "Out of range default."
+    DEFVAL      { 7 }
+    ::= { acmeLint 1 }
+
+acmeMode OBJECT-TYPE
+    SYNTAX      INTEGER { on(1), off(2) }
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "Unknown label default."
+    DEFVAL      { auto }
+    ::= { acmeLint 2 }
+
+acmeName OBJECT-TYPE
+    SYNTAX      DisplayString (SIZE (0..4))
+    MAX-ACCESS  read-write
+    STATUS      current
+    DESCRIPTION "Too long default."
+    DEFVAL      { "toolong" }
+    ::= { acmeLint 3 }
+
+acmeAlias OBJECT-TYPE
+    SYNTAX      DisplayString
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "Same OID as acmeName."
+    ::= { acmeLint 3 }
+
+acmePortTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF AcmePortEntry
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "Accessible table."
+    ::= { acmeLint 4 }
+
+acmePortEntry OBJECT-TYPE
+    SYNTAX      AcmePortEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION "Row with an accessible index."
+    INDEX       { acmePortIndex }
+    ::= { acmePortTable 1 }
+
+AcmePortEntry ::= SEQUENCE {
+    acmePortIndex Integer32,
+    acmePortSpeed Gauge32
+}
+
+acmePortIndex OBJECT-TYPE
+    SYNTAX      Integer32 (1..64)
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "Index."
+    ::= { acmePortEntry 1 }
+
+acmePortSpeed OBJECT-TYPE
+    SYNTAX      Gauge32
+    MAX-ACCESS  read-only
+    STATUS      current
+    DESCRIPTION "Speed."
+    DEFVAL      { 1000 }
+    ::= { acmePortEntry 2 }
+
+acmeInterfaces OBJECT IDENTIFIER ::= { 1 3 6 1 2 1 2 }
+
+acmeLevel OBJECT IDENTIFIER ::= { acmeLint 5 }
+
+END
+"#;
+
+#[test]
+fn mib_lint_reports_diagnostics() {
+    use super::mib::{MibTree, Severity};
+
+    let mut mibs = MibTree::new();
+    mibs.load_str(IF_MIB).unwrap();
+    let lines = |diagnostics: &[super::mib::Diagnostic]| -> Vec<String> {
+        diagnostics.iter().map(|d| d.to_string()).collect()
+    };
+
+    let broken = "ACME-BROKEN-MIB DEFINITIONS ::= BEGIN\n\
+                  IMPORTS\n    \
+                      MODULE-IDENTITY, NoSuchType FROM SNMPv2-SMI\n    \
+                      acmeThing FROM ACME-MISSING-MIB;\n\
+                  acmeBroken OBJECT IDENTIFIER ::= { ifEntry 99 }\n\
+                  END\n";
+    assert_eq!(
+        lines(&mibs.lint_str(broken, "broken.mib")),
+        [
+            "broken.mib:3: error: unresolved import: NoSuchType is not defined in SNMPv2-SMI",
+            "broken.mib:4: error: unresolved import: module ACME-MISSING-MIB not found",
+            "broken.mib:5: error: ifEntry is neither defined nor imported",
+        ]
+    );
+    let diagnostics = mibs.lint_str("ACME-MIB DEFINITIONS ::= BEGIN\nfoo ::=\n", "a.mib");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].line, 2);
+
+    assert_eq!(
+        lines(&mibs.lint_str(ACME_LINT_MIB, "ACME-LINT-MIB.txt")),
+        [
+            "ACME-LINT-MIB.txt:17: error: invalid DEFVAL of acmeLevel: 7 is outside (1..5)",
+            "ACME-LINT-MIB.txt:25: error: invalid DEFVAL of acmeMode: invalid value \"auto\"",
+            "ACME-LINT-MIB.txt:33: error: invalid DEFVAL of acmeName: size 7 is outside (0..4)",
+            "ACME-LINT-MIB.txt:41: error: acmeAlias has the same OID as acmeName on line 33",
+            "ACME-LINT-MIB.txt:48: error: table acmePortTable must be not-accessible",
+            "ACME-LINT-MIB.txt:55: warning: index object acmePortIndex of acmePortEntry \
+             should be not-accessible",
+            "ACME-LINT-MIB.txt:83: warning: OID of acmeInterfaces is also assigned to \
+             IF-MIB::interfaces",
+            "ACME-LINT-MIB.txt:85: error: acmeLevel is defined twice, first on line 17",
+        ]
+    );
+    // linting loads nothing
+    assert!(!mibs.has_module("ACME-LINT-MIB"));
+
+    // a missing module only hides the definitions that depend on it
+    let missing = ACME_LINT_MIB
+        .replace(
+            "FROM SNMPv2-TC;",
+            "FROM SNMPv2-TC\n    acmeRoot FROM ACME-MISSING-MIB;",
+        )
+        .replace(
+            "acmeInterfaces OBJECT IDENTIFIER ::= { 1 3 6 1 2 1 2 }",
+            "acmeInterfaces OBJECT IDENTIFIER ::= { acmeRoot 2 }",
+        );
+    let diagnostics = lines(&mibs.lint_str(&missing, "ACME-LINT-MIB.txt"));
+    assert_eq!(
+        diagnostics[0],
+        "ACME-LINT-MIB.txt:9: error: unresolved import: module ACME-MISSING-MIB not found"
+    );
+    for expected in [
+        "ACME-LINT-MIB.txt:18: error: invalid DEFVAL of acmeLevel: 7 is outside (1..5)",
+        "ACME-LINT-MIB.txt:42: error: acmeAlias has the same OID as acmeName on line 34",
+        "ACME-LINT-MIB.txt:86: error: acmeLevel is defined twice, first on line 18",
+    ] {
+        assert!(
+            diagnostics.iter().any(|d| d == expected),
+            "{:?}",
+            diagnostics
+        );
+    }
+    assert!(!diagnostics.iter().any(|d| d.contains("acmeInterfaces")));
+
+    // a vendor file named after a module it does not define
+    let dir = std::env::temp_dir().join(format!("snmp-lint-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let misnamed = dir.join("ACME-MISSING-MIB.txt");
+    std::fs::write(
+        &misnamed,
+        "ACME-OTHER-MIB DEFINITIONS ::= BEGIN\n\
+         IMPORTS acmeRoot FROM ACME-MISSING-MIB;\n\
+         acmeOther OBJECT IDENTIFIER ::= { acmeRoot 1 }\n\
+         END\n",
+    )
+    .unwrap();
+    let mut mibs = mibs.clone();
+    mibs.add_path(&dir);
+    let diagnostics = lines(&mibs.lint_str(&missing, "ACME-LINT-MIB.txt"));
+    assert_eq!(
+        diagnostics[0],
+        format!(
+            "ACME-LINT-MIB.txt:9: error: unresolved import: {} does not define module \
+             ACME-MISSING-MIB",
+            misnamed.display()
+        )
+    );
+    assert!(diagnostics
+        .iter()
+        .any(|d| d
+            == "ACME-LINT-MIB.txt:18: error: invalid DEFVAL of acmeLevel: 7 is outside (1..5)"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn notifications_named_by_mibs() {
+    use super::async_session::{AsyncTrapListener, AsyncTrapSender};
+    use super::mib::MibTree;
+    use super::notification::Notification;
+    use super::OwnedValue;
+    use std::sync::Arc;
+
+    let mut mibs = MibTree::new();
+    mibs.load_str(IF_MIB).unwrap();
+    let mibs = Arc::new(mibs);
+    let if_index = mibs.resolve("ifIndex.3").unwrap();
+    let if_admin_status = mibs.resolve("ifAdminStatus.3").unwrap();
+
+    let mut listener = AsyncTrapListener::new(["127.0.0.1:0"]).await.unwrap();
+    listener.set_mibs(Some(mibs.clone()));
+    let local = listener.local_addrs()[0];
+    let mut sender = AsyncTrapSender::new(local, b"public", 0).await.unwrap();
+
+    let mut notification = Notification {
+        version: snmp::VERSION_2,
+        community: b"public".to_vec(),
+        source: None,
+        local: None,
+        uptime: 1,
+        trap_oid: mibs.resolve("linkDown").unwrap(),
+        varbinds: vec![
+            (if_index.clone(), OwnedValue::Integer(3)),
+            (if_admin_status, OwnedValue::Integer(2)),
+        ],
+    };
+    sender.send_trap(&notification, None).await.unwrap();
+    let (received, named) = listener.recv_named_notification().await.unwrap();
+    assert_eq!(received.trap_oid, notification.trap_oid);
+    let named = named.unwrap();
+    assert_eq!(named.name, "IF-MIB::linkDown");
+    assert!(named.is_complete());
+    assert_eq!(named.objects["ifIndex"], OwnedValue::Integer(3));
+    assert_eq!(named.objects["ifAdminStatus"], OwnedValue::Integer(2));
+
+    // OBJECTS the notification lacks are reported, not an error
+    notification.varbinds.truncate(1);
+    let named = notification.named(&mibs).unwrap();
+    assert_eq!(named.objects.len(), 1);
+    assert_eq!(named.missing, ["ifAdminStatus"]);
+    assert!(!named.is_complete());
+
+    // SMIv1 traps are named by their TRAP-TYPE
+    notification.trap_oid = vec![1, 3, 6, 1, 4, 1, 32473, 0, 7];
+    let named = notification.named(&mibs).unwrap();
+    assert_eq!(named.name, "ACME-TRAP-MIB::acmeLinkFlap");
+    assert_eq!(named.objects["ifIndex"], OwnedValue::Integer(3));
+
+    notification.trap_oid = vec![1, 3, 6, 1, 4, 1, 32473, 0, 8];
+    assert_eq!(notification.named(&mibs), None);
+    notification.trap_oid = if_index;
+    assert_eq!(notification.named(&mibs), None);
+}
@@ -0,0 +1,201 @@
+//! Suppression of duplicate and excessive notifications.
+//!
+//! A `Suppressor` drops notifications that repeat within a deduplication
+//! window and enforces a token bucket rate limit per sending address.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use crate::notification::Notification;
+use crate::OwnedValue;
+
+#[derive(Clone, Debug)]
+pub struct SuppressionConfig {
+    /// Notifications with the same source, trap OID and key varbinds seen
+    /// within this window are suppressed. `None` disables deduplication.
+    pub dedup_window: Option<Duration>,
+    /// Varbinds that are part of the deduplication key, matched by OID
+    /// prefix, e.g. `ifIndex` to tell linkDown traps of different interfaces
+    /// apart.
+    pub dedup_varbinds: Vec<Vec<u32>>,
+    /// Sustained notifications per second allowed from a single source.
+    /// `None` disables rate limiting.
+    pub rate_limit: Option<f64>,
+    /// Number of notifications a source may send in a burst.
+    pub burst: u32,
+}
+
+impl Default for SuppressionConfig {
+    fn default() -> Self {
+        SuppressionConfig {
+            dedup_window: Some(Duration::from_secs(10)),
+            dedup_varbinds: Vec::new(),
+            rate_limit: None,
+            burst: 10,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Duplicate,
+    RateLimited,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SuppressionStats {
+    pub passed: u64,
+    pub duplicates: u64,
+    pub rate_limited: u64,
+}
+
+impl SuppressionStats {
+    pub fn suppressed(&self) -> u64 {
+        self.duplicates + self.rate_limited
+    }
+}
+
+#[derive(Hash, PartialEq, Eq)]
+struct DedupKey {
+    source: Option<IpAddr>,
+    trap_oid: Vec<u32>,
+    varbinds: Vec<(Vec<u32>, OwnedValue)>,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+pub struct Suppressor {
+    config: SuppressionConfig,
+    seen: HashMap<DedupKey, Instant>,
+    last_prune: Option<Instant>,
+    buckets: HashMap<Option<IpAddr>, TokenBucket>,
+    last_bucket_prune: Option<Instant>,
+    stats: SuppressionStats,
+}
+
+impl Suppressor {
+    pub fn new(config: SuppressionConfig) -> Self {
+        Suppressor {
+            config,
+            seen: HashMap::new(),
+            last_prune: None,
+            buckets: HashMap::new(),
+            last_bucket_prune: None,
+            stats: SuppressionStats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> &SuppressionStats {
+        &self.stats
+    }
+
+    pub fn check(&mut self, notification: &Notification) -> Verdict {
+        self.check_at(notification, Instant::now())
+    }
+
+    /// Like `check`, with an explicit clock reading.
+    pub fn check_at(&mut self, notification: &Notification, now: Instant) -> Verdict {
+        let key = self.dedup_key(notification);
+        if key.as_ref().is_some_and(|key| self.is_duplicate(key, now)) {
+            self.stats.duplicates += 1;
+            Verdict::Duplicate
+        } else if !self.take_token(notification, now) {
+            self.stats.rate_limited += 1;
+            Verdict::RateLimited
+        } else {
+            // only delivered notifications make their retransmissions
+            // duplicates
+            if let Some(key) = key {
+                self.seen.insert(key, now);
+            }
+            self.stats.passed += 1;
+            Verdict::Pass
+        }
+    }
+
+    /// The deduplication key of a notification, if deduplication is on.
+    fn dedup_key(&self, notification: &Notification) -> Option<DedupKey> {
+        self.config.dedup_window?;
+        let varbinds = notification
+            .varbinds
+            .iter()
+            .filter(|(name, _)| {
+                self.config
+                    .dedup_varbinds
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix))
+            })
+            .cloned()
+            .collect();
+
+        Some(DedupKey {
+            source: notification.source.map(|s| s.ip()),
+            trap_oid: notification.trap_oid.clone(),
+            varbinds,
+        })
+    }
+
+    fn is_duplicate(&mut self, key: &DedupKey, now: Instant) -> bool {
+        let Some(window) = self.config.dedup_window else {
+            return false;
+        };
+
+        match self.last_prune {
+            Some(last) if now.duration_since(last) < window => {}
+            _ => {
+                self.seen
+                    .retain(|_, seen| now.duration_since(*seen) < window);
+                self.last_prune = Some(now);
+            }
+        }
+
+        self.seen
+            .get(key)
+            .is_some_and(|seen| now.duration_since(*seen) < window)
+    }
+
+    fn take_token(&mut self, notification: &Notification, now: Instant) -> bool {
+        let Some(rate) = self.config.rate_limit else {
+            return true;
+        };
+        let burst = self.config.burst.max(1) as f64;
+
+        // buckets that have refilled are as good as new ones, so they are
+        // dropped once per refill time, as `seen` is once per window
+        let refill = Duration::try_from_secs_f64(burst / rate).unwrap_or(Duration::MAX);
+        match self.last_bucket_prune {
+            Some(last) if now.duration_since(last) < refill => {}
+            _ => {
+                self.buckets.retain(|_, bucket| {
+                    let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                    bucket.tokens + elapsed * rate < burst
+                });
+                self.last_bucket_prune = Some(now);
+            }
+        }
+
+        let bucket = self
+            .buckets
+            .entry(notification.source.map(|s| s.ip()))
+            .or_insert(TokenBucket {
+                tokens: burst,
+                updated: now,
+            });
+
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Fan-out of received notifications to downstream managers.
+//!
+//! Every target gets its own queue and sending task, so a slow or unreachable
+//! target only ever drops its own notifications.
+
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use crate::async_session::{AsyncTrapSender, AsyncTrapSession};
+use crate::notification::{Notification, SNMP_TRAP_ADDRESS};
+use crate::snmp;
+use crate::OwnedValue;
+use crate::SnmpError;
+use crate::SnmpResult;
+
+const DEFAULT_QUEUE_LEN: usize = 1024;
+
+/// Message version used when forwarding to a target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RelayVersion {
+    /// Forward in the version the notification was received as.
+    Keep,
+    V1,
+    V2c,
+}
+
+/// A downstream manager.
+#[derive(Clone, Debug)]
+pub struct RelayTarget {
+    pub address: SocketAddr,
+    /// Replaces the community string of forwarded notifications.
+    pub community: Option<Vec<u8>>,
+    pub version: RelayVersion,
+    /// Appends `snmpTrapAddress.0` with the original sender's address when
+    /// the notification does not already carry it.
+    pub add_trap_address: bool,
+    /// Number of notifications buffered for the target before new ones are
+    /// dropped.
+    pub queue_len: usize,
+}
+
+impl RelayTarget {
+    pub fn new(address: SocketAddr) -> Self {
+        RelayTarget {
+            address,
+            community: None,
+            version: RelayVersion::Keep,
+            add_trap_address: false,
+            queue_len: DEFAULT_QUEUE_LEN,
+        }
+    }
+}
+
+/// Delivery counters of a single target.
+#[derive(Debug, Default)]
+pub struct RelayStats {
+    pub forwarded: AtomicU64,
+    pub dropped: AtomicU64,
+    pub send_errors: AtomicU64,
+}
+
+struct TargetQueue {
+    address: SocketAddr,
+    sender: mpsc::Sender<Arc<Notification>>,
+    stats: Arc<RelayStats>,
+}
+
+pub struct TrapRelay {
+    targets: Vec<TargetQueue>,
+}
+
+impl TrapRelay {
+    /// Connects to every target and spawns its delivery task. Must be called
+    /// from within a tokio runtime.
+    pub async fn new(targets: Vec<RelayTarget>) -> io::Result<Self> {
+        let mut queues = Vec::with_capacity(targets.len());
+
+        for target in targets {
+            let sender = AsyncTrapSender::new(target.address, b"public", 0).await?;
+            let (tx, rx) = mpsc::channel(target.queue_len.max(1));
+            let stats = Arc::new(RelayStats::default());
+
+            queues.push(TargetQueue {
+                address: target.address,
+                sender: tx,
+                stats: stats.clone(),
+            });
+
+            tokio::spawn(deliver(target, sender, rx, stats));
+        }
+
+        Ok(TrapRelay { targets: queues })
+    }
+
+    /// Queues the notification for every target without waiting for delivery.
+    /// Notifications with OIDs that cannot be encoded are dropped.
+    pub fn forward(&self, notification: Notification) {
+        if !notification.is_encodable() {
+            for target in &self.targets {
+                target.stats.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            return;
+        }
+        let notification = Arc::new(notification);
+
+        for target in &self.targets {
+            if target.sender.try_send(notification.clone()).is_err() {
+                target.stats.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Receives notifications from `session` and forwards them until
+    /// receiving fails. Undecodable messages are skipped.
+    pub async fn run(&self, session: &mut AsyncTrapSession) -> SnmpResult<()> {
+        loop {
+            match session.recv_notification().await {
+                Ok(notification) => self.forward(notification),
+                Err(SnmpError::ReceiveError) => return Err(SnmpError::ReceiveError),
+                Err(_) => continue,
+            }
+        }
+    }
+
+    /// Delivery counters per target address.
+    pub fn stats(&self) -> impl Iterator<Item = (SocketAddr, &RelayStats)> {
+        self.targets.iter().map(|t| (t.address, &*t.stats))
+    }
+}
+
+/// Applies the target's rewrite rules to a notification.
+pub fn rewrite(target: &RelayTarget, notification: &Notification) -> Notification {
+    let mut out = notification.clone();
+
+    if target.add_trap_address && out.varbind(SNMP_TRAP_ADDRESS).is_none() {
+        if let Some(SocketAddr::V4(source)) = out.source {
+            out.varbinds.push((
+                SNMP_TRAP_ADDRESS.to_vec(),
+                OwnedValue::IpAddress(source.ip().octets()),
+            ));
+        }
+    }
+
+    if let Some(ref community) = target.community {
+        out.community = community.clone();
+    }
+
+    out
+}
+
+async fn deliver(
+    target: RelayTarget,
+    mut sender: AsyncTrapSender,
+    mut rx: mpsc::Receiver<Arc<Notification>>,
+    stats: Arc<RelayStats>,
+) {
+    while let Some(notification) = rx.recv().await {
+        let out = rewrite(&target, &notification);
+
+        let version = match target.version {
+            RelayVersion::Keep if out.version == snmp::VERSION_1 => RelayVersion::V1,
+            RelayVersion::Keep => RelayVersion::V2c,
+            version => version,
+        };
+
+        let res = match version {
+            RelayVersion::V1 => {
+                let mut trap = out.to_v1();
+                // Keep the original agent address unless the trap names one.
+                if trap.agent_addr == [0; 4] {
+                    if let Some(IpAddr::V4(ip)) = out.source.map(|s| s.ip()) {
+                        trap.agent_addr = ip.octets();
+                    }
+                }
+                sender.send_trap_v1(&trap, Some(&out.community)).await
+            }
+            _ => sender.send_trap(&out, Some(&out.community)).await,
+        };
+
+        match res {
+            Ok(()) => stats.forwarded.fetch_add(1, Ordering::Relaxed),
+            Err(_) => stats.send_errors.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+}